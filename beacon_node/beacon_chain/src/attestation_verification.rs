@@ -41,6 +41,7 @@ use crate::{
     BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use bls::verify_signature_sets;
+use eth2::lighthouse::ValidatorSightingKind;
 use itertools::Itertools;
 use proto_array::Block as ProtoBlock;
 use slog::debug;
@@ -668,6 +669,15 @@ impl<'a, T: BeaconChainTypes> VerifiedAggregatedAttestation<'a, T> {
         let attestation = signed_aggregate.message().aggregate();
         let aggregator_index = signed_aggregate.message().aggregator_index();
 
+        // Record the aggregate for doppelganger detection, even if it is later rejected as a
+        // duplicate.
+        chain.observe_validator_sighting(
+            aggregator_index,
+            attestation.data().slot,
+            ValidatorSightingKind::Aggregate,
+            || attestation.data().tree_hash_root(),
+        );
+
         // Observe the valid attestation so we do not re-process it.
         //
         // It's important to double check that the attestation is not already known, otherwise two
@@ -956,6 +966,15 @@ impl<'a, T: BeaconChainTypes> VerifiedUnaggregatedAttestation<'a, T> {
         validator_index: u64,
         chain: &BeaconChain<T>,
     ) -> Result<(), Error> {
+        // Record the attestation for doppelganger detection, even if it is later rejected as a
+        // duplicate.
+        chain.observe_validator_sighting(
+            validator_index,
+            attestation.data().slot,
+            ValidatorSightingKind::Attestation,
+            || attestation.data().tree_hash_root(),
+        );
+
         // Now that the attestation has been fully verified, store that we have received a valid
         // attestation from this validator.
         //
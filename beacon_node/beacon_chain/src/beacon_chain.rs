@@ -8,6 +8,7 @@ use crate::beacon_block_streamer::{BeaconBlockStreamer, CheckCaches};
 use crate::beacon_proposer_cache::compute_proposer_duties_from_head;
 use crate::beacon_proposer_cache::BeaconProposerCache;
 use crate::blob_verification::{GossipBlobError, GossipVerifiedBlob};
+use crate::block_production_timings::{duration_ms, BlockProductionTimings};
 use crate::block_times_cache::BlockTimesCache;
 use crate::block_verification::POS_PANDA_BANNER;
 use crate::block_verification::{
@@ -30,7 +31,9 @@ use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
 use crate::eth1_finalization_cache::{Eth1FinalizationCache, Eth1FinalizationData};
 use crate::events::ServerSentEventHandler;
 use crate::execution_payload::{get_execution_payload, NotifyExecutionLayer, PreparePayloadHandle};
+use crate::fork_choice_history::ForkChoiceHistory;
 use crate::fork_choice_signal::{ForkChoiceSignalRx, ForkChoiceSignalTx, ForkChoiceWaitResult};
+use crate::gossip_propagation::GossipPropagation;
 use crate::graffiti_calculator::GraffitiCalculator;
 use crate::head_tracker::{HeadTracker, HeadTrackerReader, SszHeadTracker};
 use crate::historical_blocks::HistoricalBlockError;
@@ -67,11 +70,14 @@ use crate::validator_monitor::{
     get_slot_delay_ms, timestamp_now, ValidatorMonitor,
     HISTORIC_EPOCHS as VALIDATOR_MONITOR_HISTORIC_EPOCHS,
 };
+use crate::validator_monitor_history;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
+use crate::validator_sightings::ValidatorSightings;
 use crate::{
     kzg_utils, metrics, AvailabilityPendingExecutedBlock, BeaconChainError, BeaconForkChoiceStore,
     BeaconSnapshot, CachedHead,
 };
+use eth2::lighthouse::{ValidatorMonitorEpochSummary, ValidatorSighting, ValidatorSightingKind};
 use eth2::types::{EventKind, SseBlobSidecar, SseBlock, SseExtendedPayloadAttributes};
 use execution_layer::{
     BlockProposalContents, BlockProposalContentsType, BuilderParams, ChainHealth, ExecutionLayer,
@@ -114,7 +120,7 @@ use std::collections::HashSet;
 use std::io::prelude::*;
 use std::marker::PhantomData;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use store::iter::{BlockRootsIterator, ParentRootBlockIterator, StateRootsIterator};
 use store::{
     DatabaseBlock, Error as DBError, HotColdDB, KeyValueStore, KeyValueStoreOp, StoreItem, StoreOp,
@@ -418,6 +424,10 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub observed_blob_sidecars: RwLock<ObservedBlobSidecars<T::EthSpec>>,
     /// Maintains a record of slashable message seen over the gossip network or RPC.
     pub observed_slashable: RwLock<ObservedSlashable<T::EthSpec>>,
+    /// Maintains a record of the gossip messages of validators watched for doppelgangers.
+    pub validator_sightings: RwLock<ValidatorSightings<T::EthSpec>>,
+    /// Maintains a record of the peers from which recent blocks and aggregates were received.
+    pub gossip_propagation: RwLock<GossipPropagation<T::EthSpec>>,
     /// Maintains a record of which validators have submitted voluntary exits.
     pub observed_voluntary_exits: Mutex<ObservedOperations<SignedVoluntaryExit, T::EthSpec>>,
     /// Maintains a record of which validators we've seen proposer slashings for.
@@ -435,6 +445,8 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     /// Stores information about the canonical head and finalized/justified checkpoints of the
     /// chain. Also contains the fork choice struct, for computing the canonical head.
     pub canonical_head: CanonicalHead<T>,
+    /// A ring buffer of recent changes to the head and FFG checkpoints of fork choice.
+    pub fork_choice_history: RwLock<ForkChoiceHistory>,
     /// The root of the genesis block.
     pub genesis_block_root: Hash256,
     /// The root of the genesis state.
@@ -468,6 +480,8 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub reqresp_pre_import_cache: Arc<RwLock<ReqRespPreImportCache<T::EthSpec>>>,
     /// A cache used to keep track of various block timings.
     pub block_times_cache: Arc<RwLock<BlockTimesCache>>,
+    /// A ring buffer of the time taken by each phase of recent block proposals.
+    pub block_production_timings: RwLock<BlockProductionTimings>,
     /// A cache used to track pre-finalization block roots for quick rejection.
     pub pre_finalization_block_cache: PreFinalizationBlockCache,
     /// A cache used to produce light_client server messages
@@ -712,6 +726,64 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Records that the block at `slot` was published to the network `publish_delay` after it was
+    /// received, completing its block production trace.
+    ///
+    /// The completed trace is sent to subscribers of the `block_production_trace` event. Blocks
+    /// which were not produced by this node have no trace, and are ignored.
+    pub fn register_block_publication(&self, slot: Slot, publish_delay: Duration) {
+        let trace = {
+            let mut timings = self.block_production_timings.write();
+            timings.update(slot, |trace| {
+                trace.publication_ms = Some(duration_ms(publish_delay))
+            });
+            timings.get(slot)
+        };
+
+        if let Some((event_handler, trace)) = self.event_handler.as_ref().zip(trace) {
+            if event_handler.has_block_production_trace_subscribers() {
+                event_handler.register(EventKind::BlockProductionTrace(Box::new(trace)));
+            }
+        }
+    }
+
+    /// Persists the summaries of monitored validators for completed epochs, and prunes summaries
+    /// which are older than the retention period.
+    pub fn persist_validator_monitor_history(&self) -> Result<(), Error> {
+        let validator_monitor = self.validator_monitor.read();
+        let retention_epochs = validator_monitor.history_retention_epochs();
+        let summaries = validator_monitor.take_completed_summaries();
+        drop(validator_monitor);
+
+        let Some(latest_epoch) = summaries.iter().map(|summary| summary.epoch).max() else {
+            return Ok(());
+        };
+
+        validator_monitor_history::store_summaries(&self.store.hot_db, &summaries)?;
+        validator_monitor_history::prune_summaries(
+            &self.store.hot_db,
+            latest_epoch.saturating_sub(retention_epochs),
+        )?;
+
+        Ok(())
+    }
+
+    /// Returns the persisted summaries of the monitored validator with `validator_index` from
+    /// `start_epoch` to `end_epoch` (inclusive).
+    pub fn validator_monitor_history(
+        &self,
+        validator_index: u64,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<ValidatorMonitorEpochSummary>, Error> {
+        Ok(validator_monitor_history::load_summaries(
+            &self.store.hot_db,
+            validator_index,
+            start_epoch,
+            end_epoch,
+        )?)
+    }
+
     /// Returns the slot _right now_ according to `self.slot_clock`. Returns `Err` if the slot is
     /// unavailable.
     ///
@@ -1228,6 +1300,25 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Returns the blobs of the block at `block_root` and `slot`, loading them from the blob
+    /// archive if they have been pruned from the database.
+    ///
+    /// ## Errors
+    /// May return a database error.
+    pub fn get_blobs_or_archived(
+        &self,
+        block_root: &Hash256,
+        slot: Slot,
+    ) -> Result<BlobSidecarList<T::EthSpec>, Error> {
+        match self.store.get_blobs(block_root)? {
+            Some(blobs) => Ok(blobs),
+            None => Ok(self
+                .store
+                .get_archived_blobs(block_root, slot)?
+                .unwrap_or_default()),
+        }
+    }
+
     pub fn get_blinded_block(
         &self,
         block_root: &Hash256,
@@ -4232,6 +4323,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ) -> Result<BeaconBlockResponseWrapper<T::EthSpec>, BlockProductionError> {
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_REQUESTS);
         let _complete_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_TIMES);
+        let production_start = Instant::now();
+        self.block_production_timings.write().start(slot);
+
         // Part 1/2 (blocking)
         //
         // Load the parent state from disk.
@@ -4245,21 +4339,34 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .ok_or(BlockProductionError::ShuttingDown)?
             .await
             .map_err(BlockProductionError::TokioJoin)??;
+        let state_load_duration = production_start.elapsed();
+        self.block_production_timings.write().update(slot, |trace| {
+            trace.state_load_ms = Some(duration_ms(state_load_duration))
+        });
 
         // Part 2/2 (async, with some blocking components)
         //
         // Produce the block upon the state
-        self.produce_block_on_state(
-            state,
-            state_root_opt,
+        let block_response = self
+            .produce_block_on_state(
+                state,
+                state_root_opt,
+                slot,
+                randao_reveal,
+                validator_graffiti,
+                verification,
+                builder_boost_factor,
+                block_production_version,
+            )
+            .await?;
+
+        self.block_production_timings.write().set_produced(
             slot,
-            randao_reveal,
-            validator_graffiti,
-            verification,
-            builder_boost_factor,
-            block_production_version,
-        )
-        .await
+            production_start.elapsed(),
+            timestamp_now(),
+        );
+
+        Ok(block_response)
     }
 
     /// Load a beacon state from the database for block production. This is a long-running process
@@ -4295,6 +4402,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     "slot" => slot,
                     "head_to_reorg" => %head_block_root,
                 );
+                self.fork_choice_history
+                    .write()
+                    .register_proposer_re_org(slot, head_block_root);
                 (re_org_state, Some(re_org_state_root))
             } else {
                 // Fetch the head state advanced through to `slot`, which should be present in the
@@ -4951,11 +5061,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
 
         let slot_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_SLOT_PROCESS_TIMES);
+        let state_advance_start = Instant::now();
 
         // Ensure the state has performed a complete transition into the required slot.
         complete_state_advance(&mut state, state_root_opt, produce_at_slot, &self.spec)?;
 
         drop(slot_timer);
+        let state_advance_duration = state_advance_start.elapsed();
 
         state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
         state.apply_pending_mutations()?;
@@ -5007,6 +5119,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         };
 
+        let op_pool_packing_start = Instant::now();
         let (mut proposer_slashings, mut attester_slashings, mut voluntary_exits) =
             self.op_pool.get_slashings_and_exits(&state, &self.spec);
 
@@ -5160,6 +5273,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             Some(sync_aggregate)
         };
 
+        let op_pool_packing_duration = op_pool_packing_start.elapsed();
+        self.block_production_timings.write().update(slot, |trace| {
+            trace.proposer_index = Some(proposer_index);
+            trace.state_advance_ms = Some(duration_ms(state_advance_duration));
+            trace.op_pool_packing_ms = Some(duration_ms(op_pool_packing_duration));
+        });
+
         Ok(PartialBeaconBlock {
             state,
             slot,
@@ -5434,6 +5554,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
 
         let process_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_PROCESS_TIMES);
+        let block_completion_start = Instant::now();
         let signature_strategy = match verification {
             ProduceBlockVerification::VerifyRandao => BlockSignatureStrategy::VerifyRandao,
             ProduceBlockVerification::NoVerification => BlockSignatureStrategy::NoVerification,
@@ -5463,9 +5584,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         let (mut block, _) = block.deconstruct();
         *block.state_root_mut() = state_root;
+        let block_completion_duration = block_completion_start.elapsed();
 
         let blobs_verification_timer =
             metrics::start_timer(&metrics::BLOCK_PRODUCTION_BLOBS_VERIFICATION_TIMES);
+        let blob_bundling_start = Instant::now();
         let blob_items = match maybe_blobs_and_proofs {
             Some((blobs, proofs)) => {
                 let expected_kzg_commitments =
@@ -5504,6 +5627,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         };
 
         drop(blobs_verification_timer);
+        let blob_bundling_duration = blob_bundling_start.elapsed();
+
+        self.block_production_timings.write().update(slot, |trace| {
+            trace.block_completion_ms = Some(duration_ms(block_completion_duration));
+            if blob_items.is_some() {
+                trace.blob_bundling_ms = Some(duration_ms(blob_bundling_duration));
+            }
+        });
 
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
 
@@ -6249,6 +6380,26 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             // sync anyway).
             self.naive_aggregation_pool.write().prune(slot);
             self.block_times_cache.write().prune(slot);
+            self.validator_sightings.write().prune(slot);
+            self.gossip_propagation.write().prune(slot);
+
+            // Persist the validator monitor history on a blocking thread, since it writes to the
+            // database.
+            if self.validator_monitor.read().history_retention_epochs() > 0 {
+                let chain = self.clone();
+                self.task_executor.spawn_blocking(
+                    move || {
+                        if let Err(e) = chain.persist_validator_monitor_history() {
+                            error!(
+                                chain.log,
+                                "Failed to persist validator monitor history";
+                                "error" => ?e,
+                            );
+                        }
+                    },
+                    "validator_monitor_history",
+                );
+            }
 
             // Don't run heavy-weight tasks during sync.
             if self.best_slot() + MAX_PER_SLOT_FORK_CHOICE_DISTANCE < slot {
@@ -6746,6 +6897,28 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         gossip_attested || block_attested || aggregated || produced_block
     }
 
+    /// Record a signature-verified gossip message from `validator_index` if that validator is
+    /// being watched for doppelgangers.
+    ///
+    /// The `root` is only computed for watched validators.
+    pub(crate) fn observe_validator_sighting(
+        &self,
+        validator_index: u64,
+        slot: Slot,
+        kind: ValidatorSightingKind,
+        root: impl FnOnce() -> Hash256,
+    ) {
+        if !self.validator_sightings.read().is_watched(validator_index) {
+            return;
+        }
+        self.validator_sightings.write().observe(ValidatorSighting {
+            validator_index,
+            slot,
+            kind,
+            root: root(),
+        });
+    }
+
     /// The epoch at which we require a data availability check in block processing.
     /// `None` if the `Deneb` fork is disabled.
     pub fn data_availability_boundary(&self) -> Option<Epoch> {
@@ -0,0 +1,182 @@
+//! Provides the `BlockProductionTimings` ring buffer, which records a breakdown of the time taken
+//! to produce, sign and publish recent blocks.
+//!
+//! Each phase of block production records its duration against the slot of the block, so that a
+//! late proposal can be attributed to the phase which caused it.
+
+use eth2::lighthouse::{BlockProductionTrace, BuilderCall, BuilderCallTiming};
+use std::collections::VecDeque;
+use std::time::Duration;
+use types::Slot;
+
+/// The number of block production traces which are retained.
+pub const DEFAULT_CAPACITY: usize = 64;
+
+struct Entry {
+    trace: BlockProductionTrace,
+    /// The time (since the UNIX epoch) at which the unsigned block was returned.
+    produced_at: Option<Duration>,
+}
+
+/// A ring buffer of block production traces, keyed by slot.
+///
+/// Once `capacity` traces are held, starting a new trace evicts the oldest. Traces are only
+/// created by `start`, so that blocks produced elsewhere (e.g. by another beacon node) and merely
+/// published via this node do not create traces. Updates to a slot without a trace are ignored.
+pub struct BlockProductionTimings {
+    entries: VecDeque<Entry>,
+    capacity: usize,
+}
+
+impl Default for BlockProductionTimings {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl BlockProductionTimings {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Start a new trace for `slot`, replacing any existing trace for that slot.
+    pub fn start(&mut self, slot: Slot) {
+        self.entries.retain(|entry| entry.trace.slot != slot);
+        self.push(slot);
+    }
+
+    /// Apply `f` to the trace for `slot`, if one exists.
+    pub fn update(&mut self, slot: Slot, f: impl FnOnce(&mut BlockProductionTrace)) {
+        if let Some(entry) = self.entry_mut(slot) {
+            f(&mut entry.trace)
+        }
+    }
+
+    /// Record the duration of a request made to the builder.
+    pub fn add_builder_call(
+        &mut self,
+        slot: Slot,
+        call: BuilderCall,
+        duration: Duration,
+        success: bool,
+    ) {
+        self.update(slot, |trace| {
+            trace.builder_calls.push(BuilderCallTiming {
+                call,
+                duration_ms: duration_ms(duration),
+                success,
+            })
+        })
+    }
+
+    /// Record that the unsigned block for `slot` was returned at `timestamp`, after `duration`.
+    pub fn set_produced(&mut self, slot: Slot, duration: Duration, timestamp: Duration) {
+        let Some(entry) = self.entry_mut(slot) else {
+            return;
+        };
+        entry.trace.production_ms = Some(duration_ms(duration));
+        entry.produced_at = Some(timestamp);
+    }
+
+    /// Record that the signed block for `slot` was received at `timestamp`.
+    ///
+    /// Only the first signed block received after production is recorded, so that the time spent
+    /// reconstructing a blinded block is not counted as part of the signing round-trip.
+    pub fn set_signed_block_received(&mut self, slot: Slot, timestamp: Duration) {
+        let Some(entry) = self.entry_mut(slot) else {
+            return;
+        };
+        if entry.trace.signing_round_trip_ms.is_none() {
+            entry.trace.signing_round_trip_ms = entry
+                .produced_at
+                .and_then(|produced_at| timestamp.checked_sub(produced_at))
+                .map(duration_ms);
+        }
+    }
+
+    /// Returns the trace for `slot`, if any.
+    pub fn get(&self, slot: Slot) -> Option<BlockProductionTrace> {
+        self.entries
+            .iter()
+            .find(|entry| entry.trace.slot == slot)
+            .map(|entry| entry.trace.clone())
+    }
+
+    fn entry_mut(&mut self, slot: Slot) -> Option<&mut Entry> {
+        self.entries
+            .iter_mut()
+            .find(|entry| entry.trace.slot == slot)
+    }
+
+    fn push(&mut self, slot: Slot) {
+        while self.entries.len() >= self.capacity.max(1) {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Entry {
+            trace: BlockProductionTrace {
+                slot,
+                ..Default::default()
+            },
+            produced_at: None,
+        });
+    }
+}
+
+/// Returns `duration` in whole milliseconds.
+pub fn duration_ms(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oldest_trace_is_evicted() {
+        let mut timings = BlockProductionTimings::new(2);
+        for slot in 0..3 {
+            timings.start(Slot::new(slot));
+            timings.update(Slot::new(slot), |trace| trace.state_load_ms = Some(slot));
+        }
+
+        assert!(timings.get(Slot::new(0)).is_none());
+        assert_eq!(timings.get(Slot::new(2)).unwrap().state_load_ms, Some(2));
+
+        // Restarting a trace clears it, without evicting any other trace.
+        timings.start(Slot::new(2));
+        assert_eq!(timings.get(Slot::new(2)).unwrap().state_load_ms, None);
+        assert!(timings.get(Slot::new(1)).is_some());
+    }
+
+    #[test]
+    fn signing_round_trip() {
+        let mut timings = BlockProductionTimings::default();
+        let slot = Slot::new(1);
+        timings.start(slot);
+        timings.set_produced(
+            slot,
+            Duration::from_millis(300),
+            Duration::from_millis(1_000),
+        );
+        timings.set_signed_block_received(slot, Duration::from_millis(1_050));
+        timings.set_signed_block_received(slot, Duration::from_millis(1_500));
+
+        let trace = timings.get(slot).unwrap();
+        assert_eq!(trace.production_ms, Some(300));
+        assert_eq!(trace.signing_round_trip_ms, Some(50));
+    }
+
+    #[test]
+    fn updates_without_trace_are_ignored() {
+        let mut timings = BlockProductionTimings::default();
+        let slot = Slot::new(1);
+        timings.update(slot, |trace| trace.state_load_ms = Some(1));
+        timings.add_builder_call(slot, BuilderCall::SubmitBlindedBlock, Duration::ZERO, true);
+        timings.set_produced(slot, Duration::ZERO, Duration::ZERO);
+        timings.set_signed_block_received(slot, Duration::ZERO);
+        assert!(timings.get(slot).is_none());
+    }
+}
@@ -68,6 +68,7 @@ use crate::{
     metrics, BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use derivative::Derivative;
+use eth2::lighthouse::ValidatorSightingKind;
 use eth2::types::{BlockGossip, EventKind, PublishBlockRequest};
 use execution_layer::PayloadStatus;
 pub use fork_choice::{AttestationFromBlock, PayloadVerificationStatus};
@@ -549,33 +550,48 @@ impl BlockSlashInfo<GossipDataColumnError> {
 
 /// Process invalid blocks to see if they are suitable for the slasher.
 ///
-/// If no slasher is configured, this is a no-op.
+/// Headers with a valid proposer signature are passed to the slasher, and sent to subscribers of
+/// the `rejected_block_header` event so that slashers outside of this process can check them.
+///
+/// If no slasher is configured and there are no subscribers, this is a no-op.
 pub(crate) fn process_block_slash_info<T: BeaconChainTypes, TErr: BlockBlobError>(
     chain: &BeaconChain<T>,
     slash_info: BlockSlashInfo<TErr>,
 ) -> TErr {
-    if let Some(slasher) = chain.slasher.as_ref() {
-        let (verified_header, error) = match slash_info {
-            BlockSlashInfo::SignatureNotChecked(header, e) => {
-                if verify_header_signature::<_, TErr>(chain, &header).is_ok() {
-                    (header, e)
-                } else {
-                    return e;
-                }
-            }
-            BlockSlashInfo::SignatureInvalid(e) => return e,
-            BlockSlashInfo::SignatureValid(header, e) => (header, e),
-        };
+    let event_handler = chain
+        .event_handler
+        .as_ref()
+        .filter(|event_handler| event_handler.has_rejected_block_header_subscribers());
 
-        slasher.accept_block_header(verified_header);
-        error
-    } else {
-        match slash_info {
+    if chain.slasher.is_none() && event_handler.is_none() {
+        return match slash_info {
             BlockSlashInfo::SignatureNotChecked(_, e)
             | BlockSlashInfo::SignatureInvalid(e)
             | BlockSlashInfo::SignatureValid(_, e) => e,
+        };
+    }
+
+    let (verified_header, error) = match slash_info {
+        BlockSlashInfo::SignatureNotChecked(header, e) => {
+            if verify_header_signature::<_, TErr>(chain, &header).is_ok() {
+                (header, e)
+            } else {
+                return e;
+            }
         }
+        BlockSlashInfo::SignatureInvalid(e) => return e,
+        BlockSlashInfo::SignatureValid(header, e) => (header, e),
+    };
+
+    if let Some(event_handler) = event_handler {
+        event_handler.register(EventKind::RejectedBlockHeader(Box::new(
+            verified_header.clone(),
+        )));
+    }
+    if let Some(slasher) = chain.slasher.as_ref() {
+        slasher.accept_block_header(verified_header);
     }
+    error
 }
 
 /// Verify all signatures (except deposit signatures) on all blocks in the `chain_segment`. If all
@@ -965,6 +981,13 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
             return Err(BlockError::ProposalSignatureInvalid);
         }
 
+        chain.observe_validator_sighting(
+            block.message().proposer_index(),
+            block.slot(),
+            ValidatorSightingKind::Block,
+            || block_root,
+        );
+
         chain
             .observed_slashable
             .write()
@@ -5,6 +5,7 @@ use crate::beacon_proposer_cache::BeaconProposerCache;
 use crate::data_availability_checker::DataAvailabilityChecker;
 use crate::eth1_chain::{CachingEth1Backend, SszEth1};
 use crate::eth1_finalization_cache::Eth1FinalizationCache;
+use crate::fork_choice_recorder;
 use crate::fork_choice_signal::ForkChoiceSignalTx;
 use crate::fork_revert::{reset_fork_choice_to_finalization, revert_to_fork_boundary};
 use crate::graffiti_calculator::{GraffitiCalculator, GraffitiOrigin};
@@ -855,6 +856,12 @@ where
             .map_err(|e| format!("Error writing chain & metadata to disk: {:?}", e))?;
         drop(head_tracker_reader);
 
+        if let Some(dir) = &self.chain_config.fork_choice_recording_dir {
+            fork_choice_recorder::start_recording::<
+                Witness<TSlotClock, TEth1Backend, E, THotStore, TColdStore>,
+            >(dir, &mut fork_choice, &log)?;
+        }
+
         let genesis_validators_root = head_snapshot.beacon_state.genesis_validators_root();
         let genesis_time = head_snapshot.beacon_state.genesis_time();
         let canonical_head = CanonicalHead::new(fork_choice, Arc::new(head_snapshot));
@@ -919,6 +926,8 @@ where
             observed_block_producers: <_>::default(),
             observed_blob_sidecars: <_>::default(),
             observed_slashable: <_>::default(),
+            validator_sightings: <_>::default(),
+            gossip_propagation: <_>::default(),
             observed_voluntary_exits: <_>::default(),
             observed_proposer_slashings: <_>::default(),
             observed_attester_slashings: <_>::default(),
@@ -928,6 +937,7 @@ where
             genesis_validators_root,
             genesis_time,
             canonical_head,
+            fork_choice_history: <_>::default(),
             genesis_block_root,
             genesis_state_root,
             fork_choice_signal_tx,
@@ -942,6 +952,7 @@ where
             eth1_finalization_cache: RwLock::new(Eth1FinalizationCache::new(log.clone())),
             beacon_proposer_cache,
             block_times_cache: <_>::default(),
+            block_production_timings: <_>::default(),
             pre_finalization_block_cache: <_>::default(),
             validator_pubkey_cache: RwLock::new(validator_pubkey_cache),
             attester_cache: <_>::default(),
@@ -41,6 +41,7 @@ use crate::{
     validator_monitor::{get_slot_delay_ms, timestamp_now},
     BeaconChain, BeaconChainError as Error, BeaconChainTypes, BeaconSnapshot,
 };
+use eth2::lighthouse::{CheckpointUpdate, ForkChoiceUpdate, HeadChange, HeadWeights, Reorg};
 use eth2::types::{EventKind, SseChainReorg, SseFinalizedCheckpoint, SseHead, SseLateHead};
 use fork_choice::{
     ExecutionStatus, ForkChoiceStore, ForkChoiceView, ForkchoiceUpdateParameters, ProtoBlock,
@@ -317,6 +318,13 @@ impl<T: BeaconChainTypes> CanonicalHead<T> {
             finalized_hash: forkchoice_update_params.finalized_hash,
         };
 
+        if fork_choice_write_lock.is_recording() {
+            warn!(
+                log,
+                "Stopped recording fork choice";
+                "reason" => "fork choice was restored from disk",
+            );
+        }
         *fork_choice_write_lock = fork_choice;
         // Avoid interleaving the fork choice and cached head locks.
         drop(fork_choice_write_lock);
@@ -639,6 +647,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         perform_debug_logging::<T>(&old_view, &new_view, &fork_choice_read_lock, &self.log);
 
+        // Take the weights which were used to select the head, so they can be recorded in the
+        // fork choice history.
+        let head_weights = {
+            let proposer_boost = fork_choice_read_lock
+                .proto_array()
+                .core_proto_array()
+                .previous_proposer_boost;
+            let proposer_boost_root = Some(proposer_boost.root).filter(|root| !root.is_zero());
+            HeadWeights {
+                old_head_weight: fork_choice_read_lock.get_block_weight(&old_view.head_block_root),
+                new_head_weight: fork_choice_read_lock.get_block_weight(&new_view.head_block_root),
+                proposer_boost_root,
+                proposer_boost_score: proposer_boost.score,
+                proposer_boost: proposer_boost_root == Some(new_view.head_block_root),
+            }
+        };
+
         // Drop the read lock, it's no longer required and holding it any longer than necessary
         // will just cause lock contention.
         drop(fork_choice_read_lock);
@@ -720,15 +745,45 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             cached_head_write_lock.clone()
         };
 
+        // Record any changes to the FFG checkpoints in the fork choice history.
+        {
+            let timestamp = self.slot_clock.now_duration().unwrap_or_default();
+            let mut fork_choice_history = self.fork_choice_history.write();
+            if new_view.justified_checkpoint != old_view.justified_checkpoint {
+                fork_choice_history.push(
+                    current_slot,
+                    timestamp,
+                    ForkChoiceUpdate::Justification(CheckpointUpdate {
+                        old: old_view.justified_checkpoint,
+                        new: new_view.justified_checkpoint,
+                    }),
+                );
+            }
+            if new_view.finalized_checkpoint != old_view.finalized_checkpoint {
+                fork_choice_history.push(
+                    current_slot,
+                    timestamp,
+                    ForkChoiceUpdate::Finalization(CheckpointUpdate {
+                        old: old_view.finalized_checkpoint,
+                        new: new_view.finalized_checkpoint,
+                    }),
+                );
+            }
+        }
+
         // Alias for readability.
         let new_snapshot = &new_cached_head.snapshot;
         let old_snapshot = &old_cached_head.snapshot;
 
         // If the head changed, perform some updates.
         if new_snapshot.beacon_block_root != old_snapshot.beacon_block_root {
-            if let Err(e) =
-                self.after_new_head(&old_cached_head, &new_cached_head, new_head_proto_block)
-            {
+            if let Err(e) = self.after_new_head(
+                current_slot,
+                &old_cached_head,
+                &new_cached_head,
+                new_head_proto_block,
+                head_weights,
+            ) {
                 crit!(
                     self.log,
                     "Error updating canonical head";
@@ -771,9 +826,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// Perform updates to caches and other components after the canonical head has been changed.
     fn after_new_head(
         self: &Arc<Self>,
+        current_slot: Slot,
         old_cached_head: &CachedHead<T::EthSpec>,
         new_cached_head: &CachedHead<T::EthSpec>,
         new_head_proto_block: ProtoBlock,
+        head_weights: HeadWeights,
     ) -> Result<(), Error> {
         let _timer = metrics::start_timer(&metrics::FORK_CHOICE_AFTER_NEW_HEAD_TIMES);
         let old_snapshot = &old_cached_head.snapshot;
@@ -792,6 +849,37 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             &self.log,
         );
 
+        // Record the head change in the fork choice history.
+        {
+            let old_head_block = old_snapshot.beacon_block_root;
+            let old_head_slot = old_snapshot.beacon_block.slot();
+            let new_head_block = new_snapshot.beacon_block_root;
+            let new_head_slot = new_snapshot.beacon_block.slot();
+            let timestamp = self.slot_clock.now_duration().unwrap_or_default();
+            let mut fork_choice_history = self.fork_choice_history.write();
+            let update = if let Some(depth) = reorg_distance {
+                ForkChoiceUpdate::Reorg(Reorg {
+                    depth: depth.as_u64(),
+                    old_head_block,
+                    old_head_slot,
+                    new_head_block,
+                    new_head_slot,
+                    weights: head_weights,
+                    proposer_re_org: fork_choice_history
+                        .is_proposer_re_org(new_head_slot, old_head_block),
+                })
+            } else {
+                ForkChoiceUpdate::HeadChange(HeadChange {
+                    old_head_block,
+                    old_head_slot,
+                    new_head_block,
+                    new_head_slot,
+                    weights: head_weights,
+                })
+            };
+            fork_choice_history.push(current_slot, timestamp, update);
+        }
+
         // Determine if the new head is in a later epoch to the previous head.
         let is_epoch_transition = old_snapshot
             .beacon_block
@@ -1,5 +1,6 @@
 pub use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::time::Duration;
 use types::{Checkpoint, Epoch};
 
@@ -84,6 +85,12 @@ pub struct ChainConfig {
     pub epochs_per_migration: u64,
     /// When set to true Light client server computes and caches state proofs for serving updates
     pub enable_light_client_server: bool,
+    /// Whether to record gossip propagation of blocks and aggregates for analysis.
+    pub gossip_propagation_analysis: bool,
+    /// If set, record every input to fork choice within this directory.
+    pub fork_choice_recording_dir: Option<PathBuf>,
+    /// Whether to serve blobs from the blob archive in response to `BlobsByRange` requests.
+    pub serve_archived_blobs_by_range: bool,
 }
 
 impl Default for ChainConfig {
@@ -115,6 +122,9 @@ impl Default for ChainConfig {
             always_prepare_payload: false,
             epochs_per_migration: crate::migrate::DEFAULT_EPOCHS_PER_MIGRATION,
             enable_light_client_server: false,
+            gossip_propagation_analysis: false,
+            fork_choice_recording_dir: None,
+            serve_archived_blobs_by_range: false,
         }
     }
 }
@@ -0,0 +1,413 @@
+//! A bounded on-disk log of server-sent events, which allows subscribers to resume from the last
+//! event they received.
+//!
+//! Events are assigned monotonically increasing IDs (starting at 1) and written as JSON lines to
+//! segment files named after the ID of their first event. The oldest segment is deleted once
+//! there are more than `SEGMENTS_PER_LOG` full segments, so at least `max_events` events are
+//! always retained. IDs continue from the last logged event when the node restarts.
+
+use crate::events::DEFAULT_CHANNEL_CAPACITY;
+use eth2::lighthouse::LoggedEvent;
+use eth2::types::EventKind;
+pub use eth2::types::EventTopic;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use slog::{error, Logger};
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use types::EthSpec;
+
+/// The number of full segments which are retained.
+pub const SEGMENTS_PER_LOG: u64 = 4;
+/// The default number of events which are retained.
+pub const DEFAULT_MAX_EVENTS: u64 = 65_536;
+
+const SEGMENT_PREFIX: &str = "events-";
+const SEGMENT_SUFFIX: &str = ".jsonl";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventLogConfig {
+    /// The directory in which the segments are stored.
+    pub dir: PathBuf,
+    /// The topics which are logged.
+    pub topics: Vec<EventTopic>,
+    /// The minimum number of events which are retained.
+    pub max_events: u64,
+}
+
+impl EventLogConfig {
+    /// Log the topics which are required to follow the canonical chain.
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            topics: vec![
+                EventTopic::Block,
+                EventTopic::Head,
+                EventTopic::FinalizedCheckpoint,
+                EventTopic::ChainReorg,
+            ],
+            max_events: DEFAULT_MAX_EVENTS,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The events after the requested ID have been pruned from the log.
+    Pruned {
+        last_event_id: u64,
+        oldest_event_id: u64,
+    },
+    /// The requested ID has not been assigned to an event yet.
+    UnknownEvent {
+        last_event_id: u64,
+        latest_event_id: u64,
+    },
+    Io(String),
+}
+
+/// The segment files of the log.
+struct Segments {
+    dir: PathBuf,
+    events_per_segment: u64,
+    /// The ID of the first event in each segment, oldest first.
+    first_ids: VecDeque<u64>,
+    /// The segment to which new events are written.
+    current: Option<File>,
+    next_id: u64,
+}
+
+impl Segments {
+    fn open(dir: PathBuf, events_per_segment: u64) -> Result<Self, Error> {
+        fs::create_dir_all(&dir).map_err(|e| Error::Io(format!("{:?}", e)))?;
+
+        let mut first_ids = fs::read_dir(&dir)
+            .map_err(|e| Error::Io(format!("{:?}", e)))?
+            .filter_map(|entry| {
+                entry
+                    .ok()?
+                    .file_name()
+                    .to_str()?
+                    .strip_prefix(SEGMENT_PREFIX)?
+                    .strip_suffix(SEGMENT_SUFFIX)?
+                    .parse::<u64>()
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+        first_ids.sort_unstable();
+
+        let mut segments = Self {
+            dir,
+            events_per_segment,
+            first_ids: first_ids.into(),
+            current: None,
+            next_id: 1,
+        };
+
+        // Continue from the last event which was written in full. New events are always written
+        // to a new segment, so that they never follow a partially written line.
+        while let Some(&first_id) = segments.first_ids.back() {
+            let path = segments.path(first_id);
+            if let Some(event) = read_segment(&path)?.last() {
+                segments.next_id = event.id + 1;
+                break;
+            }
+            // Remove empty segments, which would otherwise be overwritten by the new segment.
+            fs::remove_file(&path).map_err(|e| Error::Io(format!("{:?}", e)))?;
+            segments.first_ids.pop_back();
+        }
+
+        Ok(segments)
+    }
+
+    /// The ID of the oldest event which has not been pruned.
+    fn oldest_event_id(&self) -> u64 {
+        self.first_ids.front().copied().unwrap_or(self.next_id)
+    }
+
+    fn path(&self, first_id: u64) -> PathBuf {
+        self.dir.join(format!(
+            "{}{:020}{}",
+            SEGMENT_PREFIX, first_id, SEGMENT_SUFFIX
+        ))
+    }
+
+    fn append(&mut self, topic: String, data: serde_json::Value) -> Result<LoggedEvent, Error> {
+        let id = self.next_id;
+        let segment_full = self
+            .first_ids
+            .back()
+            .map_or(true, |first_id| id >= first_id + self.events_per_segment);
+
+        if self.current.is_none() || segment_full {
+            let file = File::create(self.path(id)).map_err(|e| Error::Io(format!("{:?}", e)))?;
+            self.current = Some(file);
+            self.first_ids.push_back(id);
+
+            while self.first_ids.len() as u64 > SEGMENTS_PER_LOG + 1 {
+                if let Some(first_id) = self.first_ids.pop_front() {
+                    fs::remove_file(self.path(first_id))
+                        .map_err(|e| Error::Io(format!("{:?}", e)))?;
+                }
+            }
+        }
+
+        let event = LoggedEvent { id, topic, data };
+        let mut line = serde_json::to_vec(&event).map_err(|e| Error::Io(format!("{:?}", e)))?;
+        line.push(b'\n');
+
+        // Write each event with a single call, so that readers never observe part of a line
+        // unless the write itself is interrupted.
+        if let Some(file) = self.current.as_mut() {
+            if let Err(e) = file.write_all(&line) {
+                // Skip this ID and start a new segment for the next event, rather than following
+                // a partial line.
+                self.current = None;
+                self.next_id += 1;
+                return Err(Error::Io(format!("{:?}", e)));
+            }
+        }
+        self.next_id += 1;
+
+        Ok(event)
+    }
+}
+
+/// Read the events of a segment, skipping any lines which were not written in full.
+fn read_segment(path: &Path) -> Result<Vec<LoggedEvent>, Error> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        // The segment may have been pruned since the list of segments was read, in which case
+        // the reader must check whether it still holds the events it needs.
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(Error::Io(format!("{:?}", e))),
+    };
+
+    let mut events = vec![];
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| Error::Io(format!("{:?}", e)))?;
+        if let Ok(event) = serde_json::from_str::<LoggedEvent>(&line) {
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
+
+/// Writes events to disk and broadcasts them to resumable subscriptions.
+pub struct EventLog {
+    topics: Vec<EventTopic>,
+    segments: Mutex<Segments>,
+    tx: Sender<Arc<LoggedEvent>>,
+    log: Logger,
+}
+
+impl EventLog {
+    pub fn open(
+        config: EventLogConfig,
+        capacity_multiplier: usize,
+        log: Logger,
+    ) -> Result<Self, String> {
+        let events_per_segment = std::cmp::max(config.max_events / SEGMENTS_PER_LOG, 1);
+        let segments = Segments::open(config.dir.clone(), events_per_segment).map_err(|e| {
+            format!(
+                "Unable to open event log at {}: {:?}",
+                config.dir.display(),
+                e
+            )
+        })?;
+        let (tx, _) =
+            broadcast::channel(capacity_multiplier.saturating_mul(DEFAULT_CHANNEL_CAPACITY));
+
+        Ok(Self {
+            topics: config.topics,
+            segments: Mutex::new(segments),
+            tx,
+            log,
+        })
+    }
+
+    /// Returns `true` if events with the given topic are logged.
+    pub fn is_logged(&self, topic: EventTopic) -> bool {
+        self.topics.contains(&topic)
+    }
+
+    fn is_logged_topic_name(&self, topic_name: &str) -> bool {
+        self.topics
+            .iter()
+            .any(|topic| topic.to_string() == topic_name)
+    }
+
+    /// Write `kind` to the log and send it to subscribers, if its topic is logged.
+    pub fn register<E: EthSpec>(&self, kind: &EventKind<E>) {
+        let topic = kind.topic_name();
+        if !self.is_logged_topic_name(topic) {
+            return;
+        }
+
+        let result = serde_json::to_value(kind)
+            .map_err(|e| Error::Io(format!("{:?}", e)))
+            .and_then(|data| {
+                // Hold the lock while broadcasting, so that subscribers receive events in order.
+                let mut segments = self.segments.lock();
+                let event = Arc::new(segments.append(topic.to_string(), data)?);
+                let _ = self.tx.send(event);
+                Ok(())
+            });
+
+        if let Err(e) = result {
+            error!(
+                self.log,
+                "Failed to write event log";
+                "topic" => topic,
+                "error" => ?e,
+            );
+        }
+    }
+
+    pub fn subscribe(&self) -> Receiver<Arc<LoggedEvent>> {
+        self.tx.subscribe()
+    }
+
+    /// Returns the ID which will be assigned to the next event.
+    pub fn next_event_id(&self) -> u64 {
+        self.segments.lock().next_id
+    }
+
+    /// Read all of the retained events with an ID greater than `last_event_id`, oldest first.
+    ///
+    /// Returns an error if any of those events have been pruned.
+    pub fn read_after(&self, last_event_id: u64) -> Result<Vec<LoggedEvent>, Error> {
+        let (segment_paths, first_ids, oldest_event_id, next_id) = {
+            let segments = self.segments.lock();
+            let segment_paths = segments
+                .first_ids
+                .iter()
+                .map(|first_id| segments.path(*first_id))
+                .collect::<Vec<_>>();
+            (
+                segment_paths,
+                segments.first_ids.clone(),
+                segments.oldest_event_id(),
+                segments.next_id,
+            )
+        };
+
+        if last_event_id >= next_id {
+            return Err(Error::UnknownEvent {
+                last_event_id,
+                latest_event_id: next_id.saturating_sub(1),
+            });
+        }
+        check_retained(last_event_id, oldest_event_id)?;
+
+        // Skip the segments which only contain events prior to `last_event_id`.
+        let start = first_ids
+            .iter()
+            .rposition(|first_id| *first_id <= last_event_id + 1)
+            .unwrap_or(0);
+
+        let mut events = vec![];
+        for path in segment_paths.iter().skip(start) {
+            events.extend(
+                read_segment(path)?
+                    .into_iter()
+                    .filter(|event| event.id > last_event_id && event.id < next_id),
+            );
+        }
+
+        // The segments are read without holding the lock, so the oldest of them may have been
+        // pruned by `append` before it was opened. Segments are pruned oldest first, so the
+        // events read are complete if the first event required is still retained.
+        check_retained(last_event_id, self.segments.lock().oldest_event_id())?;
+
+        Ok(events)
+    }
+}
+
+/// Returns an error if the event following `last_event_id` has been pruned.
+fn check_retained(last_event_id: u64, oldest_event_id: u64) -> Result<(), Error> {
+    if last_event_id + 1 < oldest_event_id {
+        return Err(Error::Pruned {
+            last_event_id,
+            oldest_event_id,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2::types::SseFinalizedCheckpoint;
+    use types::{Epoch, Hash256, MainnetEthSpec};
+
+    fn finalized(epoch: u64) -> EventKind<MainnetEthSpec> {
+        EventKind::FinalizedCheckpoint(SseFinalizedCheckpoint {
+            block: Hash256::zero(),
+            state: Hash256::zero(),
+            epoch: Epoch::new(epoch),
+            execution_optimistic: false,
+        })
+    }
+
+    fn ids(events: &[LoggedEvent]) -> Vec<u64> {
+        events.iter().map(|event| event.id).collect()
+    }
+
+    #[test]
+    fn replay_prune_and_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = EventLogConfig {
+            dir: dir.path().to_path_buf(),
+            topics: vec![EventTopic::FinalizedCheckpoint],
+            max_events: 8,
+        };
+        let event_log = EventLog::open(config.clone(), 1, logging::test_logger()).unwrap();
+
+        for epoch in 0..5 {
+            event_log.register(&finalized(epoch));
+        }
+        let events = event_log.read_after(0).unwrap();
+        assert_eq!(ids(&events), vec![1, 2, 3, 4, 5]);
+        assert_eq!(
+            events[2].event_kind::<MainnetEthSpec>().unwrap(),
+            finalized(2)
+        );
+        assert_eq!(ids(&event_log.read_after(3).unwrap()), vec![4, 5]);
+        assert!(event_log.read_after(5).unwrap().is_empty());
+        assert_eq!(
+            event_log.read_after(6),
+            Err(Error::UnknownEvent {
+                last_event_id: 6,
+                latest_event_id: 5
+            })
+        );
+
+        // Segments hold 2 events each and at most 5 segments are kept.
+        for epoch in 5..20 {
+            event_log.register(&finalized(epoch));
+        }
+        assert_eq!(
+            event_log.read_after(0),
+            Err(Error::Pruned {
+                last_event_id: 0,
+                oldest_event_id: 11
+            })
+        );
+        assert_eq!(
+            ids(&event_log.read_after(10).unwrap()),
+            (11..=20).collect::<Vec<_>>()
+        );
+
+        // IDs continue from the last event after the log is reopened.
+        drop(event_log);
+        let event_log = EventLog::open(config, 1, logging::test_logger()).unwrap();
+        assert_eq!(event_log.next_event_id(), 21);
+        event_log.register(&finalized(20));
+        assert_eq!(ids(&event_log.read_after(19).unwrap()), vec![20, 21]);
+    }
+}
@@ -1,10 +1,12 @@
+use crate::event_log::EventLog;
+use eth2::types::EventTopic;
 pub use eth2::types::{EventKind, SseBlock, SseFinalizedCheckpoint, SseHead};
 use slog::{trace, Logger};
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::{error::SendError, Receiver, Sender};
 use types::EthSpec;
 
-const DEFAULT_CHANNEL_CAPACITY: usize = 16;
+pub(crate) const DEFAULT_CHANNEL_CAPACITY: usize = 16;
 
 pub struct ServerSentEventHandler<E: EthSpec> {
     attestation_tx: Sender<EventKind<E>>,
@@ -20,10 +22,13 @@ pub struct ServerSentEventHandler<E: EthSpec> {
     light_client_finality_update_tx: Sender<EventKind<E>>,
     light_client_optimistic_update_tx: Sender<EventKind<E>>,
     block_reward_tx: Sender<EventKind<E>>,
+    block_production_trace_tx: Sender<EventKind<E>>,
+    rejected_block_header_tx: Sender<EventKind<E>>,
     proposer_slashing_tx: Sender<EventKind<E>>,
     attester_slashing_tx: Sender<EventKind<E>>,
     bls_to_execution_change_tx: Sender<EventKind<E>>,
     block_gossip_tx: Sender<EventKind<E>>,
+    event_log: Option<EventLog>,
     log: Logger,
 }
 
@@ -49,6 +54,8 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
         let (light_client_finality_update_tx, _) = broadcast::channel(capacity);
         let (light_client_optimistic_update_tx, _) = broadcast::channel(capacity);
         let (block_reward_tx, _) = broadcast::channel(capacity);
+        let (block_production_trace_tx, _) = broadcast::channel(capacity);
+        let (rejected_block_header_tx, _) = broadcast::channel(capacity);
         let (proposer_slashing_tx, _) = broadcast::channel(capacity);
         let (attester_slashing_tx, _) = broadcast::channel(capacity);
         let (bls_to_execution_change_tx, _) = broadcast::channel(capacity);
@@ -68,15 +75,41 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
             light_client_finality_update_tx,
             light_client_optimistic_update_tx,
             block_reward_tx,
+            block_production_trace_tx,
+            rejected_block_header_tx,
             proposer_slashing_tx,
             attester_slashing_tx,
             bls_to_execution_change_tx,
             block_gossip_tx,
+            event_log: None,
             log,
         }
     }
 
+    /// Write the events with the topics configured in `event_log` to disk, so that subscribers can
+    /// resume from the last event they received.
+    pub fn with_event_log(mut self, event_log: EventLog) -> Self {
+        self.event_log = Some(event_log);
+        self
+    }
+
+    pub fn event_log(&self) -> Option<&EventLog> {
+        self.event_log.as_ref()
+    }
+
+    /// Returns `true` if events with the given topic are written to the event log, in which case
+    /// they must be registered even if there are no subscribers.
+    fn is_logged(&self, topic: EventTopic) -> bool {
+        self.event_log
+            .as_ref()
+            .map_or(false, |event_log| event_log.is_logged(topic))
+    }
+
     pub fn register(&self, kind: EventKind<E>) {
+        if let Some(event_log) = &self.event_log {
+            event_log.register(&kind);
+        }
+
         let log_count = |name, count| {
             trace!(
                 self.log,
@@ -138,6 +171,14 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
                 .block_reward_tx
                 .send(kind)
                 .map(|count| log_count("block reward", count)),
+            EventKind::BlockProductionTrace(_) => self
+                .block_production_trace_tx
+                .send(kind)
+                .map(|count| log_count("block production trace", count)),
+            EventKind::RejectedBlockHeader(_) => self
+                .rejected_block_header_tx
+                .send(kind)
+                .map(|count| log_count("rejected block header", count)),
             EventKind::ProposerSlashing(_) => self
                 .proposer_slashing_tx
                 .send(kind)
@@ -212,6 +253,14 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
         self.block_reward_tx.subscribe()
     }
 
+    pub fn subscribe_block_production_trace(&self) -> Receiver<EventKind<E>> {
+        self.block_production_trace_tx.subscribe()
+    }
+
+    pub fn subscribe_rejected_block_header(&self) -> Receiver<EventKind<E>> {
+        self.rejected_block_header_tx.subscribe()
+    }
+
     pub fn subscribe_attester_slashing(&self) -> Receiver<EventKind<E>> {
         self.attester_slashing_tx.subscribe()
     }
@@ -229,62 +278,77 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
     }
 
     pub fn has_attestation_subscribers(&self) -> bool {
-        self.attestation_tx.receiver_count() > 0
+        self.attestation_tx.receiver_count() > 0 || self.is_logged(EventTopic::Attestation)
     }
 
     pub fn has_block_subscribers(&self) -> bool {
-        self.block_tx.receiver_count() > 0
+        self.block_tx.receiver_count() > 0 || self.is_logged(EventTopic::Block)
     }
 
     pub fn has_blob_sidecar_subscribers(&self) -> bool {
-        self.blob_sidecar_tx.receiver_count() > 0
+        self.blob_sidecar_tx.receiver_count() > 0 || self.is_logged(EventTopic::BlobSidecar)
     }
 
     pub fn has_finalized_subscribers(&self) -> bool {
-        self.finalized_tx.receiver_count() > 0
+        self.finalized_tx.receiver_count() > 0 || self.is_logged(EventTopic::FinalizedCheckpoint)
     }
 
     pub fn has_head_subscribers(&self) -> bool {
-        self.head_tx.receiver_count() > 0
+        self.head_tx.receiver_count() > 0 || self.is_logged(EventTopic::Head)
     }
 
     pub fn has_exit_subscribers(&self) -> bool {
-        self.exit_tx.receiver_count() > 0
+        self.exit_tx.receiver_count() > 0 || self.is_logged(EventTopic::VoluntaryExit)
     }
 
     pub fn has_reorg_subscribers(&self) -> bool {
-        self.chain_reorg_tx.receiver_count() > 0
+        self.chain_reorg_tx.receiver_count() > 0 || self.is_logged(EventTopic::ChainReorg)
     }
 
     pub fn has_contribution_subscribers(&self) -> bool {
         self.contribution_tx.receiver_count() > 0
+            || self.is_logged(EventTopic::ContributionAndProof)
     }
 
     pub fn has_payload_attributes_subscribers(&self) -> bool {
         self.payload_attributes_tx.receiver_count() > 0
+            || self.is_logged(EventTopic::PayloadAttributes)
     }
 
     pub fn has_late_head_subscribers(&self) -> bool {
-        self.late_head.receiver_count() > 0
+        self.late_head.receiver_count() > 0 || self.is_logged(EventTopic::LateHead)
     }
 
     pub fn has_block_reward_subscribers(&self) -> bool {
-        self.block_reward_tx.receiver_count() > 0
+        self.block_reward_tx.receiver_count() > 0 || self.is_logged(EventTopic::BlockReward)
+    }
+
+    pub fn has_block_production_trace_subscribers(&self) -> bool {
+        self.block_production_trace_tx.receiver_count() > 0
+            || self.is_logged(EventTopic::BlockProductionTrace)
+    }
+
+    pub fn has_rejected_block_header_subscribers(&self) -> bool {
+        self.rejected_block_header_tx.receiver_count() > 0
+            || self.is_logged(EventTopic::RejectedBlockHeader)
     }
 
     pub fn has_proposer_slashing_subscribers(&self) -> bool {
         self.proposer_slashing_tx.receiver_count() > 0
+            || self.is_logged(EventTopic::ProposerSlashing)
     }
 
     pub fn has_attester_slashing_subscribers(&self) -> bool {
         self.attester_slashing_tx.receiver_count() > 0
+            || self.is_logged(EventTopic::AttesterSlashing)
     }
 
     pub fn has_bls_to_execution_change_subscribers(&self) -> bool {
         self.bls_to_execution_change_tx.receiver_count() > 0
+            || self.is_logged(EventTopic::BlsToExecutionChange)
     }
 
     pub fn has_block_gossip_subscribers(&self) -> bool {
-        self.block_gossip_tx.receiver_count() > 0
+        self.block_gossip_tx.receiver_count() > 0 || self.is_logged(EventTopic::BlockGossip)
     }
 }
@@ -7,11 +7,13 @@
 //! So, this module contains functions that one might expect to find in other crates, but they live
 //! here for good reason.
 
+use crate::block_production_timings::duration_ms;
 use crate::otb_verification_service::OptimisticTransitionBlock;
 use crate::{
     BeaconChain, BeaconChainError, BeaconChainTypes, BlockError, BlockProductionError,
     ExecutionPayloadError,
 };
+use eth2::lighthouse::BuilderCall;
 use execution_layer::{
     BlockProposalContents, BlockProposalContentsType, BuilderParams, NewPayloadRequest,
     PayloadAttributes, PayloadStatus,
@@ -25,6 +27,7 @@ use state_processing::per_block_processing::{
     is_merge_transition_complete, partially_verify_execution_payload,
 };
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::task::JoinHandle;
 use tree_hash::TreeHash;
 use types::payload::BlockProductionVersion;
@@ -560,6 +563,8 @@ where
         parent_beacon_block_root,
     );
 
+    let slot = builder_params.slot;
+    let get_payload_start = Instant::now();
     let block_contents = execution_layer
         .get_payload(
             parent_hash,
@@ -573,6 +578,28 @@ where
         )
         .await
         .map_err(BlockProductionError::GetPayloadFailed)?;
+    let get_payload_duration = get_payload_start.elapsed();
+
+    // If the builder was contacted the EL records the duration of each request, otherwise the
+    // whole call was spent waiting for the local EL.
+    let request_timings = execution_layer.take_payload_request_timings(slot).await;
+    let mut block_production_timings = chain.block_production_timings.write();
+    if let Some(timings) = request_timings {
+        block_production_timings.update(slot, |trace| {
+            trace.local_payload_ms = Some(duration_ms(timings.local))
+        });
+        block_production_timings.add_builder_call(
+            slot,
+            BuilderCall::GetHeader,
+            timings.builder_header,
+            timings.builder_header_success,
+        );
+    } else {
+        block_production_timings.update(slot, |trace| {
+            trace.local_payload_ms = Some(duration_ms(get_payload_duration))
+        });
+    }
+    drop(block_production_timings);
 
     Ok(block_contents)
 }
@@ -0,0 +1,133 @@
+//! Provides the `ForkChoiceHistory` ring buffer, which records recent changes to the head and FFG
+//! checkpoints of fork choice.
+//!
+//! The `/lighthouse/proto_array` endpoint only shows the current block tree, this history allows
+//! a head change or re-org to be inspected after the fact, with the weights that fork choice used
+//! to make its decision.
+
+use eth2::lighthouse::{ForkChoiceHistoryEntry, ForkChoiceUpdate};
+use std::collections::VecDeque;
+use std::time::Duration;
+use types::{Hash256, Slot};
+
+/// The number of entries which are retained.
+pub const DEFAULT_CAPACITY: usize = 1024;
+/// The number of proposer re-org attempts which are retained.
+const MAX_PROPOSER_RE_ORGS: usize = 8;
+
+/// A ring buffer of fork choice updates, in the order in which they occurred.
+///
+/// Once `capacity` entries are held, adding a new entry evicts the oldest.
+pub struct ForkChoiceHistory {
+    entries: VecDeque<ForkChoiceHistoryEntry>,
+    capacity: usize,
+    /// The `(slot, weak_head)` pairs for which this node proposed a block which re-orgs the
+    /// weak head.
+    proposer_re_orgs: VecDeque<(Slot, Hash256)>,
+}
+
+impl Default for ForkChoiceHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl ForkChoiceHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            proposer_re_orgs: VecDeque::with_capacity(MAX_PROPOSER_RE_ORGS),
+        }
+    }
+
+    /// Record an `update` made by fork choice at `slot`, at `timestamp` (since the UNIX epoch).
+    pub fn push(&mut self, slot: Slot, timestamp: Duration, update: ForkChoiceUpdate) {
+        while self.entries.len() >= self.capacity.max(1) {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(ForkChoiceHistoryEntry {
+            slot,
+            timestamp_ms: timestamp.as_millis() as u64,
+            update,
+        });
+    }
+
+    /// Record that this node produced a block at `slot` which does not build on `weak_head`.
+    pub fn register_proposer_re_org(&mut self, slot: Slot, weak_head: Hash256) {
+        if self.proposer_re_orgs.len() >= MAX_PROPOSER_RE_ORGS {
+            self.proposer_re_orgs.pop_front();
+        }
+        self.proposer_re_orgs.push_back((slot, weak_head));
+    }
+
+    /// Returns `true` if a re-org of `old_head` by a block at `slot` was caused by this node's
+    /// proposal.
+    pub fn is_proposer_re_org(&self, slot: Slot, old_head: Hash256) -> bool {
+        self.proposer_re_orgs.contains(&(slot, old_head))
+    }
+
+    /// Returns the entries recorded between `start_slot` and `end_slot` (inclusive), oldest first.
+    pub fn get(
+        &self,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> Vec<ForkChoiceHistoryEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| start_slot.map_or(true, |start| entry.slot >= start))
+            .filter(|entry| end_slot.map_or(true, |end| entry.slot <= end))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2::lighthouse::CheckpointUpdate;
+    use types::{Checkpoint, Epoch};
+
+    fn justification(epoch: u64) -> ForkChoiceUpdate {
+        ForkChoiceUpdate::Justification(CheckpointUpdate {
+            old: Checkpoint::default(),
+            new: Checkpoint {
+                epoch: Epoch::new(epoch),
+                root: Hash256::repeat_byte(epoch as u8),
+            },
+        })
+    }
+
+    #[test]
+    fn oldest_entry_is_evicted() {
+        let mut history = ForkChoiceHistory::new(2);
+        for slot in 0..3 {
+            history.push(
+                Slot::new(slot),
+                Duration::from_secs(slot),
+                justification(slot),
+            );
+        }
+
+        let entries = history.get(None, None);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].slot, Slot::new(1));
+        assert_eq!(entries[1].timestamp_ms, 2_000);
+
+        let entries = history.get(Some(Slot::new(2)), None);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].update, justification(2));
+        assert!(history.get(None, Some(Slot::new(0))).is_empty());
+    }
+
+    #[test]
+    fn proposer_re_orgs() {
+        let mut history = ForkChoiceHistory::default();
+        let weak_head = Hash256::repeat_byte(1);
+        history.register_proposer_re_org(Slot::new(2), weak_head);
+
+        assert!(history.is_proposer_re_org(Slot::new(2), weak_head));
+        assert!(!history.is_proposer_re_org(Slot::new(3), weak_head));
+        assert!(!history.is_proposer_re_org(Slot::new(2), Hash256::zero()));
+    }
+}
@@ -0,0 +1,121 @@
+//! Records the inputs to fork choice to disk, so that they can be replayed offline with
+//! `lcli fork-choice-replay`.
+//!
+//! Each time the beacon node starts, a new directory is created within the recording directory,
+//! containing:
+//!
+//! - `fork_choice.ssz`: the `PersistedForkChoice` at the time recording started.
+//! - `inputs.jsonl`: the `ForkChoiceInput`s applied since, one JSON object per line.
+
+use crate::beacon_chain::{BeaconChainTypes, BeaconForkChoice};
+use crate::persisted_fork_choice::PersistedForkChoice;
+use fork_choice::{ForkChoiceInput, ForkChoiceRecorder, ForkChoiceStore};
+use slog::{error, info, Logger};
+use ssz::Encode;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The name of the file containing the fork choice at the start of the recording.
+pub const SNAPSHOT_FILENAME: &str = "fork_choice.ssz";
+/// The name of the file containing the recorded inputs.
+pub const INPUTS_FILENAME: &str = "inputs.jsonl";
+
+/// Writes each `ForkChoiceInput` to a file as a line of JSON.
+///
+/// Recording stops at the first error, so that a partial line is never followed by further
+/// inputs.
+pub struct FileRecorder {
+    writer: Option<BufWriter<File>>,
+    log: Logger,
+}
+
+impl ForkChoiceRecorder for FileRecorder {
+    fn record(&mut self, input: ForkChoiceInput) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+
+        // Flush after each head computation so that the recording is usable if the node crashes.
+        let flush = matches!(input, ForkChoiceInput::FindHead { .. });
+        let result = serde_json::to_writer(&mut *writer, &input)
+            .map_err(|e| format!("{:?}", e))
+            .and_then(|()| writeln!(writer).map_err(|e| format!("{:?}", e)))
+            .and_then(|()| {
+                if flush {
+                    writer.flush().map_err(|e| format!("{:?}", e))
+                } else {
+                    Ok(())
+                }
+            });
+
+        if let Err(e) = result {
+            error!(
+                self.log,
+                "Stopped recording fork choice";
+                "reason" => "failed to write input",
+                "error" => e,
+            );
+            self.writer = None;
+        }
+    }
+}
+
+/// Start recording the inputs to `fork_choice` in a new directory within `dir`.
+///
+/// Returns the path to the new directory.
+pub fn start_recording<T: BeaconChainTypes>(
+    dir: &Path,
+    fork_choice: &mut BeaconForkChoice<T>,
+    log: &Logger,
+) -> Result<PathBuf, String> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("Unable to read system time: {:?}", e))?
+        .as_secs();
+    let recording_dir = dir.join(timestamp.to_string());
+    fs::create_dir_all(&recording_dir).map_err(|e| {
+        format!(
+            "Unable to create fork choice recording directory {}: {:?}",
+            recording_dir.display(),
+            e
+        )
+    })?;
+
+    let snapshot = PersistedForkChoice {
+        fork_choice: fork_choice.to_persisted(),
+        fork_choice_store: fork_choice.fc_store().to_persisted(),
+    };
+    let snapshot_path = recording_dir.join(SNAPSHOT_FILENAME);
+    fs::write(&snapshot_path, snapshot.as_ssz_bytes()).map_err(|e| {
+        format!(
+            "Unable to write fork choice snapshot {}: {:?}",
+            snapshot_path.display(),
+            e
+        )
+    })?;
+
+    let inputs_path = recording_dir.join(INPUTS_FILENAME);
+    let inputs = File::create(&inputs_path).map_err(|e| {
+        format!(
+            "Unable to create fork choice inputs file {}: {:?}",
+            inputs_path.display(),
+            e
+        )
+    })?;
+
+    fork_choice.set_recorder(Box::new(FileRecorder {
+        writer: Some(BufWriter::new(inputs)),
+        log: log.clone(),
+    }));
+
+    info!(
+        log,
+        "Recording fork choice inputs";
+        "dir" => %recording_dir.display(),
+        "current_slot" => fork_choice.fc_store().get_current_slot(),
+    );
+
+    Ok(recording_dir)
+}
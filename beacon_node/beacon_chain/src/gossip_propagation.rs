@@ -0,0 +1,406 @@
+//! Provides the `GossipPropagation` struct which records when blocks and aggregates were received
+//! from each of our peers.
+//!
+//! Recording is only enabled with `--gossip-propagation-analysis`. The first receipt of a message
+//! is recorded once it has been accepted by gossip verification, and each subsequent receipt is
+//! reported as a duplicate by gossipsub. Duplicates which arrive before their message has been
+//! accepted are held as pending until it is. Peers which were sent an `IDONTWANT` for a message
+//! will not forward it to us, so the recorded peers are a lower bound on the peers which had the
+//! message.
+
+use eth2::lighthouse::{
+    AggregatePropagation, BlockPropagation, PeerArrival, PropagationAnalysis,
+    PropagationPercentiles,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+use std::time::Duration;
+use types::{EthSpec, Hash256, Slot};
+
+/// The number of epochs for which block records are retained.
+pub const BLOCK_RETENTION_EPOCHS: u64 = 2;
+/// The number of epochs for which aggregate records are retained.
+pub const AGGREGATE_RETENTION_EPOCHS: u64 = 1;
+/// The maximum number of duplicates recorded for each aggregate.
+pub const MAX_AGGREGATE_DUPLICATES: usize = 64;
+/// The maximum number of blocks recorded for each slot.
+pub const MAX_BLOCKS_PER_SLOT: usize = 4;
+/// The maximum number of aggregates recorded for each slot.
+pub const MAX_AGGREGATES_PER_SLOT: usize = 2_048;
+/// The maximum number of messages for which duplicates are held until the message is accepted.
+pub const MAX_PENDING_DUPLICATES: usize = 4_096;
+
+/// The gossipsub message id of a message.
+type MessageId = Vec<u8>;
+
+struct BlockRecord {
+    slot: Slot,
+    block_root: Hash256,
+    /// The peers from which the block was received and when, in order of arrival.
+    sightings: Vec<(String, Duration)>,
+}
+
+struct AggregateRecord {
+    slot: Slot,
+    first_seen: Duration,
+    duplicates: Vec<Duration>,
+}
+
+/// Duplicates of a message which has not yet been accepted.
+struct PendingDuplicates {
+    /// The slot of the last prune when the first duplicate was received.
+    pruned_at: Slot,
+    sightings: Vec<(String, Duration)>,
+}
+
+/// Records the times at which blocks and aggregates were received from each peer.
+///
+/// All times are durations since the UNIX epoch.
+///
+/// The cache does not automatically prune, you must call `Self::prune` manually.
+pub struct GossipPropagation<E: EthSpec> {
+    blocks: HashMap<MessageId, BlockRecord>,
+    aggregates: HashMap<MessageId, AggregateRecord>,
+    blocks_per_slot: HashMap<Slot, usize>,
+    aggregates_per_slot: HashMap<Slot, usize>,
+    pending: HashMap<MessageId, PendingDuplicates>,
+    last_pruned_slot: Slot,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> Default for GossipPropagation<E> {
+    fn default() -> Self {
+        Self {
+            blocks: HashMap::new(),
+            aggregates: HashMap::new(),
+            blocks_per_slot: HashMap::new(),
+            aggregates_per_slot: HashMap::new(),
+            pending: HashMap::new(),
+            last_pruned_slot: Slot::new(0),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<E: EthSpec> GossipPropagation<E> {
+    /// Record the first receipt of the block with `block_root` at `slot`, once it has been
+    /// accepted.
+    ///
+    /// Blocks beyond `MAX_BLOCKS_PER_SLOT` for the same slot are ignored.
+    pub fn observe_block(
+        &mut self,
+        message_id: &[u8],
+        slot: Slot,
+        block_root: Hash256,
+        peer_id: String,
+        seen: Duration,
+    ) {
+        let pending = self.pending.remove(message_id);
+        if self.blocks.contains_key(message_id) {
+            return;
+        }
+        let count = self.blocks_per_slot.entry(slot).or_default();
+        if *count >= MAX_BLOCKS_PER_SLOT {
+            return;
+        }
+        *count += 1;
+
+        let mut record = BlockRecord {
+            slot,
+            block_root,
+            sightings: vec![(peer_id, seen)],
+        };
+        for (peer_id, seen) in pending.into_iter().flat_map(|pending| pending.sightings) {
+            record.observe_duplicate(peer_id, seen);
+        }
+        self.blocks.insert(message_id.to_vec(), record);
+    }
+
+    /// Record the first receipt of an aggregate at `slot`, once it has been accepted.
+    ///
+    /// Aggregates beyond `MAX_AGGREGATES_PER_SLOT` for the same slot are ignored.
+    pub fn observe_aggregate(&mut self, message_id: &[u8], slot: Slot, seen: Duration) {
+        let pending = self.pending.remove(message_id);
+        if self.aggregates.contains_key(message_id) {
+            return;
+        }
+        let count = self.aggregates_per_slot.entry(slot).or_default();
+        if *count >= MAX_AGGREGATES_PER_SLOT {
+            return;
+        }
+        *count += 1;
+
+        let mut record = AggregateRecord {
+            slot,
+            first_seen: seen,
+            duplicates: vec![],
+        };
+        for (_, seen) in pending.into_iter().flat_map(|pending| pending.sightings) {
+            record.observe_duplicate(seen);
+        }
+        self.aggregates.insert(message_id.to_vec(), record);
+    }
+
+    /// Record a subsequent receipt of a block or aggregate from `peer_id`.
+    ///
+    /// Duplicates of messages which have not been accepted are held until the message is
+    /// accepted, or until they are pruned.
+    pub fn observe_duplicate(&mut self, message_id: &[u8], peer_id: String, seen: Duration) {
+        if let Some(record) = self.blocks.get_mut(message_id) {
+            record.observe_duplicate(peer_id, seen);
+        } else if let Some(record) = self.aggregates.get_mut(message_id) {
+            record.observe_duplicate(seen);
+        } else if let Some(pending) = self.pending.get_mut(message_id) {
+            if pending.sightings.len() < MAX_AGGREGATE_DUPLICATES {
+                pending.sightings.push((peer_id, seen));
+            }
+        } else if self.pending.len() < MAX_PENDING_DUPLICATES {
+            self.pending.insert(
+                message_id.to_vec(),
+                PendingDuplicates {
+                    pruned_at: self.last_pruned_slot,
+                    sightings: vec![(peer_id, seen)],
+                },
+            );
+        }
+    }
+
+    /// Record a batch of duplicates, see `Self::observe_duplicate`.
+    pub fn observe_duplicates(
+        &mut self,
+        duplicates: impl IntoIterator<Item = (MessageId, String, Duration)>,
+    ) {
+        for (message_id, peer_id, seen) in duplicates {
+            self.observe_duplicate(&message_id, peer_id, seen);
+        }
+    }
+
+    /// Analyse the propagation of the blocks and aggregates from `start_slot` to `end_slot`
+    /// (inclusive), using `slot_start` to determine the start of each slot.
+    pub fn analyse(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        slot_start: impl Fn(Slot) -> Option<Duration>,
+    ) -> PropagationAnalysis {
+        let in_range = |slot: Slot| slot >= start_slot && slot <= end_slot;
+        let delay_ms = |from: Duration, to: Duration| {
+            to.checked_sub(from).unwrap_or_default().as_millis() as u64
+        };
+
+        let mut blocks = self
+            .blocks
+            .values()
+            .filter(|record| in_range(record.slot))
+            .filter_map(|record| {
+                let (_, first_seen) = record.sightings.first()?;
+                let peers = record
+                    .sightings
+                    .iter()
+                    .map(|(peer_id, seen)| PeerArrival {
+                        peer_id: peer_id.clone(),
+                        delay_ms: delay_ms(*first_seen, *seen),
+                    })
+                    .collect::<Vec<_>>();
+                let mut delays = peers.iter().map(|peer| peer.delay_ms).collect::<Vec<_>>();
+                Some(BlockPropagation {
+                    slot: record.slot,
+                    block_root: record.block_root,
+                    first_seen_delay_ms: slot_start(record.slot)
+                        .map_or(0, |start| delay_ms(start, *first_seen)),
+                    peers,
+                    arrival: PropagationPercentiles::from_delays(&mut delays),
+                })
+            })
+            .collect::<Vec<_>>();
+        blocks.sort_by_key(|block| (block.slot, block.first_seen_delay_ms));
+
+        let mut aggregates_by_slot = BTreeMap::<Slot, (usize, Vec<u64>, Vec<u64>)>::new();
+        for record in self
+            .aggregates
+            .values()
+            .filter(|record| in_range(record.slot))
+        {
+            let (count, first_seen, arrival) = aggregates_by_slot.entry(record.slot).or_default();
+            *count += 1;
+            if let Some(start) = slot_start(record.slot) {
+                first_seen.push(delay_ms(start, record.first_seen));
+            }
+            arrival.push(0);
+            arrival.extend(
+                record
+                    .duplicates
+                    .iter()
+                    .map(|seen| delay_ms(record.first_seen, *seen)),
+            );
+        }
+        let aggregates = aggregates_by_slot
+            .into_iter()
+            .map(
+                |(slot, (count, mut first_seen, mut arrival))| AggregatePropagation {
+                    slot,
+                    aggregates: count,
+                    first_seen: PropagationPercentiles::from_delays(&mut first_seen),
+                    arrival: PropagationPercentiles::from_delays(&mut arrival),
+                },
+            )
+            .collect();
+
+        PropagationAnalysis { blocks, aggregates }
+    }
+
+    /// Remove records which are older than the retention period, along with any records from
+    /// future slots.
+    ///
+    /// Pending duplicates are removed once they have been held since before the previous slot.
+    pub fn prune(&mut self, current_slot: Slot) {
+        let slots_per_epoch = E::slots_per_epoch();
+        let future_cutoff = current_slot + 1;
+
+        let block_cutoff = current_slot.saturating_sub(BLOCK_RETENTION_EPOCHS * slots_per_epoch);
+        self.blocks
+            .retain(|_, record| record.slot >= block_cutoff && record.slot <= future_cutoff);
+
+        let aggregate_cutoff =
+            current_slot.saturating_sub(AGGREGATE_RETENTION_EPOCHS * slots_per_epoch);
+        self.aggregates
+            .retain(|_, record| record.slot >= aggregate_cutoff && record.slot <= future_cutoff);
+
+        self.blocks_per_slot
+            .retain(|slot, _| *slot >= block_cutoff && *slot <= future_cutoff);
+        self.aggregates_per_slot
+            .retain(|slot, _| *slot >= aggregate_cutoff && *slot <= future_cutoff);
+
+        let pending_cutoff = current_slot.saturating_sub(1_u64);
+        self.pending
+            .retain(|_, pending| pending.pruned_at >= pending_cutoff);
+        self.last_pruned_slot = current_slot;
+    }
+}
+
+impl BlockRecord {
+    /// Record the first receipt of the block from `peer_id`.
+    fn observe_duplicate(&mut self, peer_id: String, seen: Duration) {
+        if !self.sightings.iter().any(|(peer, _)| *peer == peer_id) {
+            self.sightings.push((peer_id, seen));
+        }
+    }
+}
+
+impl AggregateRecord {
+    /// Record a subsequent receipt of the aggregate, up to `MAX_AGGREGATE_DUPLICATES`.
+    fn observe_duplicate(&mut self, seen: Duration) {
+        if self.duplicates.len() < MAX_AGGREGATE_DUPLICATES {
+            self.duplicates.push(seen);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn block_propagation() {
+        let mut cache = GossipPropagation::<E>::default();
+        let slot = Slot::new(1);
+        // The duplicate from "b" arrives while the block is still being verified.
+        cache.observe_duplicate(&[1], "b".into(), ms(12_600));
+        cache.observe_block(&[1], slot, Hash256::repeat_byte(1), "a".into(), ms(12_500));
+        cache.observe_duplicate(&[1], "b".into(), ms(12_700));
+        cache.observe_duplicate(&[1], "c".into(), ms(13_500));
+
+        let analysis = cache.analyse(slot, slot, |slot| Some(ms(slot.as_u64() * 12_000)));
+        assert_eq!(analysis.blocks.len(), 1);
+        let block = &analysis.blocks[0];
+        assert_eq!(block.block_root, Hash256::repeat_byte(1));
+        assert_eq!(block.first_seen_delay_ms, 500);
+        assert_eq!(
+            block
+                .peers
+                .iter()
+                .map(|peer| (peer.peer_id.as_str(), peer.delay_ms))
+                .collect::<Vec<_>>(),
+            vec![("a", 0), ("b", 100), ("c", 1_000)]
+        );
+        assert_eq!(block.arrival.p50_ms, 100);
+        assert_eq!(block.arrival.max_ms, 1_000);
+    }
+
+    #[test]
+    fn aggregate_propagation_and_pruning() {
+        let mut cache = GossipPropagation::<E>::default();
+        cache.observe_aggregate(&[1], Slot::new(1), ms(16_000));
+        cache.observe_aggregate(&[2], Slot::new(1), ms(17_000));
+        cache.observe_duplicate(&[2], "a".into(), ms(17_200));
+        cache.observe_aggregate(&[3], Slot::new(2), ms(28_000));
+
+        let analysis = cache.analyse(Slot::new(1), Slot::new(1), |slot| {
+            Some(ms(slot.as_u64() * 12_000))
+        });
+        assert_eq!(analysis.aggregates.len(), 1);
+        let aggregates = &analysis.aggregates[0];
+        assert_eq!(aggregates.aggregates, 2);
+        assert_eq!(aggregates.first_seen.p50_ms, 4_000);
+        assert_eq!(aggregates.first_seen.max_ms, 5_000);
+        assert_eq!(aggregates.arrival.count, 3);
+        assert_eq!(aggregates.arrival.max_ms, 200);
+
+        cache.prune(Slot::new(2 + E::slots_per_epoch()));
+        let analysis = cache.analyse(Slot::new(0), Slot::new(2), |_| None);
+        assert_eq!(analysis.aggregates.len(), 1);
+        assert_eq!(analysis.aggregates[0].slot, Slot::new(2));
+    }
+
+    #[test]
+    fn records_per_slot_are_capped() {
+        let mut cache = GossipPropagation::<E>::default();
+        let slot = Slot::new(1);
+        for i in 0..MAX_BLOCKS_PER_SLOT + 1 {
+            cache.observe_block(
+                &[i as u8],
+                slot,
+                Hash256::repeat_byte(i as u8),
+                "a".into(),
+                ms(12_000),
+            );
+        }
+        for i in 0..MAX_AGGREGATES_PER_SLOT + 1 {
+            cache.observe_aggregate(&(i as u64).to_le_bytes(), slot, ms(16_000));
+        }
+
+        let analysis = cache.analyse(slot, slot, |_| None);
+        assert_eq!(analysis.blocks.len(), MAX_BLOCKS_PER_SLOT);
+        assert_eq!(analysis.aggregates[0].aggregates, MAX_AGGREGATES_PER_SLOT);
+
+        // The counts are removed along with the records.
+        cache.prune(slot + BLOCK_RETENTION_EPOCHS * E::slots_per_epoch() + 1);
+        assert!(cache.blocks_per_slot.is_empty());
+        assert!(cache.aggregates_per_slot.is_empty());
+    }
+
+    #[test]
+    fn pending_duplicates_are_pruned() {
+        let mut cache = GossipPropagation::<E>::default();
+        cache.prune(Slot::new(1));
+        cache.observe_duplicate(&[1], "b".into(), ms(12_600));
+        cache.observe_duplicate(&[2], "b".into(), ms(12_600));
+
+        // Duplicates are held across the next slot.
+        cache.prune(Slot::new(2));
+        cache.observe_aggregate(&[1], Slot::new(1), ms(12_500));
+        let analysis = cache.analyse(Slot::new(1), Slot::new(1), |_| None);
+        assert_eq!(analysis.aggregates[0].arrival.count, 2);
+
+        // Duplicates which are never accepted are dropped after that.
+        cache.prune(Slot::new(3));
+        assert!(cache.pending.is_empty());
+    }
+}
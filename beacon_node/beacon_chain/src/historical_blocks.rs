@@ -119,13 +119,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
             let blinded_block = block.clone_as_blinded();
             // Store block in the hot database without payload.
-            self.store
-                .blinded_block_as_kv_store_ops(&block_root, &blinded_block, &mut hot_batch);
+            self.store.blinded_block_as_kv_store_ops(
+                &block_root,
+                &blinded_block,
+                &mut hot_batch,
+            )?;
             // Store the blobs too
             if let Some(blobs) = maybe_blobs {
                 new_oldest_blob_slot = Some(block.slot());
                 self.store
-                    .blobs_as_kv_store_ops(&block_root, blobs, &mut blob_batch);
+                    .blobs_as_kv_store_ops(&block_root, blobs, &mut blob_batch)?;
             }
 
             // Store block roots, including at all skip slots in the freezer DB.
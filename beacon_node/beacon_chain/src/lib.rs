@@ -10,6 +10,7 @@ pub mod beacon_proposer_cache;
 mod beacon_snapshot;
 pub mod bellatrix_readiness;
 pub mod blob_verification;
+pub mod block_production_timings;
 pub mod block_reward;
 mod block_times_cache;
 mod block_verification;
@@ -26,10 +27,14 @@ pub mod electra_readiness;
 mod errors;
 pub mod eth1_chain;
 mod eth1_finalization_cache;
+pub mod event_log;
 pub mod events;
 pub mod execution_payload;
+pub mod fork_choice_history;
+pub mod fork_choice_recorder;
 pub mod fork_choice_signal;
 pub mod fork_revert;
+pub mod gossip_propagation;
 pub mod graffiti_calculator;
 mod head_tracker;
 pub mod historical_blocks;
@@ -46,9 +51,10 @@ mod observed_blob_sidecars;
 pub mod observed_block_producers;
 pub mod observed_operations;
 mod observed_slashable;
+pub mod op_pool_sync;
 pub mod otb_verification_service;
 mod persisted_beacon_chain;
-mod persisted_fork_choice;
+pub mod persisted_fork_choice;
 mod pre_finalization_cache;
 pub mod proposer_prep_service;
 pub mod schema_change;
@@ -58,7 +64,9 @@ pub mod sync_committee_rewards;
 pub mod sync_committee_verification;
 pub mod test_utils;
 pub mod validator_monitor;
+pub mod validator_monitor_history;
 pub mod validator_pubkey_cache;
+pub mod validator_sightings;
 
 pub use self::beacon_chain::{
     AttestationProcessingOutcome, AvailabilityProcessingStatus, BeaconBlockResponse,
@@ -478,6 +478,35 @@ lazy_static! {
     pub static ref OP_POOL_NUM_SYNC_CONTRIBUTIONS: Result<IntGauge> =
         try_create_int_gauge("beacon_op_pool_sync_contributions_total", "Count of sync contributions in the op pool");
 
+    /*
+     * Operation Pool Sync
+     */
+    pub static ref OP_POOL_SYNC_IMPORTED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_op_pool_sync_imported_total",
+        "Count of operations pulled from peer beacon nodes which were new to the op pool",
+        &["operation"]
+    );
+    pub static ref OP_POOL_SYNC_PUSHED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_op_pool_sync_pushed_total",
+        "Count of operations pushed to peer beacon nodes",
+        &["operation"]
+    );
+    pub static ref OP_POOL_SYNC_FAILURES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_op_pool_sync_failures_total",
+        "Count of failed attempts to pull operations from or push operations to a peer beacon node",
+        &["step"]
+    );
+    pub static ref OP_POOL_SYNC_TIMES: Result<HistogramVec> = try_create_histogram_vec(
+        "beacon_op_pool_sync_seconds",
+        "Time taken to pull operations from a peer beacon node, import them, or push operations to it",
+        &["step"]
+    );
+    pub static ref OP_POOL_SYNC_PULLED_ATTESTATIONS: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_op_pool_sync_pulled_attestations",
+        "Number of aggregate attestations pulled from a peer beacon node",
+        exponential_buckets(1.0, 2.0, 12)
+    );
+
 
     /*
      * Attestation Observation Metrics
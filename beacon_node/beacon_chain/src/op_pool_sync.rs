@@ -0,0 +1,780 @@
+//! Shares the contents of the operation pool with other beacon nodes run by the same operator.
+//!
+//! Once per slot, after the aggregates for the slot have been published, each configured peer is
+//! polled over the standard `beacon/pool` HTTP endpoints. Operations pulled from a peer are
+//! verified and deduplicated against the same observation caches as gossip before they are added
+//! to the op pool. Slashings, exits and BLS to execution changes in our own pool are pushed to the
+//! peer.
+//!
+//! Aggregate attestations are only pulled, since the HTTP API only accepts unaggregated
+//! attestations. For a beacon node to receive the aggregates seen by another, it must list the
+//! other as a peer.
+//!
+//! On mainnet each slot has up to 64 committees, and a peer's pool holds one or more aggregates
+//! for each, so a pull returns a few hundred aggregates. Their signatures are verified in a single
+//! batch, which is much cheaper than verifying them one at a time, and the verification runs
+//! alongside the push rather than in front of it. Only the HTTP requests are bound by the time
+//! left in the slot. The `beacon_op_pool_sync_seconds` and
+//! `beacon_op_pool_sync_pulled_attestations` metrics record how long each step takes and how
+//! many aggregates are pulled.
+use crate::attestation_verification::{
+    obtain_indexed_attestation_and_committees_per_slot, verify_attestation_signature,
+    Error as AttestationError, VerifiedAttestation,
+};
+use crate::metrics;
+use crate::observed_aggregates::{ObserveOutcome, ObservedAttestationKey};
+use crate::observed_operations::ObservationOutcome;
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use bls::verify_signature_sets;
+use eth2::{BeaconNodeHttpClient, Error as HttpError, Timeouts};
+use futures::future::{join, join_all};
+use futures::{try_join, TryFutureExt};
+use operation_pool::ReceivedPreCapella;
+use sensitive_url::SensitiveUrl;
+use serde::{Deserialize, Serialize};
+use slog::{debug, error, warn};
+use slot_clock::SlotClock;
+use state_processing::signature_sets::indexed_attestation_signature_set_from_pubkeys;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use task_executor::TaskExecutor;
+use tokio::time::{sleep, timeout_at, Instant};
+use tree_hash::TreeHash;
+use types::{
+    Attestation, AttestationRef, AttesterSlashing, EthSpec, Hash256, IndexedAttestation,
+    ProposerSlashing, SignedBlsToExecutionChange, SignedVoluntaryExit, Slot,
+};
+
+/// Sync at 5/6ths of the way through each slot (10s on mainnet), after aggregates have been
+/// published at 2/3rds of the slot and before the next proposal.
+const SYNC_DELAY_NUMERATOR: u32 = 5;
+const SYNC_DELAY_DENOMINATOR: u32 = 6;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Config {
+    /// The HTTP API endpoints of the peer beacon nodes.
+    pub peers: Vec<SensitiveUrl>,
+    /// A file containing the API key to send to the peers.
+    pub api_key_file: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Http(HttpError),
+    BeaconChain(BeaconChainError),
+    /// The pull from or push to the peer didn't complete before the next slot.
+    Timeout,
+}
+
+impl From<HttpError> for Error {
+    fn from(e: HttpError) -> Self {
+        Error::Http(e)
+    }
+}
+
+impl From<BeaconChainError> for Error {
+    fn from(e: BeaconChainError) -> Self {
+        Error::BeaconChain(e)
+    }
+}
+
+/// A peer beacon node, and the operations it is known to have.
+pub struct Peer {
+    client: BeaconNodeHttpClient,
+    /// The roots of the slashings, exits and BLS to execution changes which the peer has sent us
+    /// or which we have pushed to it.
+    known_operations: HashSet<Hash256>,
+}
+
+impl Peer {
+    pub fn new(client: BeaconNodeHttpClient) -> Self {
+        Self {
+            client,
+            known_operations: HashSet::new(),
+        }
+    }
+
+    /// Returns `true` if the peer has sent us the operation with `root`, or if it has been pushed
+    /// to the peer (whether or not the peer accepted it).
+    pub fn knows_operation(&self, root: &Hash256) -> bool {
+        self.known_operations.contains(root)
+    }
+}
+
+/// The operations pulled from a peer.
+struct PeerOperations<E: EthSpec> {
+    attestations: Vec<Attestation<E>>,
+    attester_slashings: Vec<AttesterSlashing<E>>,
+    proposer_slashings: Vec<ProposerSlashing>,
+    voluntary_exits: Vec<SignedVoluntaryExit>,
+    bls_to_execution_changes: Vec<SignedBlsToExecutionChange>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct OperationCounts {
+    pub attestations: usize,
+    pub attester_slashings: usize,
+    pub proposer_slashings: usize,
+    pub voluntary_exits: usize,
+    pub bls_to_execution_changes: usize,
+}
+
+impl OperationCounts {
+    fn update_metrics(&self, metric: &metrics::Result<metrics::IntCounterVec>) {
+        for (operation, count) in [
+            ("attestation", self.attestations),
+            ("attester_slashing", self.attester_slashings),
+            ("proposer_slashing", self.proposer_slashings),
+            ("voluntary_exit", self.voluntary_exits),
+            ("bls_to_execution_change", self.bls_to_execution_changes),
+        ] {
+            metrics::inc_counter_vec_by(metric, &[operation], count as u64);
+        }
+    }
+}
+
+/// The outcome of a sync with a peer.
+///
+/// Pulling and pushing are independent, so one can succeed while the other fails.
+#[derive(Debug)]
+pub struct SyncOutcome {
+    /// The operations pulled from the peer which were new to the op pool.
+    pub imported: Result<OperationCounts, Error>,
+    /// The operations pushed to the peer which it accepted.
+    pub pushed: Result<OperationCounts, Error>,
+}
+
+/// An aggregate pulled from a peer which has been indexed, and whose signature is verified before
+/// it is added to the op pool.
+struct SyncedAttestation<'a, E: EthSpec> {
+    attestation: AttestationRef<'a, E>,
+    indexed_attestation: IndexedAttestation<E>,
+    /// The root under which the aggregate is stored in `observed_attestations`, if it can be
+    /// observed.
+    observed_attestation_key_root: Option<Hash256>,
+}
+
+impl<'a, T: BeaconChainTypes> VerifiedAttestation<T> for SyncedAttestation<'a, T::EthSpec> {
+    fn attestation(&self) -> AttestationRef<T::EthSpec> {
+        self.attestation
+    }
+
+    fn indexed_attestation(&self) -> &IndexedAttestation<T::EthSpec> {
+        &self.indexed_attestation
+    }
+}
+
+/// Spawns a routine which syncs the op pool with each of the peers in `config` once per slot.
+pub fn start_op_pool_sync_service<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+    config: Config,
+) -> Result<(), String> {
+    if config.peers.is_empty() {
+        return Ok(());
+    }
+
+    let api_key = config
+        .api_key_file
+        .as_deref()
+        .map(BeaconNodeHttpClient::load_api_key_from_file)
+        .transpose()
+        .map_err(|e| format!("Unable to read op pool sync API key: {:?}", e))?;
+
+    let peers = config
+        .peers
+        .into_iter()
+        .map(|url| {
+            let client =
+                BeaconNodeHttpClient::new(url, Timeouts::set_all(chain.slot_clock.slot_duration()));
+            Peer::new(match &api_key {
+                Some(api_key) => client.with_api_key(api_key.clone()),
+                None => client,
+            })
+        })
+        .collect();
+
+    executor.spawn(
+        async move { op_pool_sync_service(chain, peers).await },
+        "op_pool_sync_service",
+    );
+    Ok(())
+}
+
+/// Loop indefinitely, syncing with every peer concurrently at `SYNC_DELAY_NUMERATOR /
+/// SYNC_DELAY_DENOMINATOR` of each slot.
+async fn op_pool_sync_service<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    mut peers: Vec<Peer>,
+) {
+    let slot_duration = chain.slot_clock.slot_duration();
+    let additional_delay = slot_duration * SYNC_DELAY_NUMERATOR / SYNC_DELAY_DENOMINATOR;
+    let sync_timeout = slot_duration - additional_delay;
+
+    loop {
+        match chain.slot_clock.duration_to_next_slot() {
+            Some(duration) => {
+                sleep(duration + additional_delay).await;
+
+                let Ok(current_slot) = chain.slot() else {
+                    continue;
+                };
+
+                let chain = &chain;
+                join_all(peers.iter_mut().map(|peer| async move {
+                    let outcome = sync_with_peer(chain, peer, current_slot, sync_timeout).await;
+                    record_outcome(chain, peer, current_slot, outcome);
+                }))
+                .await;
+            }
+            None => {
+                error!(chain.log, "Failed to read slot clock");
+                // If we can't read the slot clock, just wait another slot.
+                sleep(slot_duration).await;
+            }
+        }
+    }
+}
+
+/// Pull operations from `peer` and push our own operations to it.
+///
+/// The pull and the push must both complete within `sync_timeout`. The pulled operations are
+/// verified and imported while the push is in progress, and the import always runs to completion
+/// so that the time it takes can't cause the push to be abandoned.
+pub async fn sync_with_peer<T: BeaconChainTypes>(
+    chain: &Arc<BeaconChain<T>>,
+    peer: &mut Peer,
+    current_slot: Slot,
+    sync_timeout: Duration,
+) -> SyncOutcome {
+    let deadline = Instant::now() + sync_timeout;
+
+    // Forget operations which have left our pool, so the set doesn't grow without bound. This
+    // must happen before the pull, since the operations pulled from the peer aren't in our pool
+    // until they have been imported.
+    let pool_roots = operation_roots(chain);
+    peer.known_operations
+        .retain(|root| pool_roots.contains(root));
+
+    let pull_timer = metrics::start_timer_vec(&metrics::OP_POOL_SYNC_TIMES, &["pull"]);
+    let pulled = timeout_at(deadline, pull_operations::<T::EthSpec>(peer, current_slot))
+        .await
+        .unwrap_or(Err(Error::Timeout));
+    metrics::stop_timer(pull_timer);
+
+    let import = async {
+        let operations = pulled?;
+        let inner_chain = chain.clone();
+        chain
+            .spawn_blocking_handle(
+                move || import_operations(&inner_chain, operations),
+                "op_pool_sync_import",
+            )
+            .await
+            .map_err(Error::BeaconChain)
+    };
+    let push = async {
+        let _timer = metrics::start_timer_vec(&metrics::OP_POOL_SYNC_TIMES, &["push"]);
+        timeout_at(deadline, push_operations(chain, peer, current_slot))
+            .await
+            .unwrap_or(Err(Error::Timeout))
+    };
+    let (imported, pushed) = join(import, push).await;
+
+    SyncOutcome { imported, pushed }
+}
+
+/// The roots of the slashings, exits and BLS to execution changes in our op pool.
+fn operation_roots<T: BeaconChainTypes>(chain: &BeaconChain<T>) -> HashSet<Hash256> {
+    let op_pool = &chain.op_pool;
+    op_pool
+        .get_all_attester_slashings()
+        .iter()
+        .map(TreeHash::tree_hash_root)
+        .chain(
+            op_pool
+                .get_all_proposer_slashings()
+                .iter()
+                .map(TreeHash::tree_hash_root),
+        )
+        .chain(
+            op_pool
+                .get_all_voluntary_exits()
+                .iter()
+                .map(TreeHash::tree_hash_root),
+        )
+        .chain(
+            op_pool
+                .get_all_bls_to_execution_changes()
+                .iter()
+                .map(TreeHash::tree_hash_root),
+        )
+        .collect()
+}
+
+/// Update the metrics and log the `outcome` of a sync with `peer`.
+fn record_outcome<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    peer: &Peer,
+    current_slot: Slot,
+    outcome: SyncOutcome,
+) {
+    match outcome.imported {
+        Ok(imported) => {
+            imported.update_metrics(&metrics::OP_POOL_SYNC_IMPORTED);
+            debug!(
+                chain.log,
+                "Imported operations from peer";
+                "peer" => %peer.client,
+                "slot" => current_slot,
+                "imported" => ?imported,
+            );
+        }
+        Err(e) => {
+            metrics::inc_counter_vec(&metrics::OP_POOL_SYNC_FAILURES, &["pull"]);
+            warn!(
+                chain.log,
+                "Failed to pull operations from peer";
+                "peer" => %peer.client,
+                "slot" => current_slot,
+                "error" => ?e,
+            );
+        }
+    }
+
+    match outcome.pushed {
+        Ok(pushed) => {
+            pushed.update_metrics(&metrics::OP_POOL_SYNC_PUSHED);
+            debug!(
+                chain.log,
+                "Pushed operations to peer";
+                "peer" => %peer.client,
+                "slot" => current_slot,
+                "pushed" => ?pushed,
+            );
+        }
+        Err(e) => {
+            metrics::inc_counter_vec(&metrics::OP_POOL_SYNC_FAILURES, &["push"]);
+            warn!(
+                chain.log,
+                "Failed to push operations to peer";
+                "peer" => %peer.client,
+                "slot" => current_slot,
+                "error" => ?e,
+            );
+        }
+    }
+}
+
+/// Pull the aggregates for the current and previous slots, and all other operations, from `peer`.
+async fn pull_operations<E: EthSpec>(
+    peer: &mut Peer,
+    current_slot: Slot,
+) -> Result<PeerOperations<E>, Error> {
+    let client = &peer.client;
+
+    let previous_slot_attestations = async {
+        match current_slot.as_u64().checked_sub(1) {
+            Some(previous_slot) => client
+                .get_beacon_pool_attestations_v2::<E>(Some(Slot::new(previous_slot)), None)
+                .await
+                .map(|response| response.data),
+            None => Ok(vec![]),
+        }
+    };
+
+    // Make the requests concurrently, since they all count against the time left in the slot.
+    let (
+        mut attestations,
+        previous_slot_attestations,
+        attester_slashings,
+        proposer_slashings,
+        voluntary_exits,
+        bls_to_execution_changes,
+    ) = try_join!(
+        client
+            .get_beacon_pool_attestations_v2::<E>(Some(current_slot), None)
+            .map_ok(|response| response.data),
+        previous_slot_attestations,
+        client
+            .get_beacon_pool_attester_slashings_v2::<E>()
+            .map_ok(|response| response.data),
+        client
+            .get_beacon_pool_proposer_slashings()
+            .map_ok(|response| response.data),
+        client
+            .get_beacon_pool_voluntary_exits()
+            .map_ok(|response| response.data),
+        client
+            .get_beacon_pool_bls_to_execution_changes()
+            .map_ok(|response| response.data),
+    )?;
+    attestations.extend(previous_slot_attestations);
+
+    metrics::observe(
+        &metrics::OP_POOL_SYNC_PULLED_ATTESTATIONS,
+        attestations.len() as f64,
+    );
+
+    let operations = PeerOperations {
+        attestations,
+        attester_slashings,
+        proposer_slashings,
+        voluntary_exits,
+        bls_to_execution_changes,
+    };
+
+    // The peer already has these operations, so there's no need to push them back.
+    peer.known_operations.extend(
+        operations
+            .attester_slashings
+            .iter()
+            .map(TreeHash::tree_hash_root)
+            .chain(
+                operations
+                    .proposer_slashings
+                    .iter()
+                    .map(TreeHash::tree_hash_root),
+            )
+            .chain(
+                operations
+                    .voluntary_exits
+                    .iter()
+                    .map(TreeHash::tree_hash_root),
+            )
+            .chain(
+                operations
+                    .bls_to_execution_changes
+                    .iter()
+                    .map(TreeHash::tree_hash_root),
+            ),
+    );
+
+    Ok(operations)
+}
+
+/// Verify the `operations` pulled from a peer and add those which are new to the op pool.
+fn import_operations<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    operations: PeerOperations<T::EthSpec>,
+) -> OperationCounts {
+    let _timer = metrics::start_timer_vec(&metrics::OP_POOL_SYNC_TIMES, &["import"]);
+    let mut imported = OperationCounts {
+        attestations: import_attestations(chain, &operations.attestations),
+        ..OperationCounts::default()
+    };
+
+    for slashing in operations.attester_slashings {
+        match chain.verify_attester_slashing_for_gossip(slashing) {
+            Ok(ObservationOutcome::New(slashing)) => {
+                chain.import_attester_slashing(slashing);
+                imported.attester_slashings += 1;
+            }
+            Ok(ObservationOutcome::AlreadyKnown) => (),
+            Err(e) => debug!(chain.log, "Ignoring synced attester slashing"; "error" => ?e),
+        }
+    }
+
+    for slashing in operations.proposer_slashings {
+        match chain.verify_proposer_slashing_for_gossip(slashing) {
+            Ok(ObservationOutcome::New(slashing)) => {
+                chain.import_proposer_slashing(slashing);
+                imported.proposer_slashings += 1;
+            }
+            Ok(ObservationOutcome::AlreadyKnown) => (),
+            Err(e) => debug!(chain.log, "Ignoring synced proposer slashing"; "error" => ?e),
+        }
+    }
+
+    for exit in operations.voluntary_exits {
+        match chain.verify_voluntary_exit_for_gossip(exit) {
+            Ok(ObservationOutcome::New(exit)) => {
+                chain.import_voluntary_exit(exit);
+                imported.voluntary_exits += 1;
+            }
+            Ok(ObservationOutcome::AlreadyKnown) => (),
+            Err(e) => debug!(chain.log, "Ignoring synced voluntary exit"; "error" => ?e),
+        }
+    }
+
+    let received_pre_capella = if chain.current_slot_is_post_capella().unwrap_or(false) {
+        ReceivedPreCapella::No
+    } else {
+        ReceivedPreCapella::Yes
+    };
+    for change in operations.bls_to_execution_changes {
+        match chain.verify_bls_to_execution_change_for_http_api(change) {
+            Ok(ObservationOutcome::New(change)) => {
+                if chain.import_bls_to_execution_change(change, received_pre_capella) {
+                    imported.bls_to_execution_changes += 1;
+                }
+            }
+            Ok(ObservationOutcome::AlreadyKnown) => (),
+            Err(e) => debug!(chain.log, "Ignoring synced BLS to execution change"; "error" => ?e),
+        }
+    }
+
+    imported
+}
+
+/// Verify the aggregates pulled from a peer and add those which are new to the op pool, returning
+/// the number added.
+///
+/// Aggregates which are subsets of one already observed are skipped before they are indexed. The
+/// signatures of the rest are verified in a single batch, falling back to verifying each
+/// signature individually if the batch is invalid.
+fn import_attestations<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    attestations: &[Attestation<T::EthSpec>],
+) -> usize {
+    // Observe the largest aggregates first, so that any of their subsets are skipped.
+    let mut attestations = attestations
+        .iter()
+        .map(Attestation::to_ref)
+        .collect::<Vec<_>>();
+    attestations.sort_by_key(|attestation| Reverse(attestation.num_set_aggregation_bits()));
+
+    let indexed_attestations = attestations
+        .into_iter()
+        .filter_map(|attestation| match index_attestation(chain, attestation) {
+            Ok(indexed) => indexed,
+            Err(e) => {
+                debug!(
+                    chain.log,
+                    "Ignoring synced attestation";
+                    "error" => ?e,
+                    "slot" => attestation.data().slot,
+                );
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let batch_is_valid = batch_verify_attestation_signatures(chain, &indexed_attestations);
+
+    let mut imported = 0;
+    for attestation in indexed_attestations {
+        let slot = attestation.attestation.data().slot;
+        let result = if batch_is_valid {
+            Ok(())
+        } else {
+            verify_attestation_signature(chain, &attestation.indexed_attestation)
+        }
+        .and_then(|()| import_attestation(chain, attestation));
+
+        match result {
+            Ok(true) => imported += 1,
+            Ok(false) => (),
+            Err(e) => debug!(
+                chain.log,
+                "Ignoring synced attestation";
+                "error" => ?e,
+                "slot" => slot,
+            ),
+        }
+    }
+    imported
+}
+
+/// Compute the indexed form of an aggregate pulled from a peer.
+///
+/// Returns `None` if the aggregate is a subset of one which has already been observed.
+fn index_attestation<'a, T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    attestation: AttestationRef<'a, T::EthSpec>,
+) -> Result<Option<SyncedAttestation<'a, T::EthSpec>>, AttestationError> {
+    // Aggregates spanning several committees can't be observed.
+    let observed_attestation_key_root = attestation.committee_index().map(|committee_index| {
+        ObservedAttestationKey {
+            committee_index,
+            attestation_data: attestation.data().clone(),
+        }
+        .tree_hash_root()
+    });
+
+    if let Some(root) = observed_attestation_key_root {
+        if chain
+            .observed_attestations
+            .write()
+            .is_known_subset(attestation, root)
+            .map_err(|e| AttestationError::BeaconChainError(e.into()))?
+        {
+            return Ok(None);
+        }
+    }
+
+    let (indexed_attestation, _) =
+        obtain_indexed_attestation_and_committees_per_slot(chain, attestation)?;
+
+    Ok(Some(SyncedAttestation {
+        attestation,
+        indexed_attestation,
+        observed_attestation_key_root,
+    }))
+}
+
+/// Returns `true` if the signatures of all of the `attestations` are valid.
+fn batch_verify_attestation_signatures<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    attestations: &[SyncedAttestation<T::EthSpec>],
+) -> bool {
+    if attestations.is_empty() {
+        return true;
+    }
+
+    let pubkey_cache = chain.validator_pubkey_cache.read();
+
+    let signature_sets = attestations
+        .iter()
+        .map(|attestation| {
+            let indexed_attestation = &attestation.indexed_attestation;
+            let fork = chain
+                .spec
+                .fork_at_epoch(indexed_attestation.data().target.epoch);
+
+            indexed_attestation_signature_set_from_pubkeys(
+                |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
+                indexed_attestation.signature(),
+                indexed_attestation,
+                &fork,
+                chain.genesis_validators_root,
+                &chain.spec,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>();
+
+    // If a signature set can't be built, verify each signature individually to find out which.
+    signature_sets.map_or(false, |signature_sets| {
+        verify_signature_sets(signature_sets.iter())
+    })
+}
+
+/// Add an aggregate whose signature has been verified to the op pool.
+///
+/// Returns `false` if the aggregate is a subset of one which has already been observed.
+fn import_attestation<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    attestation: SyncedAttestation<T::EthSpec>,
+) -> Result<bool, AttestationError> {
+    // An aggregate pulled in the same batch may have been a superset of this one.
+    if let Some(root) = attestation.observed_attestation_key_root {
+        if let ObserveOutcome::Subset = chain
+            .observed_attestations
+            .write()
+            .observe_item(attestation.attestation, Some(root))
+            .map_err(|e| AttestationError::BeaconChainError(e.into()))?
+        {
+            return Ok(false);
+        }
+    }
+
+    chain.add_to_block_inclusion_pool(attestation)?;
+
+    Ok(true)
+}
+
+/// Push the slashings, exits and BLS to execution changes in our op pool which `peer` isn't known
+/// to have.
+///
+/// Operations rejected by the peer are not pushed again.
+async fn push_operations<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    peer: &mut Peer,
+    current_slot: Slot,
+) -> Result<OperationCounts, Error> {
+    let fork_name = chain.spec.fork_name_at_slot::<T::EthSpec>(current_slot);
+    let mut pushed = OperationCounts::default();
+
+    for slashing in chain.op_pool.get_all_attester_slashings() {
+        let root = slashing.tree_hash_root();
+        if !peer.known_operations.contains(&root) {
+            let result = peer
+                .client
+                .post_beacon_pool_attester_slashings_v2(&slashing, fork_name)
+                .await;
+            pushed.attester_slashings += record_push(chain, peer, root, result)?;
+        }
+    }
+
+    for slashing in chain.op_pool.get_all_proposer_slashings() {
+        let root = slashing.tree_hash_root();
+        if !peer.known_operations.contains(&root) {
+            let result = peer
+                .client
+                .post_beacon_pool_proposer_slashings(&slashing)
+                .await;
+            pushed.proposer_slashings += record_push(chain, peer, root, result)?;
+        }
+    }
+
+    for exit in chain.op_pool.get_all_voluntary_exits() {
+        let root = exit.tree_hash_root();
+        if !peer.known_operations.contains(&root) {
+            let result = peer.client.post_beacon_pool_voluntary_exits(&exit).await;
+            pushed.voluntary_exits += record_push(chain, peer, root, result)?;
+        }
+    }
+
+    let changes = chain
+        .op_pool
+        .get_all_bls_to_execution_changes()
+        .into_iter()
+        .filter(|change| !peer.known_operations.contains(&change.tree_hash_root()))
+        .collect::<Vec<_>>();
+    if !changes.is_empty() {
+        // The peer imports the valid changes in the batch even if it rejects some of them.
+        let result = peer
+            .client
+            .post_beacon_pool_bls_to_execution_changes(&changes)
+            .await;
+        match result {
+            Ok(()) => pushed.bls_to_execution_changes += changes.len(),
+            Err(e) if e.status().is_some() => {
+                debug!(
+                    chain.log,
+                    "Peer rejected BLS to execution changes";
+                    "peer" => %peer.client,
+                    "error" => ?e,
+                );
+            }
+            Err(e) => return Err(e.into()),
+        }
+        peer.known_operations
+            .extend(changes.iter().map(TreeHash::tree_hash_root));
+    }
+
+    Ok(pushed)
+}
+
+/// Record the `result` of pushing the operation with `root` to `peer`, returning the number of
+/// operations accepted by the peer.
+///
+/// Errors which don't come from the peer (e.g. connection errors) are returned so that the push
+/// can be retried at the next slot.
+fn record_push<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    peer: &mut Peer,
+    root: Hash256,
+    result: Result<(), HttpError>,
+) -> Result<usize, Error> {
+    let accepted = match result {
+        Ok(()) => 1,
+        Err(e) if e.status().is_some() => {
+            debug!(
+                chain.log,
+                "Peer rejected operation";
+                "peer" => %peer.client,
+                "root" => ?root,
+                "error" => ?e,
+            );
+            0
+        }
+        Err(e) => return Err(e.into()),
+    };
+    peer.known_operations.insert(root);
+    Ok(accepted)
+}
@@ -4,6 +4,7 @@
 
 use crate::beacon_proposer_cache::{BeaconProposerCache, TYPICAL_SLOTS_PER_EPOCH};
 use crate::metrics;
+use eth2::lighthouse::ValidatorMonitorEpochSummary;
 use itertools::Itertools;
 use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
@@ -61,6 +62,9 @@ pub struct ValidatorMonitorConfig {
     pub auto_register: bool,
     pub validators: Vec<PublicKeyBytes>,
     pub individual_tracking_threshold: usize,
+    /// The number of epochs for which validator summaries are kept in the database. Summaries are
+    /// not persisted if this is zero, which is the default.
+    pub history_retention_epochs: u64,
 }
 
 impl Default for ValidatorMonitorConfig {
@@ -69,6 +73,7 @@ impl Default for ValidatorMonitorConfig {
             auto_register: false,
             validators: vec![],
             individual_tracking_threshold: DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
+            history_retention_epochs: 0,
         }
     }
 }
@@ -232,6 +237,39 @@ impl EpochSummary {
     pub fn register_validator_total_balance(&mut self, total_balance: u64) {
         self.total_balance = Some(total_balance)
     }
+
+    /// Returns the summary in the form in which it is persisted, without the on-chain attestation
+    /// performance.
+    fn to_persisted(&self, epoch: Epoch, validator_index: u64) -> ValidatorMonitorEpochSummary {
+        let millis = |delay: Option<Duration>| delay.map(|delay| delay.as_millis() as u64);
+        ValidatorMonitorEpochSummary {
+            epoch,
+            validator_index,
+            attestations: self.attestations as u64,
+            attestation_min_delay: millis(self.attestation_min_delay),
+            attestation_aggregate_inclusions: self.attestation_aggregate_inclusions as u64,
+            attestation_block_inclusions: self.attestation_block_inclusions as u64,
+            attestation_min_block_inclusion_distance: self
+                .attestation_min_block_inclusion_distance
+                .map(Slot::as_u64),
+            blocks: self.blocks as u64,
+            block_min_delay: millis(self.block_min_delay),
+            aggregates: self.aggregates as u64,
+            aggregate_min_delay: millis(self.aggregate_min_delay),
+            sync_committee_messages: self.sync_committee_messages as u64,
+            sync_committee_message_min_delay: millis(self.sync_committee_message_min_delay),
+            sync_signature_block_inclusions: self.sync_signature_block_inclusions as u64,
+            sync_signature_contribution_inclusions: self.sync_signature_contribution_inclusions
+                as u64,
+            sync_contributions: self.sync_contributions as u64,
+            sync_contribution_min_delay: millis(self.sync_contribution_min_delay),
+            exits: self.exits as u64,
+            proposer_slashings: self.proposer_slashings as u64,
+            attester_slashings: self.attester_slashings as u64,
+            total_balance: self.total_balance,
+            ..Default::default()
+        }
+    }
 }
 
 type SummaryMap = HashMap<Epoch, EpochSummary>;
@@ -401,6 +439,11 @@ pub struct ValidatorMonitor<E: EthSpec> {
     beacon_proposer_cache: Arc<Mutex<BeaconProposerCache>>,
     // Unaggregated attestations generated by the committee index at each slot.
     unaggregated_attestations: HashMap<Slot, Attestation<E>>,
+    /// The number of epochs for which summaries are kept in the database, or zero if they are not
+    /// persisted.
+    history_retention_epochs: u64,
+    /// Summaries for completed epochs which are yet to be persisted to the database.
+    completed_summaries: Mutex<Vec<ValidatorMonitorEpochSummary>>,
     log: Logger,
     _phantom: PhantomData<E>,
 }
@@ -415,6 +458,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             auto_register,
             validators,
             individual_tracking_threshold,
+            history_retention_epochs,
         } = config;
 
         let mut s = Self {
@@ -425,6 +469,8 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             missed_blocks: <_>::default(),
             beacon_proposer_cache,
             unaggregated_attestations: <_>::default(),
+            history_retention_epochs,
+            completed_summaries: <_>::default(),
             log,
             _phantom: PhantomData,
         };
@@ -770,6 +816,17 @@ impl<E: EthSpec> ValidatorMonitor<E> {
         }
     }
 
+    /// Returns the number of epochs for which summaries are kept in the database, or zero if they
+    /// are not persisted.
+    pub fn history_retention_epochs(&self) -> u64 {
+        self.history_retention_epochs
+    }
+
+    /// Removes and returns the summaries of completed epochs which are yet to be persisted.
+    pub fn take_completed_summaries(&self) -> Vec<ValidatorMonitorEpochSummary> {
+        std::mem::take(&mut *self.completed_summaries.lock())
+    }
+
     /// Run `func` with the `TOTAL_LABEL` and optionally the
     /// `individual_id`.
     ///
@@ -981,6 +1038,24 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                 }
                 drop(validator_metrics);
 
+                // The previous epoch is now complete, so queue its summary for persistence.
+                if self.history_retention_epochs > 0 {
+                    let mut persisted = monitored_validator
+                        .get_from_epoch_summary(prev_epoch, |summary_opt| {
+                            summary_opt.map(|summary| summary.to_persisted(prev_epoch, i as u64))
+                        })
+                        .unwrap_or_else(|| ValidatorMonitorEpochSummary {
+                            epoch: prev_epoch,
+                            validator_index: i as u64,
+                            ..Default::default()
+                        });
+                    persisted.attestation_source_hit = previous_epoch_matched_source;
+                    persisted.attestation_target_hit = previous_epoch_matched_target;
+                    persisted.attestation_head_hit = previous_epoch_matched_head;
+                    persisted.inclusion_distance = min_inclusion_distance;
+                    self.completed_summaries.lock().push(persisted);
+                }
+
                 // Indicates the number of sync committee signatures that made it into
                 // a sync aggregate in the current_epoch (state.epoch - 1).
                 // Note: Unlike attestations, sync committee signatures must be included in the
@@ -0,0 +1,124 @@
+//! Persists the per-epoch summaries of monitored validators to the database, so that they survive
+//! restarts and can be served via the HTTP API.
+//!
+//! Summaries are keyed by `(epoch, validator_index)`, which allows pruning by epoch to be performed
+//! by iterating from the start of the column.
+
+use eth2::lighthouse::ValidatorMonitorEpochSummary;
+use ssz::{Decode, Encode};
+use store::{get_key_for_col, DBColumn, Error, KeyValueStore, KeyValueStoreOp};
+use types::{Epoch, EthSpec};
+
+const COLUMN: DBColumn = DBColumn::ValidatorMonitorHistory;
+
+fn db_key(epoch: Epoch, validator_index: u64) -> [u8; 16] {
+    let mut key = [0; 16];
+    key[..8].copy_from_slice(&epoch.as_u64().to_be_bytes());
+    key[8..].copy_from_slice(&validator_index.to_be_bytes());
+    key
+}
+
+fn epoch_from_key(key: &[u8]) -> Option<Epoch> {
+    let bytes = key.get(..8)?.try_into().ok()?;
+    Some(Epoch::new(u64::from_be_bytes(bytes)))
+}
+
+/// Store `summaries`, replacing any existing summaries for the same validator and epoch.
+pub fn store_summaries<E: EthSpec, S: KeyValueStore<E>>(
+    db: &S,
+    summaries: &[ValidatorMonitorEpochSummary],
+) -> Result<(), Error> {
+    let ops = summaries
+        .iter()
+        .map(|summary| {
+            let key = db_key(summary.epoch, summary.validator_index);
+            KeyValueStoreOp::PutKeyValue(
+                get_key_for_col(COLUMN.into(), &key),
+                summary.as_ssz_bytes(),
+            )
+        })
+        .collect();
+    db.do_atomically(ops)
+}
+
+/// Load the summaries of the validator with `validator_index` from `start_epoch` to `end_epoch`
+/// (inclusive). Epochs without a summary are skipped.
+pub fn load_summaries<E: EthSpec, S: KeyValueStore<E>>(
+    db: &S,
+    validator_index: u64,
+    start_epoch: Epoch,
+    end_epoch: Epoch,
+) -> Result<Vec<ValidatorMonitorEpochSummary>, Error> {
+    let mut summaries = vec![];
+    for epoch in (start_epoch.as_u64()..=end_epoch.as_u64()).map(Epoch::new) {
+        if let Some(bytes) = db.get_bytes(COLUMN.into(), &db_key(epoch, validator_index))? {
+            summaries.push(ValidatorMonitorEpochSummary::from_ssz_bytes(&bytes)?);
+        }
+    }
+    Ok(summaries)
+}
+
+/// Delete all summaries prior to `before_epoch`, returning the number of summaries deleted.
+pub fn prune_summaries<E: EthSpec, S: KeyValueStore<E>>(
+    db: &S,
+    before_epoch: Epoch,
+) -> Result<usize, Error> {
+    let mut ops = vec![];
+    for res in db.iter_column_keys::<Vec<u8>>(COLUMN) {
+        let key = res?;
+        if epoch_from_key(&key).map_or(true, |epoch| epoch >= before_epoch) {
+            break;
+        }
+        ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+            COLUMN.into(),
+            &key,
+        )));
+    }
+    let count = ops.len();
+    db.do_atomically(ops)?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::MemoryStore;
+    use types::MinimalEthSpec;
+
+    fn summary(epoch: u64, validator_index: u64) -> ValidatorMonitorEpochSummary {
+        ValidatorMonitorEpochSummary {
+            epoch: Epoch::new(epoch),
+            validator_index,
+            attestation_min_delay: Some(epoch * 100),
+            attestation_head_hit: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn store_load_and_prune() {
+        let db = MemoryStore::<MinimalEthSpec>::open();
+        let summaries = (0..4)
+            .flat_map(|epoch| [summary(epoch, 1), summary(epoch, 256)])
+            .collect::<Vec<_>>();
+        store_summaries(&db, &summaries).unwrap();
+
+        assert_eq!(
+            load_summaries(&db, 256, Epoch::new(1), Epoch::new(2)).unwrap(),
+            vec![summary(1, 256), summary(2, 256)]
+        );
+        assert_eq!(
+            load_summaries(&db, 1, Epoch::new(3), Epoch::new(10)).unwrap(),
+            vec![summary(3, 1)]
+        );
+        assert!(load_summaries(&db, 2, Epoch::new(0), Epoch::new(3))
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(prune_summaries(&db, Epoch::new(2)).unwrap(), 4);
+        assert_eq!(
+            load_summaries(&db, 1, Epoch::new(0), Epoch::new(3)).unwrap(),
+            vec![summary(2, 1), summary(3, 1)]
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Provides the `ValidatorSightings` struct which records the gossip messages of a watched set of
+//! validators.
+//!
+//! A validator client may use these sightings to detect another instance of its keys (a
+//! "doppelganger") within a slot of a message being published, rather than waiting for the
+//! message to be included on-chain.
+
+use eth2::lighthouse::ValidatorSighting;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::marker::PhantomData;
+use types::{EthSpec, Slot};
+
+/// The number of epochs for which a validator remains watched after it was last requested.
+pub const WATCH_EXPIRY_EPOCHS: u64 = 2;
+/// The number of epochs for which sightings are retained.
+pub const SIGHTING_RETENTION_EPOCHS: u64 = 2;
+/// The maximum number of validators which may be watched or queried in a single request.
+pub const MAX_REQUEST_INDICES: usize = 4096;
+
+/// Records the messages observed on gossip for a set of watched validators.
+///
+/// Only messages which have passed signature verification should be observed, so that a peer
+/// cannot forge a sighting.
+///
+/// The cache does not automatically prune, you must call `Self::prune` manually.
+pub struct ValidatorSightings<E: EthSpec> {
+    /// Map from validator index to the slot at which it was last requested.
+    watched: HashMap<u64, Slot>,
+    /// Map from slot to the sightings at that slot.
+    sightings: BTreeMap<Slot, Vec<ValidatorSighting>>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> Default for ValidatorSightings<E> {
+    fn default() -> Self {
+        Self {
+            watched: HashMap::new(),
+            sightings: BTreeMap::new(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<E: EthSpec> ValidatorSightings<E> {
+    /// Returns `true` if sightings of `validator_index` should be recorded.
+    pub fn is_watched(&self, validator_index: u64) -> bool {
+        self.watched.contains_key(&validator_index)
+    }
+
+    /// Watch `indices` until `WATCH_EXPIRY_EPOCHS` after `current_slot`.
+    pub fn watch(&mut self, indices: &HashSet<u64>, current_slot: Slot) {
+        for index in indices {
+            self.watched.insert(*index, current_slot);
+        }
+    }
+
+    /// Record `sighting` if its validator is watched. Duplicate sightings are ignored.
+    pub fn observe(&mut self, sighting: ValidatorSighting) {
+        if !self.is_watched(sighting.validator_index) {
+            return;
+        }
+        let sightings = self.sightings.entry(sighting.slot).or_default();
+        if !sightings.contains(&sighting) {
+            sightings.push(sighting);
+        }
+    }
+
+    /// Returns the sightings of `indices` at or after `from_slot`.
+    pub fn get(&self, indices: &HashSet<u64>, from_slot: Slot) -> Vec<ValidatorSighting> {
+        self.sightings
+            .range(from_slot..)
+            .flat_map(|(_, sightings)| sightings)
+            .filter(|sighting| indices.contains(&sighting.validator_index))
+            .cloned()
+            .collect()
+    }
+
+    /// Remove expired watches and sightings.
+    pub fn prune(&mut self, current_slot: Slot) {
+        let slots_per_epoch = E::slots_per_epoch();
+
+        let watch_cutoff = current_slot.saturating_sub(WATCH_EXPIRY_EPOCHS * slots_per_epoch);
+        self.watched
+            .retain(|_, last_requested| *last_requested >= watch_cutoff);
+
+        let sighting_cutoff =
+            current_slot.saturating_sub(SIGHTING_RETENTION_EPOCHS * slots_per_epoch);
+        self.sightings = self.sightings.split_off(&sighting_cutoff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2::lighthouse::ValidatorSightingKind;
+    use types::{Hash256, MainnetEthSpec};
+
+    type E = MainnetEthSpec;
+
+    fn sighting(validator_index: u64, slot: u64) -> ValidatorSighting {
+        ValidatorSighting {
+            validator_index,
+            slot: Slot::new(slot),
+            kind: ValidatorSightingKind::Attestation,
+            root: Hash256::repeat_byte(1),
+        }
+    }
+
+    #[test]
+    fn only_watched_validators_are_recorded() {
+        let mut cache = ValidatorSightings::<E>::default();
+        cache.watch(&HashSet::from([1]), Slot::new(0));
+
+        cache.observe(sighting(0, 1));
+        cache.observe(sighting(1, 1));
+        cache.observe(sighting(1, 1));
+
+        assert_eq!(
+            cache.get(&HashSet::from([0, 1]), Slot::new(0)),
+            vec![sighting(1, 1)]
+        );
+        assert!(cache.get(&HashSet::from([1]), Slot::new(2)).is_empty());
+    }
+
+    #[test]
+    fn prune() {
+        let slots_per_epoch = E::slots_per_epoch();
+        let mut cache = ValidatorSightings::<E>::default();
+        cache.watch(&HashSet::from([1]), Slot::new(0));
+        cache.observe(sighting(1, 0));
+        cache.observe(sighting(1, slots_per_epoch));
+
+        let current_slot = Slot::new(WATCH_EXPIRY_EPOCHS * slots_per_epoch + 1);
+        cache.prune(current_slot);
+
+        assert!(!cache.is_watched(1));
+        assert_eq!(
+            cache.get(&HashSet::from([1]), Slot::new(0)),
+            vec![sighting(1, slots_per_epoch)]
+        );
+    }
+}
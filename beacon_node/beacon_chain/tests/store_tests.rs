@@ -31,7 +31,8 @@ use store::{
     chunked_vector::{chunk_key, Field},
     get_key_for_col,
     iter::{BlockRootsIterator, StateRootsIterator},
-    BlobInfo, DBColumn, HotColdDB, KeyValueStore, KeyValueStoreOp, LevelDB, StoreConfig,
+    BlobInfo, Codec, DBColumn, HotColdDB, KeyValueStore, KeyValueStoreOp, LevelDB, StoreConfig,
+    VerificationIssue,
 };
 use tempfile::{tempdir, TempDir};
 use tokio::time::sleep;
@@ -560,6 +561,298 @@ async fn split_slot_restore() {
     assert_eq!(store.get_split_slot(), split_slot);
 }
 
+#[tokio::test]
+async fn verify_database() {
+    let num_blocks_produced = E::slots_per_epoch() * 8;
+    let db_path = tempdir().unwrap();
+    let store = get_store_generic(
+        &db_path,
+        StoreConfig {
+            slots_per_restore_point: 2 * E::slots_per_epoch(),
+            ..Default::default()
+        },
+        test_spec::<E>(),
+    );
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let report = store.verify_database(false).unwrap();
+    assert_eq!(report.issues, vec![]);
+    assert_eq!(report.blocks_checked, num_blocks_produced + 1);
+    assert!(report.frozen_state_roots_checked > 0);
+
+    // Delete the index entry for a frozen state, and a frozen block.
+    let state_slot = Slot::new(3);
+    let state_root = harness
+        .chain
+        .state_root_at_slot(state_slot)
+        .unwrap()
+        .unwrap();
+    store
+        .cold_db
+        .key_delete(DBColumn::BeaconStateSummary.into(), state_root.as_bytes())
+        .unwrap();
+    let block_slot = Slot::new(2);
+    let block_root = harness
+        .chain
+        .block_root_at_slot(block_slot, WhenSlotSkipped::None)
+        .unwrap()
+        .unwrap();
+    store
+        .hot_db
+        .key_delete(DBColumn::BeaconBlock.into(), block_root.as_bytes())
+        .unwrap();
+
+    let block_issues = vec![
+        VerificationIssue::MissingParentBlock {
+            block_root: harness
+                .chain
+                .block_root_at_slot(block_slot + 1, WhenSlotSkipped::None)
+                .unwrap()
+                .unwrap(),
+            slot: block_slot + 1,
+            parent_root: block_root,
+        },
+        VerificationIssue::MissingFrozenBlock {
+            slot: block_slot,
+            block_root,
+        },
+    ];
+    let mut expected_issues = block_issues.clone();
+    expected_issues.push(VerificationIssue::MissingColdStateSummary {
+        slot: state_slot,
+        state_root,
+    });
+
+    // Only the index entry can be repaired.
+    let report = store.verify_database(true).unwrap();
+    assert_eq!(report.issues, expected_issues);
+    assert_eq!(report.repaired, 1);
+    assert!(!report.is_consistent());
+    assert_eq!(
+        store.load_cold_state_slot(&state_root).unwrap(),
+        Some(state_slot)
+    );
+
+    let report = store.verify_database(false).unwrap();
+    assert_eq!(report.issues, block_issues);
+
+    // Store a temporary state, as left behind by a failed block import.
+    let temporary_state_root = Hash256::repeat_byte(0xaa);
+    let head_state = harness.get_current_state();
+    store
+        .put_state_possibly_temporary(&temporary_state_root, &head_state, true)
+        .unwrap();
+
+    // Close the database, and re-open it for verification. The temporary state must not be
+    // garbage collected when the database is opened.
+    drop(store);
+    drop(harness);
+    let store = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open_for_verification(
+        &db_path.path().join("hot_db"),
+        &db_path.path().join("cold_db"),
+        &db_path.path().join("blobs_db"),
+        StoreConfig::default(),
+        test_spec::<E>(),
+        test_logger(),
+    )
+    .unwrap();
+    assert_eq!(
+        store.get_config().slots_per_restore_point,
+        2 * E::slots_per_epoch()
+    );
+
+    let mut expected_issues = block_issues.clone();
+    expected_issues.push(VerificationIssue::TemporaryState {
+        state_root: temporary_state_root,
+    });
+    let report = store.verify_database(false).unwrap();
+    assert_eq!(report.issues, expected_issues);
+    assert!(store
+        .load_state_temporary_flag(&temporary_state_root)
+        .unwrap()
+        .is_some());
+
+    // The temporary state is only deleted when repairing.
+    let report = store.verify_database(true).unwrap();
+    assert_eq!(report.issues, expected_issues);
+    assert_eq!(report.repaired, 1);
+    assert!(store
+        .load_state_temporary_flag(&temporary_state_root)
+        .unwrap()
+        .is_none());
+    assert!(store
+        .load_hot_state_summary(&temporary_state_root)
+        .unwrap()
+        .is_none());
+
+    let report = store.verify_database(false).unwrap();
+    assert_eq!(report.issues, block_issues);
+}
+
+#[tokio::test]
+async fn snapshot_and_restore_database() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            (E::slots_per_epoch() * 6) as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    let snapshot_head = harness.head_block_root();
+    let split = store.get_split_info();
+    assert!(split.slot > 0);
+
+    let snapshot_dir = tempdir().unwrap();
+    let manifest = store
+        .snapshot_database(&snapshot_dir.path().join("snapshot"))
+        .unwrap();
+    assert_eq!(manifest.schema_version, CURRENT_SCHEMA_VERSION.as_u64());
+    assert_eq!(manifest.split, split);
+    assert!(manifest.hot_db.entries > 0);
+    assert!(manifest.freezer_db.entries > 0);
+
+    // Blocks imported after the snapshot are not included in it.
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    let later_head = harness.head_block_root();
+
+    // A snapshot can't be written over an existing one.
+    assert!(matches!(
+        store.snapshot_database(&snapshot_dir.path().join("snapshot")),
+        Err(store::Error::SnapshotDirectoryNotEmpty(_))
+    ));
+
+    let restore_dir = tempdir().unwrap();
+    let hot_path = restore_dir.path().join("hot_db");
+    let cold_path = restore_dir.path().join("cold_db");
+    let blobs_path = restore_dir.path().join("blobs_db");
+    let restored_manifest = store::snapshot::restore_snapshot::<E>(
+        &snapshot_dir.path().join("snapshot"),
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        &test_logger(),
+    )
+    .unwrap();
+    assert_eq!(restored_manifest, manifest);
+
+    let restored = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        store.get_config().clone(),
+        test_spec::<E>(),
+        test_logger(),
+    )
+    .unwrap();
+    assert_eq!(restored.get_split_info(), split);
+    assert!(restored.block_exists(&snapshot_head).unwrap());
+    assert!(!restored.block_exists(&later_head).unwrap());
+
+    let report = restored.verify_database(false).unwrap();
+    assert_eq!(report.issues, vec![]);
+}
+
+#[tokio::test]
+async fn compressed_database_and_recompress() {
+    let num_blocks_produced = E::slots_per_epoch() * 10;
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        block_compression: Codec::Zstd,
+        state_compression: Codec::Zstd,
+        blob_compression: Codec::Zstd,
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Every block, restore point and blob list should have been compressed.
+    assert_ne!(
+        count_values_with_codec(&store.hot_db, DBColumn::BeaconBlock, Codec::Zstd),
+        0
+    );
+    assert_eq!(
+        count_values_with_codec(&store.hot_db, DBColumn::BeaconBlock, Codec::None),
+        0
+    );
+    assert_ne!(
+        count_values_with_codec(&store.cold_db, DBColumn::BeaconState, Codec::Zstd),
+        0
+    );
+    assert_eq!(
+        count_values_with_codec(&store.cold_db, DBColumn::BeaconState, Codec::None),
+        0
+    );
+    assert_eq!(
+        count_values_with_codec(&store.blobs_db, DBColumn::BeaconBlob, Codec::None),
+        0
+    );
+    check_split_slot(&harness, store.clone());
+    check_chain_dump(&harness, num_blocks_produced + 1);
+    check_iterators(&harness);
+
+    // Decompress the blocks only, leaving the other columns compressed.
+    let num_blocks = count_values_with_codec(&store.hot_db, DBColumn::BeaconBlock, Codec::Zstd);
+    let summary = store
+        .recompress_column(DBColumn::BeaconBlock, Codec::None)
+        .unwrap();
+    assert_eq!(summary.recompressed, num_blocks as u64);
+    assert_eq!(
+        count_values_with_codec(&store.hot_db, DBColumn::BeaconBlock, Codec::None),
+        num_blocks
+    );
+    assert_eq!(
+        count_values_with_codec(&store.cold_db, DBColumn::BeaconState, Codec::None),
+        0
+    );
+
+    // All but the most recent blocks are evicted from the block cache, so must be decoded from
+    // their uncompressed values.
+    check_chain_dump(&harness, num_blocks_produced + 1);
+
+    // Recompressing with the same codec is a no-op.
+    let summary = store
+        .recompress_column(DBColumn::BeaconState, Codec::Zstd)
+        .unwrap();
+    assert_eq!(summary.recompressed, 0);
+    assert!(store
+        .recompress_column(DBColumn::BeaconStateSummary, Codec::Zstd)
+        .is_err());
+}
+
+fn count_values_with_codec(db: &LevelDB<E>, column: DBColumn, codec: Codec) -> usize {
+    db.iter_column::<Hash256>(column)
+        .map(Result::unwrap)
+        .filter(|(_, value)| Codec::of_value(value).unwrap() == codec)
+        .count()
+}
+
 // Check attestation processing and `load_epoch_boundary_state` in the presence of a split DB.
 // This is a bit of a monster test in that it tests lots of different things, but until they're
 // tested elsewhere, this is as good a place as any.
@@ -3160,6 +3453,79 @@ async fn deneb_prune_blobs_happy_case() {
     check_blob_existence(&harness, oldest_blob_slot, harness.head_slot(), true);
 }
 
+/// Check that pruned blobs are written to the blob archive and can be read back.
+#[tokio::test]
+async fn deneb_prune_blobs_to_archive() {
+    let db_path = tempdir().unwrap();
+    let archive_path = tempdir().unwrap();
+    let config = StoreConfig {
+        blob_archive_dir: Some(archive_path.path().to_path_buf()),
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+
+    if store.get_chain_spec().deneb_fork_epoch.is_none() {
+        // No-op prior to Deneb.
+        return;
+    }
+
+    let num_blocks_produced = E::slots_per_epoch() * 8;
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    assert_eq!(store.get_oldest_archived_blob_slot().unwrap(), None);
+
+    // Record the blobs which are about to be pruned.
+    let data_availability_boundary = Epoch::new(2);
+    let end_slot = data_availability_boundary.start_slot(E::slots_per_epoch()) - 1;
+    let mut pruned_blobs = vec![];
+    for (block_root, slot) in harness
+        .chain
+        .forwards_iter_block_roots_until(Slot::new(0), end_slot)
+        .unwrap()
+        .map(Result::unwrap)
+    {
+        if let Some(blobs) = store.get_blobs(&block_root).unwrap() {
+            let block = store.get_blinded_block(&block_root).unwrap().unwrap();
+            if block.slot() == slot {
+                pruned_blobs.push((block_root, slot, blobs));
+            }
+        }
+    }
+    assert!(!pruned_blobs.is_empty(), "expected blobs to be pruned");
+
+    store
+        .try_prune_blobs(true, data_availability_boundary)
+        .unwrap();
+    check_blob_existence(&harness, Slot::new(0), end_slot, false);
+
+    // The blobs should all have been archived, and be served by the chain.
+    for (block_root, slot, blobs) in &pruned_blobs {
+        assert_eq!(
+            store
+                .get_archived_blobs(block_root, *slot)
+                .unwrap()
+                .as_ref(),
+            Some(blobs)
+        );
+        assert_eq!(
+            &harness
+                .chain
+                .get_blobs_or_archived(block_root, *slot)
+                .unwrap(),
+            blobs
+        );
+    }
+    let oldest_archived_slot = store.get_oldest_archived_blob_slot().unwrap().unwrap();
+    assert!(oldest_archived_slot <= pruned_blobs[0].1);
+}
+
 /// Check that blob pruning does not prune without finalization.
 #[tokio::test]
 async fn deneb_prune_blobs_no_finalization() {
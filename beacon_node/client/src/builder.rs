@@ -6,7 +6,9 @@ use crate::notifier::spawn_notifier;
 use crate::Client;
 use beacon_chain::attestation_simulator::start_attestation_simulator_service;
 use beacon_chain::data_availability_checker::start_availability_cache_maintenance_service;
+use beacon_chain::event_log::EventLog;
 use beacon_chain::graffiti_calculator::start_engine_version_cache_refresh_service;
+use beacon_chain::op_pool_sync::{self, start_op_pool_sync_service};
 use beacon_chain::otb_verification_service::start_otb_verification_service;
 use beacon_chain::proposer_prep_service::start_proposer_prep_service;
 use beacon_chain::schema_change::migrate_schema;
@@ -87,6 +89,7 @@ pub struct ClientBuilder<T: BeaconChainTypes> {
     freezer_db_path: Option<PathBuf>,
     http_api_config: http_api::Config,
     http_metrics_config: http_metrics::Config,
+    op_pool_sync_config: Option<op_pool_sync::Config>,
     slasher: Option<Arc<Slasher<T::EthSpec>>>,
     beacon_processor_config: Option<BeaconProcessorConfig>,
     beacon_processor_channels: Option<BeaconProcessorChannels<T::EthSpec>>,
@@ -122,6 +125,7 @@ where
             freezer_db_path: None,
             http_api_config: <_>::default(),
             http_metrics_config: <_>::default(),
+            op_pool_sync_config: None,
             slasher: None,
             eth_spec_instance,
             beacon_processor_config: None,
@@ -174,10 +178,20 @@ where
         let log = context.log();
         let spec = chain_spec.ok_or("beacon_chain_start_method requires a chain spec")?;
         let event_handler = if self.http_api_config.enabled {
-            Some(ServerSentEventHandler::new(
+            let event_handler = ServerSentEventHandler::new(
                 context.log().clone(),
                 self.http_api_config.sse_capacity_multiplier,
-            ))
+            );
+            if let Some(event_log_config) = self.http_api_config.event_log.clone() {
+                let event_log = EventLog::open(
+                    event_log_config,
+                    self.http_api_config.sse_capacity_multiplier,
+                    context.log().clone(),
+                )?;
+                Some(event_handler.with_event_log(event_log))
+            } else {
+                Some(event_handler)
+            }
         } else {
             None
         };
@@ -711,6 +725,12 @@ where
         self
     }
 
+    /// Provides the peer beacon nodes to sync the operation pool with, if any.
+    pub fn op_pool_sync_config(mut self, config: Option<op_pool_sync::Config>) -> Self {
+        self.op_pool_sync_config = config;
+        self
+    }
+
     /// Immediately start the slasher service.
     ///
     /// Error if no slasher is configured.
@@ -984,6 +1004,19 @@ where
                 beacon_chain.task_executor.clone(),
                 beacon_chain.clone(),
             );
+
+            if let Some(config) = self.op_pool_sync_config.clone() {
+                info!(
+                    log,
+                    "Operation pool sync enabled";
+                    "peers" => ?config.peers,
+                );
+                start_op_pool_sync_service(
+                    runtime_context.executor.clone(),
+                    beacon_chain.clone(),
+                    config,
+                )?;
+            }
         }
 
         Ok(Client {
@@ -80,6 +80,7 @@ pub struct Config {
     pub http_metrics: http_metrics::Config,
     pub monitoring_api: Option<monitoring_api::Config>,
     pub slasher: Option<slasher::Config>,
+    pub op_pool_sync: Option<beacon_chain::op_pool_sync::Config>,
     pub logger_config: LoggerConfig,
     pub beacon_processor: BeaconProcessorConfig,
     pub genesis_state_url: Option<String>,
@@ -109,6 +110,7 @@ impl Default for Config {
             http_metrics: <_>::default(),
             monitoring_api: None,
             slasher: None,
+            op_pool_sync: None,
             validator_monitor: <_>::default(),
             logger_config: LoggerConfig::default(),
             beacon_processor: <_>::default(),
@@ -75,6 +75,10 @@ pub const DEFAULT_JWT_FILE: &str = "jwt.hex";
 /// in an LRU cache to avoid redundant lookups. This is the size of that cache.
 const EXECUTION_BLOCKS_LRU_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(128);
 
+/// The number of slots for which the timings of payload requests to the local EL and builder are
+/// retained, until they are taken by the beacon chain.
+const PAYLOAD_REQUEST_TIMINGS_LRU_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(8);
+
 /// A fee recipient address for use during block production. Only used as a very last resort if
 /// there is no address provided by the user.
 ///
@@ -329,6 +333,16 @@ pub struct BuilderParams {
     pub chain_health: ChainHealth,
 }
 
+/// The durations of the requests made to the local EL and the builder when fetching a payload from
+/// both concurrently.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PayloadRequestTimings {
+    pub local: Duration,
+    pub builder_header: Duration,
+    /// `true` if the builder returned a bid.
+    pub builder_header_success: bool,
+}
+
 #[derive(PartialEq)]
 pub enum ChainHealth {
     Healthy,
@@ -353,6 +367,7 @@ struct Inner<E: EthSpec> {
     suggested_fee_recipient: Option<Address>,
     proposer_preparation_data: Mutex<HashMap<u64, ProposerPreparationDataEntry>>,
     execution_blocks: Mutex<LruCache<ExecutionBlockHash, ExecutionBlock>>,
+    payload_request_timings: Mutex<LruCache<Slot, PayloadRequestTimings>>,
     proposers: RwLock<HashMap<ProposerKey, Proposer>>,
     executor: TaskExecutor,
     payload_cache: PayloadCache<E>,
@@ -462,6 +477,9 @@ impl<E: EthSpec> ExecutionLayer<E> {
             proposer_preparation_data: Mutex::new(HashMap::new()),
             proposers: RwLock::new(HashMap::new()),
             execution_blocks: Mutex::new(LruCache::new(EXECUTION_BLOCKS_LRU_CACHE_SIZE)),
+            payload_request_timings: Mutex::new(LruCache::new(
+                PAYLOAD_REQUEST_TIMINGS_LRU_CACHE_SIZE,
+            )),
             executor,
             payload_cache: PayloadCache::default(),
             log,
@@ -560,6 +578,12 @@ impl<E: EthSpec> ExecutionLayer<E> {
         self.inner.execution_blocks.lock().await
     }
 
+    /// Returns the timings of the payload requests made to the local EL and the builder for
+    /// `slot`, if both were contacted.
+    pub async fn take_payload_request_timings(&self, slot: Slot) -> Option<PayloadRequestTimings> {
+        self.inner.payload_request_timings.lock().await.pop(&slot)
+    }
+
     /// Gives access to a channel containing if the last engine state is online or not.
     ///
     /// This can be called several times.
@@ -966,6 +990,15 @@ impl<E: EthSpec> ExecutionLayer<E> {
             "parent_hash" => ?parent_hash,
         );
 
+        self.inner.payload_request_timings.lock().await.put(
+            slot,
+            PayloadRequestTimings {
+                local: local_duration,
+                builder_header: relay_duration,
+                builder_header_success: matches!(relay_result, Ok(Some(_))),
+            },
+        );
+
         (relay_result, local_result)
     }
 
@@ -0,0 +1,310 @@
+//! Optional API keys for the HTTP API.
+//!
+//! Each key is allowed to access a set of route groups and may be subject to a token-bucket rate
+//! limit. Requests without a key are handled according to the optional `anonymous` policy, and
+//! are rejected if there is none.
+use crate::metrics;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use warp::http::Method;
+use warp::path::FullPath;
+use warp::Filter;
+use warp_utils::reject::{forbidden, too_many_requests, unauthorized};
+
+/// The name used for requests without an API key in logs and metrics.
+const ANONYMOUS: &str = "anonymous";
+
+/// A set of routes to which access is granted as a whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RouteGroup {
+    /// Standard endpoints which read chain data, e.g. `/eth/v1/beacon/*` or `/eth/v1/node/*`.
+    Public,
+    /// Endpoints used by validator clients, i.e. `/eth/*/validator/*` and the endpoints used to
+    /// publish blocks and operations.
+    Validator,
+    /// `/eth/*/debug/*`, which serve full states and fork choice.
+    Debug,
+    /// The non-standard `/lighthouse/*` endpoints.
+    LighthouseAdmin,
+}
+
+impl RouteGroup {
+    /// Returns the group of the route which serves `method` and `path`.
+    pub fn from_request(method: &Method, path: &str) -> Self {
+        let segments = path.trim_start_matches('/').split('/').collect::<Vec<_>>();
+
+        match segments.as_slice() {
+            ["lighthouse", ..] => RouteGroup::LighthouseAdmin,
+            ["eth", _, "debug", ..] => RouteGroup::Debug,
+            ["eth", _, "validator", ..] => RouteGroup::Validator,
+            ["eth", _, "beacon", "blocks" | "blinded_blocks"]
+            | ["eth", _, "beacon", "pool", ..]
+                if method == Method::POST =>
+            {
+                RouteGroup::Validator
+            }
+            _ => RouteGroup::Public,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            RouteGroup::Public => "public",
+            RouteGroup::Validator => "validator",
+            RouteGroup::Debug => "debug",
+            RouteGroup::LighthouseAdmin => "lighthouse-admin",
+        }
+    }
+}
+
+/// A token-bucket rate limit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimit {
+    /// The rate at which tokens are added to the bucket.
+    pub requests_per_second: f64,
+    /// The size of the bucket, i.e. the number of requests which may be made at once.
+    pub burst: u32,
+}
+
+/// The route groups and rate limit which apply to a client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccessPolicy {
+    pub groups: Vec<RouteGroup>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// An API key, sent by clients in an `Authorization: Bearer <key>` header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKey {
+    /// The name of the key in logs and metrics.
+    pub name: String,
+    pub key: String,
+    pub groups: Vec<RouteGroup>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// The contents of the file passed to `--http-api-keys-file`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeysFile {
+    /// The policy for requests without an API key. They are rejected if this is `None`.
+    #[serde(default)]
+    pub anonymous: Option<AccessPolicy>,
+    #[serde(default)]
+    pub keys: Vec<ApiKey>,
+}
+
+struct TokenBucket {
+    rate_limit: RateLimit,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate_limit: RateLimit) -> Self {
+        Self {
+            tokens: f64::from(rate_limit.burst),
+            rate_limit,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take a token from the bucket, returning `false` if it is empty.
+    fn try_take(&mut self, now: Instant) -> bool {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate_limit.requests_per_second)
+            .min(f64::from(self.rate_limit.burst));
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+struct Client {
+    name: String,
+    groups: Vec<RouteGroup>,
+    bucket: Option<Mutex<TokenBucket>>,
+}
+
+impl Client {
+    fn new(name: String, groups: Vec<RouteGroup>, rate_limit: Option<RateLimit>) -> Self {
+        Self {
+            name,
+            groups,
+            bucket: rate_limit.map(|rate_limit| Mutex::new(TokenBucket::new(rate_limit))),
+        }
+    }
+}
+
+/// Checks requests against the policies of an `ApiKeysFile`.
+pub struct AccessControl {
+    clients: HashMap<String, Client>,
+    anonymous: Option<Client>,
+}
+
+impl AccessControl {
+    pub fn new(file: ApiKeysFile) -> Result<Self, String> {
+        let rate_limits = file
+            .anonymous
+            .iter()
+            .filter_map(|policy| policy.rate_limit.as_ref())
+            .chain(file.keys.iter().filter_map(|key| key.rate_limit.as_ref()));
+        for rate_limit in rate_limits {
+            if rate_limit.requests_per_second.is_nan()
+                || rate_limit.requests_per_second <= 0.0
+                || rate_limit.burst == 0
+            {
+                return Err(format!(
+                    "rate limits must be greater than zero: {:?}",
+                    rate_limit
+                ));
+            }
+        }
+
+        let mut clients = HashMap::with_capacity(file.keys.len());
+        for key in file.keys {
+            if key.key.is_empty() {
+                return Err(format!("the API key {} is empty", key.name));
+            }
+            if key.name == ANONYMOUS || clients.values().any(|c: &Client| c.name == key.name) {
+                return Err(format!("the API key name {} is not unique", key.name));
+            }
+            if clients.contains_key(&key.key) {
+                return Err(format!("the API key {} is not unique", key.name));
+            }
+            clients.insert(key.key, Client::new(key.name, key.groups, key.rate_limit));
+        }
+
+        Ok(Self {
+            clients,
+            anonymous: file
+                .anonymous
+                .map(|policy| Client::new(ANONYMOUS.to_string(), policy.groups, policy.rate_limit)),
+        })
+    }
+
+    /// Read the API keys from a YAML file.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|e| format!("Unable to open API keys file {:?}: {}", path, e))?;
+        let api_keys = serde_yaml::from_reader(file)
+            .map_err(|e| format!("Unable to parse API keys file {:?}: {}", path, e))?;
+        Self::new(api_keys).map_err(|e| format!("Invalid API keys file {:?}: {}", path, e))
+    }
+
+    /// Returns the number of API keys, excluding the anonymous policy.
+    pub fn num_keys(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Check whether a request with the given `Authorization` header may access `path`.
+    pub fn check(
+        &self,
+        method: &Method,
+        path: &str,
+        authorization: Option<&str>,
+    ) -> Result<(), warp::Rejection> {
+        let group = RouteGroup::from_request(method, path);
+        let reject = |name: &str, reason: &str, rejection: warp::Rejection| {
+            metrics::inc_counter_vec(
+                &metrics::HTTP_API_ACCESS_REJECTIONS_TOTAL,
+                &[name, group.as_str(), reason],
+            );
+            Err(rejection)
+        };
+
+        let client = match authorization {
+            Some(header) => {
+                let client = header
+                    .strip_prefix("Bearer ")
+                    .and_then(|key| self.clients.get(key.trim()));
+                match client {
+                    Some(client) => client,
+                    None => {
+                        return reject(
+                            "unknown",
+                            "invalid_key",
+                            unauthorized("invalid API key".to_string()),
+                        )
+                    }
+                }
+            }
+            None => match &self.anonymous {
+                Some(client) => client,
+                None => {
+                    return reject(
+                        ANONYMOUS,
+                        "missing_key",
+                        unauthorized("an API key is required".to_string()),
+                    )
+                }
+            },
+        };
+
+        if !client.groups.contains(&group) {
+            return reject(
+                client.name.as_str(),
+                "forbidden",
+                forbidden(format!(
+                    "{} may not access {} endpoints",
+                    client.name,
+                    group.as_str()
+                )),
+            );
+        }
+
+        if let Some(bucket) = &client.bucket {
+            if !bucket.lock().try_take(Instant::now()) {
+                return reject(
+                    client.name.as_str(),
+                    "rate_limited",
+                    too_many_requests(format!("rate limit exceeded for {}", client.name)),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a filter which rejects requests which are not allowed by `access_control`, or accepts
+/// every request if it is `None`.
+pub fn access_control_filter(
+    access_control: Option<Arc<AccessControl>>,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("Authorization"))
+        .and_then(
+            move |method: Method, path: FullPath, authorization: Option<String>| {
+                let access_control = access_control.clone();
+                async move {
+                    match access_control {
+                        Some(access_control) => {
+                            access_control.check(&method, path.as_str(), authorization.as_deref())
+                        }
+                        None => Ok(()),
+                    }
+                }
+            },
+        )
+        .untuple_one()
+}
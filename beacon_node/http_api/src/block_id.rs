@@ -258,9 +258,23 @@ impl BlockId {
         chain: &BeaconChain<T>,
     ) -> Result<BlobSidecarList<T::EthSpec>, warp::Rejection> {
         let root = self.root(chain)?.0;
-        chain
+        let blob_sidecar_list = chain
             .get_blobs(&root)
-            .map_err(warp_utils::reject::beacon_chain_error)
+            .map_err(warp_utils::reject::beacon_chain_error)?;
+        if !blob_sidecar_list.is_empty() || chain.store.get_config().blob_archive_dir.is_none() {
+            return Ok(blob_sidecar_list);
+        }
+
+        // The blobs may have been pruned into the blob archive, which is indexed by slot.
+        match chain
+            .get_blinded_block(&root)
+            .map_err(warp_utils::reject::beacon_chain_error)?
+        {
+            Some(block) => chain
+                .get_blobs_or_archived(&root, block.slot())
+                .map_err(warp_utils::reject::beacon_chain_error),
+            None => Ok(blob_sidecar_list),
+        }
     }
 
     pub fn blob_sidecar_list_filtered<T: BeaconChainTypes>(
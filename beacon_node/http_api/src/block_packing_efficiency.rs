@@ -1,28 +1,37 @@
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
 use eth2::lighthouse::{
-    BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
+    BlockPackingEfficiency, BlockPackingEfficiencyQuery, PackingRewards, ProposerInfo,
+    UniqueAttestation,
 };
+use operation_pool::{maximum_cover, AttMaxCover, MaxCover, RewardCache, SplitAttestation};
 use parking_lot::Mutex;
 use state_processing::{
+    common::get_attesting_indices_from_state, epoch_cache::initialize_epoch_cache,
     per_epoch_processing::EpochProcessingSummary, BlockReplayError, BlockReplayer,
 };
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use types::{
-    AttestationRef, BeaconCommittee, BeaconState, BeaconStateError, BlindedPayload, ChainSpec,
-    Epoch, EthSpec, Hash256, OwnedBeaconCommittee, RelativeEpoch, SignedBeaconBlock, Slot,
+    Attestation, AttestationData, AttestationRef, BeaconCommittee, BeaconState, BeaconStateError,
+    BlindedPayload, ChainSpec, Epoch, EpochCacheError, EthSpec, ForkName, Hash256,
+    OwnedBeaconCommittee, RelativeEpoch, SignedBeaconBlock, Slot, Unsigned,
 };
 use warp_utils::reject::{beacon_chain_error, custom_bad_request, custom_server_error};
 
 /// Load blocks from block roots in chunks to reduce load on memory.
 const BLOCK_ROOT_CHUNK_SIZE: usize = 100;
 
+/// The label of the max-cover metrics set while computing packing rewards.
+const MAX_COVER_LABEL: &str = "block_packing_analysis";
+
 #[derive(Debug)]
 // We don't use the inner values directly, but they're used in the Debug impl.
 enum PackingEfficiencyError {
     BlockReplay(#[allow(dead_code)] BlockReplayError),
     BeaconState(#[allow(dead_code)] BeaconStateError),
+    BeaconChain(#[allow(dead_code)] BeaconChainError),
+    EpochCache(#[allow(dead_code)] EpochCacheError),
     CommitteeStoreError(#[allow(dead_code)] Slot),
     InvalidAttestationError,
 }
@@ -39,6 +48,18 @@ impl From<BeaconStateError> for PackingEfficiencyError {
     }
 }
 
+impl From<BeaconChainError> for PackingEfficiencyError {
+    fn from(e: BeaconChainError) -> Self {
+        Self::BeaconChain(e)
+    }
+}
+
+impl From<EpochCacheError> for PackingEfficiencyError {
+    fn from(e: EpochCacheError) -> Self {
+        Self::EpochCache(e)
+    }
+}
+
 struct CommitteeStore {
     current_epoch_committees: Vec<OwnedBeaconCommittee>,
     previous_epoch_committees: Vec<OwnedBeaconCommittee>,
@@ -103,15 +124,24 @@ impl<E: EthSpec> PackingEfficiencyHandler<E> {
             .retain(|x| x.slot >= (slot.as_u64().saturating_sub(E::slots_per_epoch())));
     }
 
+    /// Apply the attestations of `block`, returning the number of new attestations it included and
+    /// the number of committees aggregated into each of its attestations.
     fn apply_block(
         &mut self,
         block: &SignedBeaconBlock<E, BlindedPayload<E>>,
-    ) -> Result<usize, PackingEfficiencyError> {
+    ) -> Result<(usize, Vec<usize>), PackingEfficiencyError> {
         let block_body = block.message().body();
         let attestations = block_body.attestations();
 
         let mut attestations_in_block = HashMap::new();
+        let mut committees_per_attestation = Vec::new();
         for attestation in attestations {
+            let inclusion_distance: u64 = block
+                .slot()
+                .as_u64()
+                .checked_sub(attestation.data().slot.as_u64())
+                .ok_or(PackingEfficiencyError::InvalidAttestationError)?;
+
             match attestation {
                 AttestationRef::Base(attn) => {
                     for (position, voted) in attn.aggregation_bits.iter().enumerate() {
@@ -121,35 +151,41 @@ impl<E: EthSpec> PackingEfficiencyHandler<E> {
                                 committee_index: attn.data.index,
                                 committee_position: position,
                             };
-                            let inclusion_distance: u64 = block
-                                .slot()
-                                .as_u64()
-                                .checked_sub(attn.data.slot.as_u64())
-                                .ok_or(PackingEfficiencyError::InvalidAttestationError)?;
 
                             self.available_attestations.remove(&unique_attestation);
                             attestations_in_block.insert(unique_attestation, inclusion_distance);
                         }
                     }
+                    committees_per_attestation.push(1);
                 }
                 AttestationRef::Electra(attn) => {
-                    for (position, voted) in attn.aggregation_bits.iter().enumerate() {
-                        if voted {
-                            let unique_attestation = UniqueAttestation {
-                                slot: attn.data.slot,
-                                committee_index: attn.data.index,
-                                committee_position: position,
-                            };
-                            let inclusion_distance: u64 = block
-                                .slot()
-                                .as_u64()
-                                .checked_sub(attn.data.slot.as_u64())
-                                .ok_or(PackingEfficiencyError::InvalidAttestationError)?;
-
-                            self.available_attestations.remove(&unique_attestation);
-                            attestations_in_block.insert(unique_attestation, inclusion_distance);
+                    // The aggregation bits are the concatenation of the bits of each committee
+                    // set in the committee bits, in order of committee index.
+                    let committee_indices = attn.get_committee_indices();
+                    let mut offset = 0;
+                    for &committee_index in &committee_indices {
+                        let committee_len =
+                            self.get_committee_len(attn.data.slot, committee_index)?;
+                        for position in 0..committee_len {
+                            let voted = attn
+                                .aggregation_bits
+                                .get(offset + position)
+                                .map_err(|_| PackingEfficiencyError::InvalidAttestationError)?;
+                            if voted {
+                                let unique_attestation = UniqueAttestation {
+                                    slot: attn.data.slot,
+                                    committee_index,
+                                    committee_position: position,
+                                };
+
+                                self.available_attestations.remove(&unique_attestation);
+                                attestations_in_block
+                                    .insert(unique_attestation, inclusion_distance);
+                            }
                         }
+                        offset += committee_len;
                     }
+                    committees_per_attestation.push(committee_indices.len());
                 }
             }
         }
@@ -159,7 +195,7 @@ impl<E: EthSpec> PackingEfficiencyHandler<E> {
         self.included_attestations
             .extend(attestations_in_block.clone());
 
-        Ok(attestations_in_block.len())
+        Ok((attestations_in_block.len(), committees_per_attestation))
     }
 
     fn add_attestations(&mut self, slot: Slot) -> Result<(), PackingEfficiencyError> {
@@ -234,6 +270,173 @@ impl<E: EthSpec> PackingEfficiencyHandler<E> {
 
         Ok(committees)
     }
+
+    fn get_committee_len(&self, slot: Slot, index: u64) -> Result<usize, PackingEfficiencyError> {
+        self.committee_store
+            .current_epoch_committees
+            .iter()
+            .chain(&self.committee_store.previous_epoch_committees)
+            .find(|committee| committee.slot == slot && committee.index == index)
+            .map(|committee| committee.committee.len())
+            .ok_or(PackingEfficiencyError::CommitteeStoreError(slot))
+    }
+}
+
+/// Compute the proposer rewards for the attestations included in `block`, for the max-cover
+/// packing of the `on_chain_attestations` which could have been included instead, and an upper
+/// bound on the reward of any packing of them.
+///
+/// The `state` must be the pre-state of `block`, advanced to its slot.
+fn compute_packing_rewards<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    state: &mut BeaconState<T::EthSpec>,
+    block: &SignedBeaconBlock<T::EthSpec, BlindedPayload<T::EthSpec>>,
+    on_chain_attestations: &BTreeMap<Slot, Vec<Attestation<T::EthSpec>>>,
+    reward_cache: &mut RewardCache,
+) -> Result<PackingRewards, PackingEfficiencyError> {
+    let spec = &chain.spec;
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+
+    state.build_all_committee_caches(spec)?;
+    state.build_total_active_balance_cache(spec)?;
+    initialize_epoch_cache(state, spec)?;
+
+    // This also updates the reward cache for the state.
+    let included = chain
+        .compute_block_reward(
+            block.message(),
+            block.canonical_root(),
+            state,
+            reward_cache,
+            false,
+        )?
+        .attestation_rewards
+        .total;
+    let state = &*state;
+    let reward_cache = &*reward_cache;
+
+    let slot = block.slot();
+    let current_epoch = state.current_epoch();
+    let previous_epoch = state.previous_epoch();
+    let fork_name = block.fork_name_unchecked();
+    let limit = max_attestations::<T::EthSpec>(fork_name);
+
+    // An attestation which could have been included in this block can be included on chain until
+    // the end of the next epoch at the latest.
+    let candidates = on_chain_attestations
+        .range(slot..=(current_epoch + 1).end_slot(slots_per_epoch))
+        .flat_map(|(_, attestations)| attestations)
+        .filter(|attestation| {
+            let data = attestation.data();
+            matches!(attestation, Attestation::Electra(_)) == fork_name.electra_enabled()
+                && data.slot + spec.min_attestation_inclusion_delay <= slot
+                && (fork_name.deneb_enabled() || slot <= data.slot + slots_per_epoch)
+                && (data.target.epoch == current_epoch || data.target.epoch == previous_epoch)
+        })
+        .map(|attestation| {
+            let attesting_indices = get_attesting_indices_from_state(state, attestation.to_ref())?;
+            Ok(SplitAttestation::new(
+                attestation.clone(),
+                attesting_indices,
+            ))
+        })
+        .collect::<Result<Vec<_>, PackingEfficiencyError>>()?;
+
+    let total_active_balance = state.get_total_active_balance()?;
+    let covers = candidates
+        .iter()
+        .filter_map(|attestation| {
+            AttMaxCover::new(
+                attestation.as_ref(),
+                state,
+                reward_cache,
+                total_active_balance,
+                spec,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Pack the attestations for each target epoch separately, as the op pool does.
+    let (prev_epoch_covers, curr_epoch_covers): (Vec<_>, Vec<_>) = covers
+        .iter()
+        .cloned()
+        .partition(|cover| cover.att.attestation_data().target.epoch == previous_epoch);
+    let max_cover_scores = maximum_cover(prev_epoch_covers, limit, MAX_COVER_LABEL)
+        .into_iter()
+        .chain(maximum_cover(curr_epoch_covers, limit, MAX_COVER_LABEL))
+        .map(|cover| cover.score() as u64)
+        .collect();
+
+    // Validators can't attest to two attestation datas with the same target without being
+    // slashable, so no packing can earn more than the attestation datas with the greatest rewards,
+    // each with all of its aggregates combined. This isn't necessarily achievable, as the
+    // aggregates may overlap and not be combinable.
+    let mut rewards_by_data = HashMap::<AttestationData, HashMap<u64, u64>>::new();
+    for cover in &covers {
+        rewards_by_data
+            .entry(cover.att.attestation_data())
+            .or_default()
+            .extend(&cover.fresh_validators_rewards);
+    }
+    let upper_bound_scores = rewards_by_data
+        .values()
+        .map(|rewards| rewards.values().sum())
+        .collect();
+
+    Ok(PackingRewards {
+        included,
+        max_cover: sum_of_greatest(max_cover_scores, limit),
+        upper_bound: sum_of_greatest(upper_bound_scores, limit),
+        candidate_aggregates: candidates.len(),
+    })
+}
+
+fn max_attestations<E: EthSpec>(fork_name: ForkName) -> usize {
+    if fork_name.electra_enabled() {
+        E::MaxAttestationsElectra::to_usize()
+    } else {
+        E::MaxAttestations::to_usize()
+    }
+}
+
+fn sum_of_greatest(mut values: Vec<u64>, limit: usize) -> u64 {
+    values.sort_unstable_by(|a, b| b.cmp(a));
+    values.into_iter().take(limit).sum()
+}
+
+/// Load the attestations included in the canonical blocks from `start_slot` to `end_slot`,
+/// keyed by the slot of their block.
+fn load_on_chain_attestations<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    start_slot: Slot,
+    end_slot: Slot,
+) -> Result<BTreeMap<Slot, Vec<Attestation<T::EthSpec>>>, warp::Rejection> {
+    let mut block_roots: Vec<Hash256> = chain
+        .forwards_iter_block_roots_until(start_slot, end_slot)
+        .map_err(beacon_chain_error)?
+        .map(|res| res.map(|(root, _)| root))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(beacon_chain_error)?;
+    block_roots.dedup();
+
+    let mut attestations = BTreeMap::new();
+    for root in block_roots {
+        let block = chain
+            .get_blinded_block(&root)
+            .and_then(|maybe_block| maybe_block.ok_or(BeaconChainError::MissingBeaconBlock(root)))
+            .map_err(beacon_chain_error)?;
+        attestations.insert(
+            block.slot(),
+            block
+                .message()
+                .body()
+                .attestations()
+                .map(|attestation| attestation.clone_as_attestation())
+                .collect(),
+        );
+    }
+
+    Ok(attestations)
 }
 
 pub fn get_block_packing_efficiency<T: BeaconChainTypes>(
@@ -292,6 +495,22 @@ pub fn get_block_packing_efficiency<T: BeaconChainTypes>(
         })
         .map_err(beacon_chain_error)?;
 
+    // Load the attestations which could have been included in the queried blocks, if required.
+    let on_chain_attestations = if query.include_rewards {
+        let head_slot = chain.canonical_head.cached_head().head_slot();
+        load_on_chain_attestations(
+            &chain,
+            start_slot_of_prior_epoch,
+            std::cmp::min(
+                (end_epoch + 1).end_slot(T::EthSpec::slots_per_epoch()),
+                head_slot,
+            ),
+        )?
+    } else {
+        BTreeMap::new()
+    };
+    let mut reward_cache = RewardCache::default();
+
     // Initialize response vector.
     let mut response = Vec::new();
 
@@ -333,7 +552,7 @@ pub fn get_block_packing_efficiency<T: BeaconChainTypes>(
         Ok(())
     };
 
-    let pre_block_hook = |_state: &mut BeaconState<T::EthSpec>,
+    let pre_block_hook = |state: &mut BeaconState<T::EthSpec>,
                           block: &SignedBeaconBlock<_, BlindedPayload<_>>|
      -> Result<(), PackingEfficiencyError> {
         let slot = block.slot();
@@ -351,7 +570,22 @@ pub fn get_block_packing_efficiency<T: BeaconChainTypes>(
         let available_count = handler.lock().available_attestations.len();
 
         // Get all attestations included in the block.
-        let included = handler.lock().apply_block(block)?;
+        let (included, committees_per_attestation) = handler.lock().apply_block(block)?;
+
+        let unused_attestation_slots = max_attestations::<T::EthSpec>(block.fork_name_unchecked())
+            .saturating_sub(committees_per_attestation.len());
+
+        let rewards = if query.include_rewards && slot >= start_slot {
+            Some(compute_packing_rewards(
+                &chain,
+                state,
+                block,
+                &on_chain_attestations,
+                &mut reward_cache,
+            )?)
+        } else {
+            None
+        };
 
         let efficiency = BlockPackingEfficiency {
             slot,
@@ -360,6 +594,9 @@ pub fn get_block_packing_efficiency<T: BeaconChainTypes>(
             available_attestations: available_count,
             included_attestations: included,
             prior_skip_slots: handler.lock().prior_skip_slots,
+            committees_per_attestation,
+            unused_attestation_slots,
+            rewards,
         };
 
         // Write to response.
@@ -0,0 +1,171 @@
+use crate::version::add_ssz_content_type_header;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{encode_frame, BlockStreamQuery};
+use futures::{stream, StreamExt};
+use slog::warn;
+use ssz::Encode;
+use std::sync::Arc;
+use types::{Hash256, Slot};
+use warp::http::Response;
+use warp::hyper::Body;
+use warp_utils::reject::{custom_bad_request, custom_server_error};
+
+/// The number of slots for which blocks are loaded at a time.
+///
+/// The next batch is only loaded once the previous one has been written to the client, which
+/// bounds the memory used by a slow client.
+const SLOTS_PER_BATCH: u64 = 32;
+
+/// The maximum number of slots which may be requested at once.
+const MAX_SLOTS_PER_REQUEST: u64 = 8192;
+
+/// Returns a response which streams the canonical blocks in the range described by `query` as
+/// length-prefixed SSZ.
+///
+/// See `eth2::lighthouse::BlockStreamDecoder` for a description of the encoding.
+pub fn get_block_stream<T: BeaconChainTypes>(
+    query: BlockStreamQuery,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Response<Body>, warp::Rejection> {
+    let BlockStreamQuery {
+        start_slot,
+        end_slot,
+        blobs,
+    } = query;
+
+    if start_slot > end_slot {
+        return Err(custom_bad_request(format!(
+            "start_slot ({}) must not be greater than end_slot ({})",
+            start_slot, end_slot
+        )));
+    }
+    if end_slot - start_slot >= MAX_SLOTS_PER_REQUEST {
+        return Err(custom_bad_request(format!(
+            "cannot stream more than {} slots at once",
+            MAX_SLOTS_PER_REQUEST
+        )));
+    }
+    let head_slot = chain.canonical_head.cached_head().head_slot();
+    if end_slot > head_slot {
+        return Err(custom_bad_request(format!(
+            "end_slot ({}) is later than the head slot ({})",
+            end_slot, head_slot
+        )));
+    }
+    let oldest_block_slot = chain.store.get_oldest_block_slot();
+    if start_slot < oldest_block_slot {
+        return Err(custom_bad_request(format!(
+            "start_slot ({}) is prior to the oldest block in the database ({})",
+            start_slot, oldest_block_slot
+        )));
+    }
+
+    let batch_starts = (start_slot.as_u64()..=end_slot.as_u64())
+        .step_by(SLOTS_PER_BATCH as usize)
+        .map(Slot::new);
+
+    let body = stream::iter(batch_starts)
+        .then(move |batch_start| {
+            let batch_end = std::cmp::min(batch_start + SLOTS_PER_BATCH - 1, end_slot);
+            let chain = chain.clone();
+            async move {
+                let result = encode_batch(&chain, batch_start, batch_end, blobs).await;
+                if let Err(e) = &result {
+                    warn!(
+                        chain.log,
+                        "Failed to stream blocks";
+                        "error" => e,
+                        "start_slot" => batch_start,
+                        "end_slot" => batch_end,
+                    );
+                }
+                result
+            }
+        })
+        // Stop after the first error, which will abort the response.
+        .scan(false, |failed, result| {
+            let item = (!*failed).then(|| {
+                *failed = result.is_err();
+                result
+            });
+            async move { item }
+        });
+
+    Response::builder()
+        .status(200)
+        .body(Body::wrap_stream(body))
+        .map(add_ssz_content_type_header)
+        .map_err(|e| custom_server_error(format!("failed to create response: {}", e)))
+}
+
+/// Load the canonical blocks between `start_slot` and `end_slot` (inclusive) and encode them as
+/// frames.
+async fn encode_batch<T: BeaconChainTypes>(
+    chain: &Arc<BeaconChain<T>>,
+    start_slot: Slot,
+    end_slot: Slot,
+    blobs: bool,
+) -> Result<Vec<u8>, String> {
+    let block_roots = {
+        let chain = chain.clone();
+        chain
+            .task_executor
+            .clone()
+            .spawn_blocking_handle(
+                move || {
+                    let mut block_roots = chain
+                        .forwards_iter_block_roots_until(start_slot, end_slot)?
+                        .map(|result| result.map(|(block_root, _)| block_root))
+                        .collect::<Result<Vec<Hash256>, _>>()?;
+                    // Skipped slots repeat the root of the previous block.
+                    block_roots.dedup();
+                    Ok::<_, beacon_chain::BeaconChainError>(block_roots)
+                },
+                "http_block_stream_roots",
+            )
+            .ok_or("runtime shutting down")?
+            .await
+            .map_err(|e| format!("{:?}", e))?
+            .map_err(|e| format!("{:?}", e))?
+    };
+
+    let mut blocks = Vec::with_capacity(block_roots.len());
+    let mut block_stream = chain
+        .get_blocks(block_roots)
+        .map_err(|e| format!("{:?}", e))?;
+    while let Some((block_root, result)) = block_stream.next().await {
+        let block = match result.as_ref() {
+            Ok(Some(block)) => block.clone(),
+            Ok(None) => return Err(format!("missing block {:?}", block_root)),
+            Err(e) => return Err(format!("{:?}", e)),
+        };
+        // The first root may belong to a block prior to the batch if its first slot was skipped.
+        if block.slot() >= start_slot {
+            blocks.push((block_root, block));
+        }
+    }
+
+    let chain = chain.clone();
+    chain
+        .task_executor
+        .clone()
+        .spawn_blocking_handle(
+            move || {
+                let mut bytes = vec![];
+                for (block_root, block) in blocks {
+                    encode_frame(&block.as_ssz_bytes(), &mut bytes);
+                    if blobs {
+                        let blob_sidecars = chain
+                            .get_blobs(&block_root)
+                            .map_err(|e| format!("{:?}", e))?;
+                        encode_frame(&blob_sidecars.as_ssz_bytes(), &mut bytes);
+                    }
+                }
+                Ok::<_, String>(bytes)
+            },
+            "http_block_stream_encode",
+        )
+        .ok_or("runtime shutting down")?
+        .await
+        .map_err(|e| format!("{:?}", e))?
+}
@@ -1,7 +1,14 @@
 use beacon_chain::store::metadata::CURRENT_SCHEMA_VERSION;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use eth2::lighthouse::DatabaseInfo;
+use eth2::lighthouse::{DatabaseInfo, DatabaseSnapshotJob, DatabaseSnapshotStatus};
+use parking_lot::Mutex;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use warp_utils::reject::{conflict, custom_not_found, custom_server_error, forbidden};
+
+/// The directory within the data directory to which snapshots are written.
+pub const SNAPSHOTS_DIR: &str = "snapshots";
 
 pub fn info<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
@@ -20,3 +27,131 @@ pub fn info<T: BeaconChainTypes>(
         blob_info,
     })
 }
+
+/// The snapshots started through the API since the beacon node started, indexed by job ID.
+#[derive(Default)]
+pub struct SnapshotJobs {
+    jobs: Mutex<Vec<DatabaseSnapshotJob>>,
+}
+
+impl SnapshotJobs {
+    fn set_status(&self, job_id: u64, status: DatabaseSnapshotStatus) {
+        if let Some(job) = self.jobs.lock().get_mut(job_id as usize) {
+            job.status = status;
+        }
+    }
+}
+
+/// A running snapshot job, which is marked as failed if it's dropped before being finished.
+///
+/// This happens if the snapshot task panics, or is never run because the executor is shutting
+/// down, so that the job is never left running forever.
+struct RunningSnapshot {
+    jobs: Arc<SnapshotJobs>,
+    job_id: u64,
+    finished: bool,
+}
+
+impl RunningSnapshot {
+    fn finish(mut self, status: DatabaseSnapshotStatus) {
+        self.jobs.set_status(self.job_id, status);
+        self.finished = true;
+    }
+}
+
+impl Drop for RunningSnapshot {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.jobs.set_status(
+                self.job_id,
+                DatabaseSnapshotStatus::Failed {
+                    error: "snapshot task ended before completing".to_string(),
+                },
+            );
+        }
+    }
+}
+
+/// Start writing a snapshot of the database to a new directory within `data_dir`, returning the
+/// job without waiting for it to complete.
+///
+/// Since this writes a copy of the whole database to disk, it requires an API key: it's
+/// unavailable without `--http-api-keys-file`, and the anonymous policy never suffices. Only one
+/// snapshot may be written at a time.
+pub fn start_snapshot<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    data_dir: PathBuf,
+    jobs: Arc<SnapshotJobs>,
+    access_control_enabled: bool,
+    authorization: Option<String>,
+) -> Result<DatabaseSnapshotJob, warp::Rejection> {
+    // Requests with an invalid key are rejected by the access control filter, so any key which
+    // reaches this point is valid.
+    if !access_control_enabled || authorization.is_none() {
+        return Err(forbidden(
+            "database snapshots require an API key from --http-api-keys-file".to_string(),
+        ));
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| custom_server_error(format!("invalid system time: {:?}", e)))?
+        .as_secs();
+
+    let job = {
+        let mut jobs = jobs.jobs.lock();
+        if let Some(running) = jobs
+            .iter()
+            .find(|job| job.status == DatabaseSnapshotStatus::Running)
+        {
+            return Err(conflict(format!(
+                "snapshot {} is already running",
+                running.job_id
+            )));
+        }
+        let job_id = jobs.len() as u64;
+        let job = DatabaseSnapshotJob {
+            job_id,
+            path: data_dir
+                .join(SNAPSHOTS_DIR)
+                .join(format!("snapshot_{}_{}", timestamp, job_id)),
+            status: DatabaseSnapshotStatus::Running,
+        };
+        jobs.push(job.clone());
+        job
+    };
+
+    // The snapshot may take a long time to copy, so run it outside of the beacon processor.
+    let running = RunningSnapshot {
+        jobs,
+        job_id: job.job_id,
+        finished: false,
+    };
+    let path = job.path.clone();
+    let store = chain.store.clone();
+    chain.task_executor.spawn_blocking(
+        move || {
+            let status = match store.snapshot_database(&path) {
+                Ok(manifest) => DatabaseSnapshotStatus::Complete { manifest },
+                Err(e) => DatabaseSnapshotStatus::Failed {
+                    error: format!("{:?}", e),
+                },
+            };
+            running.finish(status);
+        },
+        "http_database_snapshot",
+    );
+
+    Ok(job)
+}
+
+pub fn snapshot_job(
+    jobs: &SnapshotJobs,
+    job_id: u64,
+) -> Result<DatabaseSnapshotJob, warp::Rejection> {
+    jobs.jobs
+        .lock()
+        .get(job_id as usize)
+        .cloned()
+        .ok_or_else(|| custom_not_found(format!("no snapshot job with ID {}", job_id)))
+}
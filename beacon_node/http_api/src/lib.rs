@@ -5,19 +5,24 @@
 //! There are also some additional, non-standard endpoints behind the `/lighthouse/` path which are
 //! used for development.
 
+mod access_control;
 mod attestation_performance;
 mod attester_duties;
 mod block_id;
 mod block_packing_efficiency;
 mod block_rewards;
+mod block_stream;
 mod build_block_contents;
 mod builder_states;
 mod database;
 mod metrics;
+mod pending_operations;
 mod produce_block;
 mod proposer_duties;
 mod publish_attestations;
 mod publish_blocks;
+mod resumable_events;
+mod slasher_queries;
 mod standard_block_rewards;
 mod state_id;
 mod sync_committee_rewards;
@@ -27,15 +32,18 @@ pub mod test_utils;
 mod ui;
 mod validator;
 mod validator_inclusion;
+mod validator_monitor_history;
 mod validators;
 mod version;
 
+use crate::access_control::{access_control_filter, AccessControl};
 use crate::produce_block::{produce_blinded_block_v2, produce_block_v2, produce_block_v3};
 use crate::version::fork_versioned_response;
 use beacon_chain::{
-    attestation_verification::VerifiedAttestation, observed_operations::ObservationOutcome,
-    validator_monitor::timestamp_now, AttestationError as AttnError, BeaconChain, BeaconChainError,
-    BeaconChainTypes, WhenSlotSkipped,
+    attestation_verification::VerifiedAttestation, event_log::EventLogConfig,
+    observed_operations::ObservationOutcome, validator_monitor::timestamp_now,
+    validator_sightings::MAX_REQUEST_INDICES, AttestationError as AttnError, BeaconChain,
+    BeaconChainError, BeaconChainTypes, WhenSlotSkipped,
 };
 use beacon_processor::{work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorSend};
 pub use block_id::BlockId;
@@ -44,10 +52,11 @@ use bytes::Bytes;
 use directory::DEFAULT_ROOT_DIR;
 use eth2::types::{
     self as api_types, BroadcastValidation, EndpointVersion, ForkChoice, ForkChoiceNode,
-    PublishBlockRequest, ValidatorBalancesRequestBody, ValidatorId, ValidatorStatus,
-    ValidatorsRequestBody,
+    PublishBlockRequest, ValidatorBalancesRequestBody, ValidatorId, ValidatorIdentitiesRequestBody,
+    ValidatorStatus, ValidatorsRequestBody,
 };
 use eth2::{CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER};
+use futures::stream::BoxStream;
 use lighthouse_network::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
 use lighthouse_version::version_with_platform;
 use logging::SSELoggingComponents;
@@ -62,6 +71,7 @@ use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use ssz::Encode;
 pub use state_id::StateId;
+use std::collections::HashSet;
 use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
@@ -89,7 +99,8 @@ use types::{
 use validator::pubkey_to_validator_index;
 use version::{
     add_consensus_version_header, add_ssz_content_type_header,
-    execution_optimistic_finalized_fork_versioned_response, inconsistent_fork_rejection,
+    execution_optimistic_finalized_fork_versioned_response,
+    execution_optimistic_finalized_ssz_or_json_response, inconsistent_fork_rejection,
     unsupported_version_rejection, V1, V2, V3,
 };
 use warp::http::StatusCode;
@@ -151,6 +162,11 @@ pub struct Config {
     #[serde(with = "eth2::types::serde_status_code")]
     pub duplicate_block_status_code: StatusCode,
     pub enable_light_client_server: bool,
+    pub event_log: Option<EventLogConfig>,
+    /// A YAML file containing API keys, see `access_control::ApiKeysFile`.
+    ///
+    /// Only the path is stored so that the keys are not included in dumps of the config.
+    pub api_keys_file: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -167,6 +183,8 @@ impl Default for Config {
             enable_beacon_processor: true,
             duplicate_block_status_code: StatusCode::ACCEPTED,
             enable_light_client_server: false,
+            event_log: None,
+            api_keys_file: None,
         }
     }
 }
@@ -328,7 +346,7 @@ pub fn serve<T: BeaconChainTypes>(
     let cors_builder = {
         let builder = warp::cors()
             .allow_methods(vec!["GET", "POST"])
-            .allow_headers(vec!["Content-Type"]);
+            .allow_headers(vec!["Content-Type", "Authorization"]);
 
         warp_utils::cors::set_builder_origins(
             builder,
@@ -345,6 +363,22 @@ pub fn serve<T: BeaconChainTypes>(
         ));
     }
 
+    // Load the API keys, if any.
+    let access_control = config
+        .api_keys_file
+        .as_deref()
+        .map(AccessControl::open)
+        .transpose()?
+        .map(Arc::new);
+    if let Some(access_control) = &access_control {
+        info!(
+            log,
+            "HTTP API access control enabled";
+            "api_keys" => access_control.num_keys(),
+        );
+    }
+    let access_control_enabled = access_control.is_some();
+
     // Create a filter that extracts the endpoint version.
     let any_version = warp::path(API_PREFIX).and(warp::path::param::<EndpointVersion>().or_else(
         |_| async move {
@@ -664,18 +698,21 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator_balances"))
         .and(warp::path::end())
         .and(multi_key_query::<api_types::ValidatorBalancesQuery>())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             query_res: Result<api_types::ValidatorBalancesQuery, warp::Rejection>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             query_res: Result<api_types::ValidatorBalancesQuery, warp::Rejection>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
                     let query = query_res?;
-                    crate::validators::get_beacon_state_validator_balances(
+                    let response = crate::validators::get_beacon_state_validator_balances(
                         state_id,
                         chain,
                         query.id.as_deref(),
-                    )
+                    )?;
+                    execution_optimistic_finalized_ssz_or_json_response(accept_header, response)
                 })
             },
         );
@@ -686,17 +723,42 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator_balances"))
         .and(warp::path::end())
         .and(warp_utils::json::json())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             query: ValidatorBalancesRequestBody| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
-                    crate::validators::get_beacon_state_validator_balances(
+             query: ValidatorBalancesRequestBody,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let response = crate::validators::get_beacon_state_validator_balances(
                         state_id,
                         chain,
                         Some(&query.ids),
-                    )
+                    )?;
+                    execution_optimistic_finalized_ssz_or_json_response(accept_header, response)
+                })
+            },
+        );
+
+    // POST beacon/states/{state_id}/validator_identities
+    let post_beacon_state_validator_identities = beacon_states_path
+        .clone()
+        .and(warp::path("validator_identities"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .then(
+            |state_id: StateId,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             query: ValidatorIdentitiesRequestBody,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let response = crate::validators::get_beacon_state_validator_identities(
+                        state_id, chain, &query.ids,
+                    )?;
+                    execution_optimistic_finalized_ssz_or_json_response(accept_header, response)
                 })
             },
         );
@@ -707,19 +769,22 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validators"))
         .and(warp::path::end())
         .and(multi_key_query::<api_types::ValidatorsQuery>())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             query_res: Result<api_types::ValidatorsQuery, warp::Rejection>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             query_res: Result<api_types::ValidatorsQuery, warp::Rejection>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
                     let query = query_res?;
-                    crate::validators::get_beacon_state_validators(
+                    let response = crate::validators::get_beacon_state_validators(
                         state_id,
                         chain,
                         &query.id,
                         &query.status,
-                    )
+                    )?;
+                    execution_optimistic_finalized_ssz_or_json_response(accept_header, response)
                 })
             },
         );
@@ -730,18 +795,21 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validators"))
         .and(warp::path::end())
         .and(warp_utils::json::json())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             query: ValidatorsRequestBody| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
-                    crate::validators::get_beacon_state_validators(
+             query: ValidatorsRequestBody,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let response = crate::validators::get_beacon_state_validators(
                         state_id,
                         chain,
                         &query.ids,
                         &query.statuses,
-                    )
+                    )?;
+                    execution_optimistic_finalized_ssz_or_json_response(accept_header, response)
                 })
             },
         );
@@ -824,12 +892,14 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("committees"))
         .and(warp::query::<api_types::CommitteesQuery>())
         .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             query: api_types::CommitteesQuery| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             query: api_types::CommitteesQuery,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
                     let (data, execution_optimistic, finalized) = state_id
                         .map_state_and_execution_optimistic_and_finalized(
                             &chain,
@@ -989,11 +1059,14 @@ pub fn serve<T: BeaconChainTypes>(
                                 Ok((response, execution_optimistic, finalized))
                             },
                         )?;
-                    Ok(api_types::ExecutionOptimisticFinalizedResponse {
-                        data,
-                        execution_optimistic: Some(execution_optimistic),
-                        finalized: Some(finalized),
-                    })
+                    execution_optimistic_finalized_ssz_or_json_response(
+                        accept_header,
+                        api_types::ExecutionOptimisticFinalizedResponse {
+                            data,
+                            execution_optimistic: Some(execution_optimistic),
+                            finalized: Some(finalized),
+                        },
+                    )
                 })
             },
         );
@@ -1098,6 +1171,90 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET beacon/states/{state_id}/pending_deposits
+    let get_beacon_state_pending_deposits = beacon_states_path
+        .clone()
+        .and(warp::path("pending_deposits"))
+        .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .then(
+            |state_id: StateId,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    pending_operations::get_beacon_state_pending_operations(
+                        state_id,
+                        chain,
+                        accept_header,
+                        |state| {
+                            Ok(state
+                                .pending_balance_deposits()?
+                                .iter()
+                                .cloned()
+                                .collect::<Vec<_>>())
+                        },
+                    )
+                })
+            },
+        );
+
+    // GET beacon/states/{state_id}/pending_partial_withdrawals
+    let get_beacon_state_pending_partial_withdrawals = beacon_states_path
+        .clone()
+        .and(warp::path("pending_partial_withdrawals"))
+        .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .then(
+            |state_id: StateId,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    pending_operations::get_beacon_state_pending_operations(
+                        state_id,
+                        chain,
+                        accept_header,
+                        |state| {
+                            Ok(state
+                                .pending_partial_withdrawals()?
+                                .iter()
+                                .cloned()
+                                .collect::<Vec<_>>())
+                        },
+                    )
+                })
+            },
+        );
+
+    // GET beacon/states/{state_id}/pending_consolidations
+    let get_beacon_state_pending_consolidations = beacon_states_path
+        .clone()
+        .and(warp::path("pending_consolidations"))
+        .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .then(
+            |state_id: StateId,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    pending_operations::get_beacon_state_pending_operations(
+                        state_id,
+                        chain,
+                        accept_header,
+                        |state| {
+                            Ok(state
+                                .pending_consolidations()?
+                                .iter()
+                                .cloned()
+                                .collect::<Vec<_>>())
+                        },
+                    )
+                })
+            },
+        );
+
     // GET beacon/headers
     //
     // Note: this endpoint only returns information about blocks in the canonical chain. Given that
@@ -3967,6 +4124,42 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // POST lighthouse/validators/sightings
+    let post_lighthouse_validators_sightings = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path("sightings"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |request_data: eth2::lighthouse::ValidatorSightingsRequest,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    if request_data.indices.len() > MAX_REQUEST_INDICES {
+                        return Err(warp_utils::reject::custom_bad_request(format!(
+                            "at most {} indices may be requested at once",
+                            MAX_REQUEST_INDICES
+                        )));
+                    }
+                    let indices = request_data.indices.into_iter().collect::<HashSet<_>>();
+                    let current_slot = chain
+                        .slot()
+                        .map_err(warp_utils::reject::beacon_chain_error)?;
+
+                    // Requesting sightings also (re-)starts watching the validators, so that
+                    // their future messages are recorded.
+                    let mut sightings = chain.validator_sightings.write();
+                    sightings.watch(&indices, current_slot);
+
+                    Ok(api_types::GenericResponse::from(
+                        sightings.get(&indices, request_data.from_slot),
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/health
     let get_lighthouse_health = warp::path("lighthouse")
         .and(warp::path("health"))
@@ -3988,7 +4181,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(system_info_filter)
         .and(app_start_filter)
-        .and(data_dir_filter)
+        .and(data_dir_filter.clone())
         .and(network_globals.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
@@ -4158,6 +4351,36 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/fork_choice/history
+    let get_lighthouse_fork_choice_history = warp::path("lighthouse")
+        .and(warp::path("fork_choice"))
+        .and(warp::path("history"))
+        .and(warp::query::<eth2::lighthouse::ForkChoiceHistoryQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query: eth2::lighthouse::ForkChoiceHistoryQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    if let (Some(start_slot), Some(end_slot)) = (query.start_slot, query.end_slot) {
+                        if start_slot > end_slot {
+                            return Err(warp_utils::reject::custom_bad_request(format!(
+                                "start_slot ({}) must not be greater than end_slot ({})",
+                                start_slot, end_slot
+                            )));
+                        }
+                    }
+                    let history = chain
+                        .fork_choice_history
+                        .read()
+                        .get(query.start_slot, query.end_slot);
+                    Ok(api_types::GenericResponse::from(history))
+                })
+            },
+        );
+
     // GET lighthouse/validator_inclusion/{epoch}/{validator_id}
     let get_lighthouse_validator_inclusion_global = warp::path("lighthouse")
         .and(warp::path("validator_inclusion"))
@@ -4328,6 +4551,164 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // Snapshots started by `POST lighthouse/database/snapshot`.
+    let snapshot_jobs = Arc::new(database::SnapshotJobs::default());
+    let snapshot_jobs_filter = warp::any().map(move || snapshot_jobs.clone());
+
+    // POST lighthouse/database/snapshot
+    let post_lighthouse_database_snapshot = database_path
+        .and(warp::path("snapshot"))
+        .and(warp::path::end())
+        .and(warp::header::optional::<String>("Authorization"))
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(data_dir_filter.clone())
+        .and(snapshot_jobs_filter.clone())
+        .then(
+            move |authorization: Option<String>,
+                  task_spawner: TaskSpawner<T::EthSpec>,
+                  chain: Arc<BeaconChain<T>>,
+                  data_dir: PathBuf,
+                  snapshot_jobs: Arc<database::SnapshotJobs>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    database::start_snapshot(
+                        chain,
+                        data_dir,
+                        snapshot_jobs,
+                        access_control_enabled,
+                        authorization,
+                    )
+                })
+            },
+        );
+
+    // GET lighthouse/database/snapshot/{job_id}
+    let get_lighthouse_database_snapshot = database_path
+        .and(warp::path("snapshot"))
+        .and(warp::path::param::<u64>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(snapshot_jobs_filter)
+        .then(
+            |job_id: u64,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             snapshot_jobs: Arc<database::SnapshotJobs>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    database::snapshot_job(&snapshot_jobs, job_id)
+                })
+            },
+        );
+
+    let slasher_path = warp::path("lighthouse").and(warp::path("slasher"));
+
+    // GET lighthouse/slasher/attestations/{validator_index}?start_epoch,end_epoch
+    let get_lighthouse_slasher_attestations = slasher_path
+        .clone()
+        .and(warp::path("attestations"))
+        .and(warp::path::param::<u64>())
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::SlasherEpochRangeQuery>())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |validator_index: u64,
+             query: eth2::lighthouse::SlasherEpochRangeQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    slasher_queries::attestations(&chain, validator_index, query)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/slasher/proposals/{validator_index}?start_slot,end_slot
+    let get_lighthouse_slasher_proposals = slasher_path
+        .clone()
+        .and(warp::path("proposals"))
+        .and(warp::path::param::<u64>())
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::SlasherSlotRangeQuery>())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |validator_index: u64,
+             query: eth2::lighthouse::SlasherSlotRangeQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    slasher_queries::proposals(&chain, validator_index, query)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/slasher/targets/{validator_index}?start_epoch,end_epoch
+    let get_lighthouse_slasher_targets = slasher_path
+        .clone()
+        .and(warp::path("targets"))
+        .and(warp::path::param::<u64>())
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::SlasherEpochRangeQuery>())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |validator_index: u64,
+             query: eth2::lighthouse::SlasherEpochRangeQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    slasher_queries::targets(&chain, validator_index, query)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/slasher/slashings
+    let get_lighthouse_slasher_slashings = slasher_path
+        .clone()
+        .and(warp::path("slashings"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    slasher_queries::slashings(&chain).map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/slasher/database
+    let get_lighthouse_slasher_database = slasher_path
+        .and(warp::path("database"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    slasher_queries::database(&chain).map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/blocks/stream?start_slot,end_slot,blobs
+    let get_lighthouse_blocks_stream = warp::path("lighthouse")
+        .and(warp::path("blocks"))
+        .and(warp::path("stream"))
+        .and(warp::query::<eth2::lighthouse::BlockStreamQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query, task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    block_stream::get_block_stream(query, chain)
+                })
+            },
+        );
+
     // GET lighthouse/analysis/block_rewards
     let get_lighthouse_block_rewards = warp::path("lighthouse")
         .and(warp::path("analysis"))
@@ -4360,6 +4741,63 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/analysis/block_production/{slot}
+    let get_lighthouse_block_production = warp::path("lighthouse")
+        .and(warp::path("analysis"))
+        .and(warp::path("block_production"))
+        .and(warp::path::param::<Slot>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid slot".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |slot: Slot, task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .block_production_timings
+                        .read()
+                        .get(slot)
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(format!(
+                                "no block production trace for slot {}",
+                                slot
+                            ))
+                        })
+                })
+            },
+        );
+
+    // GET lighthouse/analysis/propagation?start_slot,end_slot
+    let get_lighthouse_propagation = warp::path("lighthouse")
+        .and(warp::path("analysis"))
+        .and(warp::path("propagation"))
+        .and(warp::query::<eth2::lighthouse::PropagationQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query: eth2::lighthouse::PropagationQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    if query.start_slot > query.end_slot {
+                        return Err(warp_utils::reject::custom_bad_request(format!(
+                            "start_slot ({}) must not be greater than end_slot ({})",
+                            query.start_slot, query.end_slot
+                        )));
+                    }
+                    Ok(chain.gossip_propagation.read().analyse(
+                        query.start_slot,
+                        query.end_slot,
+                        |slot| chain.slot_clock.start_of(slot),
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/analysis/attestation_performance/{index}
     let get_lighthouse_attestation_performance = warp::path("lighthouse")
         .and(warp::path("analysis"))
@@ -4377,6 +4815,34 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/validator_monitor/{index}?start_epoch,end_epoch
+    let get_lighthouse_validator_monitor_history = warp::path("lighthouse")
+        .and(warp::path("validator_monitor"))
+        .and(warp::path::param::<u64>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid validator index".to_string(),
+            ))
+        }))
+        .and(warp::query::<eth2::lighthouse::ValidatorMonitorHistoryQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |validator_index: u64,
+             query,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    validator_monitor_history::get_validator_monitor_history(
+                        validator_index,
+                        query,
+                        chain,
+                    )
+                    .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
     // GET lighthouse/analysis/block_packing_efficiency
     let get_lighthouse_block_packing_efficiency = warp::path("lighthouse")
         .and(warp::path("analysis"))
@@ -4412,23 +4878,90 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/events/ws?topics,last_event_id
+    let get_lighthouse_events_ws = warp::path("lighthouse")
+        .and(warp::path("events"))
+        .and(warp::path("ws"))
+        .and(warp::path::end())
+        .and(multi_key_query::<eth2::lighthouse::EventLogQuery>())
+        .and(warp::ws())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query_res: Result<eth2::lighthouse::EventLogQuery, warp::Rejection>,
+             ws: warp::ws::Ws,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| async move {
+                let result = task_spawner
+                    .blocking_task(Priority::P0, move || {
+                        let query = query_res?;
+                        let event_log = chain
+                            .event_handler
+                            .as_ref()
+                            .and_then(|event_handler| event_handler.event_log())
+                            .ok_or_else(|| {
+                                warp_utils::reject::custom_not_found(
+                                    "event log is not enabled".to_string(),
+                                )
+                            })?;
+                        if let Some(topic) = query
+                            .topics
+                            .iter()
+                            .find(|topic| !event_log.is_logged(**topic))
+                        {
+                            return Err(warp_utils::reject::custom_bad_request(format!(
+                                "topic {} is not logged",
+                                topic
+                            )));
+                        }
+                        resumable_events::resumable_event_stream(
+                            event_log,
+                            &query.topics,
+                            query.last_event_id,
+                        )
+                    })
+                    .await;
+
+                match result {
+                    Ok(events) => ws
+                        .on_upgrade(move |socket| resumable_events::send_events(socket, events))
+                        .into_response(),
+                    Err(e) => convert_rejection(Err::<Response<Body>, _>(e)).await,
+                }
+            },
+        );
+
     let get_events = eth_v1
         .and(warp::path("events"))
         .and(warp::path::end())
         .and(multi_key_query::<api_types::EventQuery>())
+        .and(warp::header::optional::<u64>("last-event-id"))
         .and(task_spawner_filter.clone())
         .and(chain_filter)
         .then(
             |topics_res: Result<api_types::EventQuery, warp::Rejection>,
+             last_event_id: Option<u64>,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>| {
                 task_spawner.blocking_response_task(Priority::P0, move || {
                     let topics = topics_res?;
-                    // for each topic subscribed spawn a new subscription
-                    let mut receivers = Vec::with_capacity(topics.topics.len());
+                    // for each topic subscribed spawn a new subscription, where each event is
+                    // paired with whether it should end the response
+                    let mut receivers: Vec<BoxStream<'static, (Event, bool)>> =
+                        Vec::with_capacity(topics.topics.len() + 1);
 
                     if let Some(event_handler) = chain.event_handler.as_ref() {
+                        let event_log = event_handler.event_log();
+                        let mut logged_topics = vec![];
+
                         for topic in topics.topics {
+                            // Logged topics are served from the event log so that they can be
+                            // resumed with `Last-Event-ID`.
+                            if event_log.map_or(false, |event_log| event_log.is_logged(topic)) {
+                                logged_topics.push(topic);
+                                continue;
+                            }
+
                             let receiver = match topic {
                                 api_types::EventTopic::Head => event_handler.subscribe_head(),
                                 api_types::EventTopic::Block => event_handler.subscribe_block(),
@@ -4465,6 +4998,12 @@ pub fn serve<T: BeaconChainTypes>(
                                 api_types::EventTopic::BlockReward => {
                                     event_handler.subscribe_block_reward()
                                 }
+                                api_types::EventTopic::BlockProductionTrace => {
+                                    event_handler.subscribe_block_production_trace()
+                                }
+                                api_types::EventTopic::RejectedBlockHeader => {
+                                    event_handler.subscribe_rejected_block_header()
+                                }
                                 api_types::EventTopic::AttesterSlashing => {
                                     event_handler.subscribe_attester_slashing()
                                 }
@@ -4479,7 +5018,7 @@ pub fn serve<T: BeaconChainTypes>(
                                 }
                             };
 
-                            receivers.push(
+                            receivers.push(Box::pin(
                                 BroadcastStream::new(receiver)
                                     .map(|msg| {
                                         match msg {
@@ -4500,8 +5039,36 @@ pub fn serve<T: BeaconChainTypes>(
                                             }
                                         }
                                     })
-                                    .map(Ok::<_, std::convert::Infallible>),
-                            );
+                                    .map(|event| (event, false)),
+                            ));
+                        }
+
+                        if let Some(event_log) = event_log.filter(|_| !logged_topics.is_empty()) {
+                            receivers.push(Box::pin(
+                                resumable_events::resumable_event_stream(
+                                    event_log,
+                                    &logged_topics,
+                                    last_event_id,
+                                )?
+                                .map(|result| match result {
+                                    Ok(event) => (
+                                        Event::default()
+                                            .id(event.id.to_string())
+                                            .event(&event.topic)
+                                            .json_data(&event.data)
+                                            .unwrap_or_else(|e| {
+                                                Event::default()
+                                                    .comment(format!("error - bad json: {e:?}"))
+                                            }),
+                                        false,
+                                    ),
+                                    // End the response, so that the client resumes from the last
+                                    // event it received rather than missing events.
+                                    Err(e) => {
+                                        (Event::default().comment(format!("error - {e}")), true)
+                                    }
+                                }),
+                            ));
                         }
                     } else {
                         return Err(warp_utils::reject::custom_server_error(
@@ -4509,7 +5076,16 @@ pub fn serve<T: BeaconChainTypes>(
                         ));
                     }
 
-                    let s = futures::stream::select_all(receivers);
+                    let s = futures::StreamExt::scan(
+                        futures::stream::select_all(receivers),
+                        false,
+                        |done, (event, is_last)| {
+                            let event = (!*done).then_some(event);
+                            *done = is_last;
+                            futures::future::ready(event)
+                        },
+                    )
+                    .map(Ok::<_, std::convert::Infallible>);
 
                     Ok(warp::sse::reply(warp::sse::keep_alive().stream(s)))
                 })
@@ -4564,7 +5140,9 @@ pub fn serve<T: BeaconChainTypes>(
 
     // Define the ultimate set of routes that will be provided to the server.
     // Use `uor` rather than `or` in order to simplify types (see `UnifyingOrFilter`).
+    let access_control = access_control_filter(access_control);
     let routes = warp::get()
+        .and(access_control.clone())
         .and(
             get_beacon_genesis
                 .uor(get_beacon_state_root)
@@ -4576,6 +5154,9 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_beacon_state_committees)
                 .uor(get_beacon_state_sync_committees)
                 .uor(get_beacon_state_randao)
+                .uor(get_beacon_state_pending_deposits)
+                .uor(get_beacon_state_pending_partial_withdrawals)
+                .uor(get_beacon_state_pending_consolidations)
                 .uor(get_beacon_headers)
                 .uor(get_beacon_headers_block_id)
                 .uor(get_beacon_block)
@@ -4617,6 +5198,7 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_peers)
                 .uor(get_lighthouse_peers_connected)
                 .uor(get_lighthouse_proto_array)
+                .uor(get_lighthouse_fork_choice_history)
                 .uor(get_lighthouse_validator_inclusion_global)
                 .uor(get_lighthouse_validator_inclusion)
                 .uor(get_lighthouse_eth1_syncing)
@@ -4624,8 +5206,18 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_eth1_deposit_cache)
                 .uor(get_lighthouse_staking)
                 .uor(get_lighthouse_database_info)
+                .uor(get_lighthouse_database_snapshot)
+                .uor(get_lighthouse_slasher_attestations)
+                .uor(get_lighthouse_slasher_proposals)
+                .uor(get_lighthouse_slasher_targets)
+                .uor(get_lighthouse_slasher_slashings)
+                .uor(get_lighthouse_slasher_database)
+                .uor(get_lighthouse_blocks_stream)
                 .uor(get_lighthouse_block_rewards)
+                .uor(get_lighthouse_block_production)
+                .uor(get_lighthouse_propagation)
                 .uor(get_lighthouse_attestation_performance)
+                .uor(get_lighthouse_validator_monitor_history)
                 .uor(
                     enable(ctx.config.enable_light_client_server)
                         .and(get_beacon_light_client_optimistic_update),
@@ -4641,13 +5233,14 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_block_packing_efficiency)
                 .uor(get_lighthouse_merge_readiness)
                 .uor(get_events)
+                .uor(get_lighthouse_events_ws)
                 .uor(get_expected_withdrawals)
                 .uor(lighthouse_log_events.boxed())
                 .recover(warp_utils::reject::handle_rejection),
         )
         .boxed()
         .uor(
-            warp::post().and(
+            warp::post().and(access_control).and(
                 warp::header::exact(CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER)
                     // Routes which expect `application/octet-stream` go within this `and`.
                     .and(
@@ -4668,6 +5261,7 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_beacon_pool_bls_to_execution_changes)
                     .uor(post_beacon_state_validators)
                     .uor(post_beacon_state_validator_balances)
+                    .uor(post_beacon_state_validator_identities)
                     .uor(post_beacon_rewards_attestations)
                     .uor(post_beacon_rewards_sync_committee)
                     .uor(post_validator_duties_attester)
@@ -4680,7 +5274,9 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_validator_register_validator)
                     .uor(post_validator_liveness_epoch)
                     .uor(post_lighthouse_liveness)
+                    .uor(post_lighthouse_validators_sightings)
                     .uor(post_lighthouse_database_reconstruct)
+                    .uor(post_lighthouse_database_snapshot)
                     .uor(post_lighthouse_block_rewards)
                     .uor(post_lighthouse_ui_validator_metrics)
                     .uor(post_lighthouse_ui_validator_info)
@@ -16,6 +16,11 @@ lazy_static::lazy_static! {
         "Duration to process HTTP requests per path",
         &["path"]
     );
+    pub static ref HTTP_API_ACCESS_REJECTIONS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "http_api_access_rejections_total",
+        "Count of HTTP requests rejected by access control, per API key, route group and reason",
+        &["key", "group", "reason"]
+    );
 
     pub static ref HTTP_API_BEACON_PROPOSER_CACHE_TIMES: Result<Histogram> = try_create_histogram(
         "http_api_beacon_proposer_cache_build_times",
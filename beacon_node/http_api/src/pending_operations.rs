@@ -0,0 +1,68 @@
+use crate::state_id::StateId;
+use crate::version::{
+    add_consensus_version_header, execution_optimistic_finalized_ssz_or_json_response,
+};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::types::{self as api_types, StateId as CoreStateId};
+use serde::Serialize;
+use ssz::Encode;
+use std::sync::Arc;
+use types::{BeaconState, BeaconStateError};
+
+/// Respond with one of the pending operation queues introduced to the `BeaconState` in Electra,
+/// such as `pending_consolidations`.
+///
+/// The queue is returned in the order in which it will be processed, so the position of an item
+/// indicates its place in the queue.
+pub fn get_beacon_state_pending_operations<T, O, F>(
+    state_id: StateId,
+    chain: Arc<BeaconChain<T>>,
+    accept_header: Option<api_types::Accept>,
+    get_queue: F,
+) -> Result<warp::reply::Response, warp::Rejection>
+where
+    T: BeaconChainTypes,
+    O: Serialize + Encode,
+    F: Fn(&BeaconState<T::EthSpec>) -> Result<Vec<O>, BeaconStateError>,
+{
+    // Requests for the head state by slot or root are served from the cached head, rather than
+    // loading a copy of the state from the database.
+    let (cached_head, _) = chain
+        .canonical_head
+        .head_and_execution_status()
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+    let is_head = match state_id.0 {
+        CoreStateId::Slot(slot) => slot == cached_head.head_slot(),
+        CoreStateId::Root(root) => root == cached_head.head_state_root(),
+        _ => false,
+    };
+    let state_id = if is_head {
+        StateId(CoreStateId::Head)
+    } else {
+        state_id
+    };
+
+    state_id.map_state_and_execution_optimistic_and_finalized(
+        &chain,
+        |state, execution_optimistic, finalized| {
+            let fork_name = state.fork_name_unchecked();
+            let queue = get_queue(state).map_err(|e| match e {
+                BeaconStateError::IncorrectStateVariant => {
+                    warp_utils::reject::custom_bad_request(format!(
+                        "state at epoch {} is not activated for Electra",
+                        state.current_epoch()
+                    ))
+                }
+                e => warp_utils::reject::beacon_state_error(e),
+            })?;
+
+            let response = execution_optimistic_finalized_ssz_or_json_response(
+                accept_header,
+                api_types::GenericResponse::from(queue)
+                    .add_execution_optimistic_finalized(execution_optimistic, finalized),
+            )?;
+
+            Ok(add_consensus_version_header(response, fork_name))
+        },
+    )
+}
@@ -6,6 +6,7 @@ use beacon_chain::{
     AvailabilityProcessingStatus, BeaconChain, BeaconChainError, BeaconChainTypes, BlockError,
     IntoGossipVerifiedBlockContents, NotifyExecutionLayer,
 };
+use eth2::lighthouse::BuilderCall;
 use eth2::types::{into_full_block_and_blobs, BroadcastValidation, ErrorMessage};
 use eth2::types::{FullPayloadContents, PublishBlockRequest};
 use execution_layer::ProvenancedPayload;
@@ -15,7 +16,7 @@ use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::marker::PhantomData;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::UnboundedSender;
 use tree_hash::TreeHash;
 use types::{
@@ -68,8 +69,13 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlockConten
     let block = block_contents.inner_block().clone();
     let delay = get_block_delay_ms(seen_timestamp, block.message(), &chain.slot_clock);
     debug!(log, "Signed block received in HTTP API"; "slot" => block.slot());
+    chain
+        .block_production_timings
+        .write()
+        .set_signed_block_received(block.slot(), seen_timestamp);
 
     /* actually publish a block */
+    let publish_chain = chain.clone();
     let publish_block = move |block: Arc<SignedBeaconBlock<T::EthSpec>>,
                               blobs_opt: Option<BlobSidecarList<T::EthSpec>>,
                               sender,
@@ -92,6 +98,7 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlockConten
             "slot" => block.slot(),
             "publish_delay_ms" => publish_delay.as_millis()
         );
+        publish_chain.register_block_publication(block.slot(), publish_delay);
 
         match block.as_ref() {
             SignedBeaconBlock::Base(_)
@@ -167,6 +174,10 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlockConten
     });
 
     let block_root = block_root.unwrap_or(gossip_verified_block.block_root);
+    chain
+        .block_production_timings
+        .write()
+        .update(slot, |trace| trace.block_root = Some(block_root));
 
     if let BroadcastValidation::Gossip = validation_level {
         publish_block(
@@ -314,6 +325,10 @@ pub async fn publish_blinded_block<T: BeaconChainTypes>(
     validation_level: BroadcastValidation,
     duplicate_status_code: StatusCode,
 ) -> Result<Response, Rejection> {
+    chain
+        .block_production_timings
+        .write()
+        .set_signed_block_received(blinded_block.slot(), timestamp_now());
     let block_root = blinded_block.canonical_root();
     let full_block: ProvenancedBlock<T, PublishBlockRequest<T::EthSpec>> =
         reconstruct_block(chain.clone(), block_root, blinded_block, log.clone()).await?;
@@ -378,15 +393,20 @@ pub async fn reconstruct_block<T: BeaconChainTypes>(
                 &log,
             );
 
-            let full_payload = el
-                .propose_blinded_beacon_block(block_root, &block)
-                .await
-                .map_err(|e| {
-                    warp_utils::reject::custom_server_error(format!(
-                        "Blind block proposal failed: {:?}",
-                        e
-                    ))
-                })?;
+            let submission_start = Instant::now();
+            let full_payload_result = el.propose_blinded_beacon_block(block_root, &block).await;
+            chain.block_production_timings.write().add_builder_call(
+                block.slot(),
+                BuilderCall::SubmitBlindedBlock,
+                submission_start.elapsed(),
+                full_payload_result.is_ok(),
+            );
+            let full_payload = full_payload_result.map_err(|e| {
+                warp_utils::reject::custom_server_error(format!(
+                    "Blind block proposal failed: {:?}",
+                    e
+                ))
+            })?;
             info!(log, "Successfully published a block to the builder network"; "block_hash" => ?full_payload.block_hash());
             ProvenancedPayload::Builder(full_payload)
         };
@@ -0,0 +1,126 @@
+use beacon_chain::event_log::{Error as EventLogError, EventLog};
+use eth2::lighthouse::LoggedEvent;
+use eth2::types::EventTopic;
+use futures::{future, stream, SinkExt, Stream, StreamExt};
+use std::sync::Arc;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use warp::ws::{Message, WebSocket};
+use warp_utils::reject::{custom_bad_request, custom_server_error};
+
+/// The close code sent to WebSocket subscribers which fall too far behind ("try again later").
+const LAGGED_CLOSE_CODE: u16 = 1013;
+
+/// Returns the logged events with `topics` after `last_event_id`, followed by new events as they
+/// are logged.
+///
+/// The stream ends with an error if the subscriber falls so far behind that new events are
+/// dropped, in which case it should resume from the last event it received.
+pub fn resumable_event_stream(
+    event_log: &EventLog,
+    topics: &[EventTopic],
+    last_event_id: Option<u64>,
+) -> Result<impl Stream<Item = Result<Arc<LoggedEvent>, String>> + Send + 'static, warp::Rejection>
+{
+    // Subscribe before reading the log, so that no events are missed in between.
+    let receiver = event_log.subscribe();
+
+    let replayed = match last_event_id {
+        Some(last_event_id) => event_log.read_after(last_event_id).map_err(|e| match e {
+            EventLogError::Pruned {
+                last_event_id,
+                oldest_event_id,
+            } => custom_bad_request(format!(
+                "events after {} have been pruned, the oldest event is {}",
+                last_event_id, oldest_event_id
+            )),
+            EventLogError::UnknownEvent {
+                last_event_id,
+                latest_event_id,
+            } => custom_bad_request(format!(
+                "event {} is unknown, the latest event is {}",
+                last_event_id, latest_event_id
+            )),
+            EventLogError::Io(e) => custom_server_error(format!("unable to read events: {}", e)),
+        })?,
+        None => vec![],
+    };
+    // Events which were received while reading the log have already been replayed.
+    let last_replayed_id = replayed
+        .last()
+        .map(|event| event.id)
+        .or(last_event_id)
+        .unwrap_or(0);
+
+    let live = BroadcastStream::new(receiver)
+        .filter(move |result| {
+            future::ready(
+                result
+                    .as_ref()
+                    .map_or(true, |event| event.id > last_replayed_id),
+            )
+        })
+        .map(|result| {
+            result.map_err(|BroadcastStreamRecvError::Lagged(n)| {
+                format!("dropped {} events, resume from the last event received", n)
+            })
+        });
+
+    let topic_names = topics.iter().map(ToString::to_string).collect::<Vec<_>>();
+    Ok(
+        stream::iter(replayed.into_iter().map(|event| Ok(Arc::new(event))))
+            .chain(live)
+            .filter(move |result| {
+                future::ready(
+                    result
+                        .as_ref()
+                        .map_or(true, |event| topic_names.contains(&event.topic)),
+                )
+            })
+            // End the stream after the first error.
+            .scan(false, |failed, result| {
+                let item = (!*failed).then(|| {
+                    *failed = result.is_err();
+                    result
+                });
+                future::ready(item)
+            }),
+    )
+}
+
+/// Send `events` to a WebSocket subscriber as JSON text messages until either side closes the
+/// connection.
+pub async fn send_events(
+    socket: WebSocket,
+    events: impl Stream<Item = Result<Arc<LoggedEvent>, String>> + Send + 'static,
+) {
+    let (mut socket_tx, mut socket_rx) = socket.split();
+
+    let send = async move {
+        let mut events = Box::pin(events);
+        while let Some(result) = events.next().await {
+            let message = match result.and_then(|event| {
+                serde_json::to_string(&*event).map_err(|e| format!("invalid event: {:?}", e))
+            }) {
+                Ok(json) => Message::text(json),
+                Err(e) => Message::close_with(LAGGED_CLOSE_CODE, e),
+            };
+            let is_close = message.is_close();
+            if socket_tx.send(message).await.is_err() || is_close {
+                return;
+            }
+        }
+        let _ = socket_tx.close().await;
+    };
+
+    // Messages from the subscriber are ignored, but must be read in order to respond to pings
+    // and notice when the connection is closed.
+    let receive = async move {
+        while let Some(Ok(message)) = socket_rx.next().await {
+            if message.is_close() {
+                break;
+            }
+        }
+    };
+
+    future::select(Box::pin(send), Box::pin(receive)).await;
+}
@@ -0,0 +1,135 @@
+//! Read-only access to the slasher's database, for investigating the behaviour of validators.
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{
+    SlasherDatabaseStats, SlasherEpochRangeQuery, SlasherSlashings, SlasherSlotRangeQuery,
+    SlasherTableStats, SlasherTargetBounds,
+};
+use slasher::Slasher;
+use types::{IndexedAttestation, SignedBeaconBlockHeader};
+use warp_utils::reject::{custom_bad_request, custom_not_found, custom_server_error};
+
+/// The maximum number of slots which may be queried for block proposals at once.
+///
+/// Each slot is a separate database lookup.
+const MAX_PROPOSAL_QUERY_SLOTS: u64 = 8192;
+
+/// The maximum number of epochs which may be queried for attestations or target bounds at once.
+///
+/// This is the default history length, so a query on a default slasher can cover its whole
+/// history.
+const MAX_QUERY_EPOCHS: u64 = 4096;
+
+fn get_slasher<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+) -> Result<&Slasher<T::EthSpec>, warp::Rejection> {
+    chain
+        .slasher
+        .as_deref()
+        .ok_or_else(|| custom_not_found("the slasher is not enabled".to_string()))
+}
+
+fn check_epoch_range(query: &SlasherEpochRangeQuery) -> Result<(), warp::Rejection> {
+    if query.start_epoch > query.end_epoch {
+        return Err(custom_bad_request(format!(
+            "start_epoch ({}) must not be greater than end_epoch ({})",
+            query.start_epoch, query.end_epoch
+        )));
+    }
+    if (query.end_epoch - query.start_epoch).as_u64() >= MAX_QUERY_EPOCHS {
+        return Err(custom_bad_request(format!(
+            "at most {} epochs may be queried at once",
+            MAX_QUERY_EPOCHS
+        )));
+    }
+    Ok(())
+}
+
+fn slasher_error(e: slasher::Error) -> warp::Rejection {
+    custom_server_error(format!("slasher database error: {:?}", e))
+}
+
+pub fn attestations<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    validator_index: u64,
+    query: SlasherEpochRangeQuery,
+) -> Result<Vec<IndexedAttestation<T::EthSpec>>, warp::Rejection> {
+    check_epoch_range(&query)?;
+    get_slasher(chain)?
+        .get_attestations_for_validator(validator_index, query.start_epoch, query.end_epoch)
+        .map_err(slasher_error)
+}
+
+pub fn proposals<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    validator_index: u64,
+    query: SlasherSlotRangeQuery,
+) -> Result<Vec<SignedBeaconBlockHeader>, warp::Rejection> {
+    if query.start_slot > query.end_slot {
+        return Err(custom_bad_request(format!(
+            "start_slot ({}) must not be greater than end_slot ({})",
+            query.start_slot, query.end_slot
+        )));
+    }
+    if (query.end_slot - query.start_slot).as_u64() >= MAX_PROPOSAL_QUERY_SLOTS {
+        return Err(custom_bad_request(format!(
+            "at most {} slots may be queried at once",
+            MAX_PROPOSAL_QUERY_SLOTS
+        )));
+    }
+    get_slasher(chain)?
+        .get_block_proposals(validator_index, query.start_slot, query.end_slot)
+        .map_err(slasher_error)
+}
+
+pub fn targets<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    validator_index: u64,
+    query: SlasherEpochRangeQuery,
+) -> Result<Vec<SlasherTargetBounds>, warp::Rejection> {
+    check_epoch_range(&query)?;
+    let bounds = get_slasher(chain)?
+        .get_target_bounds(validator_index, query.start_epoch, query.end_epoch)
+        .map_err(slasher_error)?;
+    Ok(bounds
+        .into_iter()
+        .map(|bounds| SlasherTargetBounds {
+            source_epoch: bounds.source_epoch,
+            min_target: bounds.min_target,
+            max_target: bounds.max_target,
+        })
+        .collect())
+}
+
+pub fn slashings<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+) -> Result<SlasherSlashings<T::EthSpec>, warp::Rejection> {
+    let history = get_slasher(chain)?.slashing_history();
+    Ok(SlasherSlashings {
+        attester_slashings: history.attester_slashings.into(),
+        proposer_slashings: history.proposer_slashings.into(),
+    })
+}
+
+pub fn database<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+) -> Result<SlasherDatabaseStats, warp::Rejection> {
+    let slasher = get_slasher(chain)?;
+    let config = slasher.config();
+    let stats = slasher.database_stats().map_err(slasher_error)?;
+    Ok(SlasherDatabaseStats {
+        backend: stats.backend.to_string(),
+        schema_version: stats.schema_version,
+        history_length: config.history_length,
+        chunk_size: config.chunk_size,
+        validator_chunk_size: config.validator_chunk_size,
+        size: stats.size,
+        tables: stats
+            .tables
+            .into_iter()
+            .map(|table| SlasherTableStats {
+                name: table.name.to_string(),
+                entries: table.entries,
+            })
+            .collect(),
+    })
+}
@@ -132,9 +132,23 @@ pub async fn create_api_server<T: BeaconChainTypes>(
     test_runtime: &TestRuntime,
     log: Logger,
 ) -> ApiServer<T, impl Future<Output = ()>> {
-    // Use port 0 to allocate a new unused port.
-    let port = 0;
+    let config = Config {
+        enabled: true,
+        // Use port 0 to allocate a new unused port.
+        listen_port: 0,
+        data_dir: std::path::PathBuf::from(DEFAULT_ROOT_DIR),
+        enable_light_client_server: true,
+        ..Config::default()
+    };
+    create_api_server_with_config(chain, test_runtime, config, log).await
+}
 
+pub async fn create_api_server_with_config<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    test_runtime: &TestRuntime,
+    config: Config,
+    log: Logger,
+) -> ApiServer<T, impl Future<Output = ()>> {
     let (network_senders, network_receivers) = NetworkSenders::new();
 
     // Default metadata
@@ -217,13 +231,7 @@ pub async fn create_api_server<T: BeaconChainTypes>(
     .unwrap();
 
     let ctx = Arc::new(Context {
-        config: Config {
-            enabled: true,
-            listen_port: port,
-            data_dir: std::path::PathBuf::from(DEFAULT_ROOT_DIR),
-            enable_light_client_server: true,
-            ..Config::default()
-        },
+        config,
         chain: Some(chain),
         network_senders: Some(network_senders),
         network_globals: Some(network_globals),
@@ -0,0 +1,35 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{ValidatorMonitorEpochSummary, ValidatorMonitorHistoryQuery};
+use std::sync::Arc;
+use warp_utils::reject::{beacon_chain_error, custom_bad_request};
+
+const MAX_REQUEST_RANGE_EPOCHS: u64 = 1_024;
+
+pub fn get_validator_monitor_history<T: BeaconChainTypes>(
+    validator_index: u64,
+    query: ValidatorMonitorHistoryQuery,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<ValidatorMonitorEpochSummary>, warp::Rejection> {
+    let ValidatorMonitorHistoryQuery {
+        start_epoch,
+        end_epoch,
+    } = query;
+
+    if start_epoch > end_epoch {
+        return Err(custom_bad_request(format!(
+            "start_epoch must not be larger than end_epoch. start: {}, end: {}",
+            start_epoch, end_epoch
+        )));
+    }
+
+    if (end_epoch - start_epoch).as_u64() > MAX_REQUEST_RANGE_EPOCHS {
+        return Err(custom_bad_request(format!(
+            "end_epoch must not exceed start_epoch by more than {} epochs. start: {}, end: {}",
+            MAX_REQUEST_RANGE_EPOCHS, start_epoch, end_epoch
+        )));
+    }
+
+    chain
+        .validator_monitor_history(validator_index, start_epoch, end_epoch)
+        .map_err(beacon_chain_error)
+}
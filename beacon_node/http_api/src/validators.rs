@@ -1,10 +1,39 @@
 use crate::state_id::StateId;
+use crate::validator::pubkey_to_validator_index;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2::types::{
     self as api_types, ExecutionOptimisticFinalizedResponse, ValidatorBalanceData, ValidatorData,
-    ValidatorId, ValidatorStatus,
+    ValidatorId, ValidatorIdentityData, ValidatorStatus,
 };
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::BTreeSet, sync::Arc};
+use types::BeaconState;
+
+/// Resolve `ids` to the indices of validators in `state`, using the validator pubkey cache rather
+/// than scanning the state for pubkeys.
+///
+/// Unknown validators are ignored, and the indices are returned in ascending order.
+fn validator_indices<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    state: &BeaconState<T::EthSpec>,
+    ids: &[ValidatorId],
+) -> Result<BTreeSet<usize>, warp::Rejection> {
+    let mut indices = BTreeSet::new();
+    for id in ids {
+        let index_opt = match id {
+            ValidatorId::PublicKey(pubkey) => pubkey_to_validator_index(chain, state, pubkey)
+                .map_err(|e| {
+                    warp_utils::reject::custom_server_error(format!(
+                        "unable to access pubkey cache: {e:?}",
+                    ))
+                })?,
+            ValidatorId::Index(index) => {
+                Some(*index as usize).filter(|&index| index < state.validators().len())
+            }
+        };
+        indices.extend(index_opt);
+    }
+    Ok(indices)
+}
 
 pub fn get_beacon_state_validators<T: BeaconChainTypes>(
     state_id: StateId,
@@ -18,8 +47,10 @@ pub fn get_beacon_state_validators<T: BeaconChainTypes>(
             |state, execution_optimistic, finalized| {
                 let epoch = state.current_epoch();
                 let far_future_epoch = chain.spec.far_future_epoch;
-                let ids_filter_set: Option<HashSet<&ValidatorId>> =
-                    query_ids.as_ref().map(HashSet::from_iter);
+                let indices_filter = query_ids
+                    .as_deref()
+                    .map(|ids| validator_indices(&chain, state, ids))
+                    .transpose()?;
 
                 Ok((
                     state
@@ -28,11 +59,10 @@ pub fn get_beacon_state_validators<T: BeaconChainTypes>(
                         .zip(state.balances().iter())
                         .enumerate()
                         // filter by validator id(s) if provided
-                        .filter(|(index, (validator, _))| {
-                            ids_filter_set.as_ref().map_or(true, |ids_set| {
-                                ids_set.contains(&ValidatorId::PublicKey(validator.pubkey))
-                                    || ids_set.contains(&ValidatorId::Index(*index as u64))
-                            })
+                        .filter(|(index, _)| {
+                            indices_filter
+                                .as_ref()
+                                .map_or(true, |indices| indices.contains(index))
                         })
                         // filter by status(es) if provided and map the result
                         .filter_map(|(index, (validator, balance))| {
@@ -81,8 +111,9 @@ pub fn get_beacon_state_validator_balances<T: BeaconChainTypes>(
         .map_state_and_execution_optimistic_and_finalized(
             &chain,
             |state, execution_optimistic, finalized| {
-                let ids_filter_set: Option<HashSet<&ValidatorId>> =
-                    optional_ids.map(|f| HashSet::from_iter(f.iter()));
+                let indices_filter = optional_ids
+                    .map(|ids| validator_indices(&chain, state, ids))
+                    .transpose()?;
 
                 Ok((
                     state
@@ -91,11 +122,10 @@ pub fn get_beacon_state_validator_balances<T: BeaconChainTypes>(
                         .zip(state.balances().iter())
                         .enumerate()
                         // filter by validator id(s) if provided
-                        .filter(|(index, (validator, _))| {
-                            ids_filter_set.as_ref().map_or(true, |ids_set| {
-                                ids_set.contains(&ValidatorId::PublicKey(validator.pubkey))
-                                    || ids_set.contains(&ValidatorId::Index(*index as u64))
-                            })
+                        .filter(|(index, _)| {
+                            indices_filter
+                                .as_ref()
+                                .map_or(true, |indices| indices.contains(index))
                         })
                         .map(|(index, (_, balance))| ValidatorBalanceData {
                             index: index as u64,
@@ -114,3 +144,37 @@ pub fn get_beacon_state_validator_balances<T: BeaconChainTypes>(
         finalized: Some(finalized),
     })
 }
+
+pub fn get_beacon_state_validator_identities<T: BeaconChainTypes>(
+    state_id: StateId,
+    chain: Arc<BeaconChain<T>>,
+    ids: &[ValidatorId],
+) -> Result<ExecutionOptimisticFinalizedResponse<Vec<ValidatorIdentityData>>, warp::Rejection> {
+    let (data, execution_optimistic, finalized) = state_id
+        .map_state_and_execution_optimistic_and_finalized(
+            &chain,
+            |state, execution_optimistic, finalized| {
+                Ok((
+                    validator_indices(&chain, state, ids)?
+                        .into_iter()
+                        .filter_map(|index| {
+                            let validator = state.validators().get(index)?;
+                            Some(ValidatorIdentityData {
+                                index: index as u64,
+                                pubkey: validator.pubkey,
+                                activation_epoch: validator.activation_epoch,
+                            })
+                        })
+                        .collect::<Vec<_>>(),
+                    execution_optimistic,
+                    finalized,
+                ))
+            },
+        )?;
+
+    Ok(ExecutionOptimisticFinalizedResponse {
+        data,
+        execution_optimistic: Some(execution_optimistic),
+        finalized: Some(finalized),
+    })
+}
@@ -1,9 +1,10 @@
-use crate::api_types::EndpointVersion;
+use crate::api_types::{Accept, EndpointVersion, ExecutionOptimisticFinalizedResponse};
 use eth2::{
     CONSENSUS_BLOCK_VALUE_HEADER, CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER,
     EXECUTION_PAYLOAD_BLINDED_HEADER, EXECUTION_PAYLOAD_VALUE_HEADER, SSZ_CONTENT_TYPE_HEADER,
 };
 use serde::Serialize;
+use ssz::Encode;
 use types::{
     fork_versioned_response::{
         ExecutionOptimisticFinalizedForkVersionedResponse, ExecutionOptimisticFinalizedMetadata,
@@ -59,6 +60,26 @@ pub fn execution_optimistic_finalized_fork_versioned_response<T: Serialize>(
     })
 }
 
+/// Respond with the SSZ encoding of the `data` of `response` if the `accept_header` requests SSZ,
+/// or with the whole `response` as JSON otherwise.
+///
+/// The `execution_optimistic` and `finalized` metadata is only included in the JSON response.
+pub fn execution_optimistic_finalized_ssz_or_json_response<T: Serialize + Encode>(
+    accept_header: Option<Accept>,
+    response: ExecutionOptimisticFinalizedResponse<T>,
+) -> Result<Response, warp::reject::Rejection> {
+    match accept_header {
+        Some(Accept::Ssz) => warp::http::Response::builder()
+            .status(200)
+            .body(response.data.as_ssz_bytes().into())
+            .map(|res: warp::http::Response<warp::hyper::Body>| add_ssz_content_type_header(res))
+            .map_err(|e| {
+                warp_utils::reject::custom_server_error(format!("failed to create response: {}", e))
+            }),
+        _ => Ok(reply::json(&response).into_response()),
+    }
+}
+
 /// Add the 'Content-Type application/octet-stream` header to a response.
 pub fn add_ssz_content_type_header<T: Reply>(reply: T) -> Response {
     reply::with_header(reply, CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER).into_response()
@@ -1,6 +1,9 @@
 //! Tests for API behaviour across fork boundaries.
 use beacon_chain::{
-    test_utils::{RelativeSyncCommittee, DEFAULT_ETH1_BLOCK_HASH, HARNESS_GENESIS_TIME},
+    test_utils::{
+        AttestationStrategy, BlockStrategy, RelativeSyncCommittee, DEFAULT_ETH1_BLOCK_HASH,
+        HARNESS_GENESIS_TIME,
+    },
     StateSkipConfig,
 };
 use eth2::types::{IndexedErrorMessage, StateId, SyncSubcommittee};
@@ -10,7 +13,7 @@ use http_api::test_utils::*;
 use std::collections::HashSet;
 use types::{
     test_utils::{generate_deterministic_keypair, generate_deterministic_keypairs},
-    Address, ChainSpec, Epoch, EthSpec, Hash256, MinimalEthSpec, Slot,
+    Address, ChainSpec, Epoch, EthSpec, ForkName, Hash256, MinimalEthSpec, Slot, Unsigned,
 };
 
 type E = MinimalEthSpec;
@@ -29,6 +32,12 @@ fn capella_spec(capella_fork_epoch: Epoch) -> ChainSpec {
     spec
 }
 
+fn electra_spec(electra_fork_epoch: Epoch) -> ChainSpec {
+    let mut spec = ForkName::Deneb.make_genesis_spec(E::default_spec());
+    spec.electra_fork_epoch = Some(electra_fork_epoch);
+    spec
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn sync_committee_duties_across_fork() {
     let validator_count = E::sync_committee_size();
@@ -548,3 +557,165 @@ async fn bls_to_execution_changes_update_all_around_capella_fork() {
         assert!(validator.has_eth1_withdrawal_credential(&spec));
     }
 }
+
+// Test that block packing analysis reports the committees of each on-chain aggregate before and
+// after Electra, and that the rewards of alternative packings are bounded as expected.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn block_packing_efficiency_across_electra_fork() {
+    let validator_count = 64;
+    let fork_epoch = Epoch::new(2);
+    let spec = electra_spec(fork_epoch);
+    let tester = InteractiveTester::<E>::new(Some(spec), validator_count).await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize * 5,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let fork_slot = fork_epoch.start_slot(E::slots_per_epoch());
+    let committees_per_slot = harness
+        .get_current_state()
+        .get_committee_count_at_slot(fork_slot)
+        .unwrap() as usize;
+    assert!(committees_per_slot > 1);
+
+    let start_epoch = Epoch::new(1);
+    let end_epoch = Epoch::new(3);
+    let packing = client
+        .get_lighthouse_analysis_block_packing_with_rewards(start_epoch, end_epoch)
+        .await
+        .unwrap();
+    assert_eq!(packing.len() as u64, 3 * E::slots_per_epoch());
+
+    for block in &packing {
+        let slot = block.slot;
+        // Each block includes an aggregate of every committee of the previous slot, which are
+        // merged into one on-chain aggregate after Electra.
+        let (max_attestations, expected_committees) = if slot < fork_slot {
+            (
+                <E as EthSpec>::MaxAttestations::to_usize(),
+                vec![1; committees_per_slot],
+            )
+        } else {
+            (
+                <E as EthSpec>::MaxAttestationsElectra::to_usize(),
+                vec![committees_per_slot],
+            )
+        };
+        // The first block after the fork can't include the attestations from before it.
+        if slot != fork_slot {
+            assert_eq!(
+                block.committees_per_attestation, expected_committees,
+                "slot {slot}"
+            );
+        }
+        assert_eq!(
+            block.unused_attestation_slots,
+            max_attestations - block.committees_per_attestation.len(),
+            "slot {slot}"
+        );
+
+        let rewards = block.rewards.as_ref().unwrap();
+        assert!(rewards.included <= rewards.max_cover, "slot {slot}");
+        assert!(rewards.max_cover <= rewards.upper_bound, "slot {slot}");
+        if slot != fork_slot {
+            assert!(rewards.included > 0, "slot {slot}");
+            assert!(rewards.candidate_aggregates > 0, "slot {slot}");
+        }
+    }
+}
+
+// Test that the packing rewards of a block without attestations account for the attestations it
+// could have included, which were included by the next block instead.
+async fn packing_rewards_of_block_without_attestations(fork_name: ForkName) {
+    let validator_count = 64;
+    let spec = fork_name.make_genesis_spec(E::default_spec());
+    let tester = InteractiveTester::<E>::new(Some(spec), validator_count).await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize * 2 - 1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Produce a block that nobody attests to yet, so that the next block has nothing to include.
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::SomeValidators(vec![]),
+        )
+        .await;
+    let head = harness.chain.head_snapshot();
+    let attestations = harness.make_attestations(
+        &harness.get_all_validators(),
+        &head.beacon_state,
+        head.beacon_state_root(),
+        head.beacon_block_root.into(),
+        head.beacon_block.slot(),
+    );
+
+    harness.advance_slot();
+    let slot = harness.get_current_slot();
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::SomeValidators(vec![]),
+        )
+        .await;
+
+    // Publish the attestations late, so that only the block after the empty one includes them.
+    harness.process_attestations(attestations);
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let epoch = slot.epoch(E::slots_per_epoch());
+    let packing = client
+        .get_lighthouse_analysis_block_packing_with_rewards(epoch, epoch)
+        .await
+        .unwrap();
+    let block = packing.iter().find(|block| block.slot == slot).unwrap();
+    assert!(block.committees_per_attestation.is_empty());
+    assert_eq!(block.included_attestations, 0);
+
+    let rewards = block.rewards.as_ref().unwrap();
+    assert_eq!(rewards.included, 0);
+    assert!(rewards.candidate_aggregates > 0);
+    assert!(rewards.max_cover > 0);
+    assert!(rewards.max_cover <= rewards.upper_bound);
+
+    // The next block includes the attestations, and has the rewards they were worth.
+    let next_block = packing.iter().find(|block| block.slot == slot + 1).unwrap();
+    let next_rewards = next_block.rewards.as_ref().unwrap();
+    assert!(next_rewards.included > 0);
+    assert!(next_rewards.included <= next_rewards.max_cover);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn packing_rewards_of_block_without_attestations_deneb() {
+    packing_rewards_of_block_without_attestations(ForkName::Deneb).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn packing_rewards_of_block_without_attestations_electra() {
+    packing_rewards_of_block_without_attestations(ForkName::Electra).await;
+}
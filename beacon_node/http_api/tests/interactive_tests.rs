@@ -1,15 +1,21 @@
 //! Generic tests that make use of the (newer) `InteractiveApiTester`
 use beacon_chain::{
     chain_config::{DisallowedReOrgOffsets, ReOrgThreshold},
-    test_utils::{AttestationStrategy, BlockStrategy, SyncCommitteeStrategy},
+    store::{snapshot::SNAPSHOT_MANIFEST_FILE, HotColdDB, LevelDB, StoreConfig},
+    test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, SyncCommitteeStrategy},
     ChainConfig,
 };
 use beacon_processor::work_reprocessing_queue::ReprocessQueueMessage;
+use eth2::lighthouse::{DatabaseSnapshotJob, DatabaseSnapshotStatus};
 use eth2::types::ProduceBlockV3Response;
 use eth2::types::{DepositContractData, StateId};
+use eth2::{reqwest, BeaconNodeHttpClient, StatusCode, Timeouts};
 use execution_layer::{ForkchoiceState, PayloadAttributes};
-use http_api::test_utils::InteractiveTester;
+use http_api::test_utils::{create_api_server_with_config, ApiServer, InteractiveTester};
+use http_api::Config;
+use logging::test_logger;
 use parking_lot::Mutex;
+use sensitive_url::SensitiveUrl;
 use slot_clock::SlotClock;
 use state_processing::{
     per_block_processing::get_expected_withdrawals, state_advance::complete_state_advance,
@@ -922,3 +928,248 @@ async fn queue_attestations_from_http() {
 
     attestation_future.await.unwrap();
 }
+
+// Test that API keys restrict requests to their route groups and rate limits.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn api_keys_access_control() {
+    let keys_dir = tempfile::tempdir().unwrap();
+    let keys_file = keys_dir.path().join("api-keys.yaml");
+    std::fs::write(
+        &keys_file,
+        "
+anonymous:
+  groups: [public]
+keys:
+  - name: validator-client
+    key: secret
+    groups: [public, validator]
+    rate_limit:
+      requests_per_second: 0.001
+      burst: 2
+  - name: slasher
+    key: other-secret
+    groups: [public, validator]
+",
+    )
+    .unwrap();
+
+    let harness = BeaconChainHarness::builder(E::default())
+        .default_spec()
+        .logger(test_logger())
+        .deterministic_keypairs(32)
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    let config = Config {
+        enabled: true,
+        listen_port: 0,
+        api_keys_file: Some(keys_file),
+        ..Config::default()
+    };
+    let ApiServer {
+        server,
+        listening_socket,
+        ..
+    } = create_api_server_with_config(
+        harness.chain.clone(),
+        &harness.runtime,
+        config,
+        harness.logger().clone(),
+    )
+    .await;
+    tokio::spawn(server);
+
+    let client = reqwest::Client::new();
+    let get = |path: &str, key: Option<&str>| {
+        let request = client.get(format!("http://{}{}", listening_socket, path));
+        let request = match key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        };
+        async move { request.send().await.unwrap().status() }
+    };
+
+    // Anonymous requests may only access public routes.
+    assert_eq!(get("/eth/v1/node/version", None).await, StatusCode::OK);
+    assert_eq!(
+        get("/eth/v1/validator/duties/proposer/0", None).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(get("/lighthouse/health", None).await, StatusCode::FORBIDDEN);
+
+    // Unknown keys are rejected, rather than treated as anonymous.
+    assert_eq!(
+        get("/eth/v1/node/version", Some("wrong")).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    // The key may access its groups until its burst is exhausted.
+    assert_eq!(
+        get("/eth/v1/validator/duties/proposer/0", Some("secret")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        get("/lighthouse/health", Some("secret")).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        get("/eth/v1/node/version", Some("secret")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        get("/eth/v1/node/version", Some("secret")).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    // The HTTP client sends its API key with every request.
+    let url = SensitiveUrl::parse(&format!("http://{}", listening_socket)).unwrap();
+    let anonymous_client =
+        BeaconNodeHttpClient::new(url.clone(), Timeouts::set_all(Duration::from_secs(10)));
+    let err = anonymous_client
+        .get_validator_duties_proposer(Epoch::new(0))
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+    let key_client = BeaconNodeHttpClient::new(url, Timeouts::set_all(Duration::from_secs(10)))
+        .with_api_key("other-secret".to_string().into());
+    key_client
+        .get_validator_duties_proposer(Epoch::new(0))
+        .await
+        .unwrap();
+}
+
+// Test that database snapshots require an API key, run in the background, and can't overlap.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn database_snapshot_jobs() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let keys_file = data_dir.path().join("api-keys.yaml");
+    std::fs::write(
+        &keys_file,
+        "
+anonymous:
+  groups: [public, lighthouse-admin]
+keys:
+  - name: admin
+    key: secret
+    groups: [lighthouse-admin]
+",
+    )
+    .unwrap();
+
+    // Snapshots need the metadata which is only written to on-disk databases.
+    let spec = E::default_spec();
+    let store = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &data_dir.path().join("chain_db"),
+        &data_dir.path().join("freezer_db"),
+        &data_dir.path().join("blobs_db"),
+        |_, _, _| Ok(()),
+        StoreConfig::default(),
+        spec.clone(),
+        test_logger(),
+    )
+    .unwrap();
+    let harness = BeaconChainHarness::builder(E::default())
+        .spec(spec)
+        .logger(test_logger())
+        .deterministic_keypairs(32)
+        .fresh_disk_store(store)
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize * 5,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    let split = harness.chain.store.get_split_info();
+    assert!(split.slot > 0);
+
+    let config = Config {
+        enabled: true,
+        listen_port: 0,
+        api_keys_file: Some(keys_file),
+        data_dir: data_dir.path().to_path_buf(),
+        ..Config::default()
+    };
+    let ApiServer {
+        server,
+        listening_socket,
+        ..
+    } = create_api_server_with_config(
+        harness.chain.clone(),
+        &harness.runtime,
+        config,
+        harness.logger().clone(),
+    )
+    .await;
+    tokio::spawn(server);
+
+    // The anonymous policy isn't sufficient, even though it may access the endpoint's group.
+    let url = SensitiveUrl::parse(&format!("http://{}", listening_socket)).unwrap();
+    let anonymous_client =
+        BeaconNodeHttpClient::new(url.clone(), Timeouts::set_all(Duration::from_secs(10)));
+    anonymous_client
+        .get_lighthouse_database_info()
+        .await
+        .unwrap();
+    let err = anonymous_client
+        .post_lighthouse_database_snapshot()
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+
+    // Only one of two concurrent requests starts a snapshot.
+    let client = BeaconNodeHttpClient::new(url, Timeouts::set_all(Duration::from_secs(10)))
+        .with_api_key("secret".to_string().into());
+    let (first, second) = tokio::join!(
+        client.post_lighthouse_database_snapshot(),
+        client.post_lighthouse_database_snapshot()
+    );
+    let (job, err) = match (first, second) {
+        (Ok(job), Err(err)) | (Err(err), Ok(job)) => (job, err),
+        results => panic!("expected exactly one snapshot to start: {:?}", results),
+    };
+    assert_eq!(err.status(), Some(StatusCode::CONFLICT));
+    assert_eq!(job.job_id, 0);
+    assert_eq!(job.status, DatabaseSnapshotStatus::Running);
+
+    let job = wait_for_snapshot(&client, job.job_id).await;
+    let DatabaseSnapshotStatus::Complete { manifest } = job.status else {
+        panic!("snapshot did not complete: {:?}", job.status);
+    };
+    assert_eq!(manifest.split, split);
+    assert!(job.path.starts_with(data_dir.path()));
+    assert!(job.path.join(SNAPSHOT_MANIFEST_FILE).exists());
+
+    let err = client
+        .get_lighthouse_database_snapshot(1)
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+
+    // Another snapshot may be started once the first is complete.
+    let job = client.post_lighthouse_database_snapshot().await.unwrap();
+    assert_eq!(job.job_id, 1);
+    let job = wait_for_snapshot(&client, job.job_id).await;
+    assert!(matches!(
+        job.status,
+        DatabaseSnapshotStatus::Complete { .. }
+    ));
+}
+
+/// Poll a snapshot job until it is no longer running.
+async fn wait_for_snapshot(client: &BeaconNodeHttpClient, job_id: u64) -> DatabaseSnapshotJob {
+    for _ in 0..100 {
+        let job = client
+            .get_lighthouse_database_snapshot(job_id)
+            .await
+            .unwrap();
+        if job.status != DatabaseSnapshotStatus::Running {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("snapshot {} did not complete", job_id);
+}
@@ -0,0 +1,232 @@
+//! Tests for syncing the op pool with peer beacon nodes over the HTTP API.
+use beacon_chain::{
+    observed_operations::ObservationOutcome,
+    op_pool_sync::{sync_with_peer, OperationCounts, Peer},
+    test_utils::HarnessAttestations,
+};
+use eth2::{BeaconNodeHttpClient, Timeouts};
+use http_api::test_utils::InteractiveTester;
+use sensitive_url::SensitiveUrl;
+use std::net::TcpListener;
+use std::time::Duration;
+use tree_hash::TreeHash;
+use types::{Hash256, MinimalEthSpec, Slot};
+
+type E = MinimalEthSpec;
+
+const VALIDATOR_COUNT: usize = 64;
+const SYNC_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Create two beacon nodes on the same chain, and extend it by `num_blocks` blocks.
+async fn two_nodes(num_blocks: usize) -> (InteractiveTester<E>, InteractiveTester<E>) {
+    let node_a = InteractiveTester::<E>::new(None, VALIDATOR_COUNT).await;
+    let node_b = InteractiveTester::<E>::new(None, VALIDATOR_COUNT).await;
+
+    for _ in 0..num_blocks {
+        extend_chain(&node_a, &node_b).await;
+    }
+
+    (node_a, node_b)
+}
+
+/// Extend the chain shared by `node_a` and `node_b` by one block.
+async fn extend_chain(node_a: &InteractiveTester<E>, node_b: &InteractiveTester<E>) {
+    node_a.harness.advance_slot();
+    node_b.harness.advance_slot();
+    let slot = node_a.harness.get_current_slot();
+    let (block_contents, _) = node_a
+        .harness
+        .make_block(node_a.harness.get_current_state(), slot)
+        .await;
+    let block_root = block_contents.0.canonical_root();
+    for node in [node_a, node_b] {
+        node.harness
+            .process_block(slot, block_root, block_contents.clone())
+            .await
+            .unwrap();
+    }
+}
+
+/// Attest to the head of `node` with every validator, adding the aggregates to its op pool.
+fn attest_to_head(node: &InteractiveTester<E>) -> HarnessAttestations<E> {
+    let harness = &node.harness;
+    let head = harness.chain.head_snapshot();
+    let attestations = harness.make_attestations(
+        &harness.get_all_validators(),
+        &head.beacon_state,
+        head.beacon_state_root(),
+        head.beacon_block_root.into(),
+        head.beacon_block.slot(),
+    );
+    harness.process_attestations(attestations.clone());
+    attestations
+}
+
+fn committee_count_at_head(node: &InteractiveTester<E>) -> usize {
+    let head = node.harness.chain.head_snapshot();
+    head.beacon_state
+        .get_committee_count_at_slot(head.beacon_block.slot())
+        .unwrap() as usize
+}
+
+fn pool_attestations_at_slot(node: &InteractiveTester<E>, slot: Slot) -> usize {
+    node.harness
+        .chain
+        .op_pool
+        .get_filtered_attestations(|data| data.slot == slot)
+        .len()
+}
+
+/// Add a proposer slashing for `validator_index` to the op pool of `node`, returning its root.
+fn insert_proposer_slashing(node: &InteractiveTester<E>, validator_index: u64) -> Hash256 {
+    let chain = &node.harness.chain;
+    let slashing = node.harness.make_proposer_slashing(validator_index);
+    let root = slashing.tree_hash_root();
+    let ObservationOutcome::New(slashing) =
+        chain.verify_proposer_slashing_for_gossip(slashing).unwrap()
+    else {
+        panic!("proposer slashing should be new");
+    };
+    chain.import_proposer_slashing(slashing);
+    root
+}
+
+/// Add an attester slashing for `validator_index` to the op pool of `node`, returning its root.
+fn insert_attester_slashing(node: &InteractiveTester<E>, validator_index: u64) -> Hash256 {
+    let chain = &node.harness.chain;
+    let slashing = node.harness.make_attester_slashing(vec![validator_index]);
+    let root = slashing.tree_hash_root();
+    let ObservationOutcome::New(slashing) =
+        chain.verify_attester_slashing_for_gossip(slashing).unwrap()
+    else {
+        panic!("attester slashing should be new");
+    };
+    chain.import_attester_slashing(slashing);
+    root
+}
+
+// Test that aggregates are pulled from a peer into the op pool, and that aggregates which have
+// already been observed, whether from gossip or an earlier sync, are not imported again.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pull_aggregates_deduplicated_against_observed_attestations() {
+    let (node_a, node_b) = two_nodes(2).await;
+    let chain_b = &node_b.harness.chain;
+    let committee_count = committee_count_at_head(&node_a);
+    assert!(committee_count > 1);
+    let mut peer = Peer::new(node_a.client.clone());
+
+    // Node B has already seen the aggregates for this slot on gossip.
+    let attestations = attest_to_head(&node_a);
+    node_b.harness.process_attestations(attestations);
+    let slot = node_b.harness.get_current_slot();
+    let outcome = sync_with_peer(chain_b, &mut peer, slot, SYNC_TIMEOUT).await;
+    assert_eq!(outcome.imported.unwrap(), OperationCounts::default());
+    assert_eq!(outcome.pushed.unwrap(), OperationCounts::default());
+
+    // Only node A has seen the aggregates for the next slot.
+    extend_chain(&node_a, &node_b).await;
+    attest_to_head(&node_a);
+    let slot = node_b.harness.get_current_slot();
+    assert_eq!(pool_attestations_at_slot(&node_b, slot), 0);
+
+    let outcome = sync_with_peer(chain_b, &mut peer, slot, SYNC_TIMEOUT).await;
+    assert_eq!(
+        outcome.imported.unwrap(),
+        OperationCounts {
+            attestations: committee_count,
+            ..OperationCounts::default()
+        }
+    );
+    assert_eq!(pool_attestations_at_slot(&node_b, slot), committee_count);
+
+    // Syncing again imports nothing, since the aggregates have now been observed.
+    let outcome = sync_with_peer(chain_b, &mut peer, slot, SYNC_TIMEOUT).await;
+    assert_eq!(outcome.imported.unwrap(), OperationCounts::default());
+    assert_eq!(pool_attestations_at_slot(&node_b, slot), committee_count);
+}
+
+// Test that operations are pushed to a peer once, and that operations pulled from the peer are
+// not pushed back to it.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn push_operations_unknown_to_peer() {
+    let (node_a, node_b) = two_nodes(1).await;
+    let chain_b = &node_b.harness.chain;
+    let mut peer = Peer::new(node_a.client.clone());
+
+    let root_a = insert_proposer_slashing(&node_a, 1);
+    let proposer_root_b = insert_proposer_slashing(&node_b, 2);
+    let attester_root_b = insert_attester_slashing(&node_b, 3);
+
+    let slot = node_b.harness.get_current_slot();
+    let outcome = sync_with_peer(chain_b, &mut peer, slot, SYNC_TIMEOUT).await;
+    assert_eq!(
+        outcome.imported.unwrap(),
+        OperationCounts {
+            proposer_slashings: 1,
+            ..OperationCounts::default()
+        }
+    );
+    assert_eq!(
+        outcome.pushed.unwrap(),
+        OperationCounts {
+            attester_slashings: 1,
+            proposer_slashings: 1,
+            ..OperationCounts::default()
+        }
+    );
+    for root in [root_a, proposer_root_b, attester_root_b] {
+        assert!(peer.knows_operation(&root));
+    }
+    assert_eq!(chain_b.op_pool.get_all_proposer_slashings().len(), 2);
+    let chain_a = &node_a.harness.chain;
+    assert_eq!(chain_a.op_pool.get_all_proposer_slashings().len(), 2);
+    assert_eq!(chain_a.op_pool.get_all_attester_slashings().len(), 1);
+
+    // Both nodes now have every operation, so nothing is imported or pushed.
+    let outcome = sync_with_peer(chain_b, &mut peer, slot, SYNC_TIMEOUT).await;
+    assert_eq!(outcome.imported.unwrap(), OperationCounts::default());
+    assert_eq!(outcome.pushed.unwrap(), OperationCounts::default());
+}
+
+// Test that an operation rejected by a peer is recorded as known to it so that it isn't pushed
+// again, while an operation which couldn't be sent is retried.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn push_rejected_by_peer() {
+    let node = InteractiveTester::<E>::new(None, VALIDATOR_COUNT).await;
+    node.harness.advance_slot();
+    let chain = &node.harness.chain;
+    let root = insert_proposer_slashing(&node, 2);
+    let slot = node.harness.get_current_slot();
+
+    // A node with a different validator set has a different genesis validators root, so it
+    // rejects the signature of the slashing.
+    let other_node = InteractiveTester::<E>::new(None, VALIDATOR_COUNT / 2).await;
+    let mut other_peer = Peer::new(other_node.client.clone());
+    let outcome = sync_with_peer(chain, &mut other_peer, slot, SYNC_TIMEOUT).await;
+    assert_eq!(outcome.imported.unwrap(), OperationCounts::default());
+    assert_eq!(outcome.pushed.unwrap(), OperationCounts::default());
+    assert!(other_peer.knows_operation(&root));
+    assert!(other_node
+        .harness
+        .chain
+        .op_pool
+        .get_all_proposer_slashings()
+        .is_empty());
+
+    // Nothing is listening on the port of an unreachable node, so both steps fail and the
+    // slashing isn't recorded as known.
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let client = BeaconNodeHttpClient::new(
+        SensitiveUrl::parse(&format!("http://127.0.0.1:{port}")).unwrap(),
+        Timeouts::set_all(Duration::from_secs(1)),
+    );
+    let mut unreachable_peer = Peer::new(client);
+    let outcome = sync_with_peer(chain, &mut unreachable_peer, slot, SYNC_TIMEOUT).await;
+    assert!(outcome.imported.is_err());
+    assert!(outcome.pushed.is_err());
+    assert!(!unreachable_peer.knows_operation(&root));
+}
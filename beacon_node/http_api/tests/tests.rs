@@ -1,10 +1,15 @@
 use beacon_chain::test_utils::RelativeSyncCommittee;
 use beacon_chain::{
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType},
+    validator_sightings::MAX_REQUEST_INDICES,
     BeaconChain, ChainConfig, StateSkipConfig, WhenSlotSkipped,
 };
 use environment::null_logger;
 use eth2::{
+    lighthouse::{
+        ForkChoiceUpdate, ValidatorMonitorEpochSummary, ValidatorSightingKind,
+        ValidatorSightingsRequest,
+    },
     mixin::{RequestAccept, ResponseForkName, ResponseOptional},
     reqwest::RequestBuilder,
     types::{
@@ -896,6 +901,14 @@ impl ApiTester {
                     .await
                     .unwrap()
                     .map(|res| res.data);
+                let result_post_ssz = self
+                    .client
+                    .post_beacon_states_validator_balances_ssz(
+                        state_id.0,
+                        validator_pubkey_ids.clone(),
+                    )
+                    .await
+                    .unwrap();
                 let result_post_pubkey_ids = self
                     .client
                     .post_beacon_states_validator_balances(state_id.0, validator_pubkey_ids)
@@ -922,6 +935,78 @@ impl ApiTester {
                 assert_eq!(result_pubkey_ids, expected, "{:?}", state_id);
                 assert_eq!(result_post_index_ids, expected, "{:?}", state_id);
                 assert_eq!(result_post_pubkey_ids, expected, "{:?}", state_id);
+                assert_eq!(result_post_ssz, expected, "{:?}", state_id);
+            }
+        }
+
+        self
+    }
+
+    pub async fn test_beacon_states_validator_identities(self) -> Self {
+        for state_id in self.interesting_state_ids() {
+            for validator_indices in self.interesting_validator_indices() {
+                let state_opt = state_id.state(&self.chain).ok();
+                let validators: Vec<Validator> = match state_opt.as_ref() {
+                    Some((state, _execution_optimistic, _finalized)) => {
+                        state.validators().clone().to_vec()
+                    }
+                    None => vec![],
+                };
+                let validator_index_ids = validator_indices
+                    .iter()
+                    .cloned()
+                    .map(|i| ValidatorId::Index(i))
+                    .collect::<Vec<ValidatorId>>();
+                let validator_pubkey_ids = validator_indices
+                    .iter()
+                    .cloned()
+                    .map(|i| {
+                        ValidatorId::PublicKey(
+                            validators
+                                .get(i as usize)
+                                .map_or(PublicKeyBytes::empty(), |val| val.pubkey),
+                        )
+                    })
+                    .collect::<Vec<ValidatorId>>();
+
+                let result_index_ids = self
+                    .client
+                    .post_beacon_states_validator_identities(state_id.0, validator_index_ids)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data);
+                let result_pubkey_ids = self
+                    .client
+                    .post_beacon_states_validator_identities(
+                        state_id.0,
+                        validator_pubkey_ids.clone(),
+                    )
+                    .await
+                    .unwrap()
+                    .map(|res| res.data);
+                let result_ssz = self
+                    .client
+                    .post_beacon_states_validator_identities_ssz(state_id.0, validator_pubkey_ids)
+                    .await
+                    .unwrap();
+
+                let expected = state_opt.map(|_| {
+                    validator_indices
+                        .iter()
+                        .filter_map(|&i| {
+                            let validator = validators.get(i as usize)?;
+                            Some(ValidatorIdentityData {
+                                index: i,
+                                pubkey: validator.pubkey,
+                                activation_epoch: validator.activation_epoch,
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                });
+
+                assert_eq!(result_index_ids, expected, "{:?}", state_id);
+                assert_eq!(result_pubkey_ids, expected, "{:?}", state_id);
+                assert_eq!(result_ssz, expected, "{:?}", state_id);
             }
         }
 
@@ -1102,6 +1187,12 @@ impl ApiTester {
                 .await
                 .unwrap()
                 .map(|res| res.data);
+            let results_ssz = self
+                .client
+                .get_beacon_states_committees_ssz(state_id.0, None, None, epoch_opt)
+                .await
+                .unwrap();
+            assert_eq!(results, results_ssz, "{}", state_id);
 
             if results.is_none() && state_opt.is_none() {
                 continue;
@@ -1165,6 +1256,82 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_beacon_states_pending_consolidations(self) -> Self {
+        for state_id in self.interesting_state_ids() {
+            let state_opt = state_id
+                .state(&self.chain)
+                .ok()
+                .map(|(state, _execution_optimistic, _finalized)| state);
+
+            let result = self
+                .client
+                .get_beacon_states_pending_consolidations(state_id.0)
+                .await;
+
+            let Some(state) = state_opt else {
+                assert!(matches!(result, Ok(None)), "{}", state_id);
+                continue;
+            };
+
+            let Ok(expected) = state.pending_consolidations() else {
+                // Pre-Electra states have no queue.
+                assert_eq!(result.unwrap_err().status().unwrap(), 400);
+                continue;
+            };
+            let expected = expected.iter().cloned().collect::<Vec<_>>();
+
+            assert_eq!(result.unwrap().unwrap().data, expected, "{}", state_id);
+
+            let result_ssz = self
+                .client
+                .get_beacon_states_pending_consolidations_ssz(state_id.0)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(result_ssz, expected, "{}", state_id);
+
+            let deposits = self
+                .client
+                .get_beacon_states_pending_deposits(state_id.0)
+                .await
+                .unwrap()
+                .unwrap()
+                .data;
+            assert_eq!(
+                deposits,
+                state
+                    .pending_balance_deposits()
+                    .unwrap()
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>(),
+                "{}",
+                state_id
+            );
+
+            let withdrawals = self
+                .client
+                .get_beacon_states_pending_partial_withdrawals(state_id.0)
+                .await
+                .unwrap()
+                .unwrap()
+                .data;
+            assert_eq!(
+                withdrawals,
+                state
+                    .pending_partial_withdrawals()
+                    .unwrap()
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>(),
+                "{}",
+                state_id
+            );
+        }
+
+        self
+    }
+
     pub async fn test_beacon_headers_all_slots(self) -> Self {
         for slot in 0..CHAIN_LENGTH {
             let slot = Slot::from(slot);
@@ -2858,9 +3025,33 @@ impl ApiTester {
 
             assert_eq!(self.chain.head_beacon_block().as_ref(), &signed_block);
 
+            let trace = self
+                .client
+                .get_lighthouse_analysis_block_production(slot)
+                .await
+                .unwrap()
+                .expect("block production is traced");
+            assert_eq!(trace.block_root, Some(signed_block.canonical_root()));
+            assert_eq!(
+                trace.proposer_index,
+                Some(signed_block.message().proposer_index())
+            );
+            assert!(trace.state_load_ms.is_some());
+            assert!(trace.op_pool_packing_ms.is_some());
+            assert!(trace.production_ms.is_some());
+            assert!(trace.signing_round_trip_ms.is_some());
+            assert!(trace.publication_ms.is_some());
+
             self.chain.slot_clock.set_slot(slot.as_u64() + 1);
         }
 
+        assert!(self
+            .client
+            .get_lighthouse_analysis_block_production(self.chain.slot().unwrap() + 1)
+            .await
+            .unwrap()
+            .is_none());
+
         self
     }
 
@@ -5348,6 +5539,113 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_fork_choice_history(self) -> Self {
+        let history = self
+            .client
+            .get_lighthouse_fork_choice_history(None, None)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            history,
+            self.chain.fork_choice_history.read().get(None, None)
+        );
+
+        // The most recent head change should be to the current head.
+        let head_block_root = self.chain.head_beacon_block_root();
+        let new_head_block = history
+            .iter()
+            .rev()
+            .find_map(|entry| match &entry.update {
+                ForkChoiceUpdate::HeadChange(change) => Some(change.new_head_block),
+                ForkChoiceUpdate::Reorg(reorg) => Some(reorg.new_head_block),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(new_head_block, head_block_root);
+
+        let slot = self.chain.slot().unwrap();
+        let history = self
+            .client
+            .get_lighthouse_fork_choice_history(Some(slot + 1), None)
+            .await
+            .unwrap()
+            .data;
+        assert!(history.is_empty());
+
+        let err = self
+            .client
+            .get_lighthouse_fork_choice_history(Some(slot + 1), Some(slot))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_blocks_stream(self) -> Self {
+        let head_slot = self.chain.head_snapshot().beacon_block.slot();
+        let mut expected_roots = self
+            .chain
+            .forwards_iter_block_roots_until(Slot::new(0), head_slot)
+            .unwrap()
+            .map(|result| result.unwrap().0)
+            .collect::<Vec<_>>();
+        expected_roots.dedup();
+
+        let items = self
+            .client
+            .get_lighthouse_blocks_stream::<E>(Slot::new(0), head_slot, true, &self.chain.spec)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(items.len(), expected_roots.len());
+        for (item, expected_root) in items.into_iter().zip(expected_roots) {
+            let item = item.unwrap();
+            assert_eq!(item.block.canonical_root(), expected_root);
+            assert_eq!(
+                item.blobs.unwrap(),
+                self.chain.get_blobs(&expected_root).unwrap()
+            );
+        }
+
+        // A range within the chain should only include the blocks in that range.
+        let start_slot = head_slot - 2;
+        let blocks = self
+            .client
+            .get_lighthouse_blocks_stream::<E>(start_slot, head_slot, false, &self.chain.spec)
+            .await
+            .unwrap()
+            .map(|item| item.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        assert!(!blocks.is_empty());
+        for item in &blocks {
+            assert!(item.block.slot() >= start_slot && item.block.slot() <= head_slot);
+            assert!(item.blobs.is_none());
+        }
+
+        let err = self
+            .client
+            .get_lighthouse_blocks_stream::<E>(head_slot, start_slot, false, &self.chain.spec)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.status().unwrap(), 400);
+
+        // Ranges beyond the head are rejected rather than streamed.
+        let err = self
+            .client
+            .get_lighthouse_blocks_stream::<E>(start_slot, head_slot + 1, false, &self.chain.spec)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
     pub async fn test_get_lighthouse_validator_inclusion_global(self) -> Self {
         let epoch = self.chain.epoch().unwrap() - 1;
         self.client
@@ -5402,6 +5700,82 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_analysis_propagation(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+        let seen = self.chain.slot_clock.start_of(slot).unwrap() + Duration::from_millis(300);
+        let block_root = Hash256::repeat_byte(42);
+        {
+            let mut propagation = self.chain.gossip_propagation.write();
+            propagation.observe_block(&[1], slot, block_root, "peer_a".into(), seen);
+            propagation.observe_duplicate(&[1], "peer_b".into(), seen + Duration::from_millis(50));
+            propagation.observe_aggregate(&[2], slot, seen);
+        }
+
+        let result = self
+            .client
+            .get_lighthouse_analysis_propagation(slot, slot)
+            .await
+            .unwrap();
+        assert_eq!(result.blocks.len(), 1);
+        assert_eq!(result.blocks[0].block_root, block_root);
+        assert_eq!(result.blocks[0].first_seen_delay_ms, 300);
+        assert_eq!(result.blocks[0].peers.len(), 2);
+        assert_eq!(result.blocks[0].arrival.max_ms, 50);
+        assert_eq!(result.aggregates.len(), 1);
+        assert_eq!(result.aggregates[0].aggregates, 1);
+
+        let err = self
+            .client
+            .get_lighthouse_analysis_propagation(slot + 1, slot)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_validator_monitor(self) -> Self {
+        let summaries = (0..3)
+            .map(|epoch| ValidatorMonitorEpochSummary {
+                epoch: Epoch::new(epoch),
+                validator_index: 7,
+                attestations: 1,
+                attestation_target_hit: true,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        beacon_chain::validator_monitor_history::store_summaries(
+            &self.chain.store.hot_db,
+            &summaries,
+        )
+        .unwrap();
+
+        let result = self
+            .client
+            .get_lighthouse_validator_monitor(7, Epoch::new(1), Epoch::new(5))
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(result, summaries[1..]);
+
+        let result = self
+            .client
+            .get_lighthouse_validator_monitor(8, Epoch::new(0), Epoch::new(5))
+            .await
+            .unwrap()
+            .data;
+        assert!(result.is_empty());
+
+        let err = self
+            .client
+            .get_lighthouse_validator_monitor(7, Epoch::new(5), Epoch::new(1))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
     pub async fn test_get_lighthouse_database_info(self) -> Self {
         let info = self.client.get_lighthouse_database_info().await.unwrap();
 
@@ -5415,6 +5789,54 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_slasher_disabled(self) -> Self {
+        // The test harness runs without a slasher.
+        let err = self
+            .client
+            .get_lighthouse_slasher_slashings::<E>()
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 404);
+
+        let err = self
+            .client
+            .get_lighthouse_slasher_database()
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 404);
+
+        let err = self
+            .client
+            .get_lighthouse_slasher_targets(0, Epoch::new(0), Epoch::new(1))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 404);
+
+        // Invalid ranges are rejected before the slasher is consulted.
+        let err = self
+            .client
+            .get_lighthouse_slasher_attestations::<E>(0, Epoch::new(2), Epoch::new(1))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        let err = self
+            .client
+            .get_lighthouse_slasher_targets(0, Epoch::new(0), Epoch::new(1 << 20))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        let err = self
+            .client
+            .get_lighthouse_slasher_proposals(0, Slot::new(0), Slot::new(1 << 20))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
     pub async fn test_post_lighthouse_database_reconstruct(self) -> Self {
         let response = self
             .client
@@ -5489,6 +5911,67 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_lighthouse_validators_sightings(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+        let head_state = self.chain.head_beacon_state_cloned();
+        let request = ValidatorSightingsRequest {
+            from_slot: slot,
+            indices: (0..head_state.validators().len() as u64).collect(),
+        };
+
+        // The first request starts watching the validators.
+        let result = self
+            .client
+            .post_lighthouse_validators_sightings(&request)
+            .await
+            .unwrap()
+            .data;
+        assert!(result.is_empty());
+
+        self.client
+            .post_beacon_pool_attestations_v1(self.attestations.as_slice())
+            .await
+            .unwrap();
+
+        let result = self
+            .client
+            .post_lighthouse_validators_sightings(&request)
+            .await
+            .unwrap()
+            .data;
+
+        let committees = head_state.get_beacon_committees_at_slot(slot).unwrap();
+        let mut expected: Vec<u64> = committees
+            .into_iter()
+            .flat_map(|committee| committee.committee.iter().map(|i| *i as u64))
+            .collect();
+        expected.sort_unstable();
+        let mut seen: Vec<u64> = result.iter().map(|s| s.validator_index).collect();
+        seen.sort_unstable();
+        assert_eq!(seen, expected);
+
+        let data_root = self.attestations[0].data().tree_hash_root();
+        assert!(result.iter().all(|sighting| {
+            sighting.kind == ValidatorSightingKind::Attestation
+                && sighting.slot == slot
+                && sighting.root == data_root
+        }));
+
+        // Requests for too many validators are rejected.
+        let request = ValidatorSightingsRequest {
+            from_slot: slot,
+            indices: (0..=MAX_REQUEST_INDICES as u64).collect(),
+        };
+        let err = self
+            .client
+            .post_lighthouse_validators_sightings(&request)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
     pub async fn test_get_events(self) -> Self {
         // Subscribe to all events
         let topics = vec![
@@ -5962,12 +6445,16 @@ async fn beacon_get() {
         .await
         .test_beacon_states_validator_balances()
         .await
+        .test_beacon_states_validator_identities()
+        .await
         .test_beacon_states_committees()
         .await
         .test_beacon_states_validator_id()
         .await
         .test_beacon_states_randao()
         .await
+        .test_beacon_states_pending_consolidations()
+        .await
         .test_beacon_headers_all_slots()
         .await
         .test_beacon_headers_all_parents()
@@ -6822,6 +7309,10 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_proto_array()
         .await
+        .test_get_lighthouse_fork_choice_history()
+        .await
+        .test_get_lighthouse_blocks_stream()
+        .await
         .test_get_lighthouse_validator_inclusion()
         .await
         .test_get_lighthouse_validator_inclusion_global()
@@ -6834,14 +7325,28 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_staking()
         .await
+        .test_get_lighthouse_validator_monitor()
+        .await
+        .test_get_lighthouse_analysis_propagation()
+        .await
         .test_get_lighthouse_database_info()
         .await
+        .test_get_lighthouse_slasher_disabled()
+        .await
         .test_post_lighthouse_database_reconstruct()
         .await
         .test_post_lighthouse_liveness()
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_lighthouse_validators_sightings() {
+    ApiTester::new()
+        .await
+        .test_post_lighthouse_validators_sightings()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn optimistic_responses() {
     ApiTester::new_with_hard_forks(true, true)
@@ -139,6 +139,17 @@ pub enum Event {
         /// The decompressed message itself.
         message: Message,
     },
+    /// A message which has already been received was received again from another peer.
+    ///
+    /// Only emitted if [`Config::report_duplicates`] is enabled.
+    DuplicateMessage {
+        /// The peer that forwarded us this message.
+        propagation_source: PeerId,
+        /// The [`MessageId`] of the message.
+        message_id: MessageId,
+        /// The topic the message was received on.
+        topic: TopicHash,
+    },
     /// A remote subscribed to a topic.
     Subscribed {
         /// Remote that has subscribed.
@@ -1824,6 +1835,14 @@ where
                 peer_score.duplicated_message(propagation_source, &msg_id, &message.topic);
             }
             self.mcache.observe_duplicate(&msg_id, propagation_source);
+            if self.config.report_duplicates() {
+                self.events
+                    .push_back(ToSwarm::GenerateEvent(Event::DuplicateMessage {
+                        propagation_source: *propagation_source,
+                        message_id: msg_id,
+                        topic: message.topic,
+                    }));
+            }
             return;
         }
         tracing::debug!(
@@ -5429,3 +5429,64 @@ fn clear_stale_idontwant() {
     let peer = gs.connected_peers.get_mut(&peers[2]).unwrap();
     assert!(peer.dont_send.is_empty());
 }
+
+#[test]
+fn test_report_duplicates() {
+    for report_duplicates in [false, true] {
+        let config = ConfigBuilder::default()
+            .report_duplicates(report_duplicates)
+            .build()
+            .unwrap();
+        let (mut gs, peers, _, topic_hashes) = inject_nodes1()
+            .peer_no(2)
+            .topics(vec!["topic".into()])
+            .to_subscribe(true)
+            .gs_config(config)
+            .create_network();
+
+        let raw_message = RawMessage {
+            source: Some(PeerId::random()),
+            data: vec![1, 2, 3],
+            sequence_number: Some(0),
+            topic: topic_hashes[0].clone(),
+            signature: None,
+            key: None,
+            validated: true,
+        };
+        let message = gs
+            .data_transform
+            .inbound_transform(raw_message.clone())
+            .unwrap();
+        let msg_id = gs.config.message_id(&message);
+
+        gs.handle_received_message(raw_message.clone(), &peers[0]);
+        gs.events.clear();
+        gs.handle_received_message(raw_message, &peers[1]);
+
+        let duplicates = gs
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                ToSwarm::GenerateEvent(Event::DuplicateMessage {
+                    propagation_source,
+                    message_id,
+                    topic,
+                }) => Some((*propagation_source, message_id.clone(), topic.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if report_duplicates {
+            assert_eq!(
+                duplicates,
+                vec![(peers[1], msg_id, topic_hashes[0].clone())],
+                "the duplicate should be reported with the peer that forwarded it"
+            );
+        } else {
+            assert!(
+                duplicates.is_empty(),
+                "duplicates should not be reported by default"
+            );
+        }
+    }
+}
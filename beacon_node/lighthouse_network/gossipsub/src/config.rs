@@ -85,6 +85,7 @@ pub struct Config {
     unsubscribe_backoff: Duration,
     backoff_slack: u32,
     flood_publish: bool,
+    report_duplicates: bool,
     graft_flood_threshold: Duration,
     mesh_outbound_min: usize,
     opportunistic_graft_ticks: u64,
@@ -282,6 +283,12 @@ impl Config {
         self.flood_publish
     }
 
+    /// Whether to emit an [`crate::Event::DuplicateMessage`] when a valid message that has already
+    /// been received is received again from another peer. The default is false.
+    pub fn report_duplicates(&self) -> bool {
+        self.report_duplicates
+    }
+
     /// If a GRAFT comes before `graft_flood_threshold` has elapsed since the last PRUNE,
     /// then there is an extra score penalty applied to the peer through P7.
     pub fn graft_flood_threshold(&self) -> Duration {
@@ -427,6 +434,7 @@ impl Default for ConfigBuilder {
                 unsubscribe_backoff: Duration::from_secs(10),
                 backoff_slack: 1,
                 flood_publish: true,
+                report_duplicates: false,
                 graft_flood_threshold: Duration::from_secs(10),
                 mesh_outbound_min: 2,
                 opportunistic_graft_ticks: 60,
@@ -702,6 +710,13 @@ impl ConfigBuilder {
         self
     }
 
+    /// Whether to emit an [`crate::Event::DuplicateMessage`] when a valid message that has already
+    /// been received is received again from another peer. The default is false.
+    pub fn report_duplicates(&mut self, report_duplicates: bool) -> &mut Self {
+        self.config.report_duplicates = report_duplicates;
+        self
+    }
+
     /// If a GRAFT comes before `graft_flood_threshold` has elapsed since the last PRUNE,
     /// then there is an extra score penalty applied to the peer through P7.
     pub fn graft_flood_threshold(&mut self, graft_flood_threshold: Duration) -> &mut Self {
@@ -883,6 +898,7 @@ impl std::fmt::Debug for Config {
         let _ = builder.field("prune_backoff", &self.prune_backoff);
         let _ = builder.field("backoff_slack", &self.backoff_slack);
         let _ = builder.field("flood_publish", &self.flood_publish);
+        let _ = builder.field("report_duplicates", &self.report_duplicates);
         let _ = builder.field("graft_flood_threshold", &self.graft_flood_threshold);
         let _ = builder.field("mesh_outbound_min", &self.mesh_outbound_min);
         let _ = builder.field("opportunistic_graft_ticks", &self.opportunistic_graft_ticks);
@@ -130,6 +130,9 @@ pub struct Config {
     /// Whether light client protocols should be enabled.
     pub enable_light_client_server: bool,
 
+    /// Whether gossipsub should report duplicate messages for propagation analysis.
+    pub gossip_propagation_analysis: bool,
+
     /// Configuration for the outbound rate limiter (requests made by this node).
     pub outbound_rate_limiter_config: Option<OutboundRateLimiterConfig>,
 
@@ -345,6 +348,7 @@ impl Default for Config {
             proposer_only: false,
             metrics_enabled: false,
             enable_light_client_server: false,
+            gossip_propagation_analysis: false,
             outbound_rate_limiter_config: None,
             invalid_block_storage: None,
             inbound_rate_limiter_config: None,
@@ -429,6 +433,7 @@ pub fn gossipsub_config(
     gossipsub_config_params: GossipsubConfigParams,
     seconds_per_slot: u64,
     slots_per_epoch: u64,
+    report_duplicates: bool,
 ) -> gossipsub::Config {
     fn prefix(
         prefix: [u8; 4],
@@ -500,6 +505,9 @@ pub fn gossipsub_config(
         .duplicate_cache_time(duplicate_cache_time)
         .message_id_fn(gossip_message_id)
         .allow_self_origin(true)
+        // Duplicates are only needed to measure how blocks and aggregates propagate through our
+        // peers, so they are reported only when propagation analysis is enabled.
+        .report_duplicates(report_duplicates)
         .build()
         .expect("valid gossipsub configuration")
 }
@@ -100,6 +100,13 @@ pub enum NetworkEvent<E: EthSpec> {
         /// The message itself.
         message: PubsubMessage<E>,
     },
+    /// A block or aggregate which has already been received was received again from another peer.
+    PubsubDuplicate {
+        /// The gossipsub message id of the original message.
+        id: MessageId,
+        /// The peer from which we received the duplicate.
+        source: PeerId,
+    },
     /// Inform the network to send a Status to this peer.
     StatusPeer(PeerId),
     NewListenAddr(Multiaddr),
@@ -191,6 +198,7 @@ impl<E: EthSpec> Network<E> {
             gossipsub_config_params,
             ctx.chain_spec.seconds_per_slot,
             E::slots_per_epoch(),
+            config.gossip_propagation_analysis,
         );
 
         let score_settings = PeerScoreSettings::new(ctx.chain_spec, gs_config.mesh_n());
@@ -1272,6 +1280,24 @@ impl<E: EthSpec> Network<E> {
                     }
                 }
             }
+            gossipsub::Event::DuplicateMessage {
+                propagation_source,
+                message_id,
+                topic,
+            } => {
+                // Only the propagation of blocks and aggregates is analysed.
+                if let Ok(topic) = GossipTopic::decode(topic.as_str()) {
+                    if matches!(
+                        topic.kind(),
+                        GossipKind::BeaconBlock | GossipKind::BeaconAggregateAndProof
+                    ) {
+                        return Some(NetworkEvent::PubsubDuplicate {
+                            id: message_id,
+                            source: propagation_source,
+                        });
+                    }
+                }
+            }
             gossipsub::Event::Subscribed { peer_id, topic } => {
                 if let Ok(topic) = GossipTopic::decode(topic.as_str()) {
                     if let Some(subnet_id) = topic.subnet_id() {
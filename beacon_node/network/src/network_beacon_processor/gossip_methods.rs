@@ -519,6 +519,14 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 let aggregate = &verified_aggregate.signed_aggregate;
                 let indexed_attestation = &verified_aggregate.indexed_attestation;
 
+                if self.chain.config.gossip_propagation_analysis {
+                    self.chain.gossip_propagation.write().observe_aggregate(
+                        &message_id.0,
+                        aggregate.message().aggregate().data().slot,
+                        seen_timestamp,
+                    );
+                }
+
                 // If the attestation is still timely, propagate it.
                 self.propagate_attestation_if_timely(
                     verified_aggregate.attestation(),
@@ -1093,6 +1101,15 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     "slot" => verified_block.block.slot(),
                     "root" => ?verified_block.block_root
                 );
+                if self.chain.config.gossip_propagation_analysis {
+                    self.chain.gossip_propagation.write().observe_block(
+                        &message_id.0,
+                        verified_block.block.slot(),
+                        verified_block.block_root,
+                        peer_id.to_string(),
+                        seen_duration,
+                    );
+                }
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Accept);
 
                 // Log metrics to keep track of propagation delay times.
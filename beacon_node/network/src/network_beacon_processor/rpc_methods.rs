@@ -675,16 +675,37 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             .get_blob_info()
             .oldest_blob_slot
             .unwrap_or(data_availability_boundary_slot);
-        if request_start_slot < oldest_blob_slot {
+
+        // Blobs which have been pruned from the database may be served from the blob archive.
+        let serve_archived_blobs = self.chain.config.serve_archived_blobs_by_range;
+        let oldest_served_slot = if serve_archived_blobs {
+            match self.chain.store.get_oldest_archived_blob_slot() {
+                Ok(oldest_archived_slot) => oldest_archived_slot.map_or(oldest_blob_slot, |slot| {
+                    std::cmp::min(slot, oldest_blob_slot)
+                }),
+                Err(e) => {
+                    error!(self.log, "Unable to read blob archive";
+                        "request" => ?req,
+                        "peer" => %peer_id,
+                        "error" => ?e
+                    );
+                    return Err((RPCResponseErrorCode::ServerError, "Database error"));
+                }
+            }
+        } else {
+            oldest_blob_slot
+        };
+
+        if request_start_slot < oldest_served_slot {
             debug!(
                 self.log,
                 "Range request start slot is older than data availability boundary.";
                 "requested_slot" => request_start_slot,
-                "oldest_blob_slot" => oldest_blob_slot,
+                "oldest_blob_slot" => oldest_served_slot,
                 "data_availability_boundary" => data_availability_boundary_slot
             );
 
-            return if data_availability_boundary_slot < oldest_blob_slot {
+            return if data_availability_boundary_slot < oldest_served_slot {
                 Err((
                     RPCResponseErrorCode::ResourceUnavailable,
                     "blobs pruned within boundary",
@@ -735,16 +756,16 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         let maybe_block_roots = process_results(forwards_block_root_iter, |iter| {
             iter.take_while(|(_, slot)| slot.as_u64() < req.start_slot.saturating_add(req.count))
                 // map skip slots to None
-                .map(|(root, _)| {
+                .map(|(root, slot)| {
                     let result = if Some(root) == last_block_root {
                         None
                     } else {
-                        Some(root)
+                        Some((root, slot))
                     };
                     last_block_root = Some(root);
                     result
                 })
-                .collect::<Vec<Option<Hash256>>>()
+                .collect::<Vec<Option<(Hash256, Slot)>>>()
         });
 
         let block_roots = match maybe_block_roots {
@@ -780,8 +801,13 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         let block_roots = block_roots.into_iter().flatten();
         let mut blobs_sent = 0;
 
-        for root in block_roots {
-            match self.chain.get_blobs(&root) {
+        for (root, slot) in block_roots {
+            let blobs = if serve_archived_blobs && slot < oldest_blob_slot {
+                self.chain.get_blobs_or_archived(&root, slot)
+            } else {
+                self.chain.get_blobs(&root)
+            };
+            match blobs {
                 Ok(blob_sidecar_list) => {
                     for blob_sidecar in blob_sidecar_list.iter() {
                         blobs_sent += 1;
@@ -29,6 +29,13 @@ use tokio::sync::mpsc;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use types::{BlobSidecar, EthSpec, SignedBeaconBlock};
 
+/// The number of duplicate gossip messages to buffer before recording them for propagation
+/// analysis.
+const GOSSIP_DUPLICATES_BATCH_SIZE: usize = 16;
+/// The maximum number of duplicate gossip messages to buffer while the propagation cache is
+/// locked.
+const MAX_GOSSIP_DUPLICATES: usize = 1_024;
+
 /// Handles messages from the network and routes them to the appropriate service to be handled.
 pub struct Router<T: BeaconChainTypes> {
     /// Access to the peer db and network information.
@@ -45,6 +52,8 @@ pub struct Router<T: BeaconChainTypes> {
     log: slog::Logger,
     /// Provides de-bounce functionality for logging.
     logger_debounce: TimeLatch,
+    /// Duplicate gossip messages which are yet to be recorded for propagation analysis.
+    gossip_duplicates: Vec<(Vec<u8>, String, Duration)>,
 }
 
 /// Types of messages the router can receive.
@@ -74,6 +83,9 @@ pub enum RouterMessage<E: EthSpec> {
     /// message, the message itself and a bool which indicates if the message should be processed
     /// by the beacon chain after successful verification.
     PubsubMessage(MessageId, PeerId, PubsubMessage<E>, bool),
+    /// A block or aggregate which was already received has been received again from a peer. The
+    /// fields are: the message id of the original message and the peer that sent us the duplicate.
+    PubsubDuplicate(MessageId, PeerId),
     /// The peer manager has requested we re-status a peer.
     StatusPeer(PeerId),
 }
@@ -133,6 +145,7 @@ impl<T: BeaconChainTypes> Router<T> {
             network_beacon_processor,
             log: message_handler_log,
             logger_debounce: TimeLatch::default(),
+            gossip_duplicates: vec![],
         };
 
         // spawn handler task and move the message handler instance into the spawned thread
@@ -185,6 +198,9 @@ impl<T: BeaconChainTypes> Router<T> {
             RouterMessage::PubsubMessage(id, peer_id, gossip, should_process) => {
                 self.handle_gossip(id, peer_id, gossip, should_process);
             }
+            RouterMessage::PubsubDuplicate(id, peer_id) => {
+                self.handle_gossip_duplicate(id, peer_id);
+            }
         }
     }
 
@@ -265,6 +281,24 @@ impl<T: BeaconChainTypes> Router<T> {
         }
     }
 
+    /// Buffer a duplicate gossip message and record the buffer for propagation analysis once it
+    /// reaches `GOSSIP_DUPLICATES_BATCH_SIZE`.
+    ///
+    /// The router must not block, so the buffer is only recorded if the propagation cache is not
+    /// locked. If the buffer is full, new duplicates are dropped.
+    fn handle_gossip_duplicate(&mut self, message_id: MessageId, peer_id: PeerId) {
+        if self.gossip_duplicates.len() < MAX_GOSSIP_DUPLICATES {
+            self.gossip_duplicates
+                .push((message_id.0, peer_id.to_string(), timestamp_now()));
+        }
+        if self.gossip_duplicates.len() < GOSSIP_DUPLICATES_BATCH_SIZE {
+            return;
+        }
+        if let Some(mut gossip_propagation) = self.chain.gossip_propagation.try_write() {
+            gossip_propagation.observe_duplicates(self.gossip_duplicates.drain(..));
+        }
+    }
+
     /// Handle RPC messages.
     /// Note: `should_process` is currently only useful for the `Attestation` variant.
     /// if `should_process` is `false`, we only propagate the message on successful verification,
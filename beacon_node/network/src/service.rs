@@ -568,6 +568,9 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                     }
                 }
             }
+            NetworkEvent::PubsubDuplicate { id, source } => {
+                self.send_to_router(RouterMessage::PubsubDuplicate(id, source));
+            }
             NetworkEvent::NewListenAddr(multiaddr) => {
                 self.network_globals
                     .listen_multiaddrs
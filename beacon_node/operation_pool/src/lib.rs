@@ -12,7 +12,7 @@ mod sync_aggregate_id;
 pub use crate::bls_to_execution_changes::ReceivedPreCapella;
 pub use attestation::{earliest_attestation_validators, AttMaxCover};
 pub use attestation_storage::{CompactAttestationRef, SplitAttestation};
-pub use max_cover::MaxCover;
+pub use max_cover::{maximum_cover, MaxCover};
 pub use persistence::{
     PersistedOperationPool, PersistedOperationPoolV15, PersistedOperationPoolV20,
 };
@@ -24,7 +24,6 @@ use crate::attestation_storage::{AttestationMap, CheckpointKey};
 use crate::bls_to_execution_changes::BlsToExecutionChanges;
 use crate::sync_aggregate_id::SyncAggregateId;
 use attester_slashing::AttesterSlashingMaxCover;
-use max_cover::maximum_cover;
 use parking_lot::{RwLock, RwLockWriteGuard};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
@@ -553,6 +553,50 @@ pub fn cli_app() -> Command {
                        Increasing this value can prevent messages from being dropped.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("http-event-log-dir")
+                .long("http-event-log-dir")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .value_name("DIR")
+                .help("Write server-sent events to a bounded log in this directory. Events with \
+                       logged topics are assigned IDs, which allows subscribers to resume from the \
+                       last event they received using the Last-Event-ID header on /eth/v1/events \
+                       or the /lighthouse/events/ws WebSocket endpoint.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-event-log-topics")
+                .long("http-event-log-topics")
+                .requires("http-event-log-dir")
+                .action(ArgAction::Set)
+                .value_name("TOPICS")
+                .help("Comma-separated list of the event topics to write to the event log. \
+                       [default: block,head,finalized_checkpoint,chain_reorg]")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-event-log-max-events")
+                .long("http-event-log-max-events")
+                .requires("http-event-log-dir")
+                .action(ArgAction::Set)
+                .value_name("N")
+                .help("The minimum number of events to retain in the event log. \
+                       [default: 65536]")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-api-keys-file")
+                .long("http-api-keys-file")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .value_name("PATH")
+                .help("Path to a YAML file of API keys for the HTTP API. Each key may access a \
+                       list of route groups (public, validator, debug, lighthouse-admin) and may \
+                       be rate limited. Requests without a key are rejected unless the file \
+                       contains an anonymous policy.")
+                .display_order(0)
+        )
         .arg(
             Arg::new("http-duplicate-block-status")
                 .long("http-duplicate-block-status")
@@ -655,6 +699,29 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("op-pool-sync-peers")
+                .long("op-pool-sync-peers")
+                .value_name("URLS")
+                .help("One or more comma-delimited HTTP API URLs of other beacon nodes run by \
+                       the same operator. Once per slot, recent aggregate attestations, \
+                       slashings, voluntary exits and BLS to execution changes are pulled from \
+                       each of them into the operation pool, and this node's own slashings, exits \
+                       and BLS to execution changes are pushed to them. Aggregates are only \
+                       pulled, so each node should list the others.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("op-pool-sync-api-key-file")
+                .long("op-pool-sync-api-key-file")
+                .value_name("PATH")
+                .help("Path to a file containing an API key to send to the --op-pool-sync-peers, \
+                       for peers which are run with --http-api-keys-file.")
+                .requires("op-pool-sync-peers")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
 
         /*
          * Standard staking flags
@@ -771,6 +838,49 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("block-compression")
+                .long("block-compression")
+                .value_name("CODEC")
+                .help("Codec used to compress blocks written to the database. Blocks written \
+                       with a different codec remain readable.")
+                .value_parser(store::Codec::VARIANTS.to_vec())
+                .default_value("none")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("state-compression")
+                .long("state-compression")
+                .value_name("CODEC")
+                .help("Codec used to compress restore point states written to the freezer \
+                       database. States written with a different codec remain readable.")
+                .value_parser(store::Codec::VARIANTS.to_vec())
+                .default_value("none")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("blob-compression")
+                .long("blob-compression")
+                .value_name("CODEC")
+                .help("Codec used to compress blobs written to the database. Blobs written \
+                       with a different codec remain readable.")
+                .value_parser(store::Codec::VARIANTS.to_vec())
+                .default_value("none")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("zstd-compression-level")
+                .long("zstd-compression-level")
+                .value_name("LEVEL")
+                .help("Compression level used for values compressed with zstd. Higher levels \
+                       are slower but produce smaller values.")
+                .default_value("3")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         /*
          * Execution Layer Integration
          */
@@ -968,6 +1078,25 @@ pub fn cli_app() -> Command {
                 .default_value("0")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("blob-archive-dir")
+                .long("blob-archive-dir")
+                .value_name("DIR")
+                .help("Directory to which blobs are archived when they are pruned, instead of being \
+                       deleted. Archived blobs remain available from the HTTP API.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("serve-archived-blobs-by-range")
+                .long("serve-archived-blobs-by-range")
+                .help("Serve blobs from the blob archive to peers in BlobsByRange requests. \
+                       Requires --blob-archive-dir.")
+                .requires("blob-archive-dir")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
 
         /*
          * Misc.
@@ -1120,6 +1249,38 @@ pub fn cli_app() -> Command {
                 .requires("slasher")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("slasher-backfill-start-epoch")
+                .long("slasher-backfill-start-epoch")
+                .value_name("EPOCH")
+                .help("Feed the attestations and blocks stored in the database from this epoch \
+                       onwards to the slasher on startup. Epochs older than the slasher's \
+                       history length or the node's oldest stored block are skipped.")
+                .action(ArgAction::Set)
+                .requires("slasher")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("slasher-backfill-end-epoch")
+                .long("slasher-backfill-end-epoch")
+                .value_name("EPOCH")
+                .help("The last epoch to feed to the slasher when backfilling. Defaults to the \
+                       current epoch.")
+                .action(ArgAction::Set)
+                .requires("slasher-backfill-start-epoch")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("slasher-backfill-batch-epochs")
+                .long("slasher-backfill-batch-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs of stored blocks to feed to the slasher at each \
+                       update when backfilling. Larger batches finish the backfill sooner, but \
+                       use more memory and lengthen each slasher update.")
+                .action(ArgAction::Set)
+                .requires("slasher-backfill-start-epoch")
+                .display_order(0)
+        )
         .arg(
             Arg::new("wss-checkpoint")
                 .long("wss-checkpoint")
@@ -1243,6 +1404,18 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("validator-monitor-history-epochs")
+                .long("validator-monitor-history-epochs")
+                .help("The number of epochs for which the per-epoch summaries of monitored \
+                    validators are kept in the database. The summaries are served by the \
+                    /lighthouse/validator_monitor/{index} HTTP API endpoint. Summaries are not \
+                    persisted unless this flag is provided. A value of 1575 retains approximately \
+                    one week of summaries.")
+                .value_name("EPOCHS")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-lock-timeouts")
                 .long("disable-lock-timeouts")
@@ -1348,6 +1521,17 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("fork-choice-recording-dir")
+                .long("fork-choice-recording-dir")
+                .value_name("DIR")
+                .help("Record every input to fork choice within this directory, so that it can \
+                       be replayed offline with `lcli fork-choice-replay`. A new directory is \
+                       created each time the beacon node starts. Recordings grow by several \
+                       gigabytes per day on mainnet and are never pruned.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("paranoid-block-proposal")
                 .long("paranoid-block-proposal")
@@ -1454,6 +1638,16 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("gossip-propagation-analysis")
+                .long("gossip-propagation-analysis")
+                .help("Record how gossiped blocks and aggregates propagate through our peers and \
+                       serve the results from /lighthouse/analysis/propagation. Requires \
+                       gossipsub to report duplicate messages, which adds some overhead.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("gui")
                 .long("gui")
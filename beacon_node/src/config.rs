@@ -3,7 +3,9 @@ use beacon_chain::chain_config::{
     DEFAULT_RE_ORG_HEAD_THRESHOLD, DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
     DEFAULT_RE_ORG_PARENT_THRESHOLD,
 };
+use beacon_chain::event_log::{EventLogConfig, EventTopic};
 use beacon_chain::graffiti_calculator::GraffitiOrigin;
+use beacon_chain::op_pool_sync::Config as OpPoolSyncConfig;
 use beacon_chain::TrustedSetup;
 use clap::{parser::ValueSource, ArgMatches, Id};
 use clap_utils::flags::DISABLE_MALLOC_TUNING_FLAG;
@@ -171,12 +173,36 @@ pub fn get_config<E: EthSpec>(
 
         client_config.http_api.enable_light_client_server =
             cli_args.get_flag("light-client-server");
+
+        if let Some(dir) = clap_utils::parse_optional(cli_args, "http-event-log-dir")? {
+            let mut event_log = EventLogConfig::new(dir);
+            if let Some(topics) = cli_args.get_one::<String>("http-event-log-topics") {
+                event_log.topics = topics
+                    .split(',')
+                    .map(EventTopic::from_str)
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| format!("Invalid --http-event-log-topics: {}", e))?;
+            }
+            if let Some(max_events) =
+                clap_utils::parse_optional(cli_args, "http-event-log-max-events")?
+            {
+                event_log.max_events = max_events;
+            }
+            client_config.http_api.event_log = Some(event_log);
+        }
+
+        client_config.http_api.api_keys_file =
+            clap_utils::parse_optional(cli_args, "http-api-keys-file")?;
     }
 
     if cli_args.get_flag("light-client-server") {
         client_config.chain.enable_light_client_server = true;
     }
 
+    if cli_args.get_flag("gossip-propagation-analysis") {
+        client_config.chain.gossip_propagation_analysis = true;
+    }
+
     if let Some(cache_size) = clap_utils::parse_optional(cli_args, "shuffling-cache-size")? {
         client_config.chain.shuffling_cache_size = cache_size;
     }
@@ -225,6 +251,23 @@ pub fn get_config<E: EthSpec>(
         });
     }
 
+    /*
+     * Operation pool sync
+     */
+    if let Some(peers) = cli_args.get_one::<String>("op-pool-sync-peers") {
+        let peers = peers
+            .split(',')
+            .map(|url| {
+                SensitiveUrl::parse(url)
+                    .map_err(|e| format!("Invalid op pool sync peer {}: {:?}", url, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        client_config.op_pool_sync = Some(OpPoolSyncConfig {
+            peers,
+            api_key_file: clap_utils::parse_optional(cli_args, "op-pool-sync-api-key-file")?,
+        });
+    }
+
     // Log a warning indicating an open HTTP server if it wasn't specified explicitly
     // (e.g. using the --staking flag).
     if cli_args.get_flag("staking") {
@@ -421,6 +464,12 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "historic-state-cache-size is not a valid integer".to_string())?;
     }
 
+    client_config.store.block_compression = parse_required(cli_args, "block-compression")?;
+    client_config.store.state_compression = parse_required(cli_args, "state-compression")?;
+    client_config.store.blob_compression = parse_required(cli_args, "blob-compression")?;
+    client_config.store.zstd_compression_level =
+        parse_required(cli_args, "zstd-compression-level")?;
+
     client_config.store.compact_on_init = cli_args.get_flag("compact-db");
     if let Some(compact_on_prune) = cli_args.get_one::<String>("auto-compact-db") {
         client_config.store.compact_on_prune = compact_on_prune
@@ -454,6 +503,13 @@ pub fn get_config<E: EthSpec>(
         client_config.store.blob_prune_margin_epochs = blob_prune_margin_epochs;
     }
 
+    if let Some(blob_archive_dir) = clap_utils::parse_optional(cli_args, "blob-archive-dir")? {
+        client_config.store.blob_archive_dir = Some(blob_archive_dir);
+    }
+
+    client_config.chain.serve_archived_blobs_by_range =
+        cli_args.get_flag("serve-archived-blobs-by-range");
+
     /*
      * Zero-ports
      *
@@ -647,7 +703,9 @@ pub fn get_config<E: EthSpec>(
         let slasher_dir = if let Some(slasher_dir) = cli_args.get_one::<String>("slasher-dir") {
             PathBuf::from(slasher_dir)
         } else {
-            client_config.data_dir().join("slasher_db")
+            client_config
+                .data_dir()
+                .join(slasher::config::DEFAULT_SLASHER_DIR)
         };
 
         let mut slasher_config = slasher::Config::new(slasher_dir);
@@ -706,6 +764,31 @@ pub fn get_config<E: EthSpec>(
             slasher_config.backend = backend;
         }
 
+        slasher_config.backfill_start_epoch =
+            clap_utils::parse_optional(cli_args, "slasher-backfill-start-epoch")?;
+        slasher_config.backfill_end_epoch =
+            clap_utils::parse_optional(cli_args, "slasher-backfill-end-epoch")?;
+        if let Some(batch_epochs) =
+            clap_utils::parse_optional(cli_args, "slasher-backfill-batch-epochs")?
+        {
+            if batch_epochs == 0 {
+                return Err("slasher-backfill-batch-epochs must be greater than 0".into());
+            }
+            slasher_config.backfill_batch_epochs = batch_epochs;
+        }
+        if let (Some(start_epoch), Some(end_epoch)) = (
+            slasher_config.backfill_start_epoch,
+            slasher_config.backfill_end_epoch,
+        ) {
+            if end_epoch < start_epoch {
+                return Err(format!(
+                    "slasher-backfill-end-epoch ({}) must not be before \
+                     slasher-backfill-start-epoch ({})",
+                    end_epoch, start_epoch
+                ));
+            }
+        }
+
         client_config.slasher = Some(slasher_config);
     }
 
@@ -752,6 +835,11 @@ pub fn get_config<E: EthSpec>(
             .individual_tracking_threshold = count;
     }
 
+    if let Some(epochs) = clap_utils::parse_optional(cli_args, "validator-monitor-history-epochs")?
+    {
+        client_config.validator_monitor.history_retention_epochs = epochs;
+    }
+
     if cli_args.get_flag("disable-lock-timeouts") {
         warn!(
             log,
@@ -818,6 +906,9 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.fork_choice_before_proposal_timeout_ms = timeout;
     }
 
+    client_config.chain.fork_choice_recording_dir =
+        clap_utils::parse_optional(cli_args, "fork-choice-recording-dir")?;
+
     client_config.chain.always_reset_payload_statuses = cli_args.get_flag("reset-payload-statuses");
 
     client_config.chain.paranoid_block_proposal = cli_args.get_flag("paranoid-block-proposal");
@@ -1413,6 +1504,9 @@ pub fn set_network_config(
     // Light client server config.
     config.enable_light_client_server = parse_flag(cli_args, "light-client-server");
 
+    // Duplicate gossip messages are only reported when propagation analysis is enabled.
+    config.gossip_propagation_analysis = parse_flag(cli_args, "gossip-propagation-analysis");
+
     // The self limiter is enabled by default. If the `self-limiter-protocols` flag is not provided,
     // the default params will be used.
     config.outbound_rate_limiter_config = if parse_flag(cli_args, "disable-self-limiter") {
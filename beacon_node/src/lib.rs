@@ -178,6 +178,7 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
             .await?
             .notifier()?
             .http_metrics_config(client_config.http_metrics.clone())
+            .op_pool_sync_config(client_config.op_pool_sync.clone())
             .build()
             .map(Self)
     }
@@ -0,0 +1,225 @@
+//! An archive of blob sidecars which have been pruned from the database, for nodes which need to
+//! keep serving blobs after the data availability window.
+//!
+//! Blobs are appended to one file per epoch within the archive directory, named after the epoch,
+//! e.g. `0000270000.blobs`. Each file is a sequence of records:
+//!
+//! ```text
+//! | block root (32 bytes) | slot (8 bytes, LE) | length (4 bytes, LE) | data (`length` bytes) |
+//! ```
+//!
+//! where the data is the snappy-compressed SSZ encoding of the block's `BlobSidecarList`.
+//!
+//! Files are only ever appended to, so the archive may be kept on slower storage than the
+//! database. A record which was partially written when the node stopped is ignored, and
+//! overwritten by the next append to its file.
+use crate::Error;
+use parking_lot::Mutex;
+use ssz::{Decode, Encode};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use types::{BlobSidecarList, Epoch, EthSpec, Hash256, Slot};
+
+/// The extension of the archive's epoch files.
+const FILE_EXTENSION: &str = "blobs";
+/// The length of a record's block root, slot and data length.
+const RECORD_HEADER_LEN: u64 = 32 + 8 + 4;
+
+/// The location of a block's blobs within an epoch file.
+struct Record {
+    block_root: Hash256,
+    slot: Slot,
+    data_offset: u64,
+    data_len: u32,
+}
+
+/// An append-only archive of blob sidecars, see the module docs.
+#[derive(Debug)]
+pub struct BlobArchive<E: EthSpec> {
+    dir: PathBuf,
+    /// Prevents concurrent appends to the same file.
+    append_lock: Mutex<()>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> BlobArchive<E> {
+    /// Open the archive in `dir`, creating the directory if it doesn't exist.
+    pub fn open(dir: &Path) -> Result<Self, Error> {
+        fs::create_dir_all(dir).map_err(|e| archive_error(dir, e))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            append_lock: Mutex::new(()),
+            _phantom: PhantomData,
+        })
+    }
+
+    fn epoch_path(&self, epoch: Epoch) -> PathBuf {
+        self.dir
+            .join(format!("{:010}.{}", epoch.as_u64(), FILE_EXTENSION))
+    }
+
+    /// Append the blobs of each block in `blobs` to the file for its epoch, and flush them to
+    /// disk.
+    ///
+    /// Blocks which are already in the archive are skipped, so that pruning can be safely retried
+    /// after an interruption.
+    pub fn append(&self, blobs: &[(Hash256, Slot, BlobSidecarList<E>)]) -> Result<(), Error> {
+        let _lock = self.append_lock.lock();
+
+        let mut blobs_by_epoch = BTreeMap::<_, Vec<_>>::new();
+        for entry in blobs {
+            blobs_by_epoch
+                .entry(entry.1.epoch(E::slots_per_epoch()))
+                .or_default()
+                .push(entry);
+        }
+
+        for (epoch, blobs) in blobs_by_epoch {
+            let path = self.epoch_path(epoch);
+            let map_err = |e| archive_error(&path, e);
+
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)
+                .map_err(map_err)?;
+            let (records, valid_len) = read_records(&mut file).map_err(map_err)?;
+
+            // Discard any partially written record before appending.
+            file.set_len(valid_len).map_err(map_err)?;
+            file.seek(SeekFrom::Start(valid_len)).map_err(map_err)?;
+
+            for (block_root, slot, blob_sidecar_list) in blobs {
+                if records
+                    .iter()
+                    .any(|record| record.block_root == *block_root)
+                {
+                    continue;
+                }
+
+                let data = snap::raw::Encoder::new()
+                    .compress_vec(&blob_sidecar_list.as_ssz_bytes())
+                    .map_err(|e| {
+                        Error::BlobArchiveError(format!("unable to compress blobs: {:?}", e))
+                    })?;
+                let data_len = u32::try_from(data.len()).map_err(|_| {
+                    Error::BlobArchiveError(format!("blobs too large to archive: {}", data.len()))
+                })?;
+
+                let mut record = Vec::with_capacity(RECORD_HEADER_LEN as usize + data.len());
+                record.extend_from_slice(block_root.as_bytes());
+                record.extend_from_slice(&slot.as_u64().to_le_bytes());
+                record.extend_from_slice(&data_len.to_le_bytes());
+                record.extend_from_slice(&data);
+                file.write_all(&record).map_err(map_err)?;
+            }
+
+            file.sync_data().map_err(map_err)?;
+        }
+
+        Ok(())
+    }
+
+    /// Load the archived blobs of the block with `block_root` at `slot`.
+    pub fn get(
+        &self,
+        block_root: &Hash256,
+        slot: Slot,
+    ) -> Result<Option<BlobSidecarList<E>>, Error> {
+        let path = self.epoch_path(slot.epoch(E::slots_per_epoch()));
+        let map_err = |e| archive_error(&path, e);
+
+        let mut file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(map_err(e)),
+        };
+        let (records, _) = read_records(&mut file).map_err(map_err)?;
+        let Some(record) = records
+            .into_iter()
+            .find(|record| record.block_root == *block_root && record.slot == slot)
+        else {
+            return Ok(None);
+        };
+
+        let mut data = vec![0; record.data_len as usize];
+        file.seek(SeekFrom::Start(record.data_offset))
+            .map_err(map_err)?;
+        file.read_exact(&mut data).map_err(map_err)?;
+
+        let bytes = snap::raw::Decoder::new()
+            .decompress_vec(&data)
+            .map_err(|e| Error::BlobArchiveError(format!("unable to decompress blobs: {:?}", e)))?;
+        Ok(Some(BlobSidecarList::from_ssz_bytes(&bytes)?))
+    }
+
+    /// Return the earliest epoch with a file in the archive.
+    pub fn oldest_epoch(&self) -> Result<Option<Epoch>, Error> {
+        let map_err = |e| archive_error(&self.dir, e);
+
+        let mut oldest_epoch = None;
+        for entry in fs::read_dir(&self.dir).map_err(map_err)? {
+            let path = entry.map_err(map_err)?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(FILE_EXTENSION) {
+                continue;
+            }
+            let epoch = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+                .map(Epoch::new);
+            oldest_epoch = match (oldest_epoch, epoch) {
+                (Some(oldest), Some(epoch)) => Some(std::cmp::min(oldest, epoch)),
+                (oldest, epoch) => oldest.or(epoch),
+            };
+        }
+        Ok(oldest_epoch)
+    }
+}
+
+/// Read the headers of the records in `file`, returning them along with the length of the file
+/// up to the end of the last complete record.
+fn read_records(file: &mut File) -> Result<(Vec<Record>, u64), io::Error> {
+    let file_len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(0))?;
+    let mut reader = BufReader::new(file);
+
+    let mut records = vec![];
+    let mut offset = 0;
+    while offset + RECORD_HEADER_LEN <= file_len {
+        let mut header = [0; RECORD_HEADER_LEN as usize];
+        reader.read_exact(&mut header)?;
+
+        let block_root = Hash256::from_slice(&header[0..32]);
+        let mut slot_bytes = [0; 8];
+        slot_bytes.copy_from_slice(&header[32..40]);
+        let mut len_bytes = [0; 4];
+        len_bytes.copy_from_slice(&header[40..44]);
+        let data_len = u32::from_le_bytes(len_bytes);
+
+        let data_offset = offset + RECORD_HEADER_LEN;
+        if data_offset + u64::from(data_len) > file_len {
+            break;
+        }
+        reader.seek_relative(i64::from(data_len))?;
+
+        records.push(Record {
+            block_root,
+            slot: Slot::new(u64::from_le_bytes(slot_bytes)),
+            data_offset,
+            data_len,
+        });
+        offset = data_offset + u64::from(data_len);
+    }
+
+    Ok((records, offset))
+}
+
+fn archive_error(path: &Path, e: io::Error) -> Error {
+    Error::BlobArchiveError(format!("{}: {:?}", path.display(), e))
+}
@@ -0,0 +1,197 @@
+//! Optional compression of the values of the block, restore point state and blob columns.
+//!
+//! A compressed value is prefixed with a header identifying its codec, so values written with
+//! different codecs (including uncompressed values written by older versions) can be read from
+//! the same column:
+//!
+//! ```text
+//! | magic (7 bytes) | codec (1 byte) | compressed SSZ bytes |
+//! ```
+//!
+//! The header can't be mistaken for the start of an uncompressed value: blocks begin with the
+//! 4-byte offset of their message, blob lists with the index of their first blob, and restore
+//! points with a genesis time, which would be billions of years in the future if read from the
+//! header.
+use crate::hot_cold_store::HotColdDB;
+use crate::{get_key_for_col, DBColumn, Error, ItemStore, KeyValueStore, KeyValueStoreOp};
+use serde::{Deserialize, Serialize};
+use slog::info;
+use strum::{Display, EnumString, EnumVariantNames};
+use types::{EthSpec, Hash256};
+
+/// The first bytes of every compressed value.
+const HEADER_MAGIC: [u8; 7] = [0xff, b'L', b'H', b'C', b'M', b'P', 0x00];
+/// The length of `HEADER_MAGIC` and the codec byte.
+const HEADER_LEN: usize = HEADER_MAGIC.len() + 1;
+
+/// The number of bytes of values rewritten in each batch by `HotColdDB::recompress_column`.
+const RECOMPRESS_BATCH_BYTES: usize = 64 * 1024 * 1024;
+
+/// The codec used to compress the values of a column.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumVariantNames,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum Codec {
+    /// Values are stored as uncompressed SSZ.
+    #[default]
+    None,
+    Zstd,
+}
+
+impl Codec {
+    /// The identifier of the codec in the value header.
+    fn id(self) -> u8 {
+        match self {
+            Codec::None => 0,
+            Codec::Zstd => 1,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self, Error> {
+        match id {
+            0 => Ok(Codec::None),
+            1 => Ok(Codec::Zstd),
+            id => Err(Error::CompressionError(format!("unknown codec id: {}", id))),
+        }
+    }
+
+    /// Return the codec of a value read from the database.
+    pub fn of_value(bytes: &[u8]) -> Result<Self, Error> {
+        match bytes.get(..HEADER_LEN) {
+            Some(header) if header[..HEADER_MAGIC.len()] == HEADER_MAGIC => {
+                Self::from_id(header[HEADER_MAGIC.len()])
+            }
+            _ => Ok(Codec::None),
+        }
+    }
+
+    /// Compress `bytes` for storage, adding a header if the codec is not `Codec::None`.
+    pub fn compress(self, bytes: Vec<u8>, zstd_level: i32) -> Result<Vec<u8>, Error> {
+        let compressed = match self {
+            Codec::None => return Ok(bytes),
+            Codec::Zstd => zstd::encode_all(bytes.as_slice(), zstd_level),
+        }
+        .map_err(|e| {
+            Error::CompressionError(format!("unable to compress with {}: {:?}", self, e))
+        })?;
+
+        let mut value = Vec::with_capacity(HEADER_LEN + compressed.len());
+        value.extend_from_slice(&HEADER_MAGIC);
+        value.push(self.id());
+        value.extend_from_slice(&compressed);
+        Ok(value)
+    }
+}
+
+/// Decompress a value read from the database, which may have been written with any codec.
+pub fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let codec = Codec::of_value(&bytes)?;
+    match codec {
+        Codec::None => Ok(bytes),
+        Codec::Zstd => zstd::decode_all(&bytes[HEADER_LEN..]),
+    }
+    .map_err(|e| Error::CompressionError(format!("unable to decompress with {}: {:?}", codec, e)))
+}
+
+/// The number of values and bytes rewritten by `HotColdDB::recompress_column`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct RecompressSummary {
+    pub entries: u64,
+    /// The number of values which were stored with a different codec and were rewritten.
+    pub recompressed: u64,
+    /// The total size of the rewritten values before they were recompressed.
+    pub bytes_before: u64,
+    /// The total size of the rewritten values after they were recompressed.
+    pub bytes_after: u64,
+}
+
+impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold> {
+    /// Rewrite every value of `column` which isn't stored with `codec`.
+    ///
+    /// Only the columns which may be compressed are supported: blocks, restore point states and
+    /// blobs.
+    pub fn recompress_column(
+        &self,
+        column: DBColumn,
+        codec: Codec,
+    ) -> Result<RecompressSummary, Error> {
+        info!(
+            self.log,
+            "Recompressing database column";
+            "column" => column.as_str(),
+            "codec" => %codec,
+        );
+
+        let zstd_level = self.get_config().zstd_compression_level;
+        let summary = match column {
+            DBColumn::BeaconBlock => recompress_db(&self.hot_db, column, codec, zstd_level),
+            DBColumn::BeaconState => recompress_db(&self.cold_db, column, codec, zstd_level),
+            DBColumn::BeaconBlob => recompress_db(&self.blobs_db, column, codec, zstd_level),
+            _ => Err(Error::CompressionError(format!(
+                "column {} cannot be compressed",
+                column.as_str()
+            ))),
+        }?;
+
+        info!(
+            self.log,
+            "Recompressed database column";
+            "column" => column.as_str(),
+            "entries" => summary.entries,
+            "recompressed" => summary.recompressed,
+            "bytes_before" => summary.bytes_before,
+            "bytes_after" => summary.bytes_after,
+        );
+
+        Ok(summary)
+    }
+}
+
+fn recompress_db<E: EthSpec, KV: KeyValueStore<E>>(
+    db: &KV,
+    column: DBColumn,
+    codec: Codec,
+    zstd_level: i32,
+) -> Result<RecompressSummary, Error> {
+    let mut summary = RecompressSummary::default();
+    let mut batch = vec![];
+    let mut batch_bytes = 0;
+
+    for res in db.iter_column::<Hash256>(column) {
+        let (key, value) = res?;
+        summary.entries += 1;
+        if Codec::of_value(&value)? == codec {
+            continue;
+        }
+
+        let bytes_before = value.len();
+        let new_value = codec.compress(decompress(value)?, zstd_level)?;
+        summary.recompressed += 1;
+        summary.bytes_before += bytes_before as u64;
+        summary.bytes_after += new_value.len() as u64;
+
+        batch_bytes += new_value.len();
+        batch.push(KeyValueStoreOp::PutKeyValue(
+            get_key_for_col(column.into(), key.as_bytes()),
+            new_value,
+        ));
+        if batch_bytes >= RECOMPRESS_BATCH_BYTES {
+            db.do_atomically(std::mem::take(&mut batch))?;
+            batch_bytes = 0;
+        }
+    }
+    db.do_atomically(batch)?;
+
+    Ok(summary)
+}
@@ -1,8 +1,9 @@
-use crate::{DBColumn, Error, StoreItem};
+use crate::{Codec, DBColumn, Error, StoreItem};
 use serde::{Deserialize, Serialize};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use types::non_zero_usize::new_non_zero_usize;
 use types::{EthSpec, MinimalEthSpec};
 
@@ -13,6 +14,7 @@ pub const DEFAULT_STATE_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(128);
 pub const DEFAULT_HISTORIC_STATE_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(1);
 pub const DEFAULT_EPOCHS_PER_BLOB_PRUNE: u64 = 1;
 pub const DEFAULT_BLOB_PUNE_MARGIN_EPOCHS: u64 = 0;
+pub const DEFAULT_ZSTD_COMPRESSION_LEVEL: i32 = 3;
 
 /// Database configuration parameters.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -40,6 +42,17 @@ pub struct StoreConfig {
     /// The margin for blob pruning in epochs. The oldest blobs are pruned up until
     /// data_availability_boundary - blob_prune_margin_epochs. Default: 0.
     pub blob_prune_margin_epochs: u64,
+    /// Directory to which blobs are archived when they are pruned. Pruned blobs are deleted if
+    /// this is `None`.
+    pub blob_archive_dir: Option<PathBuf>,
+    /// Codec used to compress blocks when they're written to the database.
+    pub block_compression: Codec,
+    /// Codec used to compress restore point states when they're written to the freezer database.
+    pub state_compression: Codec,
+    /// Codec used to compress blobs when they're written to the database.
+    pub blob_compression: Codec,
+    /// Compression level used when writing values with `Codec::Zstd`.
+    pub zstd_compression_level: i32,
 }
 
 /// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
@@ -68,6 +81,11 @@ impl Default for StoreConfig {
             prune_blobs: true,
             epochs_per_blob_prune: DEFAULT_EPOCHS_PER_BLOB_PRUNE,
             blob_prune_margin_epochs: DEFAULT_BLOB_PUNE_MARGIN_EPOCHS,
+            blob_archive_dir: None,
+            block_compression: Codec::None,
+            state_compression: Codec::None,
+            blob_compression: Codec::None,
+            zstd_compression_level: DEFAULT_ZSTD_COMPRESSION_LEVEL,
         }
     }
 }
@@ -79,6 +97,16 @@ impl StoreConfig {
         }
     }
 
+    /// Return the codec with which new values of `column` should be compressed.
+    pub fn compression_for_column(&self, column: DBColumn) -> Codec {
+        match column {
+            DBColumn::BeaconBlock => self.block_compression,
+            DBColumn::BeaconState => self.state_compression,
+            DBColumn::BeaconBlob => self.blob_compression,
+            _ => Codec::None,
+        }
+    }
+
     pub fn check_compatibility(
         &self,
         on_disk_config: &OnDiskStoreConfig,
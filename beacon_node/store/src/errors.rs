@@ -3,6 +3,7 @@ use crate::config::StoreConfigError;
 use crate::hot_cold_store::HotColdDBError;
 use ssz::DecodeError;
 use state_processing::BlockReplayError;
+use std::path::PathBuf;
 use types::{BeaconStateError, EpochCacheError, Hash256, InconsistentFork, Slot};
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -57,6 +58,15 @@ pub enum Error {
         state_root: Hash256,
         slot: Slot,
     },
+    /// Snapshots may only be written to, or restored into, empty directories.
+    SnapshotDirectoryNotEmpty(PathBuf),
+    SnapshotIoError(String),
+    /// The contents of a snapshot don't match what was expected, e.g. its manifest.
+    SnapshotInconsistent(String),
+    /// Only one snapshot may be written at a time.
+    SnapshotInProgress,
+    BlobArchiveError(String),
+    CompressionError(String),
 }
 
 pub trait HandleUnavailable<T> {
@@ -1,6 +1,8 @@
+use crate::blob_archive::BlobArchive;
 use crate::chunked_vector::{
     store_updated_vector, BlockRoots, HistoricalRoots, HistoricalSummaries, RandaoMixes, StateRoots,
 };
+use crate::compression;
 use crate::config::{
     OnDiskStoreConfig, StoreConfig, DEFAULT_SLOTS_PER_RESTORE_POINT,
     PREV_DEFAULT_SLOTS_PER_RESTORE_POINT,
@@ -38,6 +40,7 @@ use std::cmp::min;
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use types::*;
@@ -62,6 +65,8 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     pub cold_db: Cold,
     /// Database containing blobs. If None, store falls back to use `cold_db`.
     pub blobs_db: Cold,
+    /// Archive of pruned blobs, if enabled by `StoreConfig::blob_archive_dir`.
+    blob_archive: Option<BlobArchive<E>>,
     /// Hot database containing duplicated but quick-to-access recent data.
     ///
     /// The hot database also contains all blocks.
@@ -74,6 +79,10 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     state_cache: Mutex<StateCache<E>>,
     /// LRU cache of replayed states.
     historic_state_cache: Mutex<LruCache<Slot, BeaconState<E>>>,
+    /// Whether a snapshot of the database is being written. Blob pruning is deferred while it is.
+    pub(crate) snapshot_in_progress: AtomicBool,
+    /// Held while blobs are pruned, so that a snapshot can wait for pruning to complete.
+    pub(crate) blob_pruning_lock: Mutex<()>,
     /// Chain spec.
     pub(crate) spec: ChainSpec,
     /// Logger.
@@ -182,10 +191,13 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
             blob_info: RwLock::new(BlobInfo::default()),
             cold_db: MemoryStore::open(),
             blobs_db: MemoryStore::open(),
+            blob_archive: Self::open_blob_archive(&config)?,
             hot_db: MemoryStore::open(),
             block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
             state_cache: Mutex::new(StateCache::new(config.state_cache_size)),
             historic_state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
+            snapshot_in_progress: AtomicBool::new(false),
+            blob_pruning_lock: Mutex::new(()),
             config,
             spec,
             log,
@@ -212,21 +224,15 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
     ) -> Result<Arc<Self>, Error> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
 
-        let mut db = HotColdDB {
-            split: RwLock::new(Split::default()),
-            anchor_info: RwLock::new(None),
-            blob_info: RwLock::new(BlobInfo::default()),
-            cold_db: LevelDB::open(cold_path)?,
-            blobs_db: LevelDB::open(blobs_db_path)?,
-            hot_db: LevelDB::open(hot_path)?,
-            block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
-            state_cache: Mutex::new(StateCache::new(config.state_cache_size)),
-            historic_state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
+        let mut db = Self::open_leveldbs(
+            hot_path,
+            cold_path,
+            blobs_db_path,
+            LevelDB::open,
             config,
             spec,
             log,
-            _phantom: PhantomData,
-        };
+        )?;
 
         // Allow the slots-per-restore-point value to stay at the previous default if the config
         // uses the new default. Don't error on a failed read because the config itself may need
@@ -335,6 +341,79 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         Ok(db)
     }
 
+    /// Open an existing database so that it can be verified, without modifying it.
+    ///
+    /// Unlike `open`, the schema is not migrated, the config and blob info are not written, and
+    /// no garbage collection or compaction is run. The on-disk slots-per-restore-point is used in
+    /// place of the configured value.
+    pub fn open_for_verification(
+        hot_path: &Path,
+        cold_path: &Path,
+        blobs_db_path: &Path,
+        config: StoreConfig,
+        spec: ChainSpec,
+        log: Logger,
+    ) -> Result<Arc<Self>, Error> {
+        // The blob archive isn't verified, so don't create its directory.
+        let config = StoreConfig {
+            blob_archive_dir: None,
+            ..config
+        };
+        let mut db = Self::open_leveldbs(
+            hot_path,
+            cold_path,
+            blobs_db_path,
+            LevelDB::open_existing,
+            config,
+            spec,
+            log,
+        )?;
+
+        if let Some(disk_config) = db.load_config()? {
+            db.config.slots_per_restore_point = disk_config.slots_per_restore_point;
+        }
+        Self::verify_slots_per_restore_point(db.config.slots_per_restore_point)?;
+
+        if let Some(split) = db.load_split()? {
+            *db.split.write() = split;
+            *db.anchor_info.write() = db.load_anchor_info()?;
+        }
+        if let Some(blob_info) = db.load_blob_info()? {
+            *db.blob_info.write() = blob_info;
+        }
+
+        Ok(Arc::new(db))
+    }
+
+    fn open_leveldbs(
+        hot_path: &Path,
+        cold_path: &Path,
+        blobs_db_path: &Path,
+        open_db: fn(&Path) -> Result<LevelDB<E>, Error>,
+        config: StoreConfig,
+        spec: ChainSpec,
+        log: Logger,
+    ) -> Result<Self, Error> {
+        Ok(HotColdDB {
+            split: RwLock::new(Split::default()),
+            anchor_info: RwLock::new(None),
+            blob_info: RwLock::new(BlobInfo::default()),
+            cold_db: open_db(cold_path)?,
+            blobs_db: open_db(blobs_db_path)?,
+            blob_archive: Self::open_blob_archive(&config)?,
+            hot_db: open_db(hot_path)?,
+            block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
+            state_cache: Mutex::new(StateCache::new(config.state_cache_size)),
+            historic_state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
+            snapshot_in_progress: AtomicBool::new(false),
+            blob_pruning_lock: Mutex::new(()),
+            config,
+            spec,
+            log,
+            _phantom: PhantomData,
+        })
+    }
+
     /// Return an iterator over the state roots of all temporary states.
     pub fn iter_temporary_state_roots(&self) -> impl Iterator<Item = Result<Hash256, Error>> + '_ {
         let column = DBColumn::BeaconStateTemporary;
@@ -402,7 +481,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         let (blinded_block, payload) = block.into();
 
         // Store blinded block.
-        self.blinded_block_as_kv_store_ops(key, &blinded_block, ops);
+        self.blinded_block_as_kv_store_ops(key, &blinded_block, ops)?;
 
         // Store execution payload if present.
         if let Some(ref execution_payload) = payload {
@@ -421,12 +500,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         key: &Hash256,
         blinded_block: &SignedBeaconBlock<E, BlindedPayload<E>>,
         ops: &mut Vec<KeyValueStoreOp>,
-    ) {
+    ) -> Result<(), Error> {
         let db_key = get_key_for_col(DBColumn::BeaconBlock.into(), key.as_bytes());
-        ops.push(KeyValueStoreOp::PutKeyValue(
-            db_key,
-            blinded_block.as_ssz_bytes(),
-        ));
+        let bytes = self.compress_value(DBColumn::BeaconBlock, blinded_block.as_ssz_bytes())?;
+        ops.push(KeyValueStoreOp::PutKeyValue(db_key, bytes));
+        Ok(())
     }
 
     pub fn try_get_full_block(
@@ -547,9 +625,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ) -> Result<Option<SignedBeaconBlock<E, Payload>>, Error> {
         self.hot_db
             .get_bytes(DBColumn::BeaconBlock.into(), block_root.as_bytes())?
-            .map(|block_bytes| decoder(&block_bytes))
+            .map(|block_bytes| Ok(decoder(&compression::decompress(block_bytes)?)?))
             .transpose()
-            .map_err(|e| e.into())
     }
 
     /// Load the execution payload for a block from disk.
@@ -610,7 +687,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.blobs_db.put_bytes(
             DBColumn::BeaconBlob.into(),
             block_root.as_bytes(),
-            &blobs.as_ssz_bytes(),
+            &self.compress_value(DBColumn::BeaconBlob, blobs.as_ssz_bytes())?,
         )?;
         self.block_cache.lock().put_blobs(*block_root, blobs);
         Ok(())
@@ -621,9 +698,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         key: &Hash256,
         blobs: BlobSidecarList<E>,
         ops: &mut Vec<KeyValueStoreOp>,
-    ) {
+    ) -> Result<(), Error> {
         let db_key = get_key_for_col(DBColumn::BeaconBlob.into(), key.as_bytes());
-        ops.push(KeyValueStoreOp::PutKeyValue(db_key, blobs.as_ssz_bytes()));
+        let bytes = self.compress_value(DBColumn::BeaconBlob, blobs.as_ssz_bytes())?;
+        ops.push(KeyValueStoreOp::PutKeyValue(db_key, bytes));
+        Ok(())
     }
 
     pub fn put_state_summary(
@@ -906,7 +985,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 }
 
                 StoreOp::PutBlobs(block_root, blobs) => {
-                    self.blobs_as_kv_store_ops(&block_root, blobs, &mut key_value_batch);
+                    self.blobs_as_kv_store_ops(&block_root, blobs, &mut key_value_batch)?;
                 }
 
                 StoreOp::PutStateSummary(state_root, summary) => {
@@ -1271,7 +1350,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
         // 1. Convert to PartialBeaconState and store that in the DB.
         let partial_state = PartialBeaconState::from_state_forgetful(state);
-        let op = partial_state.as_kv_store_op(*state_root);
+        let op = partial_state.as_kv_store_op(
+            *state_root,
+            self.config.state_compression,
+            self.config.zstd_compression_level,
+        )?;
         ops.push(op);
 
         // 2. Store updated vector entries.
@@ -1330,8 +1413,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             .cold_db
             .get_bytes(DBColumn::BeaconState.into(), state_root.as_bytes())?
             .ok_or(HotColdDBError::MissingRestorePoint(*state_root))?;
-        let mut partial_state: PartialBeaconState<E> =
-            PartialBeaconState::from_ssz_bytes(&partial_state_bytes, &self.spec)?;
+        let mut partial_state: PartialBeaconState<E> = PartialBeaconState::from_ssz_bytes(
+            &compression::decompress(partial_state_bytes)?,
+            &self.spec,
+        )?;
 
         // Fill in the fields of the partial state.
         partial_state.load_block_roots(&self.cold_db, &self.spec)?;
@@ -1541,8 +1626,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             .blobs_db
             .get_bytes(DBColumn::BeaconBlob.into(), block_root.as_bytes())?
         {
-            Some(ref blobs_bytes) => {
-                let blobs = BlobSidecarList::from_ssz_bytes(blobs_bytes)?;
+            Some(blobs_bytes) => {
+                let blobs =
+                    BlobSidecarList::from_ssz_bytes(&compression::decompress(blobs_bytes)?)?;
                 self.block_cache
                     .lock()
                     .put_blobs(*block_root, blobs.clone());
@@ -1552,6 +1638,31 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    /// Fetch the blobs of a block which have been pruned from the database and archived.
+    ///
+    /// Returns `None` if the blobs aren't in the archive, or if the archive is disabled.
+    pub fn get_archived_blobs(
+        &self,
+        block_root: &Hash256,
+        slot: Slot,
+    ) -> Result<Option<BlobSidecarList<E>>, Error> {
+        match &self.blob_archive {
+            Some(blob_archive) => blob_archive.get(block_root, slot),
+            None => Ok(None),
+        }
+    }
+
+    /// Return the first slot of the oldest epoch in the blob archive, or `None` if the archive is
+    /// disabled or empty.
+    pub fn get_oldest_archived_blob_slot(&self) -> Result<Option<Slot>, Error> {
+        match &self.blob_archive {
+            Some(blob_archive) => Ok(blob_archive
+                .oldest_epoch()?
+                .map(|epoch| epoch.start_slot(E::slots_per_epoch()))),
+            None => Ok(None),
+        }
+    }
+
     /// Get a reference to the `ChainSpec` used by the database.
     pub fn get_chain_spec(&self) -> &ChainSpec {
         &self.spec
@@ -1605,7 +1716,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load the database schema version from disk.
-    fn load_schema_version(&self) -> Result<Option<SchemaVersion>, Error> {
+    pub(crate) fn load_schema_version(&self) -> Result<Option<SchemaVersion>, Error> {
         self.hot_db.get(&SCHEMA_VERSION_KEY)
     }
 
@@ -1876,7 +1987,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load the state root of a restore point.
-    fn load_restore_point_hash(&self, restore_point_index: u64) -> Result<Hash256, Error> {
+    pub(crate) fn load_restore_point_hash(
+        &self,
+        restore_point_index: u64,
+    ) -> Result<Hash256, Error> {
         let key = Self::restore_point_key(restore_point_index);
         self.cold_db
             .get(&key)?
@@ -1928,6 +2042,22 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.hot_db.get(state_root)
     }
 
+    /// Compress `bytes` with the codec configured for `column`.
+    fn compress_value(&self, column: DBColumn, bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+        self.config
+            .compression_for_column(column)
+            .compress(bytes, self.config.zstd_compression_level)
+    }
+
+    /// Open the blob archive, if it is enabled by the config.
+    fn open_blob_archive(config: &StoreConfig) -> Result<Option<BlobArchive<E>>, Error> {
+        config
+            .blob_archive_dir
+            .as_deref()
+            .map(BlobArchive::open)
+            .transpose()
+    }
+
     /// Verify that a parsed config is valid.
     fn verify_config(config: &StoreConfig) -> Result<(), HotColdDBError> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
@@ -2197,6 +2327,17 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             return Ok(());
         }
 
+        // Blobs are deleted before the blob info is updated, so pruning must not run while the
+        // databases are snapshotted. A snapshot sets its flag before taking this lock.
+        let _pruning_lock = self.blob_pruning_lock.lock();
+        if self.snapshot_in_progress.load(Ordering::SeqCst) {
+            debug!(
+                self.log,
+                "Deferring blob pruning until the database snapshot completes"
+            );
+            return Ok(());
+        }
+
         let blob_info = self.get_blob_info();
         let Some(oldest_blob_slot) = blob_info.oldest_blob_slot else {
             error!(self.log, "Slot of oldest blob is not known");
@@ -2257,6 +2398,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
         let mut ops = vec![];
         let mut last_pruned_block_root = None;
+        let mut blobs_to_archive: Vec<(Hash256, Slot, BlobSidecarList<E>)> = vec![];
+        let mut blob_lists_archived = 0;
 
         for res in self.forwards_block_roots_iterator_until(
             oldest_blob_slot,
@@ -2294,12 +2437,40 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 );
                 last_pruned_block_root = Some(block_root);
                 ops.push(StoreOp::DeleteBlobs(block_root));
+
+                if let Some(blob_archive) = &self.blob_archive {
+                    // Archive the blobs one epoch at a time to bound memory usage. The blobs are
+                    // archived before they're deleted, so they can't be lost by an interruption.
+                    let epoch = slot.epoch(E::slots_per_epoch());
+                    if blobs_to_archive.last().is_some_and(|(_, last_slot, _)| {
+                        last_slot.epoch(E::slots_per_epoch()) != epoch
+                    }) {
+                        blob_archive.append(&blobs_to_archive)?;
+                        blob_lists_archived += blobs_to_archive.len();
+                        blobs_to_archive.clear();
+                    }
+
+                    // Read the blobs directly to avoid displacing recent blobs from the cache.
+                    if let Some(bytes) = self
+                        .blobs_db
+                        .get_bytes(DBColumn::BeaconBlob.into(), block_root.as_bytes())?
+                    {
+                        let blobs =
+                            BlobSidecarList::from_ssz_bytes(&compression::decompress(bytes)?)?;
+                        blobs_to_archive.push((block_root, slot, blobs));
+                    }
+                }
             }
 
             if slot >= end_slot {
                 break;
             }
         }
+        if let Some(blob_archive) = &self.blob_archive {
+            blob_archive.append(&blobs_to_archive)?;
+            blob_lists_archived += blobs_to_archive.len();
+        }
+
         let blob_lists_pruned = ops.len();
         let new_blob_info = BlobInfo {
             oldest_blob_slot: Some(end_slot + 1),
@@ -2313,6 +2484,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             self.log,
             "Blob pruning complete";
             "blob_lists_pruned" => blob_lists_pruned,
+            "blob_lists_archived" => blob_lists_archived,
         );
 
         Ok(())
@@ -2751,7 +2923,7 @@ fn no_state_root_iter() -> Option<std::iter::Empty<Result<(Hash256, Slot), Error
 pub struct HotStateSummary {
     pub slot: Slot,
     pub latest_block_root: Hash256,
-    epoch_boundary_state_root: Hash256,
+    pub(crate) epoch_boundary_state_root: Hash256,
 }
 
 impl StoreItem for HotStateSummary {
@@ -3,6 +3,7 @@ use crate::hot_cold_store::HotColdDBError;
 use leveldb::compaction::Compaction;
 use leveldb::database::batch::{Batch, Writebatch};
 use leveldb::database::kv::KV;
+use leveldb::database::snapshots::{Snapshot, Snapshots};
 use leveldb::database::Database;
 use leveldb::error::Error as LevelDBError;
 use leveldb::iterator::{Iterable, KeyIterator, LevelDBIterator};
@@ -22,9 +23,18 @@ pub struct LevelDB<E: EthSpec> {
 impl<E: EthSpec> LevelDB<E> {
     /// Open a database at `path`, creating a new database if one does not already exist.
     pub fn open(path: &Path) -> Result<Self, Error> {
+        Self::open_with_options(path, true)
+    }
+
+    /// Open the existing database at `path`, failing if there is none.
+    pub fn open_existing(path: &Path) -> Result<Self, Error> {
+        Self::open_with_options(path, false)
+    }
+
+    fn open_with_options(path: &Path, create_if_missing: bool) -> Result<Self, Error> {
         let mut options = Options::new();
 
-        options.create_if_missing = true;
+        options.create_if_missing = create_if_missing;
 
         let db = Database::open(path, options)?;
         let transaction_mutex = Mutex::new(());
@@ -249,10 +259,35 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
                 }),
         )
     }
+
+    fn snapshot(&self) -> Box<dyn KeyValueSnapshot + '_> {
+        Box::new(LevelDBSnapshot {
+            snapshot: self.db.snapshot(),
+        })
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for LevelDB<E> {}
 
+/// A leveldb snapshot, which keeps the files it reads from alive until it is dropped.
+struct LevelDBSnapshot<'a> {
+    snapshot: Snapshot<'a, BytesKey>,
+}
+
+impl<'a> KeyValueSnapshot for LevelDBSnapshot<'a> {
+    fn iter_raw_entries(&self) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + '_> {
+        // Avoid evicting the blocks used by the running node from the cache.
+        let mut options = ReadOptions::new();
+        options.fill_cache = false;
+
+        Box::new(
+            self.snapshot
+                .iter(options)
+                .map(|(bytes_key, value)| (bytes_key.key, value)),
+        )
+    }
+}
+
 /// Used for keying leveldb.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct BytesKey {
@@ -297,6 +332,11 @@ impl BytesKey {
     pub fn from_vec(key: Vec<u8>) -> Self {
         Self { key }
     }
+
+    /// Return the full key, including the column.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.key
+    }
 }
 
 impl From<LevelDBError> for Error {
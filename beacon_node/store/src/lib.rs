@@ -7,9 +7,11 @@
 //!
 //! Provides a simple API for storing/retrieving all types that sometimes needs type-hints. See
 //! tests for implementation examples.
+mod blob_archive;
 mod chunk_writer;
 pub mod chunked_iter;
 pub mod chunked_vector;
+pub mod compression;
 pub mod config;
 pub mod consensus_context;
 pub mod errors;
@@ -23,11 +25,14 @@ pub mod metadata;
 pub mod metrics;
 mod partial_beacon_state;
 pub mod reconstruct;
+pub mod snapshot;
 pub mod state_cache;
+mod verify;
 
 pub mod iter;
 
 pub use self::chunk_writer::ChunkWriter;
+pub use self::compression::Codec;
 pub use self::config::StoreConfig;
 pub use self::consensus_context::OnDiskConsensusContext;
 pub use self::hot_cold_store::{HotColdDB, HotStateSummary, Split};
@@ -43,6 +48,7 @@ use parking_lot::MutexGuard;
 use std::sync::Arc;
 use strum::{EnumString, IntoStaticStr};
 pub use types::*;
+pub use verify::{Issue as VerificationIssue, VerificationReport};
 
 pub type ColumnIter<'a, K> = Box<dyn Iterator<Item = Result<(K, Vec<u8>), Error>> + 'a>;
 pub type ColumnKeyIter<'a, K> = Box<dyn Iterator<Item = Result<K, Error>> + 'a>;
@@ -50,6 +56,14 @@ pub type ColumnKeyIter<'a, K> = Box<dyn Iterator<Item = Result<K, Error>> + 'a>;
 pub type RawEntryIter<'a> = Box<dyn Iterator<Item = Result<(Vec<u8>, Vec<u8>), Error>> + 'a>;
 pub type RawKeyIter<'a> = Box<dyn Iterator<Item = Result<Vec<u8>, Error>> + 'a>;
 
+/// A consistent point-in-time view of a `KeyValueStore`, see `KeyValueStore::snapshot`.
+pub trait KeyValueSnapshot {
+    /// Iterate through every key and value in the snapshot in key order.
+    ///
+    /// Keys include their column prefix, so they can be written to another store unmodified.
+    fn iter_raw_entries(&self) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + '_>;
+}
+
 pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
     /// Retrieve some bytes in `column` with `key`.
     fn get_bytes(&self, column: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error>;
@@ -115,6 +129,11 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
 
     /// Iterate through all keys in a particular column.
     fn iter_column_keys<K: Key>(&self, column: DBColumn) -> ColumnKeyIter<K>;
+
+    /// Take a consistent point-in-time view of every column in the database.
+    ///
+    /// Writes made after the snapshot is taken are not visible through it.
+    fn snapshot(&self) -> Box<dyn KeyValueSnapshot + '_>;
 }
 
 pub trait Key: Sized + 'static {
@@ -274,6 +293,9 @@ pub enum DBColumn {
     BeaconHistoricalSummaries,
     #[strum(serialize = "olc")]
     OverflowLRUCache,
+    /// For the per-epoch summaries of validators monitored by the validator monitor.
+    #[strum(serialize = "vmh")]
+    ValidatorMonitorHistory,
 }
 
 /// A block from the database, which might have an execution payload or not.
@@ -317,6 +339,7 @@ impl DBColumn {
             | Self::BeaconHistoricalRoots
             | Self::BeaconHistoricalSummaries
             | Self::BeaconRandaoMixes => 8,
+            Self::ValidatorMonitorHistory => 16,
         }
     }
 }
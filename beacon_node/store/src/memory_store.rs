@@ -1,6 +1,6 @@
 use crate::{
     get_key_for_col, leveldb_store::BytesKey, ColumnIter, ColumnKeyIter, DBColumn, Error,
-    ItemStore, Key, KeyValueStore, KeyValueStoreOp,
+    ItemStore, Key, KeyValueSnapshot, KeyValueStore, KeyValueStoreOp,
 };
 use parking_lot::{Mutex, MutexGuard, RwLock};
 use std::collections::BTreeMap;
@@ -111,6 +111,23 @@ impl<E: EthSpec> KeyValueStore<E> for MemoryStore<E> {
     fn compact_column(&self, _column: DBColumn) -> Result<(), Error> {
         Ok(())
     }
+
+    fn snapshot(&self) -> Box<dyn KeyValueSnapshot + '_> {
+        Box::new(MemorySnapshot(self.db.read().clone()))
+    }
+}
+
+/// A copy of the contents of a `MemoryStore`.
+struct MemorySnapshot(DBMap);
+
+impl KeyValueSnapshot for MemorySnapshot {
+    fn iter_raw_entries(&self) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + '_> {
+        Box::new(
+            self.0
+                .iter()
+                .map(|(key, value)| (key.as_bytes().to_vec(), value.clone())),
+        )
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for MemoryStore<E> {}
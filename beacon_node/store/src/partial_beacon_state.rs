@@ -2,7 +2,7 @@ use crate::chunked_vector::{
     load_variable_list_from_db, load_vector_from_db, BlockRoots, HistoricalRoots,
     HistoricalSummaries, RandaoMixes, StateRoots,
 };
-use crate::{get_key_for_col, DBColumn, Error, KeyValueStore, KeyValueStoreOp};
+use crate::{get_key_for_col, Codec, DBColumn, Error, KeyValueStore, KeyValueStoreOp};
 use ssz::{Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
 use std::sync::Arc;
@@ -321,10 +321,16 @@ impl<E: EthSpec> PartialBeaconState<E> {
         ))
     }
 
-    /// Prepare the partial state for storage in the KV database.
-    pub fn as_kv_store_op(&self, state_root: Hash256) -> KeyValueStoreOp {
+    /// Prepare the partial state for storage in the KV database, compressed with `codec`.
+    pub fn as_kv_store_op(
+        &self,
+        state_root: Hash256,
+        codec: Codec,
+        zstd_level: i32,
+    ) -> Result<KeyValueStoreOp, Error> {
         let db_key = get_key_for_col(DBColumn::BeaconState.into(), state_root.as_bytes());
-        KeyValueStoreOp::PutKeyValue(db_key, self.as_ssz_bytes())
+        let bytes = codec.compress(self.as_ssz_bytes(), zstd_level)?;
+        Ok(KeyValueStoreOp::PutKeyValue(db_key, bytes))
     }
 
     pub fn load_block_roots<S: KeyValueStore<E>>(
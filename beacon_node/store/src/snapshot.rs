@@ -0,0 +1,270 @@
+//! Consistent point-in-time copies of the database, which can be taken while the node is running
+//! and later restored into a new data directory.
+//!
+//! A snapshot is a directory containing a copy of each database and a manifest:
+//!
+//! ```text
+//! snapshot/
+//! ├── chain_db/
+//! ├── freezer_db/
+//! ├── blobs_db/
+//! └── snapshot.json
+//! ```
+//!
+//! The manifest is written last, so a directory without one holds an incomplete snapshot.
+use crate::hot_cold_store::{HotColdDB, Split};
+use crate::metadata::{AnchorInfo, SchemaVersion, ANCHOR_INFO_KEY, SCHEMA_VERSION_KEY, SPLIT_KEY};
+use crate::{Error, ItemStore, KeyValueSnapshot, KeyValueStore, KeyValueStoreOp, LevelDB};
+use serde::{Deserialize, Serialize};
+use slog::{info, Logger};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use types::EthSpec;
+
+/// The name of the file describing a complete snapshot.
+pub const SNAPSHOT_MANIFEST_FILE: &str = "snapshot.json";
+/// The directory within a snapshot containing the hot database.
+pub const SNAPSHOT_HOT_DB_DIR: &str = "chain_db";
+/// The directory within a snapshot containing the freezer database.
+pub const SNAPSHOT_FREEZER_DB_DIR: &str = "freezer_db";
+/// The directory within a snapshot containing the blobs database.
+pub const SNAPSHOT_BLOBS_DB_DIR: &str = "blobs_db";
+
+/// The number of bytes of keys and values written to a snapshot database in each batch.
+const SNAPSHOT_BATCH_BYTES: usize = 64 * 1024 * 1024;
+
+/// The size of one of the databases in a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SnapshotDatabase {
+    pub entries: u64,
+    /// The total size of the values, excluding keys.
+    pub bytes: u64,
+}
+
+/// The contents of `SNAPSHOT_MANIFEST_FILE`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub schema_version: u64,
+    /// The split point to which the hot and freezer databases of the snapshot are aligned.
+    pub split: Split,
+    pub anchor: Option<AnchorInfo>,
+    pub hot_db: SnapshotDatabase,
+    pub freezer_db: SnapshotDatabase,
+    pub blobs_db: SnapshotDatabase,
+}
+
+impl SnapshotManifest {
+    /// Read the manifest of the snapshot in `snapshot_dir`.
+    pub fn read(snapshot_dir: &Path) -> Result<Self, Error> {
+        let path = snapshot_dir.join(SNAPSHOT_MANIFEST_FILE);
+        let bytes = fs::read(&path).map_err(|e| {
+            Error::SnapshotIoError(format!("unable to read {}: {:?}", path.display(), e))
+        })?;
+        serde_json::from_slice(&bytes).map_err(|e| {
+            Error::SnapshotIoError(format!("unable to parse {}: {:?}", path.display(), e))
+        })
+    }
+
+    fn write(&self, snapshot_dir: &Path) -> Result<(), Error> {
+        let path = snapshot_dir.join(SNAPSHOT_MANIFEST_FILE);
+        let bytes = serde_json::to_vec_pretty(self).map_err(|e| {
+            Error::SnapshotIoError(format!("unable to serialize manifest: {:?}", e))
+        })?;
+        fs::write(&path, bytes).map_err(|e| {
+            Error::SnapshotIoError(format!("unable to write {}: {:?}", path.display(), e))
+        })
+    }
+}
+
+impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold> {
+    /// Write a snapshot of the hot, freezer and blobs databases to `output_dir`, which must not
+    /// exist or be empty.
+    ///
+    /// The database may be written to while the snapshot is copied, including by a freezer
+    /// migration, without affecting the copy. Blob pruning is deferred until the snapshot is
+    /// complete, and only one snapshot may be written at a time.
+    pub fn snapshot_database(&self, output_dir: &Path) -> Result<SnapshotManifest, Error> {
+        if self
+            .snapshot_in_progress
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(Error::SnapshotInProgress);
+        }
+        let result = self.write_snapshot(output_dir);
+        self.snapshot_in_progress.store(false, Ordering::SeqCst);
+        result
+    }
+
+    fn write_snapshot(&self, output_dir: &Path) -> Result<SnapshotManifest, Error> {
+        ensure_empty_dir(output_dir)?;
+
+        let (split, hot_snapshot, blobs_snapshot, cold_snapshot) = {
+            // Wait for any blob pruning which started before the snapshot to complete.
+            let _pruning_lock = self.blob_pruning_lock.lock();
+            // Holding the split lock prevents a migration from persisting a new split until all of
+            // the snapshots are taken. Migrations write to the freezer DB before the split, and
+            // blobs are written before their blocks, so taking the hot snapshot first ensures that
+            // everything it refers to is in the other two.
+            let split = self.split.read_recursive();
+            let hot_snapshot = self.hot_db.snapshot();
+            let blobs_snapshot = self.blobs_db.snapshot();
+            let cold_snapshot = self.cold_db.snapshot();
+            (*split, hot_snapshot, blobs_snapshot, cold_snapshot)
+        };
+
+        info!(
+            self.log,
+            "Writing database snapshot";
+            "split_slot" => split.slot,
+            "path" => ?output_dir,
+        );
+
+        let hot_path = output_dir.join(SNAPSHOT_HOT_DB_DIR);
+        let hot_db = copy_snapshot::<E>(hot_snapshot, &hot_path)?;
+        let freezer_db =
+            copy_snapshot::<E>(cold_snapshot, &output_dir.join(SNAPSHOT_FREEZER_DB_DIR))?;
+        let blobs_db = copy_snapshot::<E>(blobs_snapshot, &output_dir.join(SNAPSHOT_BLOBS_DB_DIR))?;
+
+        // Read the metadata from the copy rather than from memory, in case it has since changed.
+        let hot = LevelDB::<E>::open(&hot_path)?;
+        check_split(&hot, &split)?;
+        let schema_version = hot
+            .get::<SchemaVersion>(&SCHEMA_VERSION_KEY)?
+            .ok_or_else(|| Error::SnapshotInconsistent("missing schema version".into()))?;
+        let anchor = hot.get::<AnchorInfo>(&ANCHOR_INFO_KEY)?;
+        drop(hot);
+
+        let manifest = SnapshotManifest {
+            schema_version: schema_version.as_u64(),
+            split,
+            anchor,
+            hot_db,
+            freezer_db,
+            blobs_db,
+        };
+        manifest.write(output_dir)?;
+
+        info!(
+            self.log,
+            "Database snapshot complete";
+            "split_slot" => split.slot,
+            "hot_db_bytes" => hot_db.bytes,
+            "freezer_db_bytes" => freezer_db.bytes,
+            "blobs_db_bytes" => blobs_db.bytes,
+        );
+
+        Ok(manifest)
+    }
+}
+
+/// Restore the snapshot in `snapshot_dir` into new hot, freezer and blobs database directories.
+///
+/// None of the directories may contain any files. The snapshot is left unmodified.
+pub fn restore_snapshot<E: EthSpec>(
+    snapshot_dir: &Path,
+    hot_path: &Path,
+    cold_path: &Path,
+    blobs_path: &Path,
+    log: &Logger,
+) -> Result<SnapshotManifest, Error> {
+    let manifest = SnapshotManifest::read(snapshot_dir)?;
+    let databases = [
+        (SNAPSHOT_HOT_DB_DIR, hot_path),
+        (SNAPSHOT_FREEZER_DB_DIR, cold_path),
+        (SNAPSHOT_BLOBS_DB_DIR, blobs_path),
+    ];
+
+    for (_, path) in databases {
+        ensure_empty_dir(path)?;
+    }
+
+    for (name, path) in databases {
+        info!(
+            log,
+            "Restoring database from snapshot";
+            "database" => name,
+            "path" => ?path,
+        );
+        copy_dir(&snapshot_dir.join(name), path)?;
+    }
+
+    check_split(&LevelDB::<E>::open(hot_path)?, &manifest.split)?;
+
+    Ok(manifest)
+}
+
+/// Copy every entry of `snapshot` into a new database at `path`.
+fn copy_snapshot<E: EthSpec>(
+    snapshot: Box<dyn KeyValueSnapshot + '_>,
+    path: &Path,
+) -> Result<SnapshotDatabase, Error> {
+    let db = LevelDB::<E>::open(path)?;
+    let mut stats = SnapshotDatabase::default();
+    let mut batch = vec![];
+    let mut batch_bytes = 0;
+
+    for (key, value) in snapshot.iter_raw_entries() {
+        stats.entries += 1;
+        stats.bytes += value.len() as u64;
+        batch_bytes += key.len() + value.len();
+        batch.push(KeyValueStoreOp::PutKeyValue(key, value));
+
+        if batch_bytes >= SNAPSHOT_BATCH_BYTES {
+            db.do_atomically(std::mem::take(&mut batch))?;
+            batch_bytes = 0;
+        }
+    }
+    db.do_atomically(batch)?;
+    db.sync()?;
+
+    Ok(stats)
+}
+
+/// Check that the split stored in `hot_db` is `expected`.
+fn check_split<E: EthSpec>(hot_db: &LevelDB<E>, expected: &Split) -> Result<(), Error> {
+    // The block root of the split isn't stored on disk.
+    match hot_db.get::<Split>(&SPLIT_KEY)? {
+        Some(split) if split.slot == expected.slot && split.state_root == expected.state_root => {
+            Ok(())
+        }
+        split => Err(Error::SnapshotInconsistent(format!(
+            "split {:?} does not match expected {:?}",
+            split, expected
+        ))),
+    }
+}
+
+/// Create the directory at `path` if it doesn't exist, or check that it is empty if it does.
+fn ensure_empty_dir(path: &Path) -> Result<(), Error> {
+    let io_error =
+        |e: std::io::Error| Error::SnapshotIoError(format!("{}: {:?}", path.display(), e));
+
+    if path.exists() {
+        if fs::read_dir(path).map_err(io_error)?.next().is_some() {
+            return Err(Error::SnapshotDirectoryNotEmpty(path.to_path_buf()));
+        }
+        Ok(())
+    } else {
+        fs::create_dir_all(path).map_err(io_error)
+    }
+}
+
+/// Copy the files of a closed database from `from` into `to`.
+fn copy_dir(from: &Path, to: &Path) -> Result<(), Error> {
+    let io_error =
+        |e: std::io::Error| Error::SnapshotIoError(format!("{}: {:?}", from.display(), e));
+
+    for entry in fs::read_dir(from).map_err(io_error)? {
+        let entry = entry.map_err(io_error)?;
+        if !entry.file_type().map_err(io_error)?.is_file() {
+            return Err(Error::SnapshotInconsistent(format!(
+                "unexpected directory {}",
+                entry.path().display()
+            )));
+        }
+        fs::copy(entry.path(), to.join(entry.file_name())).map_err(io_error)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,546 @@
+//! Offline consistency checks for the hot and freezer databases.
+use crate::chunked_iter::ChunkedVectorIter;
+use crate::chunked_vector::{BlockRoots, StateRoots};
+use crate::hot_cold_store::{ColdStateSummary, HotColdDB, HotColdDBError, HotStateSummary};
+use crate::metadata::{CURRENT_SCHEMA_VERSION, STATE_UPPER_LIMIT_NO_RETAIN};
+use crate::{DBColumn, Error, ItemStore, KeyValueStore, KeyValueStoreOp, StoreItem, StoreOp};
+use serde::Serialize;
+use slog::{info, warn};
+use std::collections::HashSet;
+use types::{EthSpec, Hash256, Slot};
+
+/// A problem found while verifying the database.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Issue {
+    /// The database has no schema version, and is likely empty. No other checks are run.
+    MissingSchemaVersion,
+    /// The database was written by a different version of Lighthouse. No other checks are run.
+    SchemaVersionMismatch {
+        found: u64,
+        expected: u64,
+    },
+    SplitSlotUnaligned {
+        slot: Slot,
+    },
+    MissingSplitState {
+        slot: Slot,
+        state_root: Hash256,
+    },
+    MissingSplitBlock {
+        slot: Slot,
+        block_root: Hash256,
+    },
+    /// The anchor's oldest block or state lower limit lies beyond the split.
+    AnchorBeyondSplit {
+        split_slot: Slot,
+        oldest_block_slot: Slot,
+        state_lower_limit: Slot,
+    },
+    UnreadableBlock {
+        block_root: Hash256,
+        error: String,
+    },
+    MissingParentBlock {
+        block_root: Hash256,
+        slot: Slot,
+        parent_root: Hash256,
+    },
+    /// A block within the data availability window has commitments but no blobs are stored.
+    MissingBlobs {
+        block_root: Hash256,
+        slot: Slot,
+    },
+    /// The freezer's block roots stop before the split.
+    MissingFrozenBlockRoots {
+        slot: Slot,
+    },
+    /// A block referenced by the freezer's block roots is not stored.
+    MissingFrozenBlock {
+        slot: Slot,
+        block_root: Hash256,
+    },
+    UnreadableHotStateSummary {
+        state_root: Hash256,
+        error: String,
+    },
+    /// A hot state summary for a state prior to the split was not deleted by the migration.
+    ///
+    /// Repaired by deleting the summary and any full state stored for it.
+    StaleHotStateSummary {
+        state_root: Hash256,
+        slot: Slot,
+    },
+    /// A state left over by a failed block import, which a beacon node would delete at start-up.
+    ///
+    /// Repaired by deleting the state and its temporary flag.
+    TemporaryState {
+        state_root: Hash256,
+    },
+    HotStateSummaryMissingBlock {
+        state_root: Hash256,
+        slot: Slot,
+        block_root: Hash256,
+    },
+    HotStateSummaryMissingBoundaryState {
+        state_root: Hash256,
+        slot: Slot,
+        epoch_boundary_state_root: Hash256,
+    },
+    MissingRestorePoint {
+        slot: Slot,
+    },
+    MissingRestorePointState {
+        slot: Slot,
+        state_root: Hash256,
+    },
+    /// The freezer's state roots stop before the latest restore point.
+    MissingFrozenStateRoots {
+        slot: Slot,
+    },
+    /// A frozen state root has no entry in the state root to slot index.
+    ///
+    /// Repaired by writing the entry.
+    MissingColdStateSummary {
+        slot: Slot,
+        state_root: Hash256,
+    },
+    /// A frozen state root is indexed at the wrong slot.
+    ///
+    /// Repaired by overwriting the entry.
+    ColdStateSummarySlotMismatch {
+        slot: Slot,
+        state_root: Hash256,
+        indexed_slot: Slot,
+    },
+}
+
+impl Issue {
+    /// Whether `verify_database` can fix this issue when repairs are enabled.
+    pub fn is_repairable(&self) -> bool {
+        matches!(
+            self,
+            Issue::StaleHotStateSummary { .. }
+                | Issue::TemporaryState { .. }
+                | Issue::MissingColdStateSummary { .. }
+                | Issue::ColdStateSummarySlotMismatch { .. }
+        )
+    }
+}
+
+/// The result of `verify_database`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VerificationReport {
+    pub schema_version: u64,
+    pub split_slot: Slot,
+    pub blocks_checked: u64,
+    pub frozen_block_roots_checked: u64,
+    pub hot_state_summaries_checked: u64,
+    pub restore_points_checked: u64,
+    pub frozen_state_roots_checked: u64,
+    pub issues: Vec<Issue>,
+    /// The number of issues which were repaired.
+    pub repaired: u64,
+}
+
+impl VerificationReport {
+    /// Whether any issues remain in the database.
+    pub fn is_consistent(&self) -> bool {
+        self.issues.len() as u64 == self.repaired
+    }
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Check the consistency of the database, optionally repairing its indices.
+    ///
+    /// The database is only written to when `repair` is set. It should be opened with
+    /// `open_for_verification`, and must not be in use by a beacon node while it is verified.
+    pub fn verify_database(&self, repair: bool) -> Result<VerificationReport, Error> {
+        let split = self.get_split_info();
+        let mut report = VerificationReport {
+            split_slot: split.slot,
+            ..VerificationReport::default()
+        };
+
+        let Some(schema_version) = self.load_schema_version()? else {
+            report.issues.push(Issue::MissingSchemaVersion);
+            return Ok(report);
+        };
+        report.schema_version = schema_version.as_u64();
+        if schema_version != CURRENT_SCHEMA_VERSION {
+            report.issues.push(Issue::SchemaVersionMismatch {
+                found: schema_version.as_u64(),
+                expected: CURRENT_SCHEMA_VERSION.as_u64(),
+            });
+            return Ok(report);
+        }
+
+        let mut hot_ops = vec![];
+        let mut cold_ops = vec![];
+
+        info!(self.log, "Verifying split"; "split_slot" => split.slot);
+        self.verify_split(&mut report)?;
+        info!(self.log, "Verifying blocks");
+        self.verify_blocks(&mut report)?;
+        info!(self.log, "Verifying frozen block roots");
+        self.verify_frozen_block_roots(&mut report)?;
+        info!(self.log, "Verifying temporary states");
+        let temporary_state_roots = self.verify_temporary_states(&mut report, &mut hot_ops)?;
+        info!(self.log, "Verifying hot state summaries");
+        self.verify_hot_state_summaries(&temporary_state_roots, &mut report, &mut hot_ops)?;
+        info!(self.log, "Verifying frozen states");
+        self.verify_frozen_states(&mut report, &mut cold_ops)?;
+
+        let repairable = report
+            .issues
+            .iter()
+            .filter(|issue| issue.is_repairable())
+            .count() as u64;
+        if repair && repairable > 0 {
+            warn!(self.log, "Repairing database"; "issues" => repairable);
+            self.cold_db.do_atomically(cold_ops)?;
+            self.do_atomically_with_block_and_blobs_cache(hot_ops)?;
+            report.repaired = repairable;
+        }
+
+        Ok(report)
+    }
+
+    fn verify_split(&self, report: &mut VerificationReport) -> Result<(), Error> {
+        let split = self.get_split_info();
+        // The split is unset until the first finalization of a database initialised from genesis.
+        if split.slot == 0 && split.state_root.is_zero() {
+            return Ok(());
+        }
+
+        if split.slot % E::slots_per_epoch() != 0 {
+            report
+                .issues
+                .push(Issue::SplitSlotUnaligned { slot: split.slot });
+        }
+        if !self
+            .hot_db
+            .key_exists(DBColumn::BeaconState.into(), split.state_root.as_bytes())?
+        {
+            report.issues.push(Issue::MissingSplitState {
+                slot: split.slot,
+                state_root: split.state_root,
+            });
+        }
+        if !self.block_exists(&split.block_root)? {
+            report.issues.push(Issue::MissingSplitBlock {
+                slot: split.slot,
+                block_root: split.block_root,
+            });
+        }
+        if let Some(anchor) = self.get_anchor_info() {
+            if anchor.oldest_block_slot > split.slot || anchor.state_lower_limit > split.slot {
+                report.issues.push(Issue::AnchorBeyondSplit {
+                    split_slot: split.slot,
+                    oldest_block_slot: anchor.oldest_block_slot,
+                    state_lower_limit: anchor.state_lower_limit,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the parent of every block is stored, and that blocks within the data
+    /// availability window have their blobs.
+    fn verify_blocks(&self, report: &mut VerificationReport) -> Result<(), Error> {
+        // The parent of the oldest block is missing if the node was checkpoint synced and has not
+        // completed backfill.
+        let oldest_block_parent = self
+            .get_anchor_info()
+            .map_or(Hash256::zero(), |anchor| anchor.oldest_block_parent);
+        let oldest_blob_slot = self.get_blob_info().oldest_blob_slot;
+
+        for block_root in self
+            .hot_db
+            .iter_column_keys::<Hash256>(DBColumn::BeaconBlock)
+        {
+            let block_root = block_root?;
+            report.blocks_checked += 1;
+
+            let block = match self.get_blinded_block(&block_root) {
+                Ok(Some(block)) => block,
+                Ok(None) => continue,
+                Err(e) => {
+                    report.issues.push(Issue::UnreadableBlock {
+                        block_root,
+                        error: format!("{e:?}"),
+                    });
+                    continue;
+                }
+            };
+
+            let parent_root = block.parent_root();
+            if block.slot() != 0
+                && parent_root != oldest_block_parent
+                && !self.block_exists(&parent_root)?
+            {
+                report.issues.push(Issue::MissingParentBlock {
+                    block_root,
+                    slot: block.slot(),
+                    parent_root,
+                });
+            }
+
+            let in_blob_window = oldest_blob_slot.map_or(false, |slot| block.slot() >= slot);
+            if in_blob_window && block.num_expected_blobs() > 0 && !self.blobs_exist(&block_root)? {
+                report.issues.push(Issue::MissingBlobs {
+                    block_root,
+                    slot: block.slot(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the freezer's block roots cover every slot from the oldest block to the split,
+    /// and that every block they reference is stored.
+    fn verify_frozen_block_roots(&self, report: &mut VerificationReport) -> Result<(), Error> {
+        let start_slot = self.get_oldest_block_slot();
+        let end_slot = self.get_split_slot();
+
+        let mut next_slot = start_slot;
+        let mut previous_block_root = None;
+        for (slot, block_root) in ChunkedVectorIter::<BlockRoots, _, _, _>::new(
+            self,
+            start_slot.as_usize(),
+            end_slot,
+            &self.spec,
+        ) {
+            let slot = Slot::from(slot);
+            next_slot = slot + 1;
+            report.frozen_block_roots_checked += 1;
+
+            // The same root is repeated at skipped slots.
+            if previous_block_root == Some(block_root) {
+                continue;
+            }
+            previous_block_root = Some(block_root);
+
+            if !self.block_exists(&block_root)? {
+                report
+                    .issues
+                    .push(Issue::MissingFrozenBlock { slot, block_root });
+            }
+        }
+
+        if next_slot < end_slot {
+            report
+                .issues
+                .push(Issue::MissingFrozenBlockRoots { slot: next_slot });
+        }
+        Ok(())
+    }
+
+    /// Find the states with a temporary flag, returning their roots.
+    fn verify_temporary_states(
+        &self,
+        report: &mut VerificationReport,
+        hot_ops: &mut Vec<StoreOp<E>>,
+    ) -> Result<HashSet<Hash256>, Error> {
+        let mut state_roots = HashSet::new();
+        for state_root in self
+            .hot_db
+            .iter_column_keys::<Hash256>(DBColumn::BeaconStateTemporary)
+        {
+            let state_root = state_root?;
+            report.issues.push(Issue::TemporaryState { state_root });
+            hot_ops.push(StoreOp::DeleteState(state_root, None));
+            hot_ops.push(StoreOp::DeleteStateTemporaryFlag(state_root));
+            state_roots.insert(state_root);
+        }
+        Ok(state_roots)
+    }
+
+    /// Check that every hot state summary lies after the split and references a stored block and
+    /// epoch boundary state.
+    ///
+    /// The summaries of temporary states are skipped, as those states are reported separately.
+    fn verify_hot_state_summaries(
+        &self,
+        temporary_state_roots: &HashSet<Hash256>,
+        report: &mut VerificationReport,
+        hot_ops: &mut Vec<StoreOp<E>>,
+    ) -> Result<(), Error> {
+        let split_slot = self.get_split_slot();
+
+        for result in self
+            .hot_db
+            .iter_column::<Hash256>(DBColumn::BeaconStateSummary)
+        {
+            let (state_root, bytes) = result?;
+            if temporary_state_roots.contains(&state_root) {
+                continue;
+            }
+            report.hot_state_summaries_checked += 1;
+
+            let summary = match HotStateSummary::from_store_bytes(&bytes) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    report.issues.push(Issue::UnreadableHotStateSummary {
+                        state_root,
+                        error: format!("{e:?}"),
+                    });
+                    continue;
+                }
+            };
+            let slot = summary.slot;
+
+            if slot < split_slot {
+                report
+                    .issues
+                    .push(Issue::StaleHotStateSummary { state_root, slot });
+                hot_ops.push(StoreOp::DeleteState(state_root, Some(slot)));
+                continue;
+            }
+            if !self.block_exists(&summary.latest_block_root)? {
+                report.issues.push(Issue::HotStateSummaryMissingBlock {
+                    state_root,
+                    slot,
+                    block_root: summary.latest_block_root,
+                });
+            }
+            let epoch_boundary_state_root = summary.epoch_boundary_state_root;
+            if !self.hot_db.key_exists(
+                DBColumn::BeaconState.into(),
+                epoch_boundary_state_root.as_bytes(),
+            )? {
+                report
+                    .issues
+                    .push(Issue::HotStateSummaryMissingBoundaryState {
+                        state_root,
+                        slot,
+                        epoch_boundary_state_root,
+                    });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the restore points and the state root to slot index of the freezer, for the slots
+    /// whose states are retained.
+    fn verify_frozen_states(
+        &self,
+        report: &mut VerificationReport,
+        cold_ops: &mut Vec<KeyValueStoreOp>,
+    ) -> Result<(), Error> {
+        // State roots are only stored in the freezer up to the latest restore point.
+        let Some(latest_restore_point_slot) = self.get_latest_restore_point_slot() else {
+            return Ok(());
+        };
+        let (lower_limit, upper_limit) = self.get_historic_state_limits();
+
+        // Ranges of retained states, each ending at a restore point (inclusive).
+        let ranges = if lower_limit >= upper_limit {
+            vec![(Slot::new(0), latest_restore_point_slot)]
+        } else if upper_limit == STATE_UPPER_LIMIT_NO_RETAIN {
+            vec![(Slot::new(0), lower_limit)]
+        } else {
+            vec![
+                (Slot::new(0), lower_limit),
+                (upper_limit, latest_restore_point_slot),
+            ]
+        };
+
+        for (start_slot, last_slot) in ranges {
+            if start_slot > last_slot {
+                continue;
+            }
+            self.verify_restore_points(start_slot, last_slot, report)?;
+            self.verify_cold_state_summaries(start_slot, last_slot, report, cold_ops)?;
+        }
+        Ok(())
+    }
+
+    /// Check that a restore point is stored for every aligned slot in `start_slot..=last_slot`.
+    fn verify_restore_points(
+        &self,
+        start_slot: Slot,
+        last_slot: Slot,
+        report: &mut VerificationReport,
+    ) -> Result<(), Error> {
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let first_index = start_slot.as_u64().div_ceil(slots_per_restore_point);
+        let last_index = last_slot.as_u64() / slots_per_restore_point;
+
+        for restore_point_index in first_index..=last_index {
+            let slot = Slot::new(restore_point_index * slots_per_restore_point);
+            report.restore_points_checked += 1;
+
+            let state_root = match self.load_restore_point_hash(restore_point_index) {
+                Ok(state_root) => state_root,
+                Err(Error::HotColdDBError(HotColdDBError::MissingRestorePointHash(_))) => {
+                    report.issues.push(Issue::MissingRestorePoint { slot });
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            if !self
+                .cold_db
+                .key_exists(DBColumn::BeaconState.into(), state_root.as_bytes())?
+            {
+                report
+                    .issues
+                    .push(Issue::MissingRestorePointState { slot, state_root });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that every frozen state root in `start_slot..end_slot` is indexed at its slot.
+    ///
+    /// The state roots of a restore point's slot are stored by the next restore point, so
+    /// `end_slot` should be a restore point.
+    fn verify_cold_state_summaries(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        report: &mut VerificationReport,
+        cold_ops: &mut Vec<KeyValueStoreOp>,
+    ) -> Result<(), Error> {
+        let mut next_slot = start_slot;
+        for (slot, state_root) in ChunkedVectorIter::<StateRoots, _, _, _>::new(
+            self,
+            start_slot.as_usize(),
+            end_slot,
+            &self.spec,
+        ) {
+            let slot = Slot::from(slot);
+            next_slot = slot + 1;
+            report.frozen_state_roots_checked += 1;
+
+            match self.load_cold_state_slot(&state_root)? {
+                Some(indexed_slot) if indexed_slot == slot => continue,
+                Some(indexed_slot) => {
+                    report.issues.push(Issue::ColdStateSummarySlotMismatch {
+                        slot,
+                        state_root,
+                        indexed_slot,
+                    });
+                }
+                None => {
+                    report
+                        .issues
+                        .push(Issue::MissingColdStateSummary { slot, state_root });
+                }
+            }
+            cold_ops.push(ColdStateSummary { slot }.as_kv_store_op(state_root));
+        }
+
+        if next_slot < end_slot {
+            report
+                .issues
+                .push(Issue::MissingFrozenStateRoots { slot: next_slot });
+        }
+        Ok(())
+    }
+}
@@ -16,6 +16,7 @@ pub mod types;
 
 use self::mixin::{RequestAccept, ResponseOptional};
 use self::types::{Error as ResponseError, *};
+use account_utils::ZeroizeString;
 use futures::Stream;
 use futures_util::StreamExt;
 use lighthouse_network::PeerId;
@@ -23,15 +24,16 @@ use pretty_reqwest_error::PrettyReqwestError;
 pub use reqwest;
 use reqwest::{
     header::{HeaderMap, HeaderValue},
-    Body, IntoUrl, RequestBuilder, Response,
+    Body, IntoUrl, Method, RequestBuilder, Response,
 };
 pub use reqwest::{StatusCode, Url};
 pub use sensitive_url::{SensitiveError, SensitiveUrl};
 use serde::{de::DeserializeOwned, Serialize};
-use ssz::Encode;
+use ssz::{Decode, Encode};
 use std::fmt;
+use std::fs;
 use std::future::Future;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use store::fork_versioned_response::ExecutionOptimisticFinalizedForkVersionedResponse;
 
@@ -159,6 +161,7 @@ pub struct BeaconNodeHttpClient {
     client: reqwest::Client,
     server: SensitiveUrl,
     timeouts: Timeouts,
+    api_key: Option<ZeroizeString>,
 }
 
 impl fmt::Display for BeaconNodeHttpClient {
@@ -179,6 +182,7 @@ impl BeaconNodeHttpClient {
             client: reqwest::Client::new(),
             server,
             timeouts,
+            api_key: None,
         }
     }
 
@@ -191,6 +195,29 @@ impl BeaconNodeHttpClient {
             client,
             server,
             timeouts,
+            api_key: None,
+        }
+    }
+
+    /// Read an API key from the specified `path`, stripping any trailing whitespace.
+    pub fn load_api_key_from_file(path: &Path) -> Result<ZeroizeString, Error> {
+        let api_key =
+            fs::read_to_string(path).map_err(|e| Error::TokenReadError(path.into(), e))?;
+        Ok(ZeroizeString::from(api_key.trim_end().to_string()))
+    }
+
+    /// Send `api_key` in an `Authorization: Bearer` header with every request.
+    pub fn with_api_key(mut self, api_key: ZeroizeString) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
+    /// Start a request to `url`, adding the API key if one is set.
+    fn request<U: IntoUrl>(&self, method: Method, url: U) -> RequestBuilder {
+        let builder = self.client.request(method, url);
+        match &self.api_key {
+            Some(api_key) => builder.bearer_auth(api_key.as_str()),
+            None => builder,
         }
     }
 
@@ -218,7 +245,7 @@ impl BeaconNodeHttpClient {
         url: U,
         builder: impl FnOnce(RequestBuilder) -> RequestBuilder,
     ) -> Result<Response, Error> {
-        let response = builder(self.client.get(url)).send().await?;
+        let response = builder(self.request(Method::GET, url)).send().await?;
         ok_or_error(response).await
     }
 
@@ -337,6 +364,25 @@ impl BeaconNodeHttpClient {
         }
     }
 
+    /// Perform a HTTP POST request using an 'accept' header, returning `None` on a 404 error.
+    async fn post_bytes_opt_accept_header<T: Serialize, U: IntoUrl>(
+        &self,
+        url: U,
+        body: &T,
+        accept_header: Accept,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let response = self
+            .request(Method::POST, url)
+            .accept(accept_header)
+            .json(body)
+            .send()
+            .await?;
+        match ok_or_error(response).await.optional()? {
+            Some(resp) => Ok(Some(resp.bytes().await?.into_iter().collect::<Vec<_>>())),
+            None => Ok(None),
+        }
+    }
+
     /// Perform a HTTP POST request with a custom timeout.
     async fn post_with_timeout<T: Serialize, U: IntoUrl>(
         &self,
@@ -382,7 +428,7 @@ impl BeaconNodeHttpClient {
         body: &T,
         timeout: Option<Duration>,
     ) -> Result<Response, Error> {
-        let mut builder = self.client.post(url);
+        let mut builder = self.request(Method::POST, url);
         if let Some(timeout) = timeout {
             builder = builder.timeout(timeout);
         }
@@ -399,7 +445,7 @@ impl BeaconNodeHttpClient {
         timeout: Option<Duration>,
         fork: ForkName,
     ) -> Result<Response, Error> {
-        let mut builder = self.client.post(url);
+        let mut builder = self.request(Method::POST, url);
         if let Some(timeout) = timeout {
             builder = builder.timeout(timeout);
         }
@@ -417,7 +463,7 @@ impl BeaconNodeHttpClient {
         url: U,
         body: &T,
     ) -> Result<Response, Error> {
-        let builder = self.client.post(url);
+        let builder = self.request(Method::POST, url);
         let mut headers = HeaderMap::new();
 
         headers.insert(
@@ -436,7 +482,7 @@ impl BeaconNodeHttpClient {
         timeout: Option<Duration>,
         fork: ForkName,
     ) -> Result<Response, Error> {
-        let mut builder = self.client.post(url);
+        let mut builder = self.request(Method::POST, url);
         if let Some(timeout) = timeout {
             builder = builder.timeout(timeout);
         }
@@ -599,6 +645,79 @@ impl BeaconNodeHttpClient {
         self.post_with_opt_response(path, &request).await
     }
 
+    /// `POST beacon/states/{state_id}/validator_balances` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn post_beacon_states_validator_balances_ssz(
+        &self,
+        state_id: StateId,
+        ids: Vec<ValidatorId>,
+    ) -> Result<Option<Vec<ValidatorBalanceData>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("validator_balances");
+
+        let request = ValidatorBalancesRequestBody { ids };
+
+        self.post_bytes_opt_accept_header(path, &request, Accept::Ssz)
+            .await?
+            .map(|bytes| Vec::from_ssz_bytes(&bytes).map_err(Error::InvalidSsz))
+            .transpose()
+    }
+
+    /// `POST beacon/states/{state_id}/validator_identities`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn post_beacon_states_validator_identities(
+        &self,
+        state_id: StateId,
+        ids: Vec<ValidatorId>,
+    ) -> Result<Option<ExecutionOptimisticFinalizedResponse<Vec<ValidatorIdentityData>>>, Error>
+    {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("validator_identities");
+
+        let request = ValidatorIdentitiesRequestBody { ids };
+
+        self.post_with_opt_response(path, &request).await
+    }
+
+    /// `POST beacon/states/{state_id}/validator_identities` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn post_beacon_states_validator_identities_ssz(
+        &self,
+        state_id: StateId,
+        ids: Vec<ValidatorId>,
+    ) -> Result<Option<Vec<ValidatorIdentityData>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("validator_identities");
+
+        let request = ValidatorIdentitiesRequestBody { ids };
+
+        self.post_bytes_opt_accept_header(path, &request, Accept::Ssz)
+            .await?
+            .map(|bytes| Vec::from_ssz_bytes(&bytes).map_err(Error::InvalidSsz))
+            .transpose()
+    }
+
     /// `GET beacon/states/{state_id}/validators?id,status`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -661,16 +780,40 @@ impl BeaconNodeHttpClient {
         self.post_with_opt_response(path, &request).await
     }
 
-    /// `GET beacon/states/{state_id}/committees?slot,index,epoch`
+    /// `POST beacon/states/{state_id}/validators` as SSZ
     ///
     /// Returns `Ok(None)` on a 404 error.
-    pub async fn get_beacon_states_committees(
+    pub async fn post_beacon_states_validators_ssz(
+        &self,
+        state_id: StateId,
+        ids: Option<Vec<ValidatorId>>,
+        statuses: Option<Vec<ValidatorStatus>>,
+    ) -> Result<Option<Vec<ValidatorData>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("validators");
+
+        let request = ValidatorsRequestBody { ids, statuses };
+
+        self.post_bytes_opt_accept_header(path, &request, Accept::Ssz)
+            .await?
+            .map(|bytes| Vec::from_ssz_bytes(&bytes).map_err(Error::InvalidSsz))
+            .transpose()
+    }
+
+    /// Path for `v1/beacon/states/{state_id}/committees?slot,index,epoch`
+    fn get_beacon_states_committees_path(
         &self,
         state_id: StateId,
         slot: Option<Slot>,
         index: Option<u64>,
         epoch: Option<Epoch>,
-    ) -> Result<Option<ExecutionOptimisticFinalizedResponse<Vec<CommitteeData>>>, Error> {
+    ) -> Result<Url, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -695,9 +838,42 @@ impl BeaconNodeHttpClient {
                 .append_pair("epoch", &epoch.to_string());
         }
 
+        Ok(path)
+    }
+
+    /// `GET beacon/states/{state_id}/committees?slot,index,epoch`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_committees(
+        &self,
+        state_id: StateId,
+        slot: Option<Slot>,
+        index: Option<u64>,
+        epoch: Option<Epoch>,
+    ) -> Result<Option<ExecutionOptimisticFinalizedResponse<Vec<CommitteeData>>>, Error> {
+        let path = self.get_beacon_states_committees_path(state_id, slot, index, epoch)?;
+
         self.get_opt(path).await
     }
 
+    /// `GET beacon/states/{state_id}/committees?slot,index,epoch` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_committees_ssz(
+        &self,
+        state_id: StateId,
+        slot: Option<Slot>,
+        index: Option<u64>,
+        epoch: Option<Epoch>,
+    ) -> Result<Option<Vec<CommitteeData>>, Error> {
+        let path = self.get_beacon_states_committees_path(state_id, slot, index, epoch)?;
+
+        self.get_bytes_opt_accept_header(path, Accept::Ssz, self.timeouts.get_debug_beacon_states)
+            .await?
+            .map(|bytes| Vec::from_ssz_bytes(&bytes).map_err(Error::InvalidSsz))
+            .transpose()
+    }
+
     /// `GET beacon/states/{state_id}/sync_committees?epoch`
     pub async fn get_beacon_states_sync_committees(
         &self,
@@ -744,6 +920,100 @@ impl BeaconNodeHttpClient {
         self.get_opt(path).await
     }
 
+    /// Path for `v1/beacon/states/{state_id}/{queue}`, where `queue` is one of the pending
+    /// operation queues added in Electra.
+    fn get_beacon_states_pending_path(&self, state_id: StateId, queue: &str) -> Result<Url, Error> {
+        let mut path = self.eth_path(V1)?;
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push(queue);
+        Ok(path)
+    }
+
+    /// `GET beacon/states/{state_id}/pending_deposits`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_pending_deposits(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<ExecutionOptimisticFinalizedResponse<Vec<PendingBalanceDeposit>>>, Error>
+    {
+        let path = self.get_beacon_states_pending_path(state_id, "pending_deposits")?;
+        self.get_opt(path).await
+    }
+
+    /// `GET beacon/states/{state_id}/pending_deposits` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_pending_deposits_ssz(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<Vec<PendingBalanceDeposit>>, Error> {
+        let path = self.get_beacon_states_pending_path(state_id, "pending_deposits")?;
+
+        self.get_bytes_opt_accept_header(path, Accept::Ssz, self.timeouts.get_debug_beacon_states)
+            .await?
+            .map(|bytes| Vec::from_ssz_bytes(&bytes).map_err(Error::InvalidSsz))
+            .transpose()
+    }
+
+    /// `GET beacon/states/{state_id}/pending_partial_withdrawals`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_pending_partial_withdrawals(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<ExecutionOptimisticFinalizedResponse<Vec<PendingPartialWithdrawal>>>, Error>
+    {
+        let path = self.get_beacon_states_pending_path(state_id, "pending_partial_withdrawals")?;
+        self.get_opt(path).await
+    }
+
+    /// `GET beacon/states/{state_id}/pending_partial_withdrawals` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_pending_partial_withdrawals_ssz(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<Vec<PendingPartialWithdrawal>>, Error> {
+        let path = self.get_beacon_states_pending_path(state_id, "pending_partial_withdrawals")?;
+
+        self.get_bytes_opt_accept_header(path, Accept::Ssz, self.timeouts.get_debug_beacon_states)
+            .await?
+            .map(|bytes| Vec::from_ssz_bytes(&bytes).map_err(Error::InvalidSsz))
+            .transpose()
+    }
+
+    /// `GET beacon/states/{state_id}/pending_consolidations`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_pending_consolidations(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<ExecutionOptimisticFinalizedResponse<Vec<PendingConsolidation>>>, Error>
+    {
+        let path = self.get_beacon_states_pending_path(state_id, "pending_consolidations")?;
+        self.get_opt(path).await
+    }
+
+    /// `GET beacon/states/{state_id}/pending_consolidations` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_pending_consolidations_ssz(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<Vec<PendingConsolidation>>, Error> {
+        let path = self.get_beacon_states_pending_path(state_id, "pending_consolidations")?;
+
+        self.get_bytes_opt_accept_header(path, Accept::Ssz, self.timeouts.get_debug_beacon_states)
+            .await?
+            .map(|bytes| Vec::from_ssz_bytes(&bytes).map_err(Error::InvalidSsz))
+            .transpose()
+    }
+
     /// `GET beacon/states/{state_id}/validators/{validator_id}`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -1531,6 +1801,21 @@ impl BeaconNodeHttpClient {
         Ok(())
     }
 
+    /// `GET beacon/pool/bls_to_execution_changes`
+    pub async fn get_beacon_pool_bls_to_execution_changes(
+        &self,
+    ) -> Result<GenericResponse<Vec<SignedBlsToExecutionChange>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("pool")
+            .push("bls_to_execution_changes");
+
+        self.get(path).await
+    }
+
     /// `GET beacon/deposit_snapshot`
     pub async fn get_deposit_snapshot(&self) -> Result<Option<types::DepositTreeSnapshot>, Error> {
         let mut path = self.eth_path(V1)?;
@@ -1561,6 +1846,29 @@ impl BeaconNodeHttpClient {
         Ok(())
     }
 
+    /// `POST beacon/rewards/sync_committee/{block_id}`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn post_beacon_rewards_sync_committee_for_block(
+        &self,
+        block_id: BlockId,
+        validators: &[ValidatorId],
+    ) -> Result<
+        Option<ExecutionOptimisticFinalizedResponse<Vec<lighthouse::SyncCommitteeReward>>>,
+        Error,
+    > {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("rewards")
+            .push("sync_committee")
+            .push(&block_id.to_string());
+
+        self.post_with_opt_response(path, &validators).await
+    }
+
     /// `GET beacon/rewards/blocks`
     pub async fn get_beacon_rewards_blocks(&self, epoch: Epoch) -> Result<(), Error> {
         let mut path = self.eth_path(V1)?;
@@ -1577,6 +1885,26 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET beacon/rewards/blocks/{block_id}`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_rewards_block(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<ExecutionOptimisticFinalizedResponse<lighthouse::StandardBlockReward>>, Error>
+    {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("rewards")
+            .push("blocks")
+            .push(&block_id.to_string());
+
+        self.get_opt(path).await
+    }
+
     /// `POST beacon/rewards/attestations`
     pub async fn post_beacon_rewards_attestations(
         &self,
@@ -1595,6 +1923,24 @@ impl BeaconNodeHttpClient {
         Ok(())
     }
 
+    /// `POST beacon/rewards/attestations/{epoch}`
+    pub async fn post_beacon_rewards_attestations_for_epoch(
+        &self,
+        epoch: Epoch,
+        validators: &[ValidatorId],
+    ) -> Result<ExecutionOptimisticResponse<lighthouse::StandardAttestationRewards>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("rewards")
+            .push("attestations")
+            .push(&epoch.to_string());
+
+        self.post_with_response(path, &validators).await
+    }
+
     // GET builder/states/{state_id}/expected_withdrawals
     pub async fn get_expected_withdrawals(
         &self,
@@ -1753,7 +2099,7 @@ impl BeaconNodeHttpClient {
             .push("node")
             .push("health");
 
-        let status = self.client.get(path).send().await?.status();
+        let status = self.request(Method::GET, path).send().await?.status();
         if status == StatusCode::OK || status == StatusCode::PARTIAL_CONTENT {
             Ok(status)
         } else {
@@ -2562,8 +2908,7 @@ impl BeaconNodeHttpClient {
         path.query_pairs_mut().append_pair("topics", &topic_string);
 
         Ok(self
-            .client
-            .get(path)
+            .request(Method::GET, path)
             .send()
             .await?
             .bytes_stream()
@@ -2573,6 +2918,44 @@ impl BeaconNodeHttpClient {
             }))
     }
 
+    /// `GET events?topics` with a `Last-Event-ID` header.
+    ///
+    /// Events for topics which are written to the event log of a Lighthouse beacon node are
+    /// returned with their ID. If `last_event_id` is provided, the events after it are replayed
+    /// before any new events.
+    pub async fn get_events_from<E: EthSpec>(
+        &self,
+        topic: &[EventTopic],
+        last_event_id: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<(Option<u64>, EventKind<E>), Error>>, Error> {
+        let mut path = self.eth_path(V1)?;
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("events");
+
+        let topic_string = topic
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        path.query_pairs_mut().append_pair("topics", &topic_string);
+
+        let response = self
+            .get_response(path, |b| match last_event_id {
+                Some(id) => b.header("Last-Event-ID", id.to_string()),
+                None => b,
+            })
+            .await?;
+
+        Ok(response.bytes_stream().map(|next| match next {
+            Ok(bytes) => {
+                let id = sse_event_id(bytes.as_ref());
+                EventKind::from_sse_bytes(bytes.as_ref()).map(|event| (id, event))
+            }
+            Err(e) => Err(Error::HttpClient(e.into())),
+        }))
+    }
+
     /// `POST validator/duties/sync/{epoch}`
     pub async fn post_validator_duties_sync(
         &self,
@@ -2597,6 +2980,17 @@ impl BeaconNodeHttpClient {
     }
 }
 
+/// Returns the value of the `id` field of a server-sent event, if any.
+fn sse_event_id(message: &[u8]) -> Option<u64> {
+    std::str::from_utf8(message)
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("id:"))?
+        .trim()
+        .parse()
+        .ok()
+}
+
 /// Returns `Ok(response)` if the response is a `200 OK` response. Otherwise, creates an
 /// appropriate error message.
 pub async fn ok_or_error(response: Response) -> Result<Response, Error> {
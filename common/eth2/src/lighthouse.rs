@@ -3,31 +3,58 @@
 mod attestation_performance;
 pub mod attestation_rewards;
 mod block_packing_efficiency;
+mod block_production_timing;
 mod block_rewards;
+mod block_stream;
+mod event_log;
+mod fork_choice_history;
+mod propagation;
+mod slasher;
 mod standard_block_rewards;
 mod sync_committee_rewards;
 
 use crate::{
     types::{
-        DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock, GenericResponse, ValidatorId,
+        ChainSpec, DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock, GenericResponse,
+        IndexedAttestation, SignedBeaconBlockHeader, ValidatorId,
     },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot,
 };
+use futures::{Stream, StreamExt};
 use proto_array::core::ProtoArray;
 use serde::{Deserialize, Serialize};
 use ssz::four_byte_option_impl;
 use ssz_derive::{Decode, Encode};
-use store::{AnchorInfo, BlobInfo, Split, StoreConfig};
+use std::path::PathBuf;
+use store::{snapshot::SnapshotManifest, AnchorInfo, BlobInfo, Split, StoreConfig};
 
 pub use attestation_performance::{
     AttestationPerformance, AttestationPerformanceQuery, AttestationPerformanceStatistics,
 };
 pub use attestation_rewards::StandardAttestationRewards;
 pub use block_packing_efficiency::{
-    BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
+    BlockPackingEfficiency, BlockPackingEfficiencyQuery, PackingRewards, ProposerInfo,
+    UniqueAttestation,
 };
+pub use block_production_timing::{BlockProductionTrace, BuilderCall, BuilderCallTiming};
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
+pub use block_stream::{
+    encode_frame, BlockStreamDecoder, BlockStreamItem, BlockStreamQuery, FRAME_LENGTH_PREFIX_BYTES,
+};
+pub use event_log::{EventLogQuery, LoggedEvent};
+pub use fork_choice_history::{
+    CheckpointUpdate, ForkChoiceHistoryEntry, ForkChoiceHistoryQuery, ForkChoiceUpdate, HeadChange,
+    HeadWeights, Reorg,
+};
 pub use lighthouse_network::{types::SyncState, PeerInfo};
+pub use propagation::{
+    AggregatePropagation, BlockPropagation, PeerArrival, PropagationAnalysis,
+    PropagationPercentiles, PropagationQuery,
+};
+pub use slasher::{
+    SlasherDatabaseStats, SlasherEpochRangeQuery, SlasherSlashings, SlasherSlotRangeQuery,
+    SlasherTableStats, SlasherTargetBounds,
+};
 pub use standard_block_rewards::StandardBlockReward;
 pub use sync_committee_rewards::SyncCommitteeReward;
 
@@ -365,6 +392,116 @@ pub struct DatabaseInfo {
     pub blob_info: BlobInfo,
 }
 
+/// A snapshot started by `POST lighthouse/database/snapshot`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseSnapshotJob {
+    pub job_id: u64,
+    /// The directory containing the snapshot, on the beacon node's filesystem.
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub status: DatabaseSnapshotStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DatabaseSnapshotStatus {
+    Running,
+    Complete { manifest: SnapshotManifest },
+    Failed { error: String },
+}
+
+/// The request body of `POST lighthouse/validators/sightings`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorSightingsRequest {
+    /// Only sightings at or after this slot are returned.
+    pub from_slot: Slot,
+    /// The validators to watch and report on.
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub indices: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorSightingKind {
+    Attestation,
+    Aggregate,
+    Block,
+}
+
+/// A signed message from a watched validator which was observed on gossip.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ValidatorSighting {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    pub slot: Slot,
+    pub kind: ValidatorSightingKind,
+    /// The root of the `AttestationData` for attestations and aggregates, or the block root for
+    /// blocks.
+    pub root: Hash256,
+}
+
+/// The activity of a monitored validator during a single epoch, as recorded by the validator
+/// monitor of the beacon node.
+///
+/// Delays are measured in milliseconds from the start of the slot in which the message should have
+/// been produced.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct ValidatorMonitorEpochSummary {
+    pub epoch: Epoch,
+    pub validator_index: u64,
+    /// The number of attestations seen.
+    pub attestations: u64,
+    #[ssz(with = "four_byte_option_u64")]
+    pub attestation_min_delay: Option<u64>,
+    /// The number of times an attestation was seen in an aggregate.
+    pub attestation_aggregate_inclusions: u64,
+    /// The number of times an attestation was seen in a block.
+    pub attestation_block_inclusions: u64,
+    #[ssz(with = "four_byte_option_u64")]
+    pub attestation_min_block_inclusion_distance: Option<u64>,
+    /// The number of blocks seen.
+    pub blocks: u64,
+    #[ssz(with = "four_byte_option_u64")]
+    pub block_min_delay: Option<u64>,
+    /// The number of aggregates seen.
+    pub aggregates: u64,
+    #[ssz(with = "four_byte_option_u64")]
+    pub aggregate_min_delay: Option<u64>,
+    /// The number of sync committee messages seen.
+    pub sync_committee_messages: u64,
+    #[ssz(with = "four_byte_option_u64")]
+    pub sync_committee_message_min_delay: Option<u64>,
+    /// The number of times a sync committee signature was included in a block.
+    pub sync_signature_block_inclusions: u64,
+    /// The number of times a sync committee signature was included in a sync contribution.
+    pub sync_signature_contribution_inclusions: u64,
+    /// The number of sync contributions seen.
+    pub sync_contributions: u64,
+    #[ssz(with = "four_byte_option_u64")]
+    pub sync_contribution_min_delay: Option<u64>,
+    pub exits: u64,
+    pub proposer_slashings: u64,
+    pub attester_slashings: u64,
+    #[ssz(with = "four_byte_option_u64")]
+    pub total_balance: Option<u64>,
+    /// True if an attestation was included on-chain with a matching source.
+    pub attestation_source_hit: bool,
+    /// True if an attestation was included on-chain with a matching target.
+    pub attestation_target_hit: bool,
+    /// True if an attestation was included on-chain with a matching head.
+    pub attestation_head_hit: bool,
+    /// The inclusion distance of the attestation included on-chain.
+    #[ssz(with = "four_byte_option_u64")]
+    pub inclusion_distance: Option<u64>,
+}
+
+/// Query parameters for the `/lighthouse/validator_monitor/{index}` endpoint.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorMonitorHistoryQuery {
+    pub start_epoch: Epoch,
+    pub end_epoch: Epoch,
+}
+
 impl BeaconNodeHttpClient {
     /// `GET lighthouse/health`
     pub async fn get_lighthouse_health(&self) -> Result<GenericResponse<Health>, Error> {
@@ -410,6 +547,84 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/fork_choice/history?start_slot,end_slot`
+    pub async fn get_lighthouse_fork_choice_history(
+        &self,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> Result<GenericResponse<Vec<ForkChoiceHistoryEntry>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("fork_choice")
+            .push("history");
+
+        if let Some(start_slot) = start_slot {
+            path.query_pairs_mut()
+                .append_pair("start_slot", &start_slot.to_string());
+        }
+        if let Some(end_slot) = end_slot {
+            path.query_pairs_mut()
+                .append_pair("end_slot", &end_slot.to_string());
+        }
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/blocks/stream?start_slot,end_slot,blobs`
+    ///
+    /// Returns the canonical blocks between `start_slot` and `end_slot` (inclusive) as they are
+    /// received, with their blob sidecars if `blobs` is `true`. The stream ends with an error if
+    /// the response is truncated.
+    pub async fn get_lighthouse_blocks_stream<E: EthSpec>(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        blobs: bool,
+        spec: &ChainSpec,
+    ) -> Result<impl Stream<Item = Result<BlockStreamItem<E>, Error>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("blocks")
+            .push("stream");
+
+        path.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string())
+            .append_pair("blobs", &blobs.to_string());
+
+        let response = self.get_response(path, |b| b).await?;
+
+        Ok(futures::stream::try_unfold(
+            (
+                response.bytes_stream().boxed(),
+                BlockStreamDecoder::new(blobs, spec.clone()),
+            ),
+            |(mut bytes_stream, mut decoder)| async move {
+                loop {
+                    if let Some(item) = decoder.decode_next().map_err(Error::InvalidSsz)? {
+                        return Ok(Some((item, (bytes_stream, decoder))));
+                    }
+
+                    match bytes_stream.next().await {
+                        Some(bytes) => decoder.extend(&bytes?),
+                        None if decoder.is_empty() => return Ok(None),
+                        None => {
+                            return Err(Error::InvalidSsz(ssz::DecodeError::BytesInvalid(
+                                "block stream ended part way through a block".to_string(),
+                            )))
+                        }
+                    }
+                }
+            },
+        ))
+    }
+
     /// `GET lighthouse/validator_inclusion/{epoch}/global`
     pub async fn get_lighthouse_validator_inclusion_global(
         &self,
@@ -528,6 +743,160 @@ impl BeaconNodeHttpClient {
         self.post_with_response(path, &()).await
     }
 
+    /// `POST lighthouse/database/snapshot`
+    ///
+    /// Starts writing a snapshot and returns immediately. Poll
+    /// `get_lighthouse_database_snapshot` with the job ID to find out when it is complete.
+    pub async fn post_lighthouse_database_snapshot(&self) -> Result<DatabaseSnapshotJob, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("database")
+            .push("snapshot");
+
+        self.post_with_response(path, &()).await
+    }
+
+    /// `GET lighthouse/database/snapshot/{job_id}`
+    pub async fn get_lighthouse_database_snapshot(
+        &self,
+        job_id: u64,
+    ) -> Result<DatabaseSnapshotJob, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("database")
+            .push("snapshot")
+            .push(&job_id.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/slasher/attestations/{validator_index}?start_epoch,end_epoch`
+    ///
+    /// Returns the attestations recorded by the slasher for the validator, by target epoch.
+    pub async fn get_lighthouse_slasher_attestations<E: EthSpec>(
+        &self,
+        validator_index: u64,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<GenericResponse<Vec<IndexedAttestation<E>>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("slasher")
+            .push("attestations")
+            .push(&validator_index.to_string());
+
+        path.query_pairs_mut()
+            .append_pair("start_epoch", &start_epoch.to_string())
+            .append_pair("end_epoch", &end_epoch.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/slasher/proposals/{validator_index}?start_slot,end_slot`
+    ///
+    /// Returns the block proposals recorded by the slasher for the validator.
+    pub async fn get_lighthouse_slasher_proposals(
+        &self,
+        validator_index: u64,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<GenericResponse<Vec<SignedBeaconBlockHeader>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("slasher")
+            .push("proposals")
+            .push(&validator_index.to_string());
+
+        path.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/slasher/targets/{validator_index}?start_epoch,end_epoch`
+    ///
+    /// Returns the validator's min and max targets for each source epoch.
+    pub async fn get_lighthouse_slasher_targets(
+        &self,
+        validator_index: u64,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<GenericResponse<Vec<SlasherTargetBounds>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("slasher")
+            .push("targets")
+            .push(&validator_index.to_string());
+
+        path.query_pairs_mut()
+            .append_pair("start_epoch", &start_epoch.to_string())
+            .append_pair("end_epoch", &end_epoch.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/slasher/slashings`
+    pub async fn get_lighthouse_slasher_slashings<E: EthSpec>(
+        &self,
+    ) -> Result<GenericResponse<SlasherSlashings<E>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("slasher")
+            .push("slashings");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/slasher/database`
+    pub async fn get_lighthouse_slasher_database(
+        &self,
+    ) -> Result<GenericResponse<SlasherDatabaseStats>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("slasher")
+            .push("database");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/validators/sightings`
+    pub async fn post_lighthouse_validators_sightings(
+        &self,
+        request: &ValidatorSightingsRequest,
+    ) -> Result<GenericResponse<Vec<ValidatorSighting>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push("sightings");
+
+        self.post_with_response(path, request).await
+    }
+
     ///
     /// Analysis endpoints.
     ///
@@ -574,6 +943,90 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET` lighthouse/analysis/block_packing?start_epoch,end_epoch,include_rewards=true
+    pub async fn get_lighthouse_analysis_block_packing_with_rewards(
+        &self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<BlockPackingEfficiency>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("block_packing_efficiency");
+
+        path.query_pairs_mut()
+            .append_pair("start_epoch", &start_epoch.to_string())
+            .append_pair("end_epoch", &end_epoch.to_string())
+            .append_pair("include_rewards", "true");
+
+        self.get(path).await
+    }
+
+    /// `GET` lighthouse/analysis/block_production/{slot}
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_analysis_block_production(
+        &self,
+        slot: Slot,
+    ) -> Result<Option<BlockProductionTrace>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("block_production")
+            .push(&slot.to_string());
+
+        self.get_opt(path).await
+    }
+
+    /// `GET` lighthouse/analysis/propagation?start_slot,end_slot
+    pub async fn get_lighthouse_analysis_propagation(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<PropagationAnalysis, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("propagation");
+
+        path.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/validator_monitor/{index}?start_epoch,end_epoch`
+    pub async fn get_lighthouse_validator_monitor(
+        &self,
+        index: u64,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<GenericResponse<Vec<ValidatorMonitorEpochSummary>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator_monitor")
+            .push(&index.to_string());
+
+        path.query_pairs_mut()
+            .append_pair("start_epoch", &start_epoch.to_string())
+            .append_pair("end_epoch", &end_epoch.to_string());
+
+        self.get(path).await
+    }
+
     /// `GET` lighthouse/analysis/attestation_performance/{index}?start_epoch,end_epoch
     pub async fn get_lighthouse_analysis_attestation_performance(
         &self,
@@ -25,10 +25,41 @@ pub struct BlockPackingEfficiency {
     pub available_attestations: usize,
     pub included_attestations: usize,
     pub prior_skip_slots: u64,
+    /// The number of committees aggregated into each of the block's attestations, which is
+    /// always 1 prior to Electra.
+    #[serde(default)]
+    pub committees_per_attestation: Vec<usize>,
+    /// The number of additional attestations which could have been included in the block.
+    #[serde(default)]
+    pub unused_attestation_slots: usize,
+    /// Only present if requested with `include_rewards`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rewards: Option<PackingRewards>,
+}
+
+/// Proposer rewards in Gwei for the attestations of a block, and for alternative packings of them.
+///
+/// The candidates for the alternative packings are the aggregates included on chain, in this
+/// block or a later one, which could have been included in this block.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PackingRewards {
+    /// The reward for the attestations included in the block.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub included: u64,
+    /// The reward for the attestations chosen by Lighthouse's max-cover packing.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub max_cover: u64,
+    /// An upper bound on the reward of any packing, which assumes that the aggregates for the same
+    /// attestation data could have been combined.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub upper_bound: u64,
+    pub candidate_aggregates: usize,
 }
 
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct BlockPackingEfficiencyQuery {
     pub start_epoch: Epoch,
     pub end_epoch: Epoch,
+    #[serde(default)]
+    pub include_rewards: bool,
 }
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use types::{Hash256, Slot};
+
+/// A breakdown of the time taken to produce, sign and publish a block via this node.
+///
+/// All durations are in milliseconds. A duration is `None` if the phase was not performed, or not
+/// observed by this node (e.g. a block produced by another beacon node but published via this
+/// one).
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BlockProductionTrace {
+    pub slot: Slot,
+    pub proposer_index: Option<u64>,
+    /// The root of the signed block, once it has been published.
+    pub block_root: Option<Hash256>,
+    /// Time taken to load the parent state from the cache or database.
+    pub state_load_ms: Option<u64>,
+    /// Time taken to advance the parent state to the slot of the block.
+    pub state_advance_ms: Option<u64>,
+    /// Time taken to pack attestations and other operations from the op pool.
+    pub op_pool_packing_ms: Option<u64>,
+    /// Time taken by the local execution engine to return a payload.
+    pub local_payload_ms: Option<u64>,
+    /// Requests made to the builder, in the order in which they completed.
+    pub builder_calls: Vec<BuilderCallTiming>,
+    /// Time taken to bundle the blobs, KZG commitments and proofs of the block.
+    pub blob_bundling_ms: Option<u64>,
+    /// Time taken to apply the block to the state and compute the state root.
+    pub block_completion_ms: Option<u64>,
+    /// Total time between receiving the block production request and returning the block.
+    pub production_ms: Option<u64>,
+    /// Time between returning the unsigned block and receiving the signed block.
+    pub signing_round_trip_ms: Option<u64>,
+    /// Time between receiving the signed block and publishing it to the network.
+    pub publication_ms: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BuilderCallTiming {
+    pub call: BuilderCall,
+    pub duration_ms: u64,
+    pub success: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuilderCall {
+    /// A request for a header (i.e. a bid) for the block.
+    GetHeader,
+    /// The submission of the signed blinded block, which reveals the payload.
+    SubmitBlindedBlock,
+}
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use ssz::{Decode, DecodeError};
+use types::{BlobSidecarList, ChainSpec, EthSpec, SignedBeaconBlock, Slot};
+
+/// The number of bytes used to encode the length of each frame.
+pub const FRAME_LENGTH_PREFIX_BYTES: usize = 4;
+
+/// Query parameters for the `/lighthouse/blocks/stream` endpoint.
+///
+/// Both bounds are inclusive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockStreamQuery {
+    pub start_slot: Slot,
+    pub end_slot: Slot,
+    /// Include the blob sidecars of each block.
+    #[serde(default)]
+    pub blobs: bool,
+}
+
+/// A canonical block returned by the `/lighthouse/blocks/stream` endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockStreamItem<E: EthSpec> {
+    pub block: SignedBeaconBlock<E>,
+    /// The blob sidecars of the block, if they were requested.
+    pub blobs: Option<BlobSidecarList<E>>,
+}
+
+/// Append `bytes` to `buf` as a single frame, prefixed by its length as a little-endian `u32`.
+pub fn encode_frame(bytes: &[u8], buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    buf.extend_from_slice(bytes);
+}
+
+/// Decodes the `BlockStreamItem`s from the body of a `/lighthouse/blocks/stream` response, which
+/// may arrive in chunks of any size.
+///
+/// Each block is sent as a frame containing its SSZ encoding. If blobs were requested, each block
+/// is followed by a frame containing the SSZ encoding of its blob sidecar list, which is empty if
+/// the block has no blobs.
+pub struct BlockStreamDecoder<E: EthSpec> {
+    buffer: Vec<u8>,
+    blobs: bool,
+    pending_block: Option<SignedBeaconBlock<E>>,
+    spec: ChainSpec,
+}
+
+impl<E: EthSpec> BlockStreamDecoder<E> {
+    pub fn new(blobs: bool, spec: ChainSpec) -> Self {
+        Self {
+            buffer: vec![],
+            blobs,
+            pending_block: None,
+            spec,
+        }
+    }
+
+    /// Add the next chunk of the response body.
+    pub fn extend(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Returns `true` if there are no partially received items.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty() && self.pending_block.is_none()
+    }
+
+    /// Decode the next item, returning `None` if it has not been fully received yet.
+    pub fn decode_next(&mut self) -> Result<Option<BlockStreamItem<E>>, DecodeError> {
+        loop {
+            let Some(frame) = self.next_frame() else {
+                return Ok(None);
+            };
+
+            match self.pending_block.take() {
+                None if self.blobs => {
+                    self.pending_block =
+                        Some(SignedBeaconBlock::from_ssz_bytes(&frame, &self.spec)?);
+                }
+                None => {
+                    return Ok(Some(BlockStreamItem {
+                        block: SignedBeaconBlock::from_ssz_bytes(&frame, &self.spec)?,
+                        blobs: None,
+                    }))
+                }
+                Some(block) => {
+                    return Ok(Some(BlockStreamItem {
+                        block,
+                        blobs: Some(BlobSidecarList::from_ssz_bytes(&frame)?),
+                    }))
+                }
+            }
+        }
+    }
+
+    fn next_frame(&mut self) -> Option<Vec<u8>> {
+        let prefix = self.buffer.get(..FRAME_LENGTH_PREFIX_BYTES)?;
+        let mut len_bytes = [0; FRAME_LENGTH_PREFIX_BYTES];
+        len_bytes.copy_from_slice(prefix);
+        let end = FRAME_LENGTH_PREFIX_BYTES + u32::from_le_bytes(len_bytes) as usize;
+
+        if self.buffer.len() < end {
+            return None;
+        }
+        let frame = self.buffer[FRAME_LENGTH_PREFIX_BYTES..end].to_vec();
+        self.buffer.drain(..end);
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ssz::Encode;
+    use types::{BeaconBlock, MainnetEthSpec, Signature};
+
+    type E = MainnetEthSpec;
+
+    #[test]
+    fn decode_chunked_frames() {
+        let spec = E::default_spec();
+        let blocks = (0..3)
+            .map(|slot| {
+                let mut block = BeaconBlock::<E>::empty(&spec);
+                *block.slot_mut() = Slot::new(slot);
+                SignedBeaconBlock::from_block(block, Signature::empty())
+            })
+            .collect::<Vec<_>>();
+
+        let mut body = vec![];
+        for block in &blocks {
+            encode_frame(&block.as_ssz_bytes(), &mut body);
+            encode_frame(&BlobSidecarList::<E>::default().as_ssz_bytes(), &mut body);
+        }
+
+        let mut decoder = BlockStreamDecoder::<E>::new(true, spec);
+        let mut decoded = vec![];
+        for chunk in body.chunks(7) {
+            decoder.extend(chunk);
+            while let Some(item) = decoder.decode_next().unwrap() {
+                decoded.push(item);
+            }
+        }
+
+        assert!(decoder.is_empty());
+        assert_eq!(
+            decoded,
+            blocks
+                .into_iter()
+                .map(|block| BlockStreamItem {
+                    block,
+                    blobs: Some(BlobSidecarList::default()),
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+}
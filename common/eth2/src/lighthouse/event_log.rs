@@ -0,0 +1,33 @@
+use crate::types::{query_vec, EventKind, EventTopic};
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use types::EthSpec;
+
+/// An event which has been written to the event log of a beacon node.
+///
+/// Event IDs increase monotonically, so a subscriber may resume from the last event it received.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedEvent {
+    pub id: u64,
+    /// The topic of the event, e.g. `block`.
+    pub topic: String,
+    /// The event, as it would be sent on the `/eth/v1/events` endpoint.
+    pub data: serde_json::Value,
+}
+
+impl LoggedEvent {
+    /// Parse the event into an `EventKind`.
+    pub fn event_kind<E: EthSpec>(&self) -> Result<EventKind<E>, Error> {
+        EventKind::from_sse_bytes(format!("event:{}\ndata:{}", self.topic, self.data).as_bytes())
+    }
+}
+
+/// Query parameters for the `/lighthouse/events/ws` endpoint.
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventLogQuery {
+    #[serde(deserialize_with = "query_vec")]
+    pub topics: Vec<EventTopic>,
+    /// Replay the events after this ID before sending new events.
+    pub last_event_id: Option<u64>,
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use types::{Checkpoint, Hash256, Slot};
+
+/// Query parameters for the `/lighthouse/fork_choice/history` endpoint.
+///
+/// Both bounds are inclusive and refer to the slot at which fork choice was run.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForkChoiceHistoryQuery {
+    pub start_slot: Option<Slot>,
+    pub end_slot: Option<Slot>,
+}
+
+/// A change to the head or FFG checkpoints of fork choice.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ForkChoiceHistoryEntry {
+    /// The slot at which fork choice was run.
+    pub slot: Slot,
+    /// The time at which the change was recorded, in milliseconds since the UNIX epoch.
+    pub timestamp_ms: u64,
+    pub update: ForkChoiceUpdate,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ForkChoiceUpdate {
+    /// The head changed to a descendant of the previous head.
+    HeadChange(HeadChange),
+    /// The head changed to a block which does not descend from the previous head.
+    Reorg(Reorg),
+    /// The justified checkpoint changed.
+    Justification(CheckpointUpdate),
+    /// The finalized checkpoint changed.
+    Finalization(CheckpointUpdate),
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct HeadChange {
+    pub old_head_block: Hash256,
+    pub old_head_slot: Slot,
+    pub new_head_block: Hash256,
+    pub new_head_slot: Slot,
+    pub weights: HeadWeights,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Reorg {
+    /// The number of blocks between the old head and the common ancestor of the two heads.
+    pub depth: u64,
+    pub old_head_block: Hash256,
+    pub old_head_slot: Slot,
+    pub new_head_block: Hash256,
+    pub new_head_slot: Slot,
+    pub weights: HeadWeights,
+    /// `true` if the re-org was caused by this node proposing a block on the parent of a weak
+    /// head (i.e. the `--proposer-reorg` logic).
+    pub proposer_re_org: bool,
+}
+
+/// The weights of the old and new heads when fork choice selected the new head.
+///
+/// A weight is `None` if the block had already been pruned from fork choice.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct HeadWeights {
+    pub old_head_weight: Option<u64>,
+    pub new_head_weight: Option<u64>,
+    /// The block which received proposer boost, if any.
+    pub proposer_boost_root: Option<Hash256>,
+    /// The score applied to `proposer_boost_root`.
+    pub proposer_boost_score: u64,
+    /// `true` if proposer boost was applied to the new head.
+    pub proposer_boost: bool,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct CheckpointUpdate {
+    pub old: Checkpoint,
+    pub new: Checkpoint,
+}
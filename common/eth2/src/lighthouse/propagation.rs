@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use types::{Hash256, Slot};
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PropagationQuery {
+    pub start_slot: Slot,
+    pub end_slot: Slot,
+}
+
+/// How blocks and aggregates propagated to this node via gossip.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PropagationAnalysis {
+    pub blocks: Vec<BlockPropagation>,
+    pub aggregates: Vec<AggregatePropagation>,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BlockPropagation {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    /// The delay between the start of the slot and the block first being received.
+    pub first_seen_delay_ms: u64,
+    /// The peers from which the block was received, in order of arrival.
+    pub peers: Vec<PeerArrival>,
+    /// The delays between the block first being received and being received from each peer.
+    pub arrival: PropagationPercentiles,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PeerArrival {
+    pub peer_id: String,
+    /// The delay between the message first being received and being received from this peer.
+    pub delay_ms: u64,
+}
+
+/// The propagation of all the aggregates received for a slot.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct AggregatePropagation {
+    pub slot: Slot,
+    pub aggregates: usize,
+    /// The delays between the start of the slot and each aggregate first being received.
+    pub first_seen: PropagationPercentiles,
+    /// The delays between each aggregate first being received and being received from each peer.
+    pub arrival: PropagationPercentiles,
+}
+
+/// Nearest-rank percentiles of a set of delays.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PropagationPercentiles {
+    pub count: usize,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+impl PropagationPercentiles {
+    /// Compute the percentiles of `delays_ms`, sorting it in the process.
+    pub fn from_delays(delays_ms: &mut [u64]) -> Self {
+        delays_ms.sort_unstable();
+        let percentile = |p: usize| {
+            let rank = (delays_ms.len() * p).div_ceil(100);
+            delays_ms
+                .get(rank.saturating_sub(1))
+                .copied()
+                .unwrap_or_default()
+        };
+        Self {
+            count: delays_ms.len(),
+            p50_ms: percentile(50),
+            p90_ms: percentile(90),
+            p99_ms: percentile(99),
+            max_ms: delays_ms.last().copied().unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use types::{AttesterSlashing, Epoch, EthSpec, ProposerSlashing, Slot};
+
+/// Query parameters for the `/lighthouse/slasher/attestations` and `/lighthouse/slasher/targets`
+/// endpoints.
+///
+/// Both bounds are inclusive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlasherEpochRangeQuery {
+    pub start_epoch: Epoch,
+    pub end_epoch: Epoch,
+}
+
+/// Query parameters for the `/lighthouse/slasher/proposals` endpoint.
+///
+/// Both bounds are inclusive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlasherSlotRangeQuery {
+    pub start_slot: Slot,
+    pub end_slot: Slot,
+}
+
+/// The min and max targets recorded by the slasher for a validator at a source epoch.
+///
+/// The min target is the lowest target of the validator's attestations with a source after
+/// `source_epoch`, and the max target is the highest target of those with a source before it.
+/// An attestation from `source_epoch` is surround-slashable if its target is greater than the min
+/// target or less than the max target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlasherTargetBounds {
+    pub source_epoch: Epoch,
+    pub min_target: Option<Epoch>,
+    pub max_target: Option<Epoch>,
+}
+
+/// The most recent slashings found by the slasher, oldest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct SlasherSlashings<E: EthSpec> {
+    pub attester_slashings: Vec<AttesterSlashing<E>>,
+    pub proposer_slashings: Vec<ProposerSlashing>,
+}
+
+/// Statistics about the contents of the slasher database.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlasherDatabaseStats {
+    pub backend: String,
+    pub schema_version: Option<u64>,
+    pub history_length: usize,
+    pub chunk_size: usize,
+    pub validator_chunk_size: usize,
+    /// The total size of the database's files in bytes.
+    pub size: u64,
+    pub tables: Vec<SlasherTableStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlasherTableStats {
+    pub name: String,
+    pub entries: u64,
+}
@@ -349,6 +349,47 @@ impl ValidatorClientHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET lighthouse/ui/validator_performance?start_epoch,end_epoch`
+    pub async fn get_lighthouse_ui_validator_performance(
+        &self,
+        start_epoch: Option<Epoch>,
+        end_epoch: Option<Epoch>,
+    ) -> Result<GenericResponse<Vec<ValidatorPerformanceData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("ui")
+            .push("validator_performance");
+
+        if let Some(start_epoch) = start_epoch {
+            path.query_pairs_mut()
+                .append_pair("start_epoch", &start_epoch.to_string());
+        }
+        if let Some(end_epoch) = end_epoch {
+            path.query_pairs_mut()
+                .append_pair("end_epoch", &end_epoch.to_string());
+        }
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/beacon/health`
+    pub async fn get_lighthouse_beacon_health(
+        &self,
+    ) -> Result<GenericResponse<Vec<BeaconNodeHealthData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("health");
+
+        self.get(path).await
+    }
+
     /// `POST lighthouse/validators`
     pub async fn post_lighthouse_validators(
         &self,
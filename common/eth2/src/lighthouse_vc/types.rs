@@ -197,3 +197,93 @@ pub struct SingleExportKeystoresResponse {
 pub struct SetGraffitiRequest {
     pub graffiti: GraffitiString,
 }
+
+/// Query parameters for the `GET lighthouse/ui/validator_performance` endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorPerformanceQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_epoch: Option<Epoch>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_epoch: Option<Epoch>,
+}
+
+/// The recorded duty outcomes for a single validator, in ascending epoch order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorPerformanceData {
+    pub voting_pubkey: PublicKeyBytes,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    pub epochs: Vec<EpochPerformance>,
+}
+
+/// The duty outcomes of a single validator in a single epoch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochPerformance {
+    pub epoch: Epoch,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation: Option<AttestationOutcome>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proposals: Vec<ProposalOutcome>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_committee: Option<SyncCommitteeOutcome>,
+}
+
+/// The outcome of an attestation duty, as reported by the beacon node.
+///
+/// All rewards are in gwei.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttestationOutcome {
+    pub head: bool,
+    pub target: bool,
+    pub source: bool,
+    /// The number of slots between the attestation slot and the first block that included it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inclusion_distance: Option<u64>,
+    #[serde(with = "serde_utils::quoted_i64")]
+    pub reward: i64,
+}
+
+/// The outcome of a block proposal duty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposalOutcome {
+    pub slot: Slot,
+    /// `true` if a block from this validator is canonical at `slot`.
+    pub included: bool,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub reward: u64,
+}
+
+/// The outcome of sync committee duties across all slots of an epoch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncCommitteeOutcome {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub participated_slots: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub missed_slots: u64,
+    #[serde(with = "serde_utils::quoted_i64")]
+    pub reward: i64,
+}
+
+/// The health of a beacon node, as returned by `GET lighthouse/beacon/health` in order of
+/// preference.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaconNodeHealthData {
+    /// The position of the beacon node in the `--beacon-nodes` list.
+    pub index: usize,
+    pub endpoint: String,
+    /// One of `synced`, `not_synced`, `offline`, `incompatible` or `uninitialized`.
+    pub status: String,
+    /// The health score, where a higher score is healthier.
+    pub score: u64,
+    /// The number of slots by which the head of the beacon node trails the current slot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_distance: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_optimistic: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub el_offline: Option<bool>,
+    /// The recent proportion of failed requests, between 0 and 1.
+    pub error_rate: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+}
@@ -20,7 +20,7 @@ use types::beacon_block_body::KzgCommitments;
 pub use types::*;
 
 #[cfg(feature = "lighthouse")]
-use crate::lighthouse::BlockReward;
+use crate::lighthouse::{BlockProductionTrace, BlockReward};
 
 /// An API error serializable to JSON.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -328,7 +328,7 @@ impl From<ValidatorId> for String {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct ValidatorData {
     #[serde(with = "serde_utils::quoted_u64")]
     pub index: u64,
@@ -338,7 +338,7 @@ pub struct ValidatorData {
     pub validator: Validator,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct ValidatorBalanceData {
     #[serde(with = "serde_utils::quoted_u64")]
     pub index: u64,
@@ -346,6 +346,14 @@ pub struct ValidatorBalanceData {
     pub balance: u64,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct ValidatorIdentityData {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub index: u64,
+    pub pubkey: PublicKeyBytes,
+    pub activation_epoch: Epoch,
+}
+
 // Implemented according to what is described here:
 //
 // https://hackmd.io/ofFJ5gOmQpu1jjHilHbdQQ
@@ -354,8 +362,9 @@ pub struct ValidatorBalanceData {
 // this proposal:
 //
 // https://hackmd.io/bQxMDRt1RbS1TLno8K4NPg?view
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Encode, Decode)]
 #[serde(rename_all = "snake_case")]
+#[ssz(enum_behaviour = "tag")]
 pub enum ValidatorStatus {
     PendingInitialized,
     PendingQueued,
@@ -512,7 +521,7 @@ pub struct ValidatorsRequestBody {
     pub statuses: Option<Vec<ValidatorStatus>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct CommitteeData {
     #[serde(with = "serde_utils::quoted_u64")]
     pub index: u64,
@@ -616,7 +625,7 @@ pub struct QueryVec<T: FromStr> {
     values: Vec<T>,
 }
 
-fn query_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+pub(crate) fn query_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
 where
     D: serde::Deserializer<'de>,
     T: FromStr,
@@ -682,6 +691,12 @@ pub struct ValidatorBalancesRequestBody {
     pub ids: Vec<ValidatorId>,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ValidatorIdentitiesRequestBody {
+    pub ids: Vec<ValidatorId>,
+}
+
 #[derive(Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct BlobIndicesQuery {
@@ -1101,6 +1116,12 @@ pub enum EventKind<E: EthSpec> {
     LightClientOptimisticUpdate(Box<LightClientOptimisticUpdate<E>>),
     #[cfg(feature = "lighthouse")]
     BlockReward(BlockReward),
+    #[cfg(feature = "lighthouse")]
+    BlockProductionTrace(Box<BlockProductionTrace>),
+    /// The header of a block with a valid proposer signature which failed verification, e.g. a
+    /// second block from the same proposer in a slot.
+    #[cfg(feature = "lighthouse")]
+    RejectedBlockHeader(Box<SignedBeaconBlockHeader>),
     PayloadAttributes(VersionedSsePayloadAttributes),
     ProposerSlashing(Box<ProposerSlashing>),
     AttesterSlashing(Box<AttesterSlashing<E>>),
@@ -1125,6 +1146,10 @@ impl<E: EthSpec> EventKind<E> {
             EventKind::LightClientOptimisticUpdate(_) => "light_client_optimistic_update",
             #[cfg(feature = "lighthouse")]
             EventKind::BlockReward(_) => "block_reward",
+            #[cfg(feature = "lighthouse")]
+            EventKind::BlockProductionTrace(_) => "block_production_trace",
+            #[cfg(feature = "lighthouse")]
+            EventKind::RejectedBlockHeader(_) => "rejected_block_header",
             EventKind::ProposerSlashing(_) => "proposer_slashing",
             EventKind::AttesterSlashing(_) => "attester_slashing",
             EventKind::BlsToExecutionChange(_) => "bls_to_execution_change",
@@ -1209,6 +1234,18 @@ impl<E: EthSpec> EventKind<E> {
             "block_reward" => Ok(EventKind::BlockReward(serde_json::from_str(data).map_err(
                 |e| ServerError::InvalidServerSentEvent(format!("Block Reward: {:?}", e)),
             )?)),
+            #[cfg(feature = "lighthouse")]
+            "block_production_trace" => Ok(EventKind::BlockProductionTrace(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Block Production Trace: {:?}", e))
+                })?,
+            )),
+            #[cfg(feature = "lighthouse")]
+            "rejected_block_header" => Ok(EventKind::RejectedBlockHeader(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Rejected Block Header: {:?}", e))
+                })?,
+            )),
             "attester_slashing" => Ok(EventKind::AttesterSlashing(
                 serde_json::from_str(data).map_err(|e| {
                     ServerError::InvalidServerSentEvent(format!("Attester Slashing: {:?}", e))
@@ -1241,7 +1278,7 @@ pub struct EventQuery {
     pub topics: Vec<EventTopic>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum EventTopic {
     Head,
@@ -1258,6 +1295,10 @@ pub enum EventTopic {
     LightClientOptimisticUpdate,
     #[cfg(feature = "lighthouse")]
     BlockReward,
+    #[cfg(feature = "lighthouse")]
+    BlockProductionTrace,
+    #[cfg(feature = "lighthouse")]
+    RejectedBlockHeader,
     AttesterSlashing,
     ProposerSlashing,
     BlsToExecutionChange,
@@ -1283,6 +1324,10 @@ impl FromStr for EventTopic {
             "light_client_optimistic_update" => Ok(EventTopic::LightClientOptimisticUpdate),
             #[cfg(feature = "lighthouse")]
             "block_reward" => Ok(EventTopic::BlockReward),
+            #[cfg(feature = "lighthouse")]
+            "block_production_trace" => Ok(EventTopic::BlockProductionTrace),
+            #[cfg(feature = "lighthouse")]
+            "rejected_block_header" => Ok(EventTopic::RejectedBlockHeader),
             "attester_slashing" => Ok(EventTopic::AttesterSlashing),
             "proposer_slashing" => Ok(EventTopic::ProposerSlashing),
             "bls_to_execution_change" => Ok(EventTopic::BlsToExecutionChange),
@@ -1309,6 +1354,10 @@ impl fmt::Display for EventTopic {
             EventTopic::LightClientOptimisticUpdate => write!(f, "light_client_optimistic_update"),
             #[cfg(feature = "lighthouse")]
             EventTopic::BlockReward => write!(f, "block_reward"),
+            #[cfg(feature = "lighthouse")]
+            EventTopic::BlockProductionTrace => write!(f, "block_production_trace"),
+            #[cfg(feature = "lighthouse")]
+            EventTopic::RejectedBlockHeader => write!(f, "rejected_block_header"),
             EventTopic::AttesterSlashing => write!(f, "attester_slashing"),
             EventTopic::ProposerSlashing => write!(f, "proposer_slashing"),
             EventTopic::BlsToExecutionChange => write!(f, "bls_to_execution_change"),
@@ -136,6 +136,42 @@ pub fn invalid_auth(msg: String) -> warp::reject::Rejection {
     warp::reject::custom(InvalidAuthorization(msg))
 }
 
+#[derive(Debug)]
+pub struct Unauthorized(pub String);
+
+impl Reject for Unauthorized {}
+
+pub fn unauthorized(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(Unauthorized(msg))
+}
+
+#[derive(Debug)]
+pub struct Forbidden(pub String);
+
+impl Reject for Forbidden {}
+
+pub fn forbidden(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(Forbidden(msg))
+}
+
+#[derive(Debug)]
+pub struct Conflict(pub String);
+
+impl Reject for Conflict {}
+
+pub fn conflict(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(Conflict(msg))
+}
+
+#[derive(Debug)]
+pub struct TooManyRequests(pub String);
+
+impl Reject for TooManyRequests {}
+
+pub fn too_many_requests(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(TooManyRequests(msg))
+}
+
 #[derive(Debug)]
 pub struct UnsupportedMediaType(pub String);
 
@@ -228,6 +264,18 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
     } else if let Some(e) = err.find::<crate::reject::InvalidAuthorization>() {
         code = StatusCode::FORBIDDEN;
         message = format!("FORBIDDEN: Invalid auth token: {}", e.0);
+    } else if let Some(e) = err.find::<crate::reject::Unauthorized>() {
+        code = StatusCode::UNAUTHORIZED;
+        message = format!("UNAUTHORIZED: {}", e.0);
+    } else if let Some(e) = err.find::<crate::reject::Forbidden>() {
+        code = StatusCode::FORBIDDEN;
+        message = format!("FORBIDDEN: {}", e.0);
+    } else if let Some(e) = err.find::<crate::reject::Conflict>() {
+        code = StatusCode::CONFLICT;
+        message = format!("CONFLICT: {}", e.0);
+    } else if let Some(e) = err.find::<crate::reject::TooManyRequests>() {
+        code = StatusCode::TOO_MANY_REQUESTS;
+        message = format!("TOO_MANY_REQUESTS: {}", e.0);
     } else if let Some(e) = err.find::<warp::reject::MissingHeader>() {
         if e.name().eq("Authorization") {
             code = StatusCode::UNAUTHORIZED;
@@ -1,4 +1,8 @@
-use crate::{ForkChoiceStore, InvalidationOperation};
+use crate::fork_choice_recorder::RecorderState;
+use crate::{
+    ForkChoiceInput, ForkChoiceRecorder, ForkChoiceStore, InvalidationOperation,
+    JustifiedBalancesUpdate,
+};
 use proto_array::{
     Block as ProtoBlock, DisallowedReOrgOffsets, ExecutionStatus, ProposerHeadError,
     ProposerHeadInfo, ProtoArrayForkChoice, ReOrgThreshold,
@@ -311,6 +315,8 @@ pub struct ForkChoice<T, E> {
     queued_attestations: Vec<QueuedAttestation>,
     /// Stores a cache of the values required to be sent to the execution layer.
     forkchoice_update_parameters: ForkchoiceUpdateParameters,
+    /// Receives every input to `proto_array`, if recording is enabled.
+    recorder: Option<RecorderState>,
     _phantom: PhantomData<E>,
 }
 
@@ -399,6 +405,7 @@ where
                 // This will be updated during the next call to `Self::get_head`.
                 head_root: Hash256::zero(),
             },
+            recorder: None,
             _phantom: PhantomData,
         };
 
@@ -487,6 +494,32 @@ where
             spec,
         )?;
 
+        if let Some(state) = &mut self.recorder {
+            let justified_balances = &store.justified_balances().effective_balances;
+            let balances_update =
+                JustifiedBalancesUpdate::diff(&state.justified_balances, justified_balances);
+            if balances_update.is_some() {
+                state.justified_balances.clone_from(justified_balances);
+            }
+            let new_equivocating_indices = store
+                .equivocating_indices()
+                .difference(&state.equivocating_indices)
+                .copied()
+                .collect::<Vec<_>>();
+            state
+                .equivocating_indices
+                .extend(new_equivocating_indices.iter().copied());
+            state.recorder.record(ForkChoiceInput::FindHead {
+                current_slot,
+                justified_checkpoint: *store.justified_checkpoint(),
+                finalized_checkpoint: *store.finalized_checkpoint(),
+                proposer_boost_root: store.proposer_boost_root(),
+                justified_balances: balances_update,
+                new_equivocating_indices,
+                head_root,
+            });
+        }
+
         // Cache some values for the next forkchoiceUpdate call to the execution layer.
         let head_hash = self
             .get_block(&head_root)
@@ -607,7 +640,9 @@ where
     ) -> Result<(), Error<T::Error>> {
         self.proto_array
             .process_execution_payload_validation(block_root)
-            .map_err(Error::FailedToProcessValidExecutionPayload)
+            .map_err(Error::FailedToProcessValidExecutionPayload)?;
+        self.record(|| ForkChoiceInput::ValidPayload { block_root });
+        Ok(())
     }
 
     /// See `ProtoArrayForkChoice::process_execution_payload_invalidation` for documentation.
@@ -617,7 +652,9 @@ where
     ) -> Result<(), Error<T::Error>> {
         self.proto_array
             .process_execution_payload_invalidation::<E>(op)
-            .map_err(Error::FailedToProcessInvalidExecutionPayload)
+            .map_err(Error::FailedToProcessInvalidExecutionPayload)?;
+        self.record(|| ForkChoiceInput::InvalidPayload { op: op.clone() });
+        Ok(())
     }
 
     /// Add `block` to the fork choice DAG.
@@ -850,33 +887,39 @@ where
 
         // This does not apply a vote to the block, it just makes fork choice aware of the block so
         // it can still be identified as the head even if it doesn't have any votes.
-        self.proto_array.process_block::<E>(
-            ProtoBlock {
-                slot: block.slot(),
-                root: block_root,
-                parent_root: Some(block.parent_root()),
-                target_root,
-                current_epoch_shuffling_id: AttestationShufflingId::new(
-                    block_root,
-                    state,
-                    RelativeEpoch::Current,
-                )
-                .map_err(Error::BeaconStateError)?,
-                next_epoch_shuffling_id: AttestationShufflingId::new(
-                    block_root,
-                    state,
-                    RelativeEpoch::Next,
-                )
-                .map_err(Error::BeaconStateError)?,
-                state_root: block.state_root(),
-                justified_checkpoint: state.current_justified_checkpoint(),
-                finalized_checkpoint: state.finalized_checkpoint(),
-                execution_status,
-                unrealized_justified_checkpoint: Some(unrealized_justified_checkpoint),
-                unrealized_finalized_checkpoint: Some(unrealized_finalized_checkpoint),
-            },
-            current_slot,
-        )?;
+        let proto_block = ProtoBlock {
+            slot: block.slot(),
+            root: block_root,
+            parent_root: Some(block.parent_root()),
+            target_root,
+            current_epoch_shuffling_id: AttestationShufflingId::new(
+                block_root,
+                state,
+                RelativeEpoch::Current,
+            )
+            .map_err(Error::BeaconStateError)?,
+            next_epoch_shuffling_id: AttestationShufflingId::new(
+                block_root,
+                state,
+                RelativeEpoch::Next,
+            )
+            .map_err(Error::BeaconStateError)?,
+            state_root: block.state_root(),
+            justified_checkpoint: state.current_justified_checkpoint(),
+            finalized_checkpoint: state.finalized_checkpoint(),
+            execution_status,
+            unrealized_justified_checkpoint: Some(unrealized_justified_checkpoint),
+            unrealized_finalized_checkpoint: Some(unrealized_finalized_checkpoint),
+        };
+        let recorded_block = self.recorder.is_some().then(|| proto_block.clone());
+        self.proto_array
+            .process_block::<E>(proto_block, current_slot)?;
+        if let Some(proto_block) = recorded_block {
+            self.record(|| ForkChoiceInput::Block {
+                current_slot,
+                block: proto_block,
+            });
+        }
 
         Ok(())
     }
@@ -1069,6 +1112,11 @@ where
                     attestation.data().target.epoch,
                 )?;
             }
+            self.record(|| ForkChoiceInput::Attestation {
+                validator_indices: attestation.attesting_indices_to_vec(),
+                block_root: attestation.data().beacon_block_root,
+                target_epoch: attestation.data().target.epoch,
+            });
         } else {
             // The spec declares:
             //
@@ -1186,6 +1234,11 @@ where
                     attestation.target_epoch,
                 )?;
             }
+            self.record(|| ForkChoiceInput::Attestation {
+                validator_indices: attestation.attesting_indices,
+                block_root: attestation.block_root,
+                target_epoch: attestation.target_epoch,
+            });
         }
 
         Ok(())
@@ -1385,9 +1438,38 @@ where
     pub fn prune(&mut self) -> Result<(), Error<T::Error>> {
         let finalized_root = self.fc_store.finalized_checkpoint().root;
 
-        self.proto_array
-            .maybe_prune(finalized_root)
-            .map_err(Into::into)
+        self.proto_array.maybe_prune(finalized_root)?;
+        self.record(|| ForkChoiceInput::Prune { finalized_root });
+        Ok(())
+    }
+
+    /// Record every subsequent input to `proto_array` with `recorder`, replacing any existing
+    /// recorder.
+    ///
+    /// The inputs may be replayed with `ForkChoiceReplay`, starting from the state of `self` at
+    /// the time of this call.
+    pub fn set_recorder(&mut self, recorder: Box<dyn ForkChoiceRecorder>) {
+        self.recorder = Some(RecorderState {
+            recorder,
+            justified_balances: self
+                .fc_store
+                .justified_balances()
+                .effective_balances
+                .clone(),
+            equivocating_indices: self.fc_store.equivocating_indices().clone(),
+        });
+    }
+
+    /// Returns `true` if the inputs to `proto_array` are being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Pass the input produced by `f` to the recorder, if any.
+    fn record(&mut self, f: impl FnOnce() -> ForkChoiceInput) {
+        if let Some(state) = &mut self.recorder {
+            state.recorder.record(f());
+        }
     }
 
     /// Instantiate `Self` from some `PersistedForkChoice` generated by a earlier call to
@@ -1467,6 +1549,7 @@ where
                 // Will be updated in the following call to `Self::get_head`.
                 head_root: Hash256::zero(),
             },
+            recorder: None,
             _phantom: PhantomData,
         };
 
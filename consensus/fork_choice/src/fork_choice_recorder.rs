@@ -0,0 +1,221 @@
+//! Provides a means to record every input to the proto-array of a `ForkChoice` so that it can be
+//! replayed offline with `ForkChoiceReplay`.
+//!
+//! Inputs are recorded at the point at which they are applied to proto-array, after they have
+//! been verified and after any queueing by `ForkChoice`. This allows the inputs to be replayed
+//! without the beacon states which were required to verify them.
+
+use proto_array::{
+    Block as ProtoBlock, InvalidationOperation, JustifiedBalances, ProtoArrayForkChoice,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use types::{ChainSpec, Checkpoint, Epoch, EthSpec, Hash256, Slot};
+
+/// An input to the proto-array of a `ForkChoice`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ForkChoiceInput {
+    /// A block was added to fork choice.
+    Block {
+        current_slot: Slot,
+        block: ProtoBlock,
+    },
+    /// The votes of `validator_indices` were applied to fork choice.
+    Attestation {
+        validator_indices: Vec<u64>,
+        block_root: Hash256,
+        target_epoch: Epoch,
+    },
+    /// The head was computed.
+    FindHead {
+        current_slot: Slot,
+        justified_checkpoint: Checkpoint,
+        finalized_checkpoint: Checkpoint,
+        proposer_boost_root: Hash256,
+        /// Changes to the justified balances since the previous `FindHead`, if any.
+        justified_balances: Option<JustifiedBalancesUpdate>,
+        /// Validators which have been found to equivocate since the previous `FindHead`.
+        new_equivocating_indices: Vec<u64>,
+        /// The head which was selected by fork choice.
+        head_root: Hash256,
+    },
+    /// The execution payload of a block was found to be valid.
+    ValidPayload { block_root: Hash256 },
+    /// The execution payload of one or more blocks was found to be invalid.
+    InvalidPayload { op: InvalidationOperation },
+    /// Fork choice was pruned.
+    Prune { finalized_root: Hash256 },
+}
+
+/// A sparse update to a list of justified effective balances.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JustifiedBalancesUpdate {
+    /// The length of the list after the update.
+    pub len: usize,
+    /// The `(index, effective_balance)` pairs which changed.
+    pub changes: Vec<(usize, u64)>,
+}
+
+impl JustifiedBalancesUpdate {
+    /// Returns the update from `old` to `new`, or `None` if they are equal.
+    pub fn diff(old: &[u64], new: &[u64]) -> Option<Self> {
+        let changes = new
+            .iter()
+            .enumerate()
+            .filter(|(i, balance)| old.get(*i) != Some(*balance))
+            .map(|(i, balance)| (i, *balance))
+            .collect::<Vec<_>>();
+
+        if changes.is_empty() && old.len() == new.len() {
+            None
+        } else {
+            Some(Self {
+                len: new.len(),
+                changes,
+            })
+        }
+    }
+
+    /// Apply the update to `balances`.
+    pub fn apply(&self, balances: &mut Vec<u64>) {
+        balances.resize(self.len, 0);
+        for (i, balance) in &self.changes {
+            if let Some(existing) = balances.get_mut(*i) {
+                *existing = *balance;
+            }
+        }
+    }
+}
+
+/// Receives the inputs to a `ForkChoice`, e.g. to write them to a file.
+pub trait ForkChoiceRecorder: Send + Sync {
+    fn record(&mut self, input: ForkChoiceInput);
+}
+
+/// The state which is required to record the differences in the fork choice store between each
+/// `FindHead`.
+pub(crate) struct RecorderState {
+    pub recorder: Box<dyn ForkChoiceRecorder>,
+    pub justified_balances: Vec<u64>,
+    pub equivocating_indices: BTreeSet<u64>,
+}
+
+/// Replays a sequence of `ForkChoiceInput`s on top of a proto-array.
+pub struct ForkChoiceReplay {
+    proto_array: ProtoArrayForkChoice,
+    justified_balances: Vec<u64>,
+    equivocating_indices: BTreeSet<u64>,
+}
+
+impl ForkChoiceReplay {
+    /// Start a replay from the state of a fork choice at the time that recording started.
+    pub fn new(
+        proto_array: ProtoArrayForkChoice,
+        justified_balances: Vec<u64>,
+        equivocating_indices: BTreeSet<u64>,
+    ) -> Self {
+        Self {
+            proto_array,
+            justified_balances,
+            equivocating_indices,
+        }
+    }
+
+    /// Apply `input`, returning the head if the input is a `FindHead`.
+    pub fn apply<E: EthSpec>(
+        &mut self,
+        input: ForkChoiceInput,
+        spec: &ChainSpec,
+    ) -> Result<Option<Hash256>, String> {
+        match input {
+            ForkChoiceInput::Block {
+                current_slot,
+                block,
+            } => self.proto_array.process_block::<E>(block, current_slot)?,
+            ForkChoiceInput::Attestation {
+                validator_indices,
+                block_root,
+                target_epoch,
+            } => {
+                for validator_index in validator_indices {
+                    self.proto_array.process_attestation(
+                        validator_index as usize,
+                        block_root,
+                        target_epoch,
+                    )?;
+                }
+            }
+            ForkChoiceInput::FindHead {
+                current_slot,
+                justified_checkpoint,
+                finalized_checkpoint,
+                proposer_boost_root,
+                justified_balances,
+                new_equivocating_indices,
+                head_root: _,
+            } => {
+                if let Some(update) = justified_balances {
+                    update.apply(&mut self.justified_balances);
+                }
+                self.equivocating_indices.extend(new_equivocating_indices);
+                let justified_balances =
+                    JustifiedBalances::from_effective_balances(self.justified_balances.clone())
+                        .map_err(|e| format!("Invalid justified balances: {:?}", e))?;
+
+                let head_root = self.proto_array.find_head::<E>(
+                    justified_checkpoint,
+                    finalized_checkpoint,
+                    &justified_balances,
+                    proposer_boost_root,
+                    &self.equivocating_indices,
+                    current_slot,
+                    spec,
+                )?;
+                return Ok(Some(head_root));
+            }
+            ForkChoiceInput::ValidPayload { block_root } => self
+                .proto_array
+                .process_execution_payload_validation(block_root)?,
+            ForkChoiceInput::InvalidPayload { op } => self
+                .proto_array
+                .process_execution_payload_invalidation::<E>(&op)?,
+            ForkChoiceInput::Prune { finalized_root } => {
+                self.proto_array.maybe_prune(finalized_root)?
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the underlying proto-array.
+    pub fn proto_array(&self) -> &ProtoArrayForkChoice {
+        &self.proto_array
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn justified_balances_update() {
+        let old = vec![32, 32, 0];
+        assert_eq!(JustifiedBalancesUpdate::diff(&old, &old), None);
+
+        let new = vec![32, 31, 0, 32];
+        let update = JustifiedBalancesUpdate::diff(&old, &new).unwrap();
+        assert_eq!(update.changes, vec![(1, 31), (3, 32)]);
+
+        let mut balances = old.clone();
+        update.apply(&mut balances);
+        assert_eq!(balances, new);
+
+        let shorter = vec![32];
+        let update = JustifiedBalancesUpdate::diff(&old, &shorter).unwrap();
+        assert!(update.changes.is_empty());
+        let mut balances = old;
+        update.apply(&mut balances);
+        assert_eq!(balances, shorter);
+    }
+}
@@ -1,4 +1,5 @@
 mod fork_choice;
+mod fork_choice_recorder;
 mod fork_choice_store;
 
 pub use crate::fork_choice::{
@@ -6,6 +7,9 @@ pub use crate::fork_choice::{
     InvalidAttestation, InvalidBlock, PayloadVerificationStatus, PersistedForkChoice,
     QueuedAttestation, ResetPayloadStatuses,
 };
+pub use fork_choice_recorder::{
+    ForkChoiceInput, ForkChoiceRecorder, ForkChoiceReplay, JustifiedBalancesUpdate,
+};
 pub use fork_choice_store::ForkChoiceStore;
 pub use proto_array::{
     Block as ProtoBlock, ExecutionStatus, InvalidationOperation, ProposerHeadError,
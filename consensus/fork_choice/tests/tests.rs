@@ -8,10 +8,12 @@ use beacon_chain::{
     StateSkipConfig, WhenSlotSkipped,
 };
 use fork_choice::{
-    ForkChoiceStore, InvalidAttestation, InvalidBlock, PayloadVerificationStatus, QueuedAttestation,
+    ForkChoiceInput, ForkChoiceRecorder, ForkChoiceReplay, ForkChoiceStore, InvalidAttestation,
+    InvalidBlock, PayloadVerificationStatus, QueuedAttestation,
 };
+use proto_array::ProtoArrayForkChoice;
 use std::fmt;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use store::MemoryStore;
 use types::{
@@ -1390,3 +1392,58 @@ async fn progressive_balances_cache_proposer_slashing() {
         .apply_blocks(MainnetEthSpec::slots_per_epoch() as usize)
         .await;
 }
+
+/// A `ForkChoiceRecorder` which stores the recorded inputs in memory.
+#[derive(Clone, Default)]
+struct MemoryRecorder(Arc<Mutex<Vec<ForkChoiceInput>>>);
+
+impl ForkChoiceRecorder for MemoryRecorder {
+    fn record(&mut self, input: ForkChoiceInput) {
+        self.0.lock().unwrap().push(input);
+    }
+}
+
+/// Checks that replaying the recorded inputs to fork choice finds the same heads as the beacon
+/// chain did.
+#[tokio::test]
+async fn recorded_inputs_replay_to_the_same_heads() {
+    let test = ForkChoiceTest::new().apply_blocks(2).await;
+
+    let recorder = MemoryRecorder::default();
+    let mut replay = {
+        let mut fork_choice = test.harness.chain.canonical_head.fork_choice_write_lock();
+        fork_choice.set_recorder(Box::new(recorder.clone()));
+        ForkChoiceReplay::new(
+            ProtoArrayForkChoice::from_bytes(&fork_choice.proto_array().as_bytes()).unwrap(),
+            fork_choice
+                .fc_store()
+                .justified_balances()
+                .effective_balances
+                .clone(),
+            fork_choice.fc_store().equivocating_indices().clone(),
+        )
+    };
+
+    // Apply enough blocks for the justified and finalized checkpoints to change.
+    let test = test.apply_blocks(E::slots_per_epoch() as usize * 3).await;
+
+    let inputs = recorder.0.lock().unwrap().clone();
+    assert!(inputs
+        .iter()
+        .any(|input| matches!(input, ForkChoiceInput::Block { .. })));
+    assert!(inputs
+        .iter()
+        .any(|input| matches!(input, ForkChoiceInput::Attestation { .. })));
+
+    let mut heads = 0;
+    for input in inputs {
+        let recorded_head = match &input {
+            ForkChoiceInput::FindHead { head_root, .. } => Some(*head_root),
+            _ => None,
+        };
+        let head = replay.apply::<E>(input, &test.harness.chain.spec).unwrap();
+        assert_eq!(head, recorded_head);
+        heads += usize::from(head.is_some());
+    }
+    assert!(heads > 0);
+}
@@ -142,7 +142,7 @@ impl fmt::Display for ExecutionStatus {
 /// A block that is to be applied to the fork choice.
 ///
 /// A simplified version of `types::BeaconBlock`.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Block {
     pub slot: Slot,
     pub root: Hash256,
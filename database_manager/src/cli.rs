@@ -3,6 +3,8 @@ use clap_utils::get_color_style;
 use clap_utils::FLAG_HEADER;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use store::config::DEFAULT_ZSTD_COMPRESSION_LEVEL;
+use store::Codec;
 
 use crate::InspectTarget;
 
@@ -55,6 +57,15 @@ pub struct DatabaseManager {
     )]
     pub blobs_dir: Option<PathBuf>,
 
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory to which blobs are archived when they are pruned, instead of being \
+                deleted.",
+        display_order = 0
+    )]
+    pub blob_archive_dir: Option<PathBuf>,
+
     #[clap(
         long,
         global = true,
@@ -79,6 +90,10 @@ pub enum DatabaseManagerSubcommand {
     PruneBlobs(PruneBlobs),
     PruneStates(PruneStates),
     Compact(Compact),
+    Verify(Verify),
+    Snapshot(Snapshot),
+    Recompress(Recompress),
+    Slasher(Slasher),
 }
 
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
@@ -227,3 +242,187 @@ pub struct Compact {
     )]
     pub output_dir: Option<PathBuf>,
 }
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Check the consistency of the database and print a JSON report of any issues found. \
+             The beacon node must be stopped while the database is verified."
+)]
+pub struct Verify {
+    #[clap(
+        long,
+        help = "Repair the issues which only affect the database's indices, and delete any \
+                temporary states. Without this flag the database is not modified.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub repair: bool,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "Write the report to a file rather than to stdout",
+        display_order = 0
+    )]
+    pub output_file: Option<PathBuf>,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(about = "Create and restore snapshots of the database.")]
+pub struct Snapshot {
+    #[clap(subcommand)]
+    pub subcommand: SnapshotSubcommand,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub enum SnapshotSubcommand {
+    Create(SnapshotCreate),
+    Restore(SnapshotRestore),
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Write a snapshot of the database to a new directory. The beacon node must be \
+             stopped; use the /lighthouse/database/snapshot endpoint to snapshot a running node."
+)]
+pub struct SnapshotCreate {
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory to write the snapshot to. Must not contain any files.",
+        display_order = 0
+    )]
+    pub output_dir: PathBuf,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Restore a snapshot into the database directories of the data directory. The \
+             directories must not already contain a database."
+)]
+pub struct SnapshotRestore {
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory containing the snapshot",
+        display_order = 0
+    )]
+    pub snapshot_dir: PathBuf,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Rewrite the blocks, restore point states and blobs of the database with different \
+             compression codecs. The beacon node must be stopped while the database is \
+             recompressed. Recompress every column with `none` before downgrading to a version \
+             of Lighthouse without compression support."
+)]
+pub struct Recompress {
+    #[clap(
+        long,
+        value_name = "CODEC",
+        help = "Codec to recompress blocks with. Blocks are left unchanged if not set.",
+        display_order = 0
+    )]
+    pub block_compression: Option<Codec>,
+
+    #[clap(
+        long,
+        value_name = "CODEC",
+        help = "Codec to recompress restore point states with. States are left unchanged if \
+                not set.",
+        display_order = 0
+    )]
+    pub state_compression: Option<Codec>,
+
+    #[clap(
+        long,
+        value_name = "CODEC",
+        help = "Codec to recompress blobs with. Blobs are left unchanged if not set.",
+        display_order = 0
+    )]
+    pub blob_compression: Option<Codec>,
+
+    #[clap(
+        long,
+        value_name = "LEVEL",
+        default_value_t = DEFAULT_ZSTD_COMPRESSION_LEVEL,
+        help = "Compression level used for values compressed with zstd.",
+        display_order = 0
+    )]
+    pub zstd_compression_level: i32,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(about = "Manage the slasher database.")]
+pub struct Slasher {
+    #[clap(subcommand)]
+    pub subcommand: SlasherSubcommand,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub enum SlasherSubcommand {
+    Convert(SlasherConvert),
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Copy the slasher database into a new directory using a different backend. \
+             This must only be run while the database is offline: the beacon node or standalone \
+             slasher using it must be stopped while the database is converted."
+)]
+pub struct SlasherConvert {
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory of the existing slasher database. Defaults to the `slasher_db` \
+                directory within the beacon node's data directory.",
+        display_order = 0
+    )]
+    pub slasher_dir: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "BACKEND",
+        help = "Backend of the existing slasher database: mdbx, lmdb or redb",
+        display_order = 0
+    )]
+    pub from_backend: String,
+
+    #[clap(
+        long,
+        value_name = "BACKEND",
+        help = "Backend of the new slasher database: mdbx, lmdb or redb",
+        display_order = 0
+    )]
+    pub to_backend: String,
+
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory to create the new slasher database in. Must not contain a database.",
+        display_order = 0
+    )]
+    pub output_dir: PathBuf,
+
+    #[clap(
+        long,
+        value_name = "GIGABYTES",
+        help = "Maximum size of the slasher databases in gigabytes, as set by the beacon \
+                node's --slasher-max-db-size flag. [default: 512]",
+        display_order = 0
+    )]
+    pub max_db_size: Option<usize>,
+
+    #[clap(
+        long,
+        help = "Convert the database even if it contains no entries. By default an empty \
+                database is refused, as it usually means that the wrong directory or backend \
+                was given.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub allow_empty: bool,
+}
@@ -9,7 +9,7 @@ use beacon_chain::{
 use beacon_node::{get_data_dir, get_slots_per_restore_point, ClientConfig};
 use clap::ArgMatches;
 use clap::ValueEnum;
-use cli::{Compact, Inspect};
+use cli::{Compact, Inspect, Recompress, SlasherConvert, SnapshotCreate, SnapshotRestore, Verify};
 use environment::{Environment, RuntimeContext};
 use serde::{Deserialize, Serialize};
 use slog::{info, warn, Logger};
@@ -20,7 +20,7 @@ use store::metadata::STATE_UPPER_LIMIT_NO_RETAIN;
 use store::{
     errors::Error,
     metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION},
-    DBColumn, HotColdDB, KeyValueStore, LevelDB,
+    Codec, DBColumn, HotColdDB, KeyValueStore, LevelDB,
 };
 use strum::{EnumString, EnumVariantNames};
 use types::{BeaconState, EthSpec, Slot};
@@ -46,6 +46,10 @@ fn parse_client_config<E: EthSpec>(
     client_config.store.slots_per_restore_point = sprp;
     client_config.store.slots_per_restore_point_set_explicitly = sprp_explicit;
     client_config.store.blob_prune_margin_epochs = database_manager_config.blob_prune_margin_epochs;
+    client_config
+        .store
+        .blob_archive_dir
+        .clone_from(&database_manager_config.blob_archive_dir);
 
     Ok(client_config)
 }
@@ -473,6 +477,280 @@ pub fn prune_states<E: EthSpec>(
     Ok(())
 }
 
+pub struct VerifyConfig {
+    repair: bool,
+    output_file: Option<PathBuf>,
+}
+
+fn parse_verify_config(verify_config: &Verify) -> Result<VerifyConfig, String> {
+    Ok(VerifyConfig {
+        repair: verify_config.repair,
+        output_file: verify_config.output_file.clone(),
+    })
+}
+
+pub fn verify_db<E: EthSpec>(
+    verify_config: VerifyConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    // Open the database without migrating it or collecting garbage, so that the verifier sees
+    // it as it was found.
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open_for_verification(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))?;
+
+    let report = db
+        .verify_database(verify_config.repair)
+        .map_err(|e| format!("Unable to verify database: {e:?}"))?;
+    let report_json = serde_json::to_string_pretty(&report)
+        .map_err(|e| format!("Unable to serialize report: {e:?}"))?;
+
+    match verify_config.output_file {
+        Some(path) => fs::write(&path, report_json)
+            .map_err(|e| format!("Unable to write report to {}: {e:?}", path.display()))?,
+        None => println!("{report_json}"),
+    }
+
+    if report.is_consistent() {
+        info!(
+            log,
+            "Database verified";
+            "issues" => report.issues.len(),
+            "repaired" => report.repaired,
+        );
+        Ok(())
+    } else {
+        Err(format!(
+            "Database has {} unrepaired issues",
+            report.issues.len() as u64 - report.repaired
+        ))
+    }
+}
+
+pub struct RecompressConfig {
+    columns: Vec<(DBColumn, Codec)>,
+    zstd_compression_level: i32,
+}
+
+fn parse_recompress_config(recompress_config: &Recompress) -> Result<RecompressConfig, String> {
+    let columns = [
+        (DBColumn::BeaconBlock, recompress_config.block_compression),
+        (DBColumn::BeaconState, recompress_config.state_compression),
+        (DBColumn::BeaconBlob, recompress_config.blob_compression),
+    ]
+    .into_iter()
+    .filter_map(|(column, codec)| Some((column, codec?)))
+    .collect::<Vec<_>>();
+
+    if columns.is_empty() {
+        return Err(
+            "At least one of --block-compression, --state-compression or --blob-compression \
+             must be set"
+                .to_string(),
+        );
+    }
+
+    Ok(RecompressConfig {
+        columns,
+        zstd_compression_level: recompress_config.zstd_compression_level,
+    })
+}
+
+pub fn recompress_db<E: EthSpec>(
+    recompress_config: RecompressConfig,
+    mut client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    client_config.store.zstd_compression_level = recompress_config.zstd_compression_level;
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))?;
+
+    for (column, codec) in recompress_config.columns {
+        db.recompress_column(column, codec)
+            .map_err(|e| format!("Unable to recompress {column:?}: {e:?}"))?;
+    }
+
+    info!(
+        log,
+        "Database recompressed";
+        "info" => "set the same codecs on the beacon node to compress new values",
+    );
+    Ok(())
+}
+
+pub struct SnapshotCreateConfig {
+    output_dir: PathBuf,
+}
+
+fn parse_snapshot_create_config(
+    create_config: &SnapshotCreate,
+) -> Result<SnapshotCreateConfig, String> {
+    Ok(SnapshotCreateConfig {
+        output_dir: create_config.output_dir.clone(),
+    })
+}
+
+pub fn create_snapshot<E: EthSpec>(
+    create_config: SnapshotCreateConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))?;
+
+    let manifest = db
+        .snapshot_database(&create_config.output_dir)
+        .map_err(|e| format!("Unable to create snapshot: {e:?}"))?;
+
+    info!(
+        log,
+        "Snapshot created";
+        "schema_version" => manifest.schema_version,
+        "split_slot" => manifest.split.slot,
+        "path" => ?create_config.output_dir,
+    );
+    Ok(())
+}
+
+pub struct SnapshotRestoreConfig {
+    snapshot_dir: PathBuf,
+}
+
+fn parse_snapshot_restore_config(
+    restore_config: &SnapshotRestore,
+) -> Result<SnapshotRestoreConfig, String> {
+    Ok(SnapshotRestoreConfig {
+        snapshot_dir: restore_config.snapshot_dir.clone(),
+    })
+}
+
+pub fn restore_snapshot<E: EthSpec>(
+    restore_config: SnapshotRestoreConfig,
+    client_config: ClientConfig,
+    log: Logger,
+) -> Result<(), String> {
+    let manifest = store::snapshot::restore_snapshot::<E>(
+        &restore_config.snapshot_dir,
+        &client_config.get_db_path(),
+        &client_config.get_freezer_db_path(),
+        &client_config.get_blobs_db_path(),
+        &log,
+    )
+    .map_err(|e| format!("Unable to restore snapshot: {e:?}"))?;
+
+    info!(
+        log,
+        "Snapshot restored";
+        "schema_version" => manifest.schema_version,
+        "split_slot" => manifest.split.slot,
+        "advice" => "start the beacon node with the same data directory to resume from the snapshot",
+    );
+    Ok(())
+}
+
+pub struct SlasherConvertConfig {
+    from: slasher::Config,
+    to: slasher::Config,
+    allow_empty: bool,
+}
+
+fn parse_slasher_convert_config(
+    client_config: &ClientConfig,
+    convert_config: &SlasherConvert,
+) -> Result<SlasherConvertConfig, String> {
+    let parse_backend = |backend: &str| {
+        backend
+            .parse::<slasher::DatabaseBackend>()
+            .map_err(|e| format!("Invalid slasher backend {backend:?}: {e}"))
+    };
+    let slasher_dir = convert_config.slasher_dir.clone().unwrap_or_else(|| {
+        client_config
+            .data_dir()
+            .join(slasher::config::DEFAULT_SLASHER_DIR)
+    });
+
+    let mut from = slasher::Config::new(slasher_dir);
+    from.backend = parse_backend(&convert_config.from_backend)?;
+    if let Some(max_db_size_gbs) = convert_config.max_db_size {
+        from.max_db_size_mbs = max_db_size_gbs * 1024;
+    }
+
+    let mut to = from.clone();
+    to.database_path.clone_from(&convert_config.output_dir);
+    to.backend = parse_backend(&convert_config.to_backend)?;
+
+    Ok(SlasherConvertConfig {
+        from,
+        to,
+        allow_empty: convert_config.allow_empty,
+    })
+}
+
+pub fn convert_slasher_db(convert_config: SlasherConvertConfig, log: Logger) -> Result<(), String> {
+    let tables = slasher::convert_database(
+        &convert_config.from,
+        &convert_config.to,
+        convert_config.allow_empty,
+        &log,
+    )
+    .map_err(|e| format!("Unable to convert slasher database: {e:?}"))?;
+    let entries = tables.iter().map(|table| table.entries).sum::<u64>();
+
+    info!(
+        log,
+        "Slasher database converted";
+        "entries" => entries,
+        "path" => ?convert_config.to.database_path,
+        "advice" => format!(
+            "replace the slasher directory with the new database and run the beacon node \
+             with --slasher-backend {}",
+            convert_config.to.backend
+        ),
+    );
+    Ok(())
+}
+
 /// Run the database manager, returning an error string if the operation did not succeed.
 pub fn run<E: EthSpec>(
     cli_args: &ArgMatches,
@@ -530,5 +808,34 @@ pub fn run<E: EthSpec>(
             let compact_config = parse_compact_config(compact_config)?;
             compact_db::<E>(compact_config, client_config, log).map_err(format_err)
         }
+        cli::DatabaseManagerSubcommand::Verify(verify_config) => {
+            let verify_config = parse_verify_config(verify_config)?;
+            verify_db(verify_config, client_config, &context, log)
+        }
+        cli::DatabaseManagerSubcommand::Snapshot(snapshot_config) => {
+            match &snapshot_config.subcommand {
+                cli::SnapshotSubcommand::Create(create_config) => {
+                    let create_config = parse_snapshot_create_config(create_config)?;
+                    create_snapshot(create_config, client_config, &context, log)
+                }
+                cli::SnapshotSubcommand::Restore(restore_config) => {
+                    let restore_config = parse_snapshot_restore_config(restore_config)?;
+                    restore_snapshot::<E>(restore_config, client_config, log)
+                }
+            }
+        }
+        cli::DatabaseManagerSubcommand::Recompress(recompress_config) => {
+            let recompress_config = parse_recompress_config(recompress_config)?;
+            recompress_db(recompress_config, client_config, &context, log)
+        }
+        cli::DatabaseManagerSubcommand::Slasher(slasher_config) => {
+            match &slasher_config.subcommand {
+                cli::SlasherSubcommand::Convert(convert_config) => {
+                    let convert_config =
+                        parse_slasher_convert_config(&client_config, convert_config)?;
+                    convert_slasher_db(convert_config, log)
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,183 @@
+//! # Fork Choice Replay
+//!
+//! Use this tool to replay the fork choice inputs recorded by a beacon node running with
+//! `--fork-choice-recording-dir`, printing the head and its weight each time the head was
+//! computed.
+//!
+//! Each line of output is a JSON object describing one input. Inputs which compute the head
+//! include the head found by the replay, the head which was found by the beacon node and whether
+//! they match. This makes it possible to reproduce the behaviour of fork choice during an incident
+//! and to extract a minimal sequence of inputs as a test case.
+//!
+//! ## Examples
+//!
+//! ```ignore
+//! lcli fork-choice-replay \
+//!     --snapshot /tmp/fork-choice/1700000000/fork_choice.ssz \
+//!     --inputs /tmp/fork-choice/1700000000/inputs.jsonl \
+//!     --weights
+//! ```
+use crate::transition_blocks::load_from_ssz_with;
+use beacon_chain::persisted_fork_choice::PersistedForkChoice;
+use clap::ArgMatches;
+use clap_utils::parse_required;
+use eth2_network_config::Eth2NetworkConfig;
+use fork_choice::{ForkChoiceInput, ForkChoiceReplay};
+use log::{info, warn};
+use proto_array::ProtoArrayForkChoice;
+use serde::Serialize;
+use ssz::Decode;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use types::{Checkpoint, EthSpec, Hash256, Slot};
+
+#[derive(Serialize)]
+struct ReplayStep {
+    step: usize,
+    input: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    head: Option<HeadSummary>,
+}
+
+#[derive(Serialize)]
+struct HeadSummary {
+    current_slot: Slot,
+    justified_checkpoint: Checkpoint,
+    finalized_checkpoint: Checkpoint,
+    head_root: Hash256,
+    head_weight: Option<u64>,
+    recorded_head_root: Hash256,
+    matches: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weights: Option<Vec<NodeWeight>>,
+}
+
+#[derive(Serialize)]
+struct NodeWeight {
+    root: Hash256,
+    slot: Slot,
+    weight: u64,
+}
+
+pub fn run<E: EthSpec>(
+    network_config: Eth2NetworkConfig,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let spec = &network_config.chain_spec::<E>()?;
+
+    let snapshot_path: PathBuf = parse_required(matches, "snapshot")?;
+    let inputs_path: PathBuf = parse_required(matches, "inputs")?;
+    let print_weights = matches.get_flag("weights");
+
+    info!("Snapshot path: {:?}", snapshot_path);
+    let snapshot = load_from_ssz_with(&snapshot_path, spec, |bytes, _| {
+        PersistedForkChoice::from_ssz_bytes(bytes)
+    })?;
+    let proto_array = ProtoArrayForkChoice::from_bytes(&snapshot.fork_choice.proto_array_bytes)
+        .map_err(|e| format!("Invalid proto array in snapshot: {}", e))?;
+    let mut replay = ForkChoiceReplay::new(
+        proto_array,
+        snapshot.fork_choice_store.justified_balances,
+        snapshot.fork_choice_store.equivocating_indices,
+    );
+
+    info!("Inputs path: {:?}", inputs_path);
+    let inputs = File::open(&inputs_path)
+        .map_err(|e| format!("Unable to open file {:?}: {:?}", inputs_path, e))?;
+
+    let mut mismatches = 0;
+    let mut step = 0;
+    for line in BufReader::new(inputs).lines() {
+        let line = line.map_err(|e| format!("Unable to read inputs: {:?}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let input: ForkChoiceInput = serde_json::from_str(&line)
+            .map_err(|e| format!("Invalid input at step {}: {:?}", step, e))?;
+
+        let (input_name, find_head_params) = match &input {
+            ForkChoiceInput::Block { .. } => ("block", None),
+            ForkChoiceInput::Attestation { .. } => ("attestation", None),
+            ForkChoiceInput::FindHead {
+                current_slot,
+                justified_checkpoint,
+                finalized_checkpoint,
+                head_root,
+                ..
+            } => (
+                "find_head",
+                Some((
+                    *current_slot,
+                    *justified_checkpoint,
+                    *finalized_checkpoint,
+                    *head_root,
+                )),
+            ),
+            ForkChoiceInput::ValidPayload { .. } => ("valid_payload", None),
+            ForkChoiceInput::InvalidPayload { .. } => ("invalid_payload", None),
+            ForkChoiceInput::Prune { .. } => ("prune", None),
+        };
+
+        let head_root = replay
+            .apply::<E>(input, spec)
+            .map_err(|e| format!("Failed to apply input at step {}: {}", step, e))?;
+
+        let head = if let (
+            Some(head_root),
+            Some((current_slot, justified_checkpoint, finalized_checkpoint, recorded_head_root)),
+        ) = (head_root, find_head_params)
+        {
+            let proto_array = replay.proto_array();
+            let weights = print_weights.then(|| {
+                proto_array
+                    .core_proto_array()
+                    .nodes
+                    .iter()
+                    .map(|node| NodeWeight {
+                        root: node.root,
+                        slot: node.slot,
+                        weight: node.weight,
+                    })
+                    .collect()
+            });
+            Some(HeadSummary {
+                current_slot,
+                justified_checkpoint,
+                finalized_checkpoint,
+                head_root,
+                head_weight: proto_array.get_weight(&head_root),
+                recorded_head_root,
+                matches: head_root == recorded_head_root,
+                weights,
+            })
+        } else {
+            None
+        };
+
+        if let Some(head) = head.as_ref().filter(|head| !head.matches) {
+            mismatches += 1;
+            warn!(
+                "Head mismatch at step {}: replayed {:?}, recorded {:?}",
+                step, head.head_root, head.recorded_head_root
+            );
+        }
+
+        let output = serde_json::to_string(&ReplayStep {
+            step,
+            input: input_name,
+            head,
+        })
+        .map_err(|e| format!("Unable to serialize step {}: {:?}", step, e))?;
+        println!("{}", output);
+
+        step += 1;
+    }
+
+    info!(
+        "Replayed {} inputs with {} head mismatches",
+        step, mismatches
+    );
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 mod block_root;
 mod check_deposit_data;
+mod fork_choice_replay;
 mod generate_bootnode_enr;
 mod indexed_attestations;
 mod mnemonic_validators;
@@ -482,6 +483,39 @@ fn main() {
                         .display_order(0)
                 )
         )
+        .subcommand(
+            Command::new("fork-choice-replay")
+                .about("Replays the fork choice inputs recorded by a beacon node running with \
+                --fork-choice-recording-dir, printing the head after each input as JSON.")
+                .arg(
+                    Arg::new("snapshot")
+                        .long("snapshot")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("Path to the fork choice snapshot (fork_choice.ssz) from which \
+                            recording started.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("inputs")
+                        .long("inputs")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("Path to the recorded fork choice inputs (inputs.jsonl).")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("weights")
+                        .long("weights")
+                        .action(ArgAction::SetTrue)
+                        .help_heading(FLAG_HEADER)
+                        .help("Print the weight of every block in fork choice each time the head \
+                            is computed.")
+                        .display_order(0)
+                )
+        )
         .subcommand(
             Command::new("mock-el")
                 .about("Creates a mock execution layer server. This is NOT SAFE and should only \
@@ -654,6 +688,11 @@ fn run<E: EthSpec>(env_builder: EnvironmentBuilder<E>, matches: &ArgMatches) ->
             state_root::run::<E>(env, network_config, matches)
                 .map_err(|e| format!("Failed to run state-root command: {}", e))
         }
+        Some(("fork-choice-replay", matches)) => {
+            let network_config = get_network_config()?;
+            fork_choice_replay::run::<E>(network_config, matches)
+                .map_err(|e| format!("Failed to run fork-choice-replay command: {}", e))
+        }
         Some(("mock-el", matches)) => mock_el::run::<E>(env, matches)
             .map_err(|e| format!("Failed to run mock-el command: {}", e)),
         Some((other, _)) => Err(format!("Unknown subcommand {}. See --help.", other)),
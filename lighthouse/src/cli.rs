@@ -1,9 +1,12 @@
 use clap::Parser;
 use database_manager::cli::DatabaseManager;
 use serde::{Deserialize, Serialize};
+use slasher_standalone::cli::StandaloneSlasher;
 
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
 pub enum LighthouseSubcommands {
     #[clap(name = "database_manager")]
     DatabaseManager(DatabaseManager),
+    #[clap(name = "slasher")]
+    Slasher(StandaloneSlasher),
 }
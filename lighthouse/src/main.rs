@@ -759,6 +759,31 @@ fn run<E: EthSpec>(
                 "validator_client",
             );
         }
+        Some((slasher_standalone::CMD, _)) => {
+            let Ok(LighthouseSubcommands::Slasher(slasher_config)) =
+                LighthouseSubcommands::from_arg_matches(matches)
+            else {
+                return Err("Unable to parse slasher arguments".into());
+            };
+            let context = environment.core_context();
+            let log = context.log().clone();
+            let executor = context.executor.clone();
+            let config = slasher_standalone::Config::from_cli(matches, &slasher_config)?;
+
+            executor.clone().spawn(
+                async move {
+                    if let Err(e) = slasher_standalone::run(context, config).await {
+                        crit!(log, "Failed to start slasher"; "reason" => e);
+                        // Ignore the error since it always occurs during normal operation when
+                        // shutting down.
+                        let _ = executor
+                            .shutdown_sender()
+                            .try_send(ShutdownReason::Failure("Failed to start slasher"));
+                    }
+                },
+                "slasher",
+            );
+        }
         _ => {
             crit!(log, "No subcommand supplied. See --help .");
             return Err("No subcommand supplied.".into());
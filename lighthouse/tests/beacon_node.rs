@@ -206,6 +206,26 @@ fn fork_choice_before_proposal_timeout_zero() {
         .with_config(|config| assert_eq!(config.chain.fork_choice_before_proposal_timeout_ms, 0));
 }
 
+#[test]
+fn fork_choice_recording_dir_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.fork_choice_recording_dir, None));
+}
+
+#[test]
+fn fork_choice_recording_dir_flag() {
+    CommandLineTest::new()
+        .flag("fork-choice-recording-dir", Some("/tmp/fork-choice"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.fork_choice_recording_dir,
+                Some(PathBuf::from("/tmp/fork-choice"))
+            )
+        });
+}
+
 #[test]
 fn checkpoint_sync_url_timeout_flag() {
     CommandLineTest::new()
@@ -1837,6 +1857,19 @@ fn validator_monitor_metrics_threshold_custom() {
             assert_eq!(config.validator_monitor.individual_tracking_threshold, 42)
         });
 }
+#[test]
+fn validator_monitor_history_epochs_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.validator_monitor.history_retention_epochs, 0));
+}
+#[test]
+fn validator_monitor_history_epochs_custom() {
+    CommandLineTest::new()
+        .flag("validator-monitor-history-epochs", Some("1575"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.validator_monitor.history_retention_epochs, 1575));
+}
 
 // Tests for Store flags.
 #[test]
@@ -1925,6 +1958,37 @@ fn historic_state_cache_size_default() {
         });
 }
 #[test]
+fn db_compression_default() {
+    use beacon_node::beacon_chain::store::{config::DEFAULT_ZSTD_COMPRESSION_LEVEL, Codec};
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.store.block_compression, Codec::None);
+            assert_eq!(config.store.state_compression, Codec::None);
+            assert_eq!(config.store.blob_compression, Codec::None);
+            assert_eq!(
+                config.store.zstd_compression_level,
+                DEFAULT_ZSTD_COMPRESSION_LEVEL
+            );
+        });
+}
+#[test]
+fn db_compression_flags() {
+    use beacon_node::beacon_chain::store::Codec;
+    CommandLineTest::new()
+        .flag("block-compression", Some("zstd"))
+        .flag("state-compression", Some("zstd"))
+        .flag("blob-compression", Some("none"))
+        .flag("zstd-compression-level", Some("9"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.store.block_compression, Codec::Zstd);
+            assert_eq!(config.store.state_compression, Codec::Zstd);
+            assert_eq!(config.store.blob_compression, Codec::None);
+            assert_eq!(config.store.zstd_compression_level, 9);
+        });
+}
+#[test]
 fn auto_compact_db_flag() {
     CommandLineTest::new()
         .flag("auto-compact-db", Some("false"))
@@ -1992,6 +2056,35 @@ fn blob_prune_margin_epochs_on_startup_ten() {
         .with_config(|config| assert!(config.store.blob_prune_margin_epochs == 10));
 }
 #[test]
+fn blob_archive_dir_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.store.blob_archive_dir, None);
+            assert!(!config.chain.serve_archived_blobs_by_range);
+        });
+}
+#[test]
+fn blob_archive_dir_flag() {
+    CommandLineTest::new()
+        .flag("blob-archive-dir", Some("/tmp/blob-archive"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.blob_archive_dir,
+                Some(PathBuf::from("/tmp/blob-archive"))
+            )
+        });
+}
+#[test]
+fn serve_archived_blobs_by_range_flag() {
+    CommandLineTest::new()
+        .flag("blob-archive-dir", Some("/tmp/blob-archive"))
+        .flag("serve-archived-blobs-by-range", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.serve_archived_blobs_by_range));
+}
+#[test]
 fn reconstruct_historic_states_flag() {
     CommandLineTest::new()
         .flag("reconstruct-historic-states", None)
@@ -2110,6 +2203,69 @@ fn slasher_history_length_flag() {
         });
 }
 #[test]
+fn slasher_backfill_flags() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .flag("slasher-backfill-start-epoch", Some("100"))
+        .flag("slasher-backfill-end-epoch", Some("200"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config.slasher.as_ref().unwrap();
+            assert_eq!(slasher_config.backfill_start_epoch, Some(Epoch::new(100)));
+            assert_eq!(slasher_config.backfill_end_epoch, Some(Epoch::new(200)));
+        });
+}
+#[test]
+fn slasher_backfill_batch_epochs_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .flag("slasher-backfill-start-epoch", Some("100"))
+        .flag("slasher-backfill-batch-epochs", Some("32"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config.slasher.as_ref().unwrap();
+            assert_eq!(slasher_config.backfill_batch_epochs, 32);
+        });
+}
+#[test]
+fn slasher_backfill_default() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config.slasher.as_ref().unwrap();
+            assert_eq!(slasher_config.backfill_start_epoch, None);
+            assert_eq!(slasher_config.backfill_end_epoch, None);
+            assert_eq!(
+                slasher_config.backfill_batch_epochs,
+                slasher::config::DEFAULT_BACKFILL_BATCH_EPOCHS
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn slasher_backfill_end_before_start() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .flag("slasher-backfill-start-epoch", Some("200"))
+        .flag("slasher-backfill-end-epoch", Some("100"))
+        .run_with_zero_port();
+}
+#[test]
+#[should_panic]
+fn slasher_backfill_zero_batch_epochs() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .flag("slasher-backfill-start-epoch", Some("100"))
+        .flag("slasher-backfill-batch-epochs", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
 fn slasher_max_db_size_flag() {
     CommandLineTest::new()
         .flag("slasher", None)
@@ -2404,6 +2560,46 @@ fn monitoring_endpoint() {
         });
 }
 
+#[test]
+fn op_pool_sync_peers_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.op_pool_sync, None));
+}
+#[test]
+fn op_pool_sync_peers_flag() {
+    CommandLineTest::new()
+        .flag(
+            "op-pool-sync-peers",
+            Some("http://bn-1:5052,http://bn-2:5052"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            let peers = &config.op_pool_sync.as_ref().unwrap().peers;
+            assert_eq!(peers.len(), 2);
+            assert_eq!(peers[0].full.as_str(), "http://bn-1:5052/");
+            assert_eq!(peers[1].full.as_str(), "http://bn-2:5052/");
+            assert_eq!(config.op_pool_sync.as_ref().unwrap().api_key_file, None);
+        });
+}
+#[test]
+fn op_pool_sync_api_key_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("op-pool-sync-peers", Some("http://bn-1:5052"))
+        .flag(
+            "op-pool-sync-api-key-file",
+            dir.path().join("api-key.txt").to_str(),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.op_pool_sync.as_ref().unwrap().api_key_file,
+                Some(dir.path().join("api-key.txt"))
+            );
+        });
+}
+
 // Tests for Logger flags.
 #[test]
 fn default_log_color_flag() {
@@ -2508,6 +2704,27 @@ fn sync_eth1_chain_disable_deposit_contract_sync_flag() {
         .with_config(|config| assert_eq!(config.sync_eth1_chain, false));
 }
 
+#[test]
+fn gossip_propagation_analysis_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.network.gossip_propagation_analysis);
+            assert!(!config.chain.gossip_propagation_analysis);
+        });
+}
+
+#[test]
+fn gossip_propagation_analysis_enabled() {
+    CommandLineTest::new()
+        .flag("gossip-propagation-analysis", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.network.gossip_propagation_analysis);
+            assert!(config.chain.gossip_propagation_analysis);
+        });
+}
+
 #[test]
 fn light_client_server_default() {
     CommandLineTest::new()
@@ -2665,6 +2882,81 @@ fn http_sse_capacity_multiplier_override() {
         .with_config(|config| assert_eq!(config.http_api.sse_capacity_multiplier, 10));
 }
 
+#[test]
+fn http_event_log_default() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.event_log, None));
+}
+
+#[test]
+fn http_event_log_dir_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-event-log-dir", dir.path().as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            let event_log = config.http_api.event_log.as_ref().unwrap();
+            assert_eq!(event_log.dir, dir.path());
+            assert_eq!(
+                event_log
+                    .topics
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+                vec!["block", "head", "finalized_checkpoint", "chain_reorg"]
+            );
+            assert_eq!(event_log.max_events, 65_536);
+        });
+}
+
+#[test]
+fn http_event_log_topics_and_max_events_flags() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-event-log-dir", dir.path().as_os_str().to_str())
+        .flag("http-event-log-topics", Some("block,voluntary_exit"))
+        .flag("http-event-log-max-events", Some("1024"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let event_log = config.http_api.event_log.as_ref().unwrap();
+            assert_eq!(
+                event_log
+                    .topics
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+                vec!["block", "voluntary_exit"]
+            );
+            assert_eq!(event_log.max_events, 1024);
+        });
+}
+
+#[test]
+fn http_api_keys_file_default() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.api_keys_file, None));
+}
+
+#[test]
+fn http_api_keys_file_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-api-keys-file", Some("/tmp/api-keys.yaml"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_api.api_keys_file,
+                Some(PathBuf::from("/tmp/api-keys.yaml"))
+            )
+        });
+}
+
 #[test]
 fn http_duplicate_block_status_default() {
     CommandLineTest::new()
@@ -127,6 +127,23 @@ fn use_long_timeouts_flag() {
         .with_config(|config| assert!(config.use_long_timeouts));
 }
 
+#[test]
+fn beacon_nodes_api_key_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag(
+            "beacon-nodes-api-key-file",
+            dir.path().join("api-key.txt").to_str(),
+        )
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_nodes_api_key_file,
+                Some(dir.path().join("api-key.txt"))
+            )
+        });
+}
+
 #[test]
 fn beacon_nodes_tls_certs_flag() {
     let dir = TempDir::new().expect("Unable to create temporary directory");
@@ -627,6 +644,97 @@ fn validator_registration_batch_size_zero_value() {
         .run();
 }
 
+#[test]
+fn proposer_config_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("proposer_config.json");
+    let mut file = File::create(&path).expect("Unable to create file");
+    file.write_all(
+        br#"{
+            "default_config": { "gas_limit": 36000000 },
+            "proposer_config": {
+                "0x800012708dc03f611751aad7a43a082142832b5c1aceed07ff9b543cf836381861352aa923c70eeb02018b638aa306aa": {
+                    "fee_recipient": "0x00000000219ab540356cbb839cbe05303d7705fa"
+                }
+            }
+        }"#,
+    )
+    .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag("proposer-config-file", path.as_os_str().to_str())
+        .run()
+        .with_config(|config| assert_eq!(config.proposer_config_file, Some(path.clone())));
+}
+
+#[test]
+#[should_panic]
+fn proposer_config_file_invalid() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("proposer_config.json");
+    let mut file = File::create(&path).expect("Unable to create file");
+    file.write_all(br#"{ "default_config": { "gas_limit": 0 } }"#)
+        .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag("proposer-config-file", path.as_os_str().to_str())
+        .run();
+}
+
+#[test]
+fn shadow_flag() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(!config.shadow);
+    });
+    CommandLineTest::new()
+        .flag("shadow", None)
+        .run()
+        .with_config(|config| assert!(config.shadow));
+}
+
+#[test]
+#[should_panic]
+fn shadow_flag_conflicts_with_doppelganger_protection() {
+    CommandLineTest::new()
+        .flag("shadow", None)
+        .flag("enable-doppelganger-protection", None)
+        .run();
+}
+
+#[test]
+fn doppelganger_gossip_monitoring_flag() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(!config.doppelganger_gossip_monitoring);
+    });
+    CommandLineTest::new()
+        .flag("enable-doppelganger-protection", None)
+        .flag("doppelganger-gossip-monitoring", None)
+        .run()
+        .with_config(|config| assert!(config.doppelganger_gossip_monitoring));
+}
+
+#[test]
+#[should_panic]
+fn doppelganger_gossip_monitoring_requires_doppelganger_protection() {
+    CommandLineTest::new()
+        .flag("doppelganger-gossip-monitoring", None)
+        .run();
+}
+
+#[test]
+fn enable_validator_performance() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(!config.enable_validator_performance);
+        assert_eq!(config.validator_performance_retention_epochs, 8192);
+    });
+    CommandLineTest::new()
+        .flag("enable-validator-performance", None)
+        .flag("validator-performance-retention-epochs", Some("64"))
+        .run()
+        .with_config(|config| {
+            assert!(config.enable_validator_performance);
+            assert_eq!(config.validator_performance_retention_epochs, 64);
+        });
+}
+
 #[test]
 fn validator_disable_web3_signer_slashing_protection_default() {
     CommandLineTest::new().run().with_config(|config| {
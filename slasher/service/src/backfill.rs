@@ -0,0 +1,373 @@
+//! Feeds the blocks and attestations already stored in the database to the slasher, so that a
+//! newly enabled slasher covers recent history rather than only the messages it sees live.
+use beacon_chain::{BeaconChain, BeaconChainTypes, StateSkipConfig};
+use slasher::Slasher;
+use slog::{debug, error, info, warn, Logger};
+use state_processing::ConsensusContext;
+use std::cmp::{max, min};
+use std::sync::mpsc::SyncSender;
+use std::sync::Arc;
+use types::{Epoch, EthSpec, IndexedAttestation, RelativeEpoch, SignedBeaconBlockHeader};
+
+/// The blocks and attestations from a range of epochs, ready to be queued in the slasher.
+pub struct BackfillBatch<E: EthSpec> {
+    pub start_epoch: Epoch,
+    pub end_epoch: Epoch,
+    pub block_headers: Vec<SignedBeaconBlockHeader>,
+    pub attestations: Vec<IndexedAttestation<E>>,
+}
+
+impl<E: EthSpec> BackfillBatch<E> {
+    /// Add the blocks and attestations of this batch to the slasher's queues.
+    pub fn queue(self, slasher: &Slasher<E>) {
+        info!(
+            slasher.log(),
+            "Processing slasher backfill";
+            "start_epoch" => self.start_epoch,
+            "end_epoch" => self.end_epoch,
+            "num_blocks" => self.block_headers.len(),
+            "num_attestations" => self.attestations.len(),
+        );
+        for block_header in self.block_headers {
+            slasher.accept_block_header(block_header);
+        }
+        for attestation in self.attestations {
+            slasher.accept_attestation(attestation);
+        }
+    }
+}
+
+/// Returns the range of epochs to backfill, if backfill is enabled.
+///
+/// The configured range is clamped to the epochs that the slasher retains and the node has blocks
+/// for.
+pub fn backfill_range<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    log: &Logger,
+) -> Option<(Epoch, Epoch)> {
+    let config = beacon_chain.slasher.as_ref()?.config();
+    let requested_start_epoch = config.backfill_start_epoch?;
+
+    let current_epoch = match beacon_chain.epoch() {
+        Ok(epoch) => epoch,
+        Err(e) => {
+            error!(log, "Unable to read slot clock for slasher backfill"; "error" => ?e);
+            return None;
+        }
+    };
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+    let oldest_block_slot = beacon_chain.store.get_oldest_block_slot();
+    // The state at the start of the epoch containing the oldest block is not stored, so begin at
+    // the first full epoch.
+    let oldest_block_epoch = (oldest_block_slot + slots_per_epoch - 1).epoch(slots_per_epoch);
+    let oldest_slasher_epoch = current_epoch.saturating_sub(config.history_length as u64);
+
+    let start_epoch = max(
+        requested_start_epoch,
+        max(oldest_block_epoch, oldest_slasher_epoch),
+    );
+    let end_epoch = min(
+        config.backfill_end_epoch.unwrap_or(current_epoch),
+        current_epoch,
+    );
+
+    if start_epoch != requested_start_epoch {
+        warn!(
+            log,
+            "Slasher backfill start epoch adjusted";
+            "info" => "epochs before the node's oldest block or the slasher's history length \
+                       cannot be backfilled",
+            "requested_epoch" => requested_start_epoch,
+            "start_epoch" => start_epoch,
+        );
+    }
+    if end_epoch < start_epoch {
+        warn!(
+            log,
+            "Skipping slasher backfill";
+            "info" => "no blocks are available in the requested range",
+            "start_epoch" => start_epoch,
+            "end_epoch" => end_epoch,
+        );
+        return None;
+    }
+    Some((start_epoch, end_epoch))
+}
+
+/// Load the blocks of `start_epoch..=end_epoch` from the database and send them to the processor
+/// in batches of `batch_epochs` epochs.
+///
+/// The channel holds a single batch, so loading stays at most one batch ahead of the processor.
+pub fn run_backfill<T: BeaconChainTypes>(
+    beacon_chain: Arc<BeaconChain<T>>,
+    start_epoch: Epoch,
+    end_epoch: Epoch,
+    batch_epochs: u64,
+    batch_sender: SyncSender<BackfillBatch<T::EthSpec>>,
+    log: Logger,
+) {
+    info!(
+        log,
+        "Starting slasher backfill";
+        "start_epoch" => start_epoch,
+        "end_epoch" => end_epoch,
+    );
+
+    let mut batch_start_epoch = start_epoch;
+    while batch_start_epoch <= end_epoch {
+        let batch_end_epoch = min(batch_start_epoch + batch_epochs - 1, end_epoch);
+
+        let mut batch = BackfillBatch {
+            start_epoch: batch_start_epoch,
+            end_epoch: batch_end_epoch,
+            block_headers: vec![],
+            attestations: vec![],
+        };
+        for epoch in batch_start_epoch.as_u64()..=batch_end_epoch.as_u64() {
+            if let Err(e) = load_epoch(&beacon_chain, Epoch::new(epoch), &mut batch, &log) {
+                error!(
+                    log,
+                    "Slasher backfill failed";
+                    "error" => e,
+                    "epoch" => epoch,
+                );
+                return;
+            }
+        }
+
+        // The receiver is dropped when the processor shuts down.
+        if batch_sender.send(batch).is_err() {
+            return;
+        }
+        batch_start_epoch = batch_end_epoch + 1;
+    }
+
+    info!(
+        log,
+        "Finished loading slasher backfill";
+        "start_epoch" => start_epoch,
+        "end_epoch" => end_epoch,
+    );
+}
+
+/// Add the canonical blocks of `epoch`, and the attestations they contain, to `batch`.
+fn load_epoch<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    epoch: Epoch,
+    batch: &mut BackfillBatch<T::EthSpec>,
+    log: &Logger,
+) -> Result<(), String> {
+    let spec = &beacon_chain.spec;
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+    let start_slot = epoch.start_slot(slots_per_epoch);
+    let head_slot = beacon_chain.best_slot();
+    if start_slot > head_slot {
+        return Ok(());
+    }
+    let end_slot = min(epoch.end_slot(slots_per_epoch), head_slot);
+
+    // Blocks in `epoch` contain attestations from `epoch` and the epoch before it, so the
+    // committees of both are available from the state at its first slot.
+    let mut state = beacon_chain
+        .state_at_slot(start_slot, StateSkipConfig::WithoutStateRoots)
+        .map_err(|e| format!("unable to load state at slot {}: {:?}", start_slot, e))?;
+    for relative_epoch in [RelativeEpoch::Previous, RelativeEpoch::Current] {
+        state
+            .build_committee_cache(relative_epoch, spec)
+            .map_err(|e| format!("unable to build committee cache: {:?}", e))?;
+    }
+
+    let block_roots = beacon_chain
+        .forwards_iter_block_roots_until(start_slot, end_slot)
+        .map_err(|e| format!("unable to iterate block roots: {:?}", e))?;
+    let mut previous_block_root = None;
+    for result in block_roots {
+        let (block_root, _) =
+            result.map_err(|e| format!("unable to iterate block roots: {:?}", e))?;
+        // The same root is returned for each skipped slot.
+        if previous_block_root == Some(block_root) {
+            continue;
+        }
+        previous_block_root = Some(block_root);
+
+        let block = beacon_chain
+            .get_blinded_block(&block_root)
+            .map_err(|e| format!("unable to load block {:?}: {:?}", block_root, e))?
+            .ok_or_else(|| format!("missing block {:?}", block_root))?;
+        // Skipped slots at the start of the epoch return the last block of the previous epoch.
+        if block.slot() < start_slot {
+            continue;
+        }
+        batch.block_headers.push(block.signed_block_header());
+
+        let mut ctxt = ConsensusContext::new(block.slot());
+        for attestation in block.message().body().attestations() {
+            match ctxt.get_indexed_attestation(&state, attestation) {
+                Ok(indexed) => batch
+                    .attestations
+                    .push(indexed.clone_as_indexed_attestation()),
+                Err(e) => debug!(
+                    log,
+                    "Failed to get indexed attestation";
+                    "purpose" => "slasher backfill",
+                    "attestation_slot" => attestation.data().slot,
+                    "error" => ?e,
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::{
+        test_spec, AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
+    };
+    use logging::test_logger;
+    use slasher::Config;
+    use std::collections::HashSet;
+    use std::sync::mpsc::sync_channel;
+    use tempfile::{tempdir, TempDir};
+    use types::{MinimalEthSpec, Slot};
+
+    type E = MinimalEthSpec;
+
+    const VALIDATOR_COUNT: usize = 32;
+    const NUM_EPOCHS: u64 = 5;
+    const BATCH_EPOCHS: u64 = 2;
+
+    type Harness = BeaconChainHarness<EphemeralHarnessType<E>>;
+
+    /// Build a chain of `NUM_EPOCHS` epochs of attested blocks with a slasher attached.
+    async fn harness_with_slasher(
+        backfill_start_epoch: Epoch,
+    ) -> (Harness, Arc<Slasher<E>>, TempDir) {
+        let slasher_dir = tempdir().unwrap();
+        let mut slasher_config = Config::new(slasher_dir.path().into());
+        slasher_config.backfill_start_epoch = Some(backfill_start_epoch);
+        slasher_config.backfill_batch_epochs = BATCH_EPOCHS;
+        let spec = test_spec::<E>();
+        let slasher =
+            Arc::new(Slasher::open(slasher_config, Arc::new(spec.clone()), test_logger()).unwrap());
+
+        let inner_slasher = slasher.clone();
+        let harness = BeaconChainHarness::builder(E::default())
+            .spec(spec)
+            .deterministic_keypairs(VALIDATOR_COUNT)
+            .fresh_ephemeral_store()
+            .initial_mutator(Box::new(move |builder| builder.slasher(inner_slasher)))
+            .mock_execution_layer()
+            .build();
+        harness.advance_slot();
+        harness
+            .extend_chain(
+                (NUM_EPOCHS * E::slots_per_epoch()) as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        // Discard the messages which the slasher was given while the chain was built.
+        slasher
+            .process_queued(harness.chain.epoch().unwrap())
+            .unwrap();
+
+        (harness, slasher, slasher_dir)
+    }
+
+    #[tokio::test]
+    async fn backfill_range_is_clamped_to_current_epoch() {
+        let (harness, _slasher, _slasher_dir) = harness_with_slasher(Epoch::new(1)).await;
+        let current_epoch = harness.chain.epoch().unwrap();
+
+        assert_eq!(
+            backfill_range(&harness.chain, &test_logger()),
+            Some((Epoch::new(1), current_epoch))
+        );
+    }
+
+    #[tokio::test]
+    async fn backfill_range_after_current_epoch_is_skipped() {
+        let (harness, _slasher, _slasher_dir) =
+            harness_with_slasher(Epoch::new(NUM_EPOCHS + 10)).await;
+
+        assert_eq!(backfill_range(&harness.chain, &test_logger()), None);
+    }
+
+    #[tokio::test]
+    async fn backfilled_blocks_and_attestations_reach_slasher() {
+        let (harness, slasher, _slasher_dir) = harness_with_slasher(Epoch::new(0)).await;
+        let (start_epoch, end_epoch) = backfill_range(&harness.chain, &test_logger()).unwrap();
+
+        let (batch_sender, batch_receiver) = sync_channel(1);
+        let chain = harness.chain.clone();
+        let loader = std::thread::spawn(move || {
+            run_backfill(
+                chain,
+                start_epoch,
+                end_epoch,
+                BATCH_EPOCHS,
+                batch_sender,
+                test_logger(),
+            )
+        });
+        let batches = batch_receiver.iter().collect::<Vec<_>>();
+        loader.join().unwrap();
+
+        // Batches cover the range in order, `BATCH_EPOCHS` at a time.
+        let expected_batches = (end_epoch - start_epoch + 1)
+            .as_u64()
+            .div_ceil(BATCH_EPOCHS);
+        assert_eq!(batches.len() as u64, expected_batches);
+        let mut next_epoch = start_epoch;
+        for batch in &batches {
+            assert_eq!(batch.start_epoch, next_epoch);
+            assert!(batch.end_epoch < batch.start_epoch + BATCH_EPOCHS);
+            next_epoch = batch.end_epoch + 1;
+        }
+        assert_eq!(next_epoch, end_epoch + 1);
+
+        // Every canonical block is loaded once, along with every attestation it contains.
+        let block_slots = batches
+            .iter()
+            .flat_map(|batch| &batch.block_headers)
+            .map(|header| header.message.slot)
+            .collect::<Vec<_>>();
+        let head_slot = harness.chain.head_snapshot().beacon_block.slot();
+        assert_eq!(
+            block_slots.iter().copied().collect::<HashSet<_>>(),
+            (0..=head_slot.as_u64()).map(Slot::new).collect()
+        );
+        assert_eq!(block_slots.len() as u64, head_slot.as_u64() + 1);
+
+        let mut expected_attestations = 0;
+        for header in batches.iter().flat_map(|batch| &batch.block_headers) {
+            let block = harness
+                .chain
+                .get_blinded_block(&header.message.canonical_root())
+                .unwrap()
+                .unwrap();
+            expected_attestations += block.message().body().attestations_len();
+        }
+        let num_attestations = batches
+            .iter()
+            .map(|batch| batch.attestations.len())
+            .sum::<usize>();
+        assert!(num_attestations > 0);
+        assert_eq!(num_attestations, expected_attestations);
+
+        // Queue the batches as the slasher service does and check that the slasher processes them.
+        for batch in batches {
+            batch.queue(&slasher);
+        }
+        let stats = slasher
+            .process_queued(harness.chain.epoch().unwrap())
+            .unwrap();
+        assert_eq!(stats.block_stats.num_processed, block_slots.len());
+        assert_eq!(stats.attestation_stats.num_processed, num_attestations);
+        assert_eq!(stats.block_stats.num_slashings, 0);
+        assert!(slasher.get_attester_slashings().is_empty());
+    }
+}
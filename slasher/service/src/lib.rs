@@ -1,3 +1,4 @@
+mod backfill;
 mod service;
 
 pub use service::SlasherService;
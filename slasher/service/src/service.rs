@@ -1,3 +1,4 @@
+use crate::backfill::{backfill_range, run_backfill, BackfillBatch};
 use beacon_chain::{
     observed_operations::ObservationOutcome, BeaconChain, BeaconChainError, BeaconChainTypes,
 };
@@ -16,7 +17,7 @@ use state_processing::{
     },
     VerifyOperation,
 };
-use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TryRecvError, TrySendError};
 use std::sync::Arc;
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc::UnboundedSender;
@@ -59,6 +60,32 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         let beacon_chain = self.beacon_chain.clone();
         let network_sender = self.network_sender.clone();
 
+        // Backfill batches are picked up by the processor alongside live messages, so only one
+        // batch is loaded ahead of it at a time.
+        let backfill_receiver =
+            if let Some((start_epoch, end_epoch)) = backfill_range(&beacon_chain, &log) {
+                let (batch_sender, batch_receiver) = sync_channel(1);
+                let batch_epochs = slasher.config().backfill_batch_epochs;
+                let beacon_chain = beacon_chain.clone();
+                let log = log.clone();
+                executor.spawn_blocking(
+                    move || {
+                        run_backfill(
+                            beacon_chain,
+                            start_epoch,
+                            end_epoch,
+                            batch_epochs,
+                            batch_sender,
+                            log,
+                        )
+                    },
+                    "slasher_server_backfill",
+                );
+                Some(batch_receiver)
+            } else {
+                None
+            };
+
         executor.spawn(
             Self::run_notifier(
                 beacon_chain.clone(),
@@ -71,7 +98,15 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         );
 
         executor.spawn_blocking(
-            || Self::run_processor(beacon_chain, slasher, notif_receiver, network_sender),
+            || {
+                Self::run_processor(
+                    beacon_chain,
+                    slasher,
+                    notif_receiver,
+                    backfill_receiver,
+                    network_sender,
+                )
+            },
             "slasher_server_processor",
         );
 
@@ -114,12 +149,23 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         beacon_chain: Arc<BeaconChain<T>>,
         slasher: Arc<Slasher<T::EthSpec>>,
         notif_receiver: Receiver<Epoch>,
+        mut backfill_receiver: Option<Receiver<BackfillBatch<T::EthSpec>>>,
         network_sender: UnboundedSender<NetworkMessage<T::EthSpec>>,
     ) {
         let log = slasher.log();
         while let Ok(current_epoch) = notif_receiver.recv() {
             let t = Instant::now();
 
+            // Queue at most one batch of historical messages per update, so that live messages
+            // are never delayed by more than one batch.
+            if let Some(receiver) = &backfill_receiver {
+                match receiver.try_recv() {
+                    Ok(batch) => batch.queue(&slasher),
+                    Err(TryRecvError::Empty) => (),
+                    Err(TryRecvError::Disconnected) => backfill_receiver = None,
+                }
+            }
+
             let batch_timer = metrics::start_timer(&SLASHER_RUN_TIME);
             let stats = match slasher.process_queued(current_epoch) {
                 Ok(stats) => Some(stats),
@@ -1,7 +1,7 @@
 use crate::metrics::{self, SLASHER_COMPRESSION_RATIO, SLASHER_NUM_CHUNKS_UPDATED};
 use crate::{
-    AttesterSlashingStatus, Config, Database, Error, IndexedAttesterRecord, RwTransaction,
-    SlasherDB,
+    AttesterSlashingStatus, Config, Database, Error, IndexedAttesterRecord, ReadTransaction,
+    RwTransaction, SlasherDB,
 };
 use flate2::bufread::{ZlibDecoder, ZlibEncoder};
 use serde::{Deserialize, Serialize};
@@ -13,6 +13,18 @@ use types::{AttesterSlashing, Epoch, EthSpec, IndexedAttestation};
 
 pub const MAX_DISTANCE: u16 = u16::MAX;
 
+/// The min and max targets of a validator's attestations for a source epoch.
+///
+/// The min target is the lowest target of any attestation with a source after `source_epoch`,
+/// and the max target is the highest target of any attestation with a source before it. Either
+/// is `None` if there is no such attestation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetBounds {
+    pub source_epoch: Epoch,
+    pub min_target: Option<Epoch>,
+    pub max_target: Option<Epoch>,
+}
+
 /// Terminology:
 ///
 /// Let
@@ -151,7 +163,7 @@ pub trait TargetArrayChunk: Sized + serde::Serialize + serde::de::DeserializeOwn
 
     fn load<E: EthSpec>(
         db: &SlasherDB<E>,
-        txn: &mut RwTransaction<'_>,
+        txn: &impl ReadTransaction,
         validator_chunk_index: usize,
         chunk_index: usize,
         config: &Config,
@@ -394,9 +406,67 @@ impl TargetArrayChunk for MaxTargetChunk {
     }
 }
 
+/// Read the min and max targets of `validator_index` for each source epoch in
+/// `start_epoch..=end_epoch`.
+///
+/// Only the `history_length` epochs up to the validator's current epoch are available.
+pub fn get_target_bounds<E: EthSpec>(
+    db: &SlasherDB<E>,
+    txn: &impl ReadTransaction,
+    validator_index: u64,
+    start_epoch: Epoch,
+    end_epoch: Epoch,
+    config: &Config,
+) -> Result<Vec<TargetBounds>, Error> {
+    let Some(current_epoch) = db.get_current_epoch_for_validator(validator_index, txn)? else {
+        return Ok(vec![]);
+    };
+    let oldest_epoch = (current_epoch + 1).saturating_sub(config.history_length as u64);
+    let start_epoch = std::cmp::max(start_epoch, oldest_epoch);
+    let end_epoch = std::cmp::min(end_epoch, current_epoch);
+
+    let validator_chunk_index = config.validator_chunk_index(validator_index);
+    let mut min_chunks = BTreeMap::<usize, MinTargetChunk>::new();
+    let mut max_chunks = BTreeMap::<usize, MaxTargetChunk>::new();
+    let mut bounds = vec![];
+    for source_epoch in (start_epoch.as_u64()..=end_epoch.as_u64()).map(Epoch::new) {
+        let chunk_index = config.chunk_index(source_epoch);
+        let min_chunk = get_chunk_for_update(
+            db,
+            txn,
+            &mut min_chunks,
+            validator_chunk_index,
+            chunk_index,
+            config,
+        )?;
+        let min_target = min_chunk
+            .chunk
+            .get_target(validator_index, source_epoch, config)?;
+        let max_chunk = get_chunk_for_update(
+            db,
+            txn,
+            &mut max_chunks,
+            validator_chunk_index,
+            chunk_index,
+            config,
+        )?;
+        let max_target = max_chunk
+            .chunk
+            .get_target(validator_index, source_epoch, config)?;
+
+        bounds.push(TargetBounds {
+            source_epoch,
+            min_target: Some(min_target)
+                .filter(|target| *target != source_epoch + u64::from(MAX_DISTANCE)),
+            max_target: Some(max_target).filter(|target| *target != source_epoch),
+        });
+    }
+    Ok(bounds)
+}
+
 pub fn get_chunk_for_update<'a, E: EthSpec, T: TargetArrayChunk>(
     db: &SlasherDB<E>,
-    txn: &mut RwTransaction<'_>,
+    txn: &impl ReadTransaction,
     updated_chunks: &'a mut BTreeMap<usize, T>,
     validator_chunk_index: usize,
     chunk_index: usize,
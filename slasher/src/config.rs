@@ -14,6 +14,7 @@ pub const DEFAULT_SLOT_OFFSET: f64 = 10.5;
 pub const DEFAULT_MAX_DB_SIZE: usize = 512 * 1024; // 512 GiB
 pub const DEFAULT_ATTESTATION_ROOT_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(100_000);
 pub const DEFAULT_BROADCAST: bool = false;
+pub const DEFAULT_BACKFILL_BATCH_EPOCHS: u64 = 8;
 
 #[cfg(all(feature = "mdbx", not(any(feature = "lmdb", feature = "redb"))))]
 pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::Mdbx;
@@ -27,7 +28,10 @@ pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::Disabled;
 pub const MAX_HISTORY_LENGTH: usize = 1 << 16;
 pub const MEGABYTE: usize = 1 << 20;
 pub const MDBX_DATA_FILENAME: &str = "mdbx.dat";
+pub const LMDB_DATA_FILENAME: &str = "data.mdb";
 pub const REDB_DATA_FILENAME: &str = "slasher.redb";
+/// Name of the slasher's directory within the beacon node's data directory.
+pub const DEFAULT_SLASHER_DIR: &str = "slasher_db";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -48,6 +52,14 @@ pub struct Config {
     pub broadcast: bool,
     /// Database backend to use.
     pub backend: DatabaseBackend,
+    /// First epoch of historical blocks to feed to the slasher on startup, if any.
+    pub backfill_start_epoch: Option<Epoch>,
+    /// Last epoch of historical blocks to feed to the slasher on startup.
+    ///
+    /// Defaults to the current epoch.
+    pub backfill_end_epoch: Option<Epoch>,
+    /// Number of epochs of historical blocks to feed to the slasher at each update.
+    pub backfill_batch_epochs: u64,
 }
 
 /// Immutable configuration parameters which are stored on disk and checked for consistency.
@@ -92,6 +104,9 @@ impl Config {
             attestation_root_cache_size: DEFAULT_ATTESTATION_ROOT_CACHE_SIZE,
             broadcast: DEFAULT_BROADCAST,
             backend: DEFAULT_BACKEND,
+            backfill_start_epoch: None,
+            backfill_end_epoch: None,
+            backfill_batch_epochs: DEFAULT_BACKFILL_BATCH_EPOCHS,
         }
     }
 
@@ -100,6 +115,7 @@ impl Config {
             || self.validator_chunk_size == 0
             || self.history_length == 0
             || self.max_db_size_mbs == 0
+            || self.backfill_batch_epochs == 0
         {
             Err(Error::ConfigInvalidZeroParameter {
                 config: self.clone(),
@@ -176,6 +192,20 @@ impl Config {
             .copied()
     }
 
+    /// The file which holds the database's data with the configured backend.
+    pub fn database_file(&self) -> Option<PathBuf> {
+        let filename = match self.backend {
+            #[cfg(feature = "mdbx")]
+            DatabaseBackend::Mdbx => MDBX_DATA_FILENAME,
+            #[cfg(feature = "lmdb")]
+            DatabaseBackend::Lmdb => LMDB_DATA_FILENAME,
+            #[cfg(feature = "redb")]
+            DatabaseBackend::Redb => REDB_DATA_FILENAME,
+            DatabaseBackend::Disabled => return None,
+        };
+        Some(self.database_path.join(filename))
+    }
+
     pub fn override_backend(&mut self) -> DatabaseBackendOverride {
         let mdbx_path = self.database_path.join(MDBX_DATA_FILENAME);
 
@@ -0,0 +1,217 @@
+//! Copy a slasher database into a new database, possibly with a different backend.
+use crate::database::interface::{Database, Environment, OpenDatabases, RwTransaction};
+use crate::{Config, Error};
+use ethereum_hashing::{Context, Sha256Context};
+use slog::{info, Logger};
+use types::Hash256;
+
+/// The number of entries written to the new database in each transaction.
+pub const CONVERSION_BATCH_SIZE: u64 = 16_384;
+
+/// The number of entries in a table, and a checksum of their keys and values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableChecksum {
+    pub entries: u64,
+    pub checksum: Hash256,
+}
+
+/// The contents of one table copied by `convert_database`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableConversion {
+    pub name: &'static str,
+    pub entries: u64,
+    pub checksum: Hash256,
+}
+
+/// Accumulates a `TableChecksum` over the entries of a table in key order.
+struct ChecksumBuilder {
+    entries: u64,
+    context: Context,
+}
+
+impl ChecksumBuilder {
+    fn new() -> Self {
+        Self {
+            entries: 0,
+            context: Context::new(),
+        }
+    }
+
+    fn update(&mut self, key: &[u8], value: &[u8]) {
+        self.entries += 1;
+        // Length-prefix the key and value so that entries can't collide by shifting bytes.
+        for bytes in [key, value] {
+            self.context.update(&(bytes.len() as u64).to_be_bytes());
+            self.context.update(bytes);
+        }
+    }
+
+    fn finish(self) -> TableChecksum {
+        TableChecksum {
+            entries: self.entries,
+            checksum: Hash256::from_slice(&self.context.finalize()),
+        }
+    }
+}
+
+/// Copy every table of the database described by `source_config` into a new database described by
+/// `dest_config`, and verify the copy.
+///
+/// Only the `database_path`, `backend` and `max_db_size_mbs` of each config are used, so the
+/// backends may differ. The destination must be a different directory and must not already contain
+/// any data. An empty source is refused unless `allow_empty` is set, as it most likely means that
+/// the wrong directory or backend was given.
+///
+/// The source is read in a single transaction, but it must not be in use by a slasher while it is
+/// copied: the slasher's writes would be missing from the copy, and MDBX and LMDB databases are
+/// not safe to share between processes which open them with different settings.
+pub fn convert_database(
+    source_config: &Config,
+    dest_config: &Config,
+    allow_empty: bool,
+    log: &Logger,
+) -> Result<Vec<TableConversion>, Error> {
+    if source_config.database_path == dest_config.database_path {
+        return Err(Error::ConversionSameDirectory(
+            source_config.database_path.clone(),
+        ));
+    }
+    // Opening a database which doesn't exist creates it, so check for the source's data file in
+    // case the wrong directory or backend was given.
+    let source_file = source_config
+        .database_file()
+        .ok_or(Error::SlasherDatabaseBackendDisabled)?;
+    if !source_file.is_file() {
+        return Err(Error::ConversionSourceMissing(source_file));
+    }
+
+    let source_env = Environment::new(source_config)?;
+    let source_databases = source_env.create_databases()?;
+    if !allow_empty && count_entries(&source_env, &source_databases)? == 0 {
+        return Err(Error::ConversionSourceEmpty(source_file));
+    }
+
+    std::fs::create_dir_all(&dest_config.database_path)?;
+    let dest_env = Environment::new(dest_config)?;
+    let dest_databases = dest_env.create_databases()?;
+
+    #[cfg(windows)]
+    {
+        for database_file in dest_env.filenames(dest_config) {
+            filesystem::restrict_file_permissions(database_file)
+                .map_err(Error::DatabasePermissionsError)?;
+        }
+    }
+
+    if count_entries(&dest_env, &dest_databases)? != 0 {
+        return Err(Error::ConversionDestinationNotEmpty(
+            dest_config.database_path.clone(),
+        ));
+    }
+
+    info!(
+        log,
+        "Converting slasher database";
+        "from_backend" => %source_config.backend,
+        "to_backend" => %dest_config.backend,
+        "source" => ?source_config.database_path,
+        "destination" => ?dest_config.database_path,
+    );
+
+    // A single transaction is used to read the source so that every table is copied from the same
+    // snapshot.
+    let mut source_txn = source_env.begin_rw_txn()?;
+    let mut conversions = vec![];
+
+    for ((name, source_db), (_, dest_db)) in source_databases
+        .tables()
+        .into_iter()
+        .zip(dest_databases.tables())
+    {
+        let source_checksum = copy_table(&mut source_txn, source_db, &dest_env, dest_db)?;
+
+        let mut dest_txn = dest_env.begin_rw_txn()?;
+        let dest_checksum = checksum_table(&mut dest_txn, dest_db)?;
+        drop(dest_txn);
+
+        if source_checksum != dest_checksum {
+            return Err(Error::ConversionMismatch {
+                table: name,
+                source: source_checksum,
+                destination: dest_checksum,
+            });
+        }
+
+        info!(
+            log,
+            "Copied slasher table";
+            "table" => name,
+            "entries" => source_checksum.entries,
+            "checksum" => ?source_checksum.checksum,
+        );
+        conversions.push(TableConversion {
+            name,
+            entries: source_checksum.entries,
+            checksum: source_checksum.checksum,
+        });
+    }
+
+    // Nothing was written to the source, so there is nothing to commit.
+    drop(source_txn);
+
+    Ok(conversions)
+}
+
+/// Copy the entries of `source_db` into `dest_db`, committing every `CONVERSION_BATCH_SIZE`
+/// entries, and return the checksum of the entries read.
+fn copy_table(
+    source_txn: &mut RwTransaction<'_>,
+    source_db: &Database,
+    dest_env: &Environment,
+    dest_db: &Database,
+) -> Result<TableChecksum, Error> {
+    let mut checksum = ChecksumBuilder::new();
+    let mut dest_txn = dest_env.begin_rw_txn()?;
+
+    let mut cursor = source_txn.cursor(source_db)?;
+    let mut has_entry = cursor.first_key()?.is_some();
+    while has_entry {
+        let (key, value) = cursor.get_current()?.ok_or(Error::MissingKey)?;
+        checksum.update(&key, &value);
+        dest_txn.put(dest_db, &key, &value)?;
+
+        if checksum.entries % CONVERSION_BATCH_SIZE == 0 {
+            dest_txn.commit()?;
+            dest_txn = dest_env.begin_rw_txn()?;
+        }
+        has_entry = cursor.next_key()?.is_some();
+    }
+    dest_txn.commit()?;
+
+    Ok(checksum.finish())
+}
+
+/// Count the entries in all the tables of a database.
+fn count_entries(env: &Environment, databases: &OpenDatabases) -> Result<u64, Error> {
+    let txn = env.begin_ro_txn()?;
+    let mut entries = 0;
+    for (_, db) in databases.tables() {
+        entries += txn.entries(db)?;
+    }
+    Ok(entries)
+}
+
+/// Compute the checksum of all the entries in `db`.
+fn checksum_table(txn: &mut RwTransaction<'_>, db: &Database) -> Result<TableChecksum, Error> {
+    let mut checksum = ChecksumBuilder::new();
+
+    let mut cursor = txn.cursor(db)?;
+    let mut has_entry = cursor.first_key()?.is_some();
+    while has_entry {
+        let (key, value) = cursor.get_current()?.ok_or(Error::MissingKey)?;
+        checksum.update(&key, &value);
+        has_entry = cursor.next_key()?.is_some();
+    }
+
+    Ok(checksum.finish())
+}
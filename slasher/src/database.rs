@@ -4,11 +4,11 @@ mod mdbx_impl;
 mod redb_impl;
 
 use crate::{
-    metrics, AttesterRecord, AttesterSlashingStatus, CompactAttesterRecord, Config, Error,
-    ProposerSlashingStatus,
+    metrics, AttesterRecord, AttesterSlashingStatus, CompactAttesterRecord, Config,
+    DatabaseBackend, Error, ProposerSlashingStatus,
 };
 use byteorder::{BigEndian, ByteOrder};
-use interface::{Environment, OpenDatabases, RwTransaction};
+use interface::{Environment, OpenDatabases, ReadTransaction, RoTransaction, RwTransaction};
 use lru::LruCache;
 use parking_lot::Mutex;
 use serde::de::DeserializeOwned;
@@ -66,6 +66,40 @@ const CURRENT_EPOCH_KEY_SIZE: usize = 8;
 const INDEXED_ATTESTATION_ID_SIZE: usize = 6;
 const INDEXED_ATTESTATION_ID_KEY_SIZE: usize = 40;
 
+/// Statistics about the contents of the slasher database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseStats {
+    pub backend: DatabaseBackend,
+    pub schema_version: Option<u64>,
+    /// The total size of the database's files in bytes.
+    pub size: u64,
+    pub tables: Vec<TableStats>,
+}
+
+/// The number of entries in one of the database's tables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableStats {
+    pub name: &'static str,
+    pub entries: u64,
+}
+
+impl<'env> OpenDatabases<'env> {
+    /// Every table in the database, along with its name.
+    pub fn tables(&self) -> [(&'static str, &interface::Database<'env>); MAX_NUM_DBS] {
+        [
+            (INDEXED_ATTESTATION_DB, &self.indexed_attestation_db),
+            (INDEXED_ATTESTATION_ID_DB, &self.indexed_attestation_id_db),
+            (ATTESTERS_DB, &self.attesters_db),
+            (ATTESTERS_MAX_TARGETS_DB, &self.attesters_max_targets_db),
+            (MIN_TARGETS_DB, &self.min_targets_db),
+            (MAX_TARGETS_DB, &self.max_targets_db),
+            (CURRENT_EPOCHS_DB, &self.current_epochs_db),
+            (PROPOSERS_DB, &self.proposers_db),
+            (METADATA_DB, &self.metadata_db),
+        ]
+    }
+}
+
 #[derive(Debug)]
 pub struct SlasherDB<E: EthSpec> {
     pub(crate) env: &'static Environment,
@@ -335,6 +369,11 @@ impl<E: EthSpec> SlasherDB<E> {
         self.env.begin_rw_txn()
     }
 
+    /// Begin a read-only transaction, for queries which must not block the slasher's updates.
+    pub fn begin_ro_txn(&self) -> Result<RoTransaction, Error> {
+        self.env.begin_ro_txn()
+    }
+
     pub fn load_schema_version(&self, txn: &mut RwTransaction<'_>) -> Result<Option<u64>, Error> {
         txn.get(&self.databases.metadata_db, METADATA_VERSION_KEY)?
             .map(bincode_deserialize)
@@ -375,7 +414,7 @@ impl<E: EthSpec> SlasherDB<E> {
     pub fn get_attester_max_target(
         &self,
         validator_index: u64,
-        txn: &mut RwTransaction<'_>,
+        txn: &impl ReadTransaction,
     ) -> Result<Option<Epoch>, Error> {
         txn.get(
             &self.databases.attesters_max_targets_db,
@@ -426,7 +465,7 @@ impl<E: EthSpec> SlasherDB<E> {
     pub fn get_current_epoch_for_validator(
         &self,
         validator_index: u64,
-        txn: &mut RwTransaction<'_>,
+        txn: &impl ReadTransaction,
     ) -> Result<Option<Epoch>, Error> {
         txn.get(
             &self.databases.current_epochs_db,
@@ -512,7 +551,7 @@ impl<E: EthSpec> SlasherDB<E> {
 
     pub fn get_indexed_attestation(
         &self,
-        txn: &mut RwTransaction<'_>,
+        txn: &impl ReadTransaction,
         indexed_attestation_id: IndexedAttestationId,
     ) -> Result<IndexedAttestation<E>, Error> {
         let bytes = txn
@@ -646,9 +685,47 @@ impl<E: EthSpec> SlasherDB<E> {
         self.get_indexed_attestation(txn, record.indexed_attestation_id)
     }
 
+    /// Load the attestations stored for `validator_index` with target epochs in
+    /// `start_epoch..=end_epoch`.
+    ///
+    /// Target epochs are stored modulo the history length, so only the `history_length` epochs up
+    /// to the validator's latest target are available.
+    pub fn get_attestations_for_validator_in_range(
+        &self,
+        txn: &impl ReadTransaction,
+        validator_index: u64,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<IndexedAttestation<E>>, Error> {
+        let Some(max_target) = self.get_attester_max_target(validator_index, txn)? else {
+            return Ok(vec![]);
+        };
+        let oldest_target = (max_target + 1).saturating_sub(self.config.history_length as u64);
+        let start_epoch = std::cmp::max(start_epoch, oldest_target);
+        let end_epoch = std::cmp::min(end_epoch, max_target);
+
+        let mut attestations = vec![];
+        for target_epoch in (start_epoch.as_u64()..=end_epoch.as_u64()).map(Epoch::new) {
+            let Some(record) =
+                self.get_attester_record(txn, validator_index, target_epoch, Some(max_target))?
+            else {
+                continue;
+            };
+            // The attestation may have been pruned before its record was overwritten.
+            match self.get_indexed_attestation(txn, record.indexed_attestation_id) {
+                Ok(attestation) if attestation.data().target.epoch == target_epoch => {
+                    attestations.push(attestation)
+                }
+                Ok(_) | Err(Error::MissingIndexedAttestation { .. }) => (),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(attestations)
+    }
+
     pub fn get_attester_record(
         &self,
-        txn: &mut RwTransaction<'_>,
+        txn: &impl ReadTransaction,
         validator_index: u64,
         target: Epoch,
         prev_max_target: Option<Epoch>,
@@ -667,7 +744,7 @@ impl<E: EthSpec> SlasherDB<E> {
 
     pub fn get_block_proposal(
         &self,
-        txn: &mut RwTransaction<'_>,
+        txn: &impl ReadTransaction,
         proposer_index: u64,
         slot: Slot,
     ) -> Result<Option<SignedBeaconBlockHeader>, Error> {
@@ -677,6 +754,23 @@ impl<E: EthSpec> SlasherDB<E> {
             .transpose()
     }
 
+    /// Load the block proposals stored for `proposer_index` at slots in `start_slot..=end_slot`.
+    pub fn get_block_proposals_in_range(
+        &self,
+        txn: &impl ReadTransaction,
+        proposer_index: u64,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<Vec<SignedBeaconBlockHeader>, Error> {
+        let mut proposals = vec![];
+        for slot in (start_slot.as_u64()..=end_slot.as_u64()).map(Slot::new) {
+            if let Some(proposal) = self.get_block_proposal(txn, proposer_index, slot)? {
+                proposals.push(proposal);
+            }
+        }
+        Ok(proposals)
+    }
+
     pub fn check_or_insert_block_proposal(
         &self,
         txn: &mut RwTransaction<'_>,
@@ -706,6 +800,44 @@ impl<E: EthSpec> SlasherDB<E> {
         }
     }
 
+    /// Count the entries in each table and the size of the database on disk.
+    ///
+    /// The entry counts are read from the backend's own statistics in a read-only transaction, so
+    /// this is cheap and does not block the slasher's updates.
+    pub fn stats(&self) -> Result<DatabaseStats, Error> {
+        let txn = self.env.begin_ro_txn()?;
+        let schema_version = txn
+            .get(&self.databases.metadata_db, METADATA_VERSION_KEY)?
+            .map(bincode_deserialize)
+            .transpose()?;
+        let size = self
+            .env
+            .filenames(&self.config)
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+
+        let tables = self
+            .databases
+            .tables()
+            .into_iter()
+            .map(|(name, db)| {
+                Ok(TableStats {
+                    name,
+                    entries: txn.entries(db)?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(DatabaseStats {
+            backend: self.config.backend,
+            schema_version,
+            size,
+            tables,
+        })
+    }
+
     /// Attempt to prune the database, deleting old blocks and attestations.
     pub fn prune(&self, current_epoch: Epoch) -> Result<(), Error> {
         let mut txn = self.begin_rw_txn()?;
@@ -32,6 +32,17 @@ pub enum RwTransaction<'env> {
     Disabled(PhantomData<&'env ()>),
 }
 
+#[derive(Debug)]
+pub enum RoTransaction<'env> {
+    #[cfg(feature = "mdbx")]
+    Mdbx(mdbx_impl::RoTransaction<'env>),
+    #[cfg(feature = "lmdb")]
+    Lmdb(lmdb_impl::RoTransaction<'env>),
+    #[cfg(feature = "redb")]
+    Redb(redb_impl::RoTransaction<'env>),
+    Disabled(PhantomData<&'env ()>),
+}
+
 #[derive(Debug)]
 pub enum Database<'env> {
     #[cfg(feature = "mdbx")]
@@ -70,6 +81,15 @@ pub enum Cursor<'env> {
 pub type Key<'a> = Cow<'a, [u8]>;
 pub type Value<'a> = Cow<'a, [u8]>;
 
+/// Reads shared by read-only and read-write transactions, so that queries can run in either.
+pub trait ReadTransaction {
+    fn get<'a, K: AsRef<[u8]> + ?Sized>(
+        &'a self,
+        db: &'a Database,
+        key: &K,
+    ) -> Result<Option<Cow<'a, [u8]>>, Error>;
+}
+
 impl Environment {
     pub fn new(config: &Config) -> Result<Environment, Error> {
         match config.backend {
@@ -107,6 +127,19 @@ impl Environment {
         }
     }
 
+    /// Begin a read-only transaction, which does not block writers.
+    pub fn begin_ro_txn(&self) -> Result<RoTransaction, Error> {
+        match self {
+            #[cfg(feature = "mdbx")]
+            Self::Mdbx(env) => env.begin_ro_txn().map(RoTransaction::Mdbx),
+            #[cfg(feature = "lmdb")]
+            Self::Lmdb(env) => env.begin_ro_txn().map(RoTransaction::Lmdb),
+            #[cfg(feature = "redb")]
+            Self::Redb(env) => env.begin_ro_txn().map(RoTransaction::Redb),
+            _ => Err(Error::MismatchedDatabaseVariant),
+        }
+    }
+
     /// List of all files used by the database.
     pub fn filenames(&self, config: &Config) -> Vec<PathBuf> {
         match self {
@@ -192,6 +225,57 @@ impl<'env> RwTransaction<'env> {
     }
 }
 
+impl<'env> RoTransaction<'env> {
+    pub fn get<K: AsRef<[u8]> + ?Sized>(
+        &'env self,
+        db: &'env Database,
+        key: &K,
+    ) -> Result<Option<Cow<'env, [u8]>>, Error> {
+        match (self, db) {
+            #[cfg(feature = "mdbx")]
+            (Self::Mdbx(txn), Database::Mdbx(db)) => txn.get(db, key),
+            #[cfg(feature = "lmdb")]
+            (Self::Lmdb(txn), Database::Lmdb(db)) => txn.get(db, key),
+            #[cfg(feature = "redb")]
+            (Self::Redb(txn), Database::Redb(db)) => txn.get(db, key),
+            _ => Err(Error::MismatchedDatabaseVariant),
+        }
+    }
+
+    /// Return the number of entries in `db`, as recorded by the database without reading them.
+    pub fn entries(&self, db: &Database) -> Result<u64, Error> {
+        match (self, db) {
+            #[cfg(feature = "mdbx")]
+            (Self::Mdbx(txn), Database::Mdbx(db)) => txn.entries(db),
+            #[cfg(feature = "lmdb")]
+            (Self::Lmdb(txn), Database::Lmdb(db)) => txn.entries(db),
+            #[cfg(feature = "redb")]
+            (Self::Redb(txn), Database::Redb(db)) => txn.entries(db),
+            _ => Err(Error::MismatchedDatabaseVariant),
+        }
+    }
+}
+
+impl<'env> ReadTransaction for RwTransaction<'env> {
+    fn get<'a, K: AsRef<[u8]> + ?Sized>(
+        &'a self,
+        db: &'a Database,
+        key: &K,
+    ) -> Result<Option<Cow<'a, [u8]>>, Error> {
+        RwTransaction::get(self, db, key)
+    }
+}
+
+impl<'env> ReadTransaction for RoTransaction<'env> {
+    fn get<'a, K: AsRef<[u8]> + ?Sized>(
+        &'a self,
+        db: &'a Database,
+        key: &K,
+    ) -> Result<Option<Cow<'a, [u8]>>, Error> {
+        RoTransaction::get(self, db, key)
+    }
+}
+
 impl<'env> Cursor<'env> {
     /// Return the first key in the current database while advancing the cursor's position.
     pub fn first_key(&mut self) -> Result<Option<Key>, Error> {
@@ -231,6 +315,19 @@ impl<'env> Cursor<'env> {
         }
     }
 
+    /// Return the key and value at the cursor's current position.
+    pub fn get_current(&mut self) -> Result<Option<(Key<'env>, Value<'env>)>, Error> {
+        match self {
+            #[cfg(feature = "mdbx")]
+            Cursor::Mdbx(cursor) => cursor.get_current(),
+            #[cfg(feature = "lmdb")]
+            Cursor::Lmdb(cursor) => cursor.get_current(),
+            #[cfg(feature = "redb")]
+            Cursor::Redb(cursor) => cursor.get_current(),
+            _ => Err(Error::MismatchedDatabaseVariant),
+        }
+    }
+
     pub fn delete_current(&mut self) -> Result<(), Error> {
         match self {
             #[cfg(feature = "mdbx")]
@@ -1,7 +1,7 @@
 #![cfg(feature = "lmdb")]
 
 use crate::{
-    config::MEGABYTE,
+    config::{LMDB_DATA_FILENAME, MEGABYTE},
     database::{
         interface::{Key, Value},
         *,
@@ -21,6 +21,11 @@ pub struct RwTransaction<'env> {
     txn: lmdb::RwTransaction<'env>,
 }
 
+#[derive(Debug)]
+pub struct RoTransaction<'env> {
+    txn: lmdb::RoTransaction<'env>,
+}
+
 #[derive(Debug)]
 pub struct Database<'env> {
     db: lmdb::Database,
@@ -85,9 +90,14 @@ impl Environment {
         Ok(RwTransaction { txn })
     }
 
+    pub fn begin_ro_txn(&self) -> Result<RoTransaction, Error> {
+        let txn = self.env.begin_ro_txn()?;
+        Ok(RoTransaction { txn })
+    }
+
     pub fn filenames(&self, config: &Config) -> Vec<PathBuf> {
         vec![
-            config.database_path.join("data.mdb"),
+            config.database_path.join(LMDB_DATA_FILENAME),
             config.database_path.join("lock.mdb"),
         ]
     }
@@ -136,6 +146,20 @@ impl<'env> RwTransaction<'env> {
     }
 }
 
+impl<'env> RoTransaction<'env> {
+    pub fn get<K: AsRef<[u8]> + ?Sized>(
+        &'env self,
+        db: &'env Database,
+        key: &K,
+    ) -> Result<Option<Cow<'env, [u8]>>, Error> {
+        Ok(self.txn.get(db.db, key).optional()?.map(Cow::Borrowed))
+    }
+
+    pub fn entries(&self, db: &Database) -> Result<u64, Error> {
+        Ok(self.txn.stat(db.db)?.entries() as u64)
+    }
+}
+
 impl<'env> Cursor<'env> {
     pub fn first_key(&mut self) -> Result<Option<Key>, Error> {
         let opt_key = self
@@ -25,6 +25,11 @@ pub struct RwTransaction<'env> {
     txn: mdbx::Transaction<'env, mdbx::RW, mdbx::NoWriteMap>,
 }
 
+#[derive(Debug)]
+pub struct RoTransaction<'env> {
+    txn: mdbx::Transaction<'env, mdbx::RO, mdbx::NoWriteMap>,
+}
+
 #[derive(Debug)]
 pub struct Database<'env> {
     db: mdbx::Database<'env>,
@@ -82,6 +87,11 @@ impl Environment {
         Ok(RwTransaction { txn })
     }
 
+    pub fn begin_ro_txn(&self) -> Result<RoTransaction, Error> {
+        let txn = self.env.begin_ro_txn()?;
+        Ok(RoTransaction { txn })
+    }
+
     pub fn filenames(&self, config: &Config) -> Vec<PathBuf> {
         vec![
             config.database_path.join("mdbx.dat"),
@@ -153,6 +163,20 @@ impl<'env> RwTransaction<'env> {
     }
 }
 
+impl<'env> RoTransaction<'env> {
+    pub fn get<K: AsRef<[u8]> + ?Sized>(
+        &'env self,
+        db: &'env Database,
+        key: &K,
+    ) -> Result<Option<Cow<'env, [u8]>>, Error> {
+        Ok(self.txn.get(&db.db, key.as_ref())?)
+    }
+
+    pub fn entries(&self, db: &Database) -> Result<u64, Error> {
+        Ok(self.txn.db_stat(&db.db)?.entries() as u64)
+    }
+}
+
 impl<'env> Cursor<'env> {
     pub fn first_key(&mut self) -> Result<Option<Cow<'env, [u8]>>, Error> {
         let opt_key = self.cursor.first()?.map(|(key_bytes, ())| key_bytes);
@@ -8,8 +8,8 @@ use crate::{
     Config, Error,
 };
 use derivative::Derivative;
-use redb::{ReadableTable, TableDefinition};
-use std::{borrow::Cow, path::PathBuf};
+use redb::{ReadableTable, ReadableTableMetadata, TableDefinition};
+use std::{borrow::Cow, ops::Bound, path::PathBuf};
 
 #[derive(Debug)]
 pub struct Environment {
@@ -31,6 +31,14 @@ pub struct RwTransaction<'env> {
     _phantom: PhantomData<&'env ()>,
 }
 
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct RoTransaction<'env> {
+    #[derivative(Debug = "ignore")]
+    txn: redb::ReadTransaction,
+    _phantom: PhantomData<&'env ()>,
+}
+
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct Cursor<'env> {
@@ -102,6 +110,35 @@ impl Environment {
             _phantom: PhantomData,
         })
     }
+
+    pub fn begin_ro_txn(&self) -> Result<RoTransaction, Error> {
+        let txn = self.db.begin_read()?;
+        Ok(RoTransaction {
+            txn,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<'env> RoTransaction<'env> {
+    pub fn get<K: AsRef<[u8]> + ?Sized>(
+        &'env self,
+        db: &'env Database,
+        key: &K,
+    ) -> Result<Option<Cow<'env, [u8]>>, Error> {
+        let table_definition: TableDefinition<'_, &[u8], &[u8]> =
+            TableDefinition::new(&db.table_name);
+        let table = self.txn.open_table(table_definition)?;
+        let result = table.get(key.as_ref())?;
+        Ok(result.map(|access_guard| Cow::from(access_guard.value().to_vec())))
+    }
+
+    pub fn entries(&self, db: &Database) -> Result<u64, Error> {
+        let table_definition: TableDefinition<'_, &[u8], &[u8]> =
+            TableDefinition::new(&db.table_name);
+        let table = self.txn.open_table(table_definition)?;
+        Ok(table.len()?)
+    }
 }
 
 impl<'env> RwTransaction<'env> {
@@ -215,7 +252,9 @@ impl<'env> Cursor<'env> {
             TableDefinition::new(&self.db.table_name);
         let table = self.txn.open_table(table_definition)?;
         if let Some(current_key) = &self.current_key {
-            let range: std::ops::RangeFrom<&[u8]> = current_key..;
+            // Exclude the current key, which would otherwise be returned again.
+            let range: (Bound<&[u8]>, Bound<&[u8]>) =
+                (Bound::Excluded(current_key.as_ref()), Bound::Unbounded);
 
             let next = table
                 .range(range)?
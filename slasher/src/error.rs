@@ -1,5 +1,7 @@
 use crate::config::{Config, DiskConfig};
+use crate::convert::TableChecksum;
 use std::io;
+use std::path::PathBuf;
 use types::Epoch;
 
 #[derive(Debug)]
@@ -70,6 +72,16 @@ pub enum Error {
     MissingIndexedAttestationIdKey,
     InconsistentAttestationDataRoot,
     MissingKey,
+    ConversionSameDirectory(PathBuf),
+    ConversionSourceMissing(PathBuf),
+    ConversionSourceEmpty(PathBuf),
+    ConversionDestinationNotEmpty(PathBuf),
+    /// The contents of a table differ after it was copied to the new database.
+    ConversionMismatch {
+        table: &'static str,
+        source: TableChecksum,
+        destination: TableChecksum,
+    },
 }
 
 #[cfg(feature = "mdbx")]
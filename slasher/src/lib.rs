@@ -10,23 +10,28 @@ mod attester_record;
 mod batch_stats;
 mod block_queue;
 pub mod config;
+mod convert;
 mod database;
 mod error;
 pub mod metrics;
 mod migrate;
 mod slasher;
+mod slashing_history;
 pub mod test_utils;
 
 pub use crate::slasher::Slasher;
+pub use array::TargetBounds;
 pub use attestation_queue::{AttestationBatch, AttestationQueue, SimpleBatch};
 pub use attester_record::{AttesterRecord, CompactAttesterRecord, IndexedAttesterRecord};
 pub use block_queue::BlockQueue;
 pub use config::{Config, DatabaseBackend, DatabaseBackendOverride};
+pub use convert::{convert_database, TableChecksum, TableConversion, CONVERSION_BATCH_SIZE};
 pub use database::{
-    interface::{Database, Environment, RwTransaction},
-    IndexedAttestationId, SlasherDB,
+    interface::{Database, Environment, ReadTransaction, RoTransaction, RwTransaction},
+    DatabaseStats, IndexedAttestationId, SlasherDB, TableStats,
 };
 pub use error::Error;
+pub use slashing_history::{SlashingHistory, SLASHING_HISTORY_LENGTH};
 
 use types::{AttesterSlashing, AttesterSlashingBase, AttesterSlashingElectra};
 use types::{EthSpec, IndexedAttestation, ProposerSlashing};
@@ -5,8 +5,9 @@ use crate::metrics::{
     SLASHER_NUM_BLOCKS_PROCESSED,
 };
 use crate::{
-    array, AttestationBatch, AttestationQueue, AttesterRecord, BlockQueue, Config, Error,
-    IndexedAttestationId, ProposerSlashingStatus, RwTransaction, SimpleBatch, SlasherDB,
+    array, AttestationBatch, AttestationQueue, AttesterRecord, BlockQueue, Config, DatabaseStats,
+    Error, IndexedAttestationId, ProposerSlashingStatus, RwTransaction, SimpleBatch, SlasherDB,
+    SlashingHistory, TargetBounds,
 };
 use parking_lot::Mutex;
 use slog::{debug, error, info, Logger};
@@ -14,7 +15,7 @@ use std::collections::HashSet;
 use std::sync::Arc;
 use types::{
     AttesterSlashing, ChainSpec, Epoch, EthSpec, IndexedAttestation, ProposerSlashing,
-    SignedBeaconBlockHeader,
+    SignedBeaconBlockHeader, Slot,
 };
 
 #[derive(Debug)]
@@ -24,6 +25,7 @@ pub struct Slasher<E: EthSpec> {
     block_queue: BlockQueue,
     attester_slashings: Mutex<HashSet<AttesterSlashing<E>>>,
     proposer_slashings: Mutex<HashSet<ProposerSlashing>>,
+    slashing_history: Mutex<SlashingHistory<E>>,
     config: Arc<Config>,
     log: Logger,
 }
@@ -35,6 +37,7 @@ impl<E: EthSpec> Slasher<E> {
         let db = SlasherDB::open(config.clone(), spec, log.clone())?;
         let attester_slashings = Mutex::new(HashSet::new());
         let proposer_slashings = Mutex::new(HashSet::new());
+        let slashing_history = Mutex::new(SlashingHistory::default());
         let attestation_queue = AttestationQueue::default();
         let block_queue = BlockQueue::default();
         Ok(Self {
@@ -43,6 +46,7 @@ impl<E: EthSpec> Slasher<E> {
             block_queue,
             attester_slashings,
             proposer_slashings,
+            slashing_history,
             config,
             log,
         })
@@ -58,6 +62,11 @@ impl<E: EthSpec> Slasher<E> {
         std::mem::take(&mut self.proposer_slashings.lock())
     }
 
+    /// Return the most recent slashings found, including those which have been harvested.
+    pub fn slashing_history(&self) -> SlashingHistory<E> {
+        self.slashing_history.lock().clone()
+    }
+
     pub fn config(&self) -> &Config {
         &self.config
     }
@@ -113,6 +122,9 @@ impl<E: EthSpec> Slasher<E> {
                 "Found {} new proposer slashings!",
                 slashings.len(),
             );
+            self.slashing_history
+                .lock()
+                .add_proposer_slashings(slashings.iter().cloned());
             self.proposer_slashings.lock().extend(slashings);
         }
 
@@ -227,7 +239,7 @@ impl<E: EthSpec> Slasher<E> {
                             slashings.len()
                         );
                     }
-                    self.attester_slashings.lock().extend(slashings);
+                    self.record_attester_slashings(slashings);
                 }
                 Err(e) => {
                     error!(
@@ -257,7 +269,7 @@ impl<E: EthSpec> Slasher<E> {
                         slashings.len()
                     );
                 }
-                self.attester_slashings.lock().extend(slashings);
+                self.record_attester_slashings(slashings);
             }
             Err(e) => {
                 error!(
@@ -272,6 +284,13 @@ impl<E: EthSpec> Slasher<E> {
         Ok(())
     }
 
+    fn record_attester_slashings(&self, slashings: HashSet<AttesterSlashing<E>>) {
+        self.slashing_history
+            .lock()
+            .add_attester_slashings(slashings.iter().cloned());
+        self.attester_slashings.lock().extend(slashings);
+    }
+
     /// Check for double votes from all validators on `attestation` who match the `subqueue_id`.
     fn check_double_votes(
         &self,
@@ -353,4 +372,57 @@ impl<E: EthSpec> Slasher<E> {
     pub fn prune_database(&self, current_epoch: Epoch) -> Result<(), Error> {
         self.db.prune(current_epoch)
     }
+
+    /// Load the attestations stored for `validator_index` with target epochs in
+    /// `start_epoch..=end_epoch`.
+    pub fn get_attestations_for_validator(
+        &self,
+        validator_index: u64,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<IndexedAttestation<E>>, Error> {
+        let txn = self.db.begin_ro_txn()?;
+        self.db.get_attestations_for_validator_in_range(
+            &txn,
+            validator_index,
+            start_epoch,
+            end_epoch,
+        )
+    }
+
+    /// Load the block proposals stored for `proposer_index` at slots in `start_slot..=end_slot`.
+    pub fn get_block_proposals(
+        &self,
+        proposer_index: u64,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<Vec<SignedBeaconBlockHeader>, Error> {
+        let txn = self.db.begin_ro_txn()?;
+        self.db
+            .get_block_proposals_in_range(&txn, proposer_index, start_slot, end_slot)
+    }
+
+    /// Load the min and max targets of `validator_index` for each source epoch in
+    /// `start_epoch..=end_epoch`.
+    pub fn get_target_bounds(
+        &self,
+        validator_index: u64,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<TargetBounds>, Error> {
+        let txn = self.db.begin_ro_txn()?;
+        array::get_target_bounds(
+            &self.db,
+            &txn,
+            validator_index,
+            start_epoch,
+            end_epoch,
+            &self.config,
+        )
+    }
+
+    /// Count the entries in each of the database's tables.
+    pub fn database_stats(&self) -> Result<DatabaseStats, Error> {
+        self.db.stats()
+    }
 }
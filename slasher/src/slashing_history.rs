@@ -0,0 +1,47 @@
+use std::collections::VecDeque;
+use types::{AttesterSlashing, EthSpec, ProposerSlashing};
+
+/// The number of slashings of each kind which are retained.
+pub const SLASHING_HISTORY_LENGTH: usize = 1024;
+
+/// The most recent slashings found by the slasher.
+///
+/// Unlike the slashings harvested by the beacon chain, these are retained after they are
+/// collected so that they can be inspected via the HTTP API. They are not persisted across
+/// restarts.
+#[derive(Debug, Clone)]
+pub struct SlashingHistory<E: EthSpec> {
+    pub attester_slashings: VecDeque<AttesterSlashing<E>>,
+    pub proposer_slashings: VecDeque<ProposerSlashing>,
+}
+
+impl<E: EthSpec> Default for SlashingHistory<E> {
+    fn default() -> Self {
+        Self {
+            attester_slashings: VecDeque::new(),
+            proposer_slashings: VecDeque::new(),
+        }
+    }
+}
+
+impl<E: EthSpec> SlashingHistory<E> {
+    pub fn add_attester_slashings(
+        &mut self,
+        slashings: impl IntoIterator<Item = AttesterSlashing<E>>,
+    ) {
+        self.attester_slashings.extend(slashings);
+        while self.attester_slashings.len() > SLASHING_HISTORY_LENGTH {
+            self.attester_slashings.pop_front();
+        }
+    }
+
+    pub fn add_proposer_slashings(
+        &mut self,
+        slashings: impl IntoIterator<Item = ProposerSlashing>,
+    ) {
+        self.proposer_slashings.extend(slashings);
+        while self.proposer_slashings.len() > SLASHING_HISTORY_LENGTH {
+            self.proposer_slashings.pop_front();
+        }
+    }
+}
@@ -0,0 +1,109 @@
+pub use clap::{FromArgMatches, Parser};
+use clap_utils::get_color_style;
+use clap_utils::FLAG_HEADER;
+use serde::{Deserialize, Serialize};
+use slasher::DatabaseBackend;
+use std::path::PathBuf;
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    name = "slasher",
+    about = "Run a slasher which is fed by the event streams of one or more beacon nodes, \
+             and which publishes the slashings it finds to those beacon nodes.",
+    styles = get_color_style(),
+    next_line_help = true,
+    term_width = 80,
+    disable_help_flag = true,
+    disable_help_subcommand = true,
+    display_order = 0,
+)]
+pub struct StandaloneSlasher {
+    #[clap(
+        long,
+        value_name = "NETWORK_ADDRESSES",
+        value_delimiter = ',',
+        required = true,
+        help = "Comma-separated addresses of the beacon nodes to subscribe to, e.g. \
+                http://localhost:5052. Slashings are published to every beacon node.",
+        display_order = 0
+    )]
+    pub beacon_nodes: Vec<String>,
+
+    #[clap(
+        long,
+        value_name = "PATH",
+        help = "Path to a file containing an API key to send to the beacon nodes, for beacon \
+                nodes which are run with --http-api-keys-file.",
+        display_order = 0
+    )]
+    pub beacon_nodes_api_key_file: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "PATH",
+        help = "Set the slasher's database directory. [default: <DATADIR>/slasher_db]",
+        display_order = 0
+    )]
+    pub slasher_dir: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "SECONDS",
+        help = "Configure how often the slasher runs batch processing.",
+        display_order = 0
+    )]
+    pub slasher_update_period: Option<u64>,
+
+    #[clap(
+        long,
+        value_name = "SECONDS",
+        help = "Set the delay from the start of the slot at which the slasher should ingest \
+                attestations. Only effective if the slasher-update-period is a multiple of the \
+                slot duration.",
+        display_order = 0
+    )]
+    pub slasher_slot_offset: Option<f64>,
+
+    #[clap(
+        long,
+        value_name = "EPOCHS",
+        help = "Configure how many epochs of history the slasher keeps. Immutable after \
+                initialization.",
+        display_order = 0
+    )]
+    pub slasher_history_length: Option<usize>,
+
+    #[clap(
+        long,
+        value_name = "GIGABYTES",
+        help = "Maximum size of the MDBX database used by the slasher.",
+        display_order = 0
+    )]
+    pub slasher_max_db_size: Option<usize>,
+
+    #[clap(
+        long,
+        value_name = "DATABASE",
+        help = "Set the database backend to be used by the slasher.",
+        display_order = 0
+    )]
+    pub slasher_backend: Option<DatabaseBackend>,
+
+    #[clap(
+        long,
+        help = "Find slashings without publishing them to the beacon nodes.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub disable_publish: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Prints help information",
+        action = clap::ArgAction::HelpLong,
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    help: Option<bool>,
+}
@@ -0,0 +1,126 @@
+use eth2::types::{BlockId, StateId};
+use eth2::BeaconNodeHttpClient;
+use parking_lot::Mutex;
+use state_processing::common::{attesting_indices_base, attesting_indices_electra};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use types::{Attestation, BeaconCommittee, Checkpoint, Epoch, EthSpec, IndexedAttestation, Slot};
+
+/// The number of epochs prior to the latest epoch for which committees are retained.
+const RETAINED_EPOCHS: u64 = 2;
+
+/// The committees of each slot in an epoch, ordered by committee index.
+type EpochCommittees = HashMap<Slot, Vec<Vec<usize>>>;
+
+/// Converts attestations to `IndexedAttestation`s using committees fetched from beacon nodes.
+///
+/// Committees are fetched from the post-state of the attestation's target block, so that
+/// attestations on any fork known to the beacon node are indexed with the committees of that fork.
+pub struct AttestationIndexer<E: EthSpec> {
+    committees: Mutex<HashMap<Checkpoint, Arc<EpochCommittees>>>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> Default for AttestationIndexer<E> {
+    fn default() -> Self {
+        Self {
+            committees: Mutex::new(HashMap::new()),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<E: EthSpec> AttestationIndexer<E> {
+    pub async fn get_indexed_attestation(
+        &self,
+        beacon_node: &BeaconNodeHttpClient,
+        attestation: &Attestation<E>,
+    ) -> Result<IndexedAttestation<E>, String> {
+        let slot = attestation.data().slot;
+        let target = attestation.data().target;
+        if slot.epoch(E::slots_per_epoch()) != target.epoch {
+            return Err(format!(
+                "slot {} is not in the target epoch {}",
+                slot, target.epoch
+            ));
+        }
+        let committees = self.get_committees(beacon_node, target).await?;
+        let slot_committees = committees
+            .get(&slot)
+            .ok_or_else(|| format!("no committees for slot {}", slot))?;
+
+        match attestation {
+            Attestation::Base(attestation) => {
+                let committee = slot_committees
+                    .get(attestation.data.index as usize)
+                    .ok_or_else(|| format!("unknown committee {}", attestation.data.index))?;
+                attesting_indices_base::get_indexed_attestation(committee, attestation)
+            }
+            Attestation::Electra(attestation) => {
+                let committees = slot_committees
+                    .iter()
+                    .enumerate()
+                    .map(|(index, committee)| BeaconCommittee {
+                        slot,
+                        index: index as u64,
+                        committee,
+                    })
+                    .collect::<Vec<_>>();
+                attesting_indices_electra::get_indexed_attestation(&committees, attestation)
+            }
+        }
+        .map_err(|e| format!("invalid attestation: {:?}", e))
+    }
+
+    /// Fetch the committees of the epoch of `target` from the post-state of the target block.
+    ///
+    /// The target block is at or before the first slot of the epoch, so its post-state is able to
+    /// compute the committees of the epoch unless a whole epoch prior to it was skipped.
+    async fn get_committees(
+        &self,
+        beacon_node: &BeaconNodeHttpClient,
+        target: Checkpoint,
+    ) -> Result<Arc<EpochCommittees>, String> {
+        if let Some(committees) = self.committees.lock().get(&target) {
+            return Ok(committees.clone());
+        }
+
+        let epoch = target.epoch;
+        let state_root = beacon_node
+            .get_beacon_headers_block_id(BlockId::Root(target.root))
+            .await
+            .map_err(|e| format!("unable to fetch target block {:?}: {:?}", target.root, e))?
+            .ok_or_else(|| format!("target block {:?} not found", target.root))?
+            .data
+            .header
+            .message
+            .state_root;
+        let committee_data = beacon_node
+            .get_beacon_states_committees(StateId::Root(state_root), None, None, Some(epoch))
+            .await
+            .map_err(|e| format!("unable to fetch committees for epoch {}: {:?}", epoch, e))?
+            .ok_or_else(|| format!("target state {:?} not found", state_root))?
+            .data;
+
+        let mut committees = EpochCommittees::new();
+        for committee in committee_data {
+            let slot_committees = committees.entry(committee.slot).or_default();
+            let index = committee.index as usize;
+            if slot_committees.len() <= index {
+                slot_committees.resize(index + 1, vec![]);
+            }
+            slot_committees[index] = committee
+                .validators
+                .into_iter()
+                .map(|validator_index| validator_index as usize)
+                .collect();
+        }
+        let committees = Arc::new(committees);
+
+        let mut cache = self.committees.lock();
+        cache.retain(|cached, _| cached.epoch + RETAINED_EPOCHS >= epoch);
+        cache.insert(target, committees.clone());
+        Ok(committees)
+    }
+}
@@ -0,0 +1,361 @@
+use crate::committees::AttestationIndexer;
+use eth2::types::{BlockId, EventKind, EventTopic};
+use eth2::BeaconNodeHttpClient;
+use futures::StreamExt;
+use lru::LruCache;
+use parking_lot::Mutex;
+use slasher::Slasher;
+use slog::{debug, info, warn, Logger};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use types::non_zero_usize::new_non_zero_usize;
+use types::{EthSpec, Hash256};
+
+/// The topics which the slasher subscribes to.
+const TOPICS: [EventTopic; 3] = [
+    EventTopic::Attestation,
+    EventTopic::Block,
+    EventTopic::RejectedBlockHeader,
+];
+
+/// The delay before re-subscribing to a beacon node after its event stream fails.
+const RECONNECT_DELAY: Duration = Duration::from_secs(6);
+
+/// The number of recently imported blocks to remember, so that each block is only fetched once
+/// when subscribed to several beacon nodes.
+pub const SEEN_BLOCKS_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(1024);
+
+/// Block roots whose block and attestations have already been passed to the slasher.
+pub type SeenBlocks = Mutex<LruCache<Hash256, ()>>;
+
+/// Forwards the events of a single beacon node to `event_sender`, re-subscribing whenever the
+/// event stream fails. Returns once the receiver has been dropped.
+///
+/// Events are handled by `run_event_handler` rather than while reading the stream, so that
+/// fetching a block or committees from the beacon node doesn't stall the stream.
+pub async fn run_event_listener<E: EthSpec>(
+    beacon_node: BeaconNodeHttpClient,
+    event_sender: UnboundedSender<EventKind<E>>,
+    log: Logger,
+) {
+    loop {
+        match beacon_node.get_events::<E>(&TOPICS).await {
+            Ok(events) => {
+                info!(
+                    log,
+                    "Subscribed to beacon node events";
+                    "beacon_node" => %beacon_node,
+                );
+                let mut events = Box::pin(events);
+                while let Some(result) = events.next().await {
+                    match result {
+                        Ok(event) => {
+                            if event_sender.send(event).is_err() {
+                                return;
+                            }
+                        }
+                        Err(e) => {
+                            warn!(
+                                log,
+                                "Beacon node event stream failed";
+                                "error" => ?e,
+                                "beacon_node" => %beacon_node,
+                            );
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => warn!(
+                log,
+                "Unable to subscribe to beacon node events";
+                "error" => ?e,
+                "beacon_node" => %beacon_node,
+            ),
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Feeds the events received from `run_event_listener` to the slasher, fetching any blocks and
+/// committees they require from `beacon_node`. Returns once the sender has been dropped.
+pub async fn run_event_handler<E: EthSpec>(
+    beacon_node: BeaconNodeHttpClient,
+    slasher: Arc<Slasher<E>>,
+    indexer: Arc<AttestationIndexer<E>>,
+    seen_blocks: Arc<SeenBlocks>,
+    mut event_receiver: UnboundedReceiver<EventKind<E>>,
+    log: Logger,
+) {
+    while let Some(event) = event_receiver.recv().await {
+        handle_event(&beacon_node, &slasher, &indexer, &seen_blocks, event, &log)
+            .await
+            .unwrap_or_else(|e| {
+                debug!(
+                    log,
+                    "Unable to process beacon node event";
+                    "error" => e,
+                    "beacon_node" => %beacon_node,
+                )
+            })
+    }
+}
+
+async fn handle_event<E: EthSpec>(
+    beacon_node: &BeaconNodeHttpClient,
+    slasher: &Slasher<E>,
+    indexer: &AttestationIndexer<E>,
+    seen_blocks: &SeenBlocks,
+    event: EventKind<E>,
+    log: &Logger,
+) -> Result<(), String> {
+    match event {
+        EventKind::Attestation(attestation) => {
+            let indexed_attestation = indexer
+                .get_indexed_attestation(beacon_node, &attestation)
+                .await?;
+            slasher.accept_attestation(indexed_attestation);
+        }
+        // Imported blocks are fetched in full so that the attestations they contain are also
+        // checked, which covers attestations that were never seen on gossip.
+        EventKind::Block(block) => {
+            let block_root = block.block;
+            if seen_blocks.lock().contains(&block_root) {
+                return Ok(());
+            }
+            let Some(response) = beacon_node
+                .get_beacon_blocks::<E>(BlockId::Root(block_root))
+                .await
+                .map_err(|e| format!("unable to fetch block: {:?}", e))?
+            else {
+                return Err(format!("block {:?} not found", block_root));
+            };
+            let block = response.data;
+
+            slasher.accept_block_header(block.signed_block_header());
+            let attestations = block
+                .message()
+                .body()
+                .attestations()
+                .map(|attestation| attestation.clone_as_attestation())
+                .collect::<Vec<_>>();
+            let mut failed = 0;
+            for attestation in attestations {
+                match indexer
+                    .get_indexed_attestation(beacon_node, &attestation)
+                    .await
+                {
+                    Ok(indexed_attestation) => slasher.accept_attestation(indexed_attestation),
+                    Err(e) => {
+                        debug!(
+                            log,
+                            "Unable to index block attestation";
+                            "error" => e,
+                            "block_root" => ?block_root,
+                            "beacon_node" => %beacon_node,
+                        );
+                        failed += 1;
+                    }
+                }
+            }
+
+            // The block is only marked as seen once all of its attestations have been indexed,
+            // so that the event from another beacon node retries any which failed.
+            if failed > 0 {
+                return Err(format!(
+                    "unable to index {} attestations in block {:?}",
+                    failed, block_root
+                ));
+            }
+            seen_blocks.lock().put(block_root, ());
+        }
+        // Blocks which are rejected after their proposer signature is checked, including
+        // equivocations, are never stored by the beacon node, so their headers are sent with the
+        // event instead.
+        EventKind::RejectedBlockHeader(header) => slasher.accept_block_header(*header),
+        _ => (),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::BlockError;
+    use http_api::test_utils::InteractiveTester;
+    use logging::test_logger;
+    use slasher::Config;
+    use std::time::Instant;
+    use tempfile::tempdir;
+    use tokio::sync::mpsc::unbounded_channel;
+    use types::{Epoch, Graffiti, MinimalEthSpec, Slot};
+
+    type E = MinimalEthSpec;
+
+    const VALIDATOR_COUNT: usize = 64;
+    const DETECTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Start a chain with a slasher listening to its events.
+    async fn tester_with_slasher() -> (InteractiveTester<E>, Arc<Slasher<E>>, tempfile::TempDir) {
+        let tester = InteractiveTester::<E>::new(None, VALIDATOR_COUNT).await;
+        let tempdir = tempdir().unwrap();
+        let slasher = Arc::new(
+            Slasher::open(
+                Config::new(tempdir.path().into()),
+                Arc::new(tester.harness.spec.clone()),
+                test_logger(),
+            )
+            .unwrap(),
+        );
+        let (event_sender, event_receiver) = unbounded_channel();
+        tokio::spawn(run_event_listener(
+            tester.client.clone(),
+            event_sender,
+            test_logger(),
+        ));
+        tokio::spawn(run_event_handler(
+            tester.client.clone(),
+            slasher.clone(),
+            Arc::new(AttestationIndexer::default()),
+            Arc::new(Mutex::new(LruCache::new(SEEN_BLOCKS_CACHE_SIZE))),
+            event_receiver,
+            test_logger(),
+        ));
+
+        // Wait for the subscription so that no events are missed.
+        let event_handler = tester.harness.chain.event_handler.clone().unwrap();
+        let start = Instant::now();
+        while !event_handler.has_rejected_block_header_subscribers() {
+            assert!(
+                start.elapsed() < DETECTION_TIMEOUT,
+                "slasher did not subscribe"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        (tester, slasher, tempdir)
+    }
+
+    /// Process the slasher's queue until `found` returns `true`.
+    async fn wait_for_slashing(slasher: &Slasher<E>, mut found: impl FnMut(&Slasher<E>) -> bool) {
+        let start = Instant::now();
+        loop {
+            slasher.process_queued(Epoch::new(0)).unwrap();
+            if found(slasher) {
+                return;
+            }
+            assert!(start.elapsed() < DETECTION_TIMEOUT, "no slashing found");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn detects_double_proposal_rejected_on_gossip() {
+        let (tester, slasher, _tempdir) = tester_with_slasher().await;
+        let harness = &tester.harness;
+
+        let state = harness.get_current_state();
+        let (_, _, state) = harness
+            .add_block_at_slot(Slot::new(1), state)
+            .await
+            .unwrap();
+
+        // The first block at slot 2 is imported and sent with the `block` event.
+        let slot = Slot::new(2);
+        let (first_block, _) = harness.make_block(state.clone(), slot).await;
+        harness
+            .process_block(slot, first_block.0.canonical_root(), first_block)
+            .await
+            .unwrap();
+
+        // The second block is rejected as slashable, and only its header is sent.
+        let ((second_block, _), _) = harness
+            .make_block_with_modifier(state, slot, |block| {
+                *block.body_mut().graffiti_mut() = Graffiti::from([1; 32]);
+            })
+            .await;
+        let proposer_index = second_block.message().proposer_index();
+        assert!(matches!(
+            harness.chain.verify_block_for_gossip(second_block).await,
+            Err(BlockError::Slashable)
+        ));
+
+        wait_for_slashing(&slasher, |slasher| {
+            let slashings = slasher.get_proposer_slashings();
+            assert!(slashings
+                .iter()
+                .all(|slashing| slashing.proposer_index() == proposer_index));
+            !slashings.is_empty()
+        })
+        .await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn detects_double_vote_from_gossip_and_block() {
+        let (tester, slasher, _tempdir) = tester_with_slasher().await;
+        let harness = &tester.harness;
+
+        let state = harness.get_current_state();
+        let (first_root, _, state) = harness
+            .add_block_at_slot(Slot::new(1), state)
+            .await
+            .unwrap();
+        let (second_root, _, state) = harness
+            .add_block_at_slot(Slot::new(2), state)
+            .await
+            .unwrap();
+        let state_root = harness.get_current_state_and_root().1;
+
+        // A single validator votes for two different heads with the same target.
+        let slot = Slot::new(2);
+        let validator_index = state.get_beacon_committee(slot, 0).unwrap().committee[0];
+        let make_attestation = |head_root| {
+            harness
+                .make_attestations(&[validator_index], &state, state_root, head_root, slot)
+                .into_iter()
+                .flat_map(|(attestations, _)| attestations)
+                .map(|(attestation, _)| attestation)
+                .next()
+                .unwrap()
+        };
+        let gossip_attestation = make_attestation(second_root);
+        let block_attestation = make_attestation(first_root);
+        assert_eq!(
+            gossip_attestation.data().target,
+            block_attestation.data().target
+        );
+
+        // The conflicting vote is only seen by the slasher in the block which includes it.
+        harness
+            .chain
+            .op_pool
+            .insert_attestation(block_attestation, vec![validator_index as u64])
+            .unwrap();
+        let (_, (block, _), _) = harness
+            .add_block_at_slot(Slot::new(3), state)
+            .await
+            .unwrap();
+        assert_eq!(block.message().body().attestations_len(), 1);
+
+        // The other vote is seen with the `attestation` event.
+        let fork_name = harness.spec.fork_name_at_slot::<E>(slot);
+        tester
+            .client
+            .post_beacon_pool_attestations_v2::<E>(&[gossip_attestation], fork_name)
+            .await
+            .unwrap();
+
+        wait_for_slashing(&slasher, |slasher| {
+            let slashings = slasher.get_attester_slashings();
+            assert!(slashings.iter().all(|slashing| {
+                slashing
+                    .attestation_1()
+                    .attesting_indices_to_vec()
+                    .contains(&(validator_index as u64))
+            }));
+            !slashings.is_empty()
+        })
+        .await;
+    }
+}
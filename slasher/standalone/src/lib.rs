@@ -0,0 +1,367 @@
+//! A slasher which runs in its own process, rather than inside a beacon node.
+//!
+//! Attestations and blocks are received from the event streams of one or more beacon nodes, and
+//! any slashings found are published back to them via their operation pool endpoints.
+pub mod cli;
+mod committees;
+mod events;
+
+use crate::cli::StandaloneSlasher;
+use crate::committees::AttestationIndexer;
+use crate::events::{run_event_handler, run_event_listener, SEEN_BLOCKS_CACHE_SIZE};
+use clap::ArgMatches;
+use directory::size_of_dir;
+use environment::RuntimeContext;
+use eth2::{BeaconNodeHttpClient, Timeouts};
+use lru::LruCache;
+use parking_lot::Mutex;
+use sensitive_url::SensitiveUrl;
+use slasher::{
+    metrics::{self, SLASHER_DATABASE_SIZE, SLASHER_RUN_TIME},
+    Slasher,
+};
+use slog::{debug, error, info, trace, warn, Logger};
+use slot_clock::{SlotClock, SystemTimeSlotClock};
+use std::path::PathBuf;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::time::{interval_at, Instant};
+use types::{AttesterSlashing, ChainSpec, Epoch, EthSpec, ProposerSlashing};
+
+pub const CMD: &str = "slasher";
+
+/// The directory within the data directory which contains the slasher database by default.
+pub const DEFAULT_SLASHER_DIR: &str = "slasher_db";
+
+/// The delay between attempts to fetch the genesis time from the beacon nodes.
+const GENESIS_RETRY_DELAY: Duration = Duration::from_secs(12);
+
+/// Configuration for the standalone slasher.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub beacon_nodes: Vec<SensitiveUrl>,
+    /// A file containing the API key to send to the beacon nodes.
+    pub beacon_nodes_api_key_file: Option<PathBuf>,
+    pub slasher: slasher::Config,
+    /// Publish slashings to the beacon nodes.
+    pub publish: bool,
+}
+
+impl Config {
+    pub fn from_cli(cli_args: &ArgMatches, cli_config: &StandaloneSlasher) -> Result<Self, String> {
+        let beacon_nodes = cli_config
+            .beacon_nodes
+            .iter()
+            .map(|url| {
+                SensitiveUrl::parse(url)
+                    .map_err(|e| format!("Invalid beacon node address {}: {:?}", url, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let slasher_dir = match &cli_config.slasher_dir {
+            Some(dir) => dir.clone(),
+            None => directory::parse_path_or_default_with_flag(
+                cli_args,
+                "datadir",
+                DEFAULT_SLASHER_DIR,
+            )?,
+        };
+        let mut slasher = slasher::Config::new(slasher_dir);
+        if let Some(update_period) = cli_config.slasher_update_period {
+            slasher.update_period = update_period;
+        }
+        if let Some(slot_offset) = cli_config.slasher_slot_offset {
+            if !slot_offset.is_finite() {
+                return Err(format!(
+                    "invalid float for slasher-slot-offset: {}",
+                    slot_offset
+                ));
+            }
+            slasher.slot_offset = slot_offset;
+        }
+        if let Some(history_length) = cli_config.slasher_history_length {
+            slasher.history_length = history_length;
+        }
+        if let Some(max_db_size_gbs) = cli_config.slasher_max_db_size {
+            slasher.max_db_size_mbs = max_db_size_gbs * 1024;
+        }
+        if let Some(backend) = cli_config.slasher_backend {
+            slasher.backend = backend;
+        }
+
+        Ok(Self {
+            beacon_nodes,
+            beacon_nodes_api_key_file: cli_config.beacon_nodes_api_key_file.clone(),
+            slasher,
+            publish: !cli_config.disable_publish,
+        })
+    }
+}
+
+/// A slashing found by the slasher, to be published to the beacon nodes.
+enum Slashing<E: EthSpec> {
+    Attester(AttesterSlashing<E>),
+    Proposer(ProposerSlashing),
+}
+
+/// Run the slasher until the process is shut down.
+pub async fn run<E: EthSpec>(context: RuntimeContext<E>, config: Config) -> Result<(), String> {
+    let log = context.log().clone();
+    let executor = context.executor.clone();
+    let spec = Arc::new(context.eth2_config.spec.clone());
+
+    if config.beacon_nodes.is_empty() {
+        return Err("At least one beacon node is required".to_string());
+    }
+    let api_key = config
+        .beacon_nodes_api_key_file
+        .as_deref()
+        .map(BeaconNodeHttpClient::load_api_key_from_file)
+        .transpose()
+        .map_err(|e| format!("Unable to read beacon node API key: {:?}", e))?;
+    let beacon_nodes = config
+        .beacon_nodes
+        .iter()
+        .map(|url| {
+            let beacon_node = BeaconNodeHttpClient::new(
+                url.clone(),
+                Timeouts::set_all(Duration::from_secs(spec.seconds_per_slot)),
+            );
+            match &api_key {
+                Some(api_key) => beacon_node.with_api_key(api_key.clone()),
+                None => beacon_node,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let slot_clock = get_slot_clock(&beacon_nodes, &spec, &log).await;
+
+    info!(
+        log,
+        "Starting slasher";
+        "beacon_nodes" => beacon_nodes.len(),
+        "publish" => config.publish,
+    );
+    let slasher = Arc::new(
+        Slasher::open(config.slasher, spec.clone(), log.clone())
+            .map_err(|e| format!("Unable to open slasher database: {:?}", e))?,
+    );
+
+    let indexer = Arc::new(AttestationIndexer::default());
+    let seen_blocks = Arc::new(Mutex::new(LruCache::new(SEEN_BLOCKS_CACHE_SIZE)));
+    for beacon_node in &beacon_nodes {
+        let (event_sender, event_receiver) = unbounded_channel();
+        executor.spawn(
+            run_event_listener(beacon_node.clone(), event_sender, log.clone()),
+            "slasher_event_listener",
+        );
+        executor.spawn(
+            run_event_handler(
+                beacon_node.clone(),
+                slasher.clone(),
+                indexer.clone(),
+                seen_blocks.clone(),
+                event_receiver,
+                log.clone(),
+            ),
+            "slasher_event_handler",
+        );
+    }
+
+    let (slashing_sender, slashing_receiver) = unbounded_channel();
+    if config.publish {
+        executor.spawn(
+            publish_slashings(beacon_nodes, slashing_receiver, spec, log.clone()),
+            "slasher_publisher",
+        );
+    }
+
+    // Buffer just a single message in the channel. If the receiver is still processing, we
+    // don't need to burden them with more work (we can wait).
+    let (notif_sender, notif_receiver) = sync_channel(1);
+    executor.spawn(
+        run_notifier::<E>(slot_clock, slasher.clone(), notif_sender, log),
+        "slasher_notifier",
+    );
+    executor.spawn_blocking(
+        move || run_processor(slasher, notif_receiver, slashing_sender),
+        "slasher_processor",
+    );
+
+    Ok(())
+}
+
+/// Fetch the genesis time from the first beacon node which responds, retrying until one does.
+async fn get_slot_clock(
+    beacon_nodes: &[BeaconNodeHttpClient],
+    spec: &ChainSpec,
+    log: &Logger,
+) -> SystemTimeSlotClock {
+    loop {
+        for beacon_node in beacon_nodes {
+            match beacon_node.get_beacon_genesis().await {
+                Ok(genesis) => {
+                    return SystemTimeSlotClock::new(
+                        spec.genesis_slot,
+                        Duration::from_secs(genesis.data.genesis_time),
+                        Duration::from_secs(spec.seconds_per_slot),
+                    )
+                }
+                Err(e) => warn!(
+                    log,
+                    "Unable to fetch genesis from beacon node";
+                    "error" => ?e,
+                    "beacon_node" => %beacon_node,
+                ),
+            }
+        }
+        tokio::time::sleep(GENESIS_RETRY_DELAY).await;
+    }
+}
+
+/// Run the async notifier which periodically prompts the processor to run.
+async fn run_notifier<E: EthSpec>(
+    slot_clock: SystemTimeSlotClock,
+    slasher: Arc<Slasher<E>>,
+    notif_sender: SyncSender<Epoch>,
+    log: Logger,
+) {
+    let slot_offset = Duration::from_secs_f64(slasher.config().slot_offset);
+    let start_instant = if let Some(duration_to_next_slot) = slot_clock.duration_to_next_slot() {
+        Instant::now() + duration_to_next_slot + slot_offset
+    } else {
+        error!(log, "Error aligning slasher to slot clock");
+        Instant::now()
+    };
+    let mut interval = interval_at(
+        start_instant,
+        Duration::from_secs(slasher.config().update_period),
+    );
+
+    loop {
+        interval.tick().await;
+        if let Some(current_slot) = slot_clock.now() {
+            let current_epoch = current_slot.epoch(E::slots_per_epoch());
+            if let Err(TrySendError::Disconnected(_)) = notif_sender.try_send(current_epoch) {
+                break;
+            }
+        } else {
+            trace!(log, "Slasher has nothing to do: we are pre-genesis");
+        }
+    }
+}
+
+/// Run the blocking task that performs work.
+fn run_processor<E: EthSpec>(
+    slasher: Arc<Slasher<E>>,
+    notif_receiver: Receiver<Epoch>,
+    slashing_sender: UnboundedSender<Slashing<E>>,
+) {
+    let log = slasher.log();
+    while let Ok(current_epoch) = notif_receiver.recv() {
+        let t = Instant::now();
+
+        let batch_timer = metrics::start_timer(&SLASHER_RUN_TIME);
+        let stats = match slasher.process_queued(current_epoch) {
+            Ok(stats) => Some(stats),
+            Err(e) => {
+                error!(
+                    log,
+                    "Error during scheduled slasher processing";
+                    "epoch" => current_epoch,
+                    "error" => ?e,
+                );
+                None
+            }
+        };
+        drop(batch_timer);
+
+        // Prune the database, even in the case where batch processing failed.
+        // If the database is full then pruning could help to free it up.
+        if let Err(e) = slasher.prune_database(current_epoch) {
+            error!(
+                log,
+                "Error during slasher database pruning";
+                "epoch" => current_epoch,
+                "error" => ?e,
+            );
+            continue;
+        };
+
+        // The receiver is dropped if publishing is disabled, in which case slashings are only
+        // logged.
+        for slashing in slasher.get_attester_slashings() {
+            info!(
+                log,
+                "Found attester slashing";
+                "slashing" => ?slashing,
+            );
+            let _ = slashing_sender.send(Slashing::Attester(slashing));
+        }
+        for slashing in slasher.get_proposer_slashings() {
+            info!(
+                log,
+                "Found proposer slashing";
+                "slashing" => ?slashing,
+            );
+            let _ = slashing_sender.send(Slashing::Proposer(slashing));
+        }
+
+        let database_size = size_of_dir(&slasher.config().database_path);
+        metrics::set_gauge(&SLASHER_DATABASE_SIZE, database_size as i64);
+
+        if let Some(stats) = stats {
+            debug!(
+                log,
+                "Completed slasher update";
+                "epoch" => current_epoch,
+                "time_taken" => format!("{}ms", t.elapsed().as_millis()),
+                "num_attestations" => stats.attestation_stats.num_processed,
+                "num_blocks" => stats.block_stats.num_processed,
+            );
+        }
+    }
+}
+
+/// Publish each slashing to every beacon node, which verifies it, adds it to its operation pool
+/// and broadcasts it to the network.
+async fn publish_slashings<E: EthSpec>(
+    beacon_nodes: Vec<BeaconNodeHttpClient>,
+    mut slashing_receiver: UnboundedReceiver<Slashing<E>>,
+    spec: Arc<ChainSpec>,
+    log: Logger,
+) {
+    while let Some(slashing) = slashing_receiver.recv().await {
+        for beacon_node in &beacon_nodes {
+            let result = match &slashing {
+                Slashing::Attester(slashing) => {
+                    let fork_name =
+                        spec.fork_name_at_slot::<E>(slashing.attestation_1().data().slot);
+                    beacon_node
+                        .post_beacon_pool_attester_slashings_v2(slashing, fork_name)
+                        .await
+                }
+                Slashing::Proposer(slashing) => {
+                    beacon_node
+                        .post_beacon_pool_proposer_slashings(slashing)
+                        .await
+                }
+            };
+            match result {
+                Ok(()) => info!(
+                    log,
+                    "Published slashing";
+                    "beacon_node" => %beacon_node,
+                ),
+                Err(e) => warn!(
+                    log,
+                    "Unable to publish slashing";
+                    "error" => ?e,
+                    "beacon_node" => %beacon_node,
+                ),
+            }
+        }
+    }
+}
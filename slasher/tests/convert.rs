@@ -0,0 +1,144 @@
+#![cfg(any(feature = "mdbx", feature = "lmdb", feature = "redb"))]
+
+use logging::test_logger;
+use slasher::{convert_database, Config, Environment, Error, CONVERSION_BATCH_SIZE};
+use std::path::Path;
+use tempfile::tempdir;
+
+fn key(index: u64) -> [u8; 8] {
+    index.to_be_bytes()
+}
+
+fn value(table_index: usize, index: u64) -> Vec<u8> {
+    vec![table_index as u8; index as usize % 64]
+}
+
+/// The number of entries written to each table, enough for the first to need several batches.
+fn num_entries(table_index: usize) -> u64 {
+    if table_index == 0 {
+        CONVERSION_BATCH_SIZE + 1
+    } else {
+        table_index as u64 * 3
+    }
+}
+
+fn new_config(path: &Path) -> Config {
+    std::fs::create_dir_all(path).unwrap();
+    Config::new(path.into())
+}
+
+fn populate(config: &Config) {
+    let env = Environment::new(config).unwrap();
+    let databases = env.create_databases().unwrap();
+    let mut txn = env.begin_rw_txn().unwrap();
+    for (table_index, (_, db)) in databases.tables().into_iter().enumerate() {
+        for index in 0..num_entries(table_index) {
+            txn.put(db, key(index), value(table_index, index)).unwrap();
+        }
+    }
+    txn.commit().unwrap();
+}
+
+#[test]
+fn convert_copies_all_tables() {
+    let source_dir = tempdir().unwrap();
+    let dest_dir = tempdir().unwrap();
+    let source_config = new_config(source_dir.path());
+    let dest_config = Config::new(dest_dir.path().join("converted"));
+    populate(&source_config);
+
+    let tables = convert_database(&source_config, &dest_config, false, &test_logger()).unwrap();
+    assert_eq!(tables.len(), 9);
+    for (table_index, table) in tables.iter().enumerate() {
+        assert_eq!(table.entries, num_entries(table_index));
+    }
+
+    let env = Environment::new(&dest_config).unwrap();
+    let databases = env.create_databases().unwrap();
+    let txn = env.begin_rw_txn().unwrap();
+    for (table_index, (_, db)) in databases.tables().into_iter().enumerate() {
+        for index in 0..num_entries(table_index) {
+            assert_eq!(
+                txn.get(db, &key(index)).unwrap().unwrap().as_ref(),
+                value(table_index, index).as_slice()
+            );
+        }
+    }
+}
+
+#[test]
+fn convert_into_non_empty_destination() {
+    let source_dir = tempdir().unwrap();
+    let dest_dir = tempdir().unwrap();
+    let source_config = new_config(source_dir.path());
+    let dest_config = new_config(dest_dir.path());
+    populate(&source_config);
+    populate(&dest_config);
+
+    assert!(matches!(
+        convert_database(&source_config, &dest_config, false, &test_logger()),
+        Err(Error::ConversionDestinationNotEmpty(_))
+    ));
+}
+
+#[test]
+fn convert_into_same_directory() {
+    let dir = tempdir().unwrap();
+    let config = new_config(dir.path());
+    populate(&config);
+
+    assert!(matches!(
+        convert_database(&config, &config, false, &test_logger()),
+        Err(Error::ConversionSameDirectory(_))
+    ));
+}
+
+#[test]
+fn convert_missing_source() {
+    let dir = tempdir().unwrap();
+    let source_config = Config::new(dir.path().join("missing"));
+    let dest_config = Config::new(dir.path().join("converted"));
+
+    assert!(matches!(
+        convert_database(&source_config, &dest_config, false, &test_logger()),
+        Err(Error::ConversionSourceMissing(_))
+    ));
+}
+
+/// A directory without a data file for the backend, e.g. one holding a database of a different
+/// backend, must not be mistaken for an empty database.
+#[test]
+fn convert_source_without_data_file() {
+    let source_dir = tempdir().unwrap();
+    let dest_dir = tempdir().unwrap();
+    let source_config = new_config(source_dir.path());
+    let dest_config = Config::new(dest_dir.path().join("converted"));
+    let source_file = source_config.database_file().unwrap();
+
+    assert!(matches!(
+        convert_database(&source_config, &dest_config, false, &test_logger()),
+        Err(Error::ConversionSourceMissing(path)) if path == source_file
+    ));
+    assert!(!source_file.exists());
+    assert!(!dest_config.database_path.exists());
+}
+
+#[test]
+fn convert_empty_source() {
+    let source_dir = tempdir().unwrap();
+    let dest_dir = tempdir().unwrap();
+    let source_config = new_config(source_dir.path());
+    let dest_config = Config::new(dest_dir.path().join("converted"));
+    let env = Environment::new(&source_config).unwrap();
+    env.create_databases().unwrap();
+    drop(env);
+
+    assert!(matches!(
+        convert_database(&source_config, &dest_config, false, &test_logger()),
+        Err(Error::ConversionSourceEmpty(_))
+    ));
+    assert!(!dest_config.database_path.exists());
+
+    let tables = convert_database(&source_config, &dest_config, true, &test_logger()).unwrap();
+    assert!(tables.iter().all(|table| table.entries == 0));
+}
@@ -0,0 +1,52 @@
+#![cfg(any(feature = "mdbx", feature = "lmdb", feature = "redb"))]
+
+use slasher::{Config, DatabaseBackend, Environment};
+use tempfile::tempdir;
+
+const NUM_KEYS: u8 = 10;
+
+fn backends() -> Vec<DatabaseBackend> {
+    vec![
+        #[cfg(feature = "mdbx")]
+        DatabaseBackend::Mdbx,
+        #[cfg(feature = "lmdb")]
+        DatabaseBackend::Lmdb,
+        #[cfg(feature = "redb")]
+        DatabaseBackend::Redb,
+    ]
+}
+
+/// Iterating a table with `next_key` should visit each key once, in order.
+#[test]
+fn next_key_visits_each_key_once() {
+    for backend in backends() {
+        let tempdir = tempdir().unwrap();
+        let mut config = Config::new(tempdir.path().into());
+        config.backend = backend;
+        let env = Environment::new(&config).unwrap();
+        let databases = env.create_databases().unwrap();
+        let db = &databases.proposers_db;
+
+        let keys = (0..NUM_KEYS).map(|i| vec![i; 8]).collect::<Vec<_>>();
+        let mut txn = env.begin_rw_txn().unwrap();
+        for key in keys.iter().rev() {
+            txn.put(db, key, [0]).unwrap();
+        }
+        txn.commit().unwrap();
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        let mut cursor = txn.cursor(db).unwrap();
+        let mut visited = vec![];
+        let mut next = cursor.first_key().unwrap().map(|key| key.to_vec());
+        while let Some(key) = next {
+            visited.push(key);
+            assert!(
+                visited.len() <= keys.len(),
+                "{} cursor returned a key twice",
+                backend
+            );
+            next = cursor.next_key().unwrap().map(|key| key.to_vec());
+        }
+        assert_eq!(visited, keys, "{}", backend);
+    }
+}
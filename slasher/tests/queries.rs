@@ -0,0 +1,133 @@
+#![cfg(any(feature = "mdbx", feature = "lmdb", feature = "redb"))]
+
+use logging::test_logger;
+use slasher::{
+    test_utils::{block, chain_spec, indexed_att, E},
+    Config, Slasher, TargetBounds,
+};
+use tempfile::tempdir;
+use types::{Epoch, Slot};
+
+fn open_slasher(path: &std::path::Path) -> Slasher<E> {
+    Slasher::open(Config::new(path.into()), chain_spec(), test_logger()).unwrap()
+}
+
+#[test]
+fn attestations_for_validator() {
+    let tempdir = tempdir().unwrap();
+    let slasher = open_slasher(tempdir.path());
+
+    for (source, target) in [(0, 1), (1, 2), (2, 3)] {
+        slasher.accept_attestation(indexed_att([0, 1], source, target, 0));
+    }
+    slasher.process_queued(Epoch::new(3)).unwrap();
+
+    let targets = |validator_index, start_epoch, end_epoch| {
+        slasher
+            .get_attestations_for_validator(
+                validator_index,
+                Epoch::new(start_epoch),
+                Epoch::new(end_epoch),
+            )
+            .unwrap()
+            .iter()
+            .map(|attestation| attestation.data().target.epoch.as_u64())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(targets(0, 0, 10), vec![1, 2, 3]);
+    assert_eq!(targets(1, 2, 2), vec![2]);
+    assert_eq!(targets(2, 0, 10), Vec::<u64>::new());
+}
+
+#[test]
+fn target_bounds() {
+    let tempdir = tempdir().unwrap();
+    let slasher = open_slasher(tempdir.path());
+
+    for (source, target) in [(0, 1), (1, 2), (2, 3)] {
+        slasher.accept_attestation(indexed_att([0], source, target, 0));
+    }
+    slasher.process_queued(Epoch::new(3)).unwrap();
+
+    let bounds = slasher
+        .get_target_bounds(0, Epoch::new(0), Epoch::new(10))
+        .unwrap();
+    let expected = [
+        (0, Some(2), None),
+        (1, Some(3), Some(1)),
+        (2, None, Some(2)),
+        (3, None, Some(3)),
+    ]
+    .into_iter()
+    .map(|(source_epoch, min_target, max_target)| TargetBounds {
+        source_epoch: Epoch::new(source_epoch),
+        min_target: min_target.map(Epoch::new),
+        max_target: max_target.map(Epoch::new),
+    })
+    .collect::<Vec<_>>();
+    assert_eq!(bounds, expected);
+}
+
+#[test]
+fn block_proposals() {
+    let tempdir = tempdir().unwrap();
+    let slasher = open_slasher(tempdir.path());
+
+    for slot in [1, 2, 5] {
+        slasher.accept_block_header(block(slot, 5, 0));
+    }
+    slasher.accept_block_header(block(2, 6, 0));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+
+    let proposals = slasher
+        .get_block_proposals(5, Slot::new(0), Slot::new(3))
+        .unwrap();
+    assert_eq!(proposals, vec![block(1, 5, 0), block(2, 5, 0)]);
+}
+
+#[test]
+fn slashing_history_retained_after_harvest() {
+    let tempdir = tempdir().unwrap();
+    let slasher = open_slasher(tempdir.path());
+
+    slasher.accept_attestation(indexed_att([0], 0, 1, 0));
+    slasher.accept_attestation(indexed_att([0], 0, 1, 1));
+    slasher.accept_block_header(block(1, 0, 0));
+    slasher.accept_block_header(block(1, 0, 1));
+    slasher.process_queued(Epoch::new(1)).unwrap();
+
+    assert_eq!(slasher.get_attester_slashings().len(), 1);
+    assert_eq!(slasher.get_proposer_slashings().len(), 1);
+
+    let history = slasher.slashing_history();
+    assert_eq!(history.attester_slashings.len(), 1);
+    assert_eq!(history.proposer_slashings.len(), 1);
+}
+
+#[test]
+fn database_stats() {
+    let tempdir = tempdir().unwrap();
+    let slasher = open_slasher(tempdir.path());
+
+    for slot in 1..=3 {
+        slasher.accept_block_header(block(slot, 0, 0));
+    }
+    slasher.accept_attestation(indexed_att([0, 1], 0, 1, 0));
+    slasher.process_queued(Epoch::new(1)).unwrap();
+
+    let stats = slasher.database_stats().unwrap();
+    assert_eq!(stats.backend, slasher.config().backend);
+    assert!(stats.size > 0);
+
+    let entries = |name| {
+        stats
+            .tables
+            .iter()
+            .find(|table| table.name == name)
+            .unwrap()
+            .entries
+    };
+    assert_eq!(entries("proposers"), 3);
+    assert_eq!(entries("indexed_attestations"), 1);
+    assert_eq!(entries("indexed_attestation_ids"), 1);
+}
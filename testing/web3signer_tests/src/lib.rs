@@ -366,6 +366,7 @@ mod tests {
                 None,
                 slot_clock,
                 &config,
+                None,
                 executor,
                 log.clone(),
             );
@@ -2,6 +2,7 @@
 
 use super::*;
 use crate::test_utils::*;
+use tempfile::tempdir;
 use types::{BeaconBlockHeader, Slot};
 
 pub fn block(slot: u64) -> BeaconBlockHeader {
@@ -122,3 +123,30 @@ fn invalid_unregistered_validator() {
     }
     .run()
 }
+
+#[test]
+fn read_only_check_does_not_insert() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+    let pk = pubkey(DEFAULT_VALIDATOR_INDEX);
+    slashing_db.register_validator(pk).unwrap();
+
+    let first_block = block(1);
+    let mut conflicting_block = block(1);
+    conflicting_block.body_root = Hash256::random();
+
+    assert_eq!(
+        slashing_db.check_block_proposal_read_only(&pk, &first_block, DEFAULT_DOMAIN),
+        Ok(Safe::Valid)
+    );
+    // A conflicting block is still safe to sign because the first block was not recorded.
+    assert_eq!(
+        slashing_db.check_and_insert_block_proposal(&pk, &conflicting_block, DEFAULT_DOMAIN),
+        Ok(Safe::Valid)
+    );
+    assert!(matches!(
+        slashing_db.check_block_proposal_read_only(&pk, &first_block, DEFAULT_DOMAIN),
+        Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(_)))
+    ));
+}
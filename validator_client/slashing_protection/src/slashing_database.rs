@@ -670,6 +670,46 @@ impl SlashingDatabase {
         Ok(safe)
     }
 
+    /// Check a block proposal for slash safety *without* recording it in the database.
+    ///
+    /// This is only intended for use by a shadow validator client, which must never sign
+    /// anything and so must never mutate the slashing protection history.
+    pub fn check_block_proposal_read_only(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        block_header: &BeaconBlockHeader,
+        domain: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+        self.check_block_proposal(
+            &txn,
+            validator_pubkey,
+            block_header.slot,
+            block_header.signing_root(domain).into(),
+        )
+    }
+
+    /// Check an attestation for slash safety *without* recording it in the database.
+    ///
+    /// See `check_block_proposal_read_only`.
+    pub fn check_attestation_read_only(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        attestation: &AttestationData,
+        domain: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+        self.check_attestation(
+            &txn,
+            validator_pubkey,
+            attestation.source.epoch,
+            attestation.target.epoch,
+            attestation.signing_root(domain).into(),
+        )
+    }
+
     /// Import slashing protection from another client in the interchange format.
     ///
     /// This function will atomically import the entire interchange, failing if *any*
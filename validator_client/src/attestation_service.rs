@@ -455,6 +455,18 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .spec
             .fork_name_at_slot::<E>(attestation_data.slot);
 
+        if self.validator_store.shadow_mode() {
+            debug!(
+                log,
+                "Not publishing shadow attestations";
+                "count" => attestations.len(),
+                "head_block" => ?attestation_data.beacon_block_root,
+                "committee_index" => attestation_data.index,
+                "slot" => attestation_data.slot.as_u64(),
+            );
+            return Ok(Some(attestation_data));
+        }
+
         // Post the attestations to the BN.
         match self
             .beacon_nodes
@@ -633,7 +645,15 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .flatten()
             .collect::<Vec<_>>();
 
-        if !signed_aggregate_and_proofs.is_empty() {
+        if self.validator_store.shadow_mode() {
+            debug!(
+                log,
+                "Not publishing shadow aggregates";
+                "count" => signed_aggregate_and_proofs.len(),
+                "committee_index" => attestation_data.index,
+                "slot" => attestation_data.slot.as_u64(),
+            );
+        } else if !signed_aggregate_and_proofs.is_empty() {
             let signed_aggregate_and_proofs_slice = signed_aggregate_and_proofs.as_slice();
             match self
                 .beacon_nodes
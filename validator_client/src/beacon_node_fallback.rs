@@ -1,12 +1,20 @@
 //! Allows for a list of `BeaconNodeHttpClient` to appear as a single entity which will exhibits
 //! "fallback" behaviour; it will try a request on all of the nodes until one or none of them
 //! succeed.
+//!
+//! Candidates are tried in order of their health score (see `beacon_node_health`), which is
+//! re-evaluated each slot.
 
+use crate::beacon_node_health::{rerank, BeaconNodeHealth, SyncHealth};
 use crate::check_synced::check_synced;
-use crate::http_metrics::metrics::{inc_counter_vec, ENDPOINT_ERRORS, ENDPOINT_REQUESTS};
+use crate::http_metrics::metrics::{
+    inc_counter_vec, set_gauge_vec, ENDPOINT_ERRORS, ENDPOINT_REQUESTS, VC_BEACON_NODE_HEALTH_SCORE,
+};
 use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::BeaconNodeHealthData;
 use eth2::BeaconNodeHttpClient;
 use futures::future;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
@@ -149,11 +157,23 @@ pub enum CandidateError {
     NotSynced,
 }
 
+impl CandidateError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CandidateError::Uninitialized => "uninitialized",
+            CandidateError::Offline => "offline",
+            CandidateError::Incompatible => "incompatible",
+            CandidateError::NotSynced => "not_synced",
+        }
+    }
+}
+
 /// Represents a `BeaconNodeHttpClient` inside a `BeaconNodeFallback` that may or may not be used
 /// for a query.
 pub struct CandidateBeaconNode<E> {
     beacon_node: BeaconNodeHttpClient,
     status: RwLock<Result<(), CandidateError>>,
+    health: Mutex<BeaconNodeHealth>,
     _phantom: PhantomData<E>,
 }
 
@@ -163,10 +183,21 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         Self {
             beacon_node,
             status: RwLock::new(Err(CandidateError::Uninitialized)),
+            health: Mutex::new(BeaconNodeHealth::default()),
             _phantom: PhantomData,
         }
     }
 
+    /// Returns the health of `self`.
+    pub fn health(&self) -> BeaconNodeHealth {
+        *self.health.lock()
+    }
+
+    /// Record the outcome of a request to `self`, for the purpose of health scoring.
+    fn record_request(&self, success: bool) {
+        self.health.lock().record_request(success);
+    }
+
     /// Returns the status of `self`.
     ///
     /// If `RequiredSynced::No`, any `NotSynced` node will be ignored and mapped to `Ok(())`.
@@ -332,7 +363,10 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         log: &Logger,
     ) -> Result<(), CandidateError> {
         if let Some(slot_clock) = slot_clock {
-            check_synced(&self.beacon_node, slot_clock, Some(log)).await
+            let (result, syncing) = check_synced(&self.beacon_node, slot_clock, Some(log)).await;
+            self.health.lock().sync =
+                syncing.map(|data| SyncHealth::from_syncing_data(&data, slot_clock.now()));
+            result
         } else {
             // Skip this check if we don't supply a slot clock.
             Ok(())
@@ -345,6 +379,8 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
 /// identical query.
 pub struct BeaconNodeFallback<T, E> {
     candidates: Vec<CandidateBeaconNode<E>>,
+    /// The indices of `candidates` in order of preference.
+    ranking: Mutex<Vec<usize>>,
     slot_clock: Option<T>,
     broadcast_topics: Vec<ApiTopic>,
     spec: ChainSpec,
@@ -358,8 +394,10 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         spec: ChainSpec,
         log: Logger,
     ) -> Self {
+        let ranking = Mutex::new((0..candidates.len()).collect());
         Self {
             candidates,
+            ranking,
             slot_clock: None,
             broadcast_topics,
             spec,
@@ -430,6 +468,78 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
 
         // run all updates concurrently and ignore errors
         let _ = future::join_all(futures).await;
+
+        self.rerank_candidates();
+    }
+
+    /// Re-order the candidates according to their health scores.
+    fn rerank_candidates(&self) {
+        let scores = self
+            .candidates
+            .iter()
+            .map(|candidate| {
+                let score = candidate.health().score();
+                set_gauge_vec(
+                    &VC_BEACON_NODE_HEALTH_SCORE,
+                    &[candidate.beacon_node.as_ref()],
+                    score as i64,
+                );
+                score
+            })
+            .collect::<Vec<_>>();
+
+        let mut ranking = self.ranking.lock();
+        let previous_first = ranking.first().copied();
+        rerank(&mut ranking, &scores);
+
+        if let Some(first) = ranking.first().copied() {
+            if Some(first) != previous_first {
+                info!(
+                    self.log,
+                    "Preferred beacon node changed";
+                    "endpoint" => %self.candidates[first].beacon_node,
+                    "score" => scores[first],
+                );
+            }
+        }
+    }
+
+    /// Returns the candidates in order of preference.
+    fn ranked_candidates(&self) -> Vec<&CandidateBeaconNode<E>> {
+        self.ranking
+            .lock()
+            .iter()
+            .map(|index| &self.candidates[*index])
+            .collect()
+    }
+
+    /// Returns the health of each candidate in order of preference.
+    pub async fn health(&self) -> Vec<BeaconNodeHealthData> {
+        let ranking = self.ranking.lock().clone();
+        let mut health = Vec::with_capacity(ranking.len());
+        for index in ranking {
+            let candidate = &self.candidates[index];
+            let status = match candidate.status(RequireSynced::Yes).await {
+                Ok(()) => "synced",
+                Err(e) => e.as_str(),
+            };
+            let candidate_health = candidate.health();
+            let sync = candidate_health.sync;
+            health.push(BeaconNodeHealthData {
+                index,
+                endpoint: candidate.beacon_node.to_string(),
+                status: status.to_string(),
+                score: candidate_health.score(),
+                head_distance: sync.map(|sync| sync.head_distance),
+                is_optimistic: sync.map(|sync| sync.is_optimistic),
+                el_offline: sync.map(|sync| sync.el_offline),
+                error_rate: candidate_health.error_rate,
+                latency_ms: candidate_health
+                    .latency
+                    .map(|latency| latency.as_millis() as u64),
+            });
+        }
+        health
     }
 
     /// Concurrently send a request to all candidates (regardless of
@@ -439,7 +549,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         let futures: Vec<_> = self
             .candidates
             .iter()
-            .map(|candidate| async {
+            .map(|candidate| async move {
                 let beacon_node_id = candidate.beacon_node.to_string();
                 // The `node/version` endpoint is used since I imagine it would
                 // require the least processing in the BN and therefore measure
@@ -453,7 +563,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
                     .await
                     .ok()
                     .map(|_| Instant::now());
-                (beacon_node_id, response_instant)
+                (candidate, beacon_node_id, response_instant)
             })
             .collect();
 
@@ -465,10 +575,16 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         future::join_all(futures)
             .await
             .into_iter()
-            .map(|(beacon_node_id, response_instant)| LatencyMeasurement {
-                beacon_node_id,
-                latency: response_instant
-                    .and_then(|response| response.checked_duration_since(request_instant)),
+            .map(|(candidate, beacon_node_id, response_instant)| {
+                let latency = response_instant
+                    .and_then(|response| response.checked_duration_since(request_instant));
+                if latency.is_some() {
+                    candidate.health.lock().latency = latency;
+                }
+                LatencyMeasurement {
+                    beacon_node_id,
+                    latency,
+                }
             })
             .collect()
     }
@@ -506,8 +622,12 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
                 // There exists a race condition where `func` may be called when the candidate is
                 // actually not ready. We deem this an acceptable inefficiency.
                 match func(&$candidate.beacon_node).await {
-                    Ok(val) => return Ok(val),
+                    Ok(val) => {
+                        $candidate.record_request(true);
+                        return Ok(val);
+                    }
                     Err(e) => {
+                        $candidate.record_request(false);
                         debug!(
                             log,
                             "Request to beacon node failed";
@@ -529,10 +649,10 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
             }};
         }
 
-        // First pass: try `func` on all synced and ready candidates.
+        // First pass: try `func` on all synced and ready candidates, in order of preference.
         //
         // This ensures that we always choose a synced node if it is available.
-        for candidate in &self.candidates {
+        for candidate in self.ranked_candidates() {
             match candidate.status(RequireSynced::Yes).await {
                 Err(e @ CandidateError::NotSynced) if require_synced == false => {
                     // This client is unsynced we will try it after trying all synced clients
@@ -615,8 +735,12 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
             // There exists a race condition where `func` may be called when the candidate is
             // actually not ready. We deem this an acceptable inefficiency.
             match func(&candidate.beacon_node).await {
-                Ok(val) => Ok(val),
+                Ok(val) => {
+                    candidate.record_request(true);
+                    Ok(val)
+                }
                 Err(e) => {
+                    candidate.record_request(false);
                     // If we have an error on this function, mark the client as not-ready.
                     //
                     // There exists a race condition where the candidate may have been marked
@@ -635,7 +759,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         //
         // This ensures that we always choose a synced node if it is available.
         let mut first_batch_futures = vec![];
-        for candidate in &self.candidates {
+        for candidate in self.ranked_candidates() {
             match candidate.status(RequireSynced::Yes).await {
                 Ok(_) => {
                     first_batch_futures.push(run_on_candidate(candidate));
@@ -0,0 +1,208 @@
+//! Provides a health score for each beacon node, used by `BeaconNodeFallback` to prefer the
+//! healthiest candidate.
+//!
+//! The score starts at `MAX_HEALTH_SCORE` and is reduced by a penalty for each of:
+//!
+//! - The distance between the head of the beacon node and the current slot.
+//! - The beacon node being optimistically synced, or its execution layer being offline.
+//! - The recent rate of failed requests.
+//! - The latency of the beacon node.
+//!
+//! To avoid flip-flopping between candidates with similar scores, a candidate is only ranked ahead
+//! of another if its score is higher by more than `HYSTERESIS_MARGIN`.
+
+use eth2::types::SyncingData;
+use std::time::Duration;
+use types::Slot;
+
+/// The score of a perfectly healthy beacon node.
+pub const MAX_HEALTH_SCORE: u64 = 1_000;
+/// The penalty for each slot that the head of the beacon node is behind the current slot.
+pub const HEAD_DISTANCE_PENALTY: u64 = 20;
+/// The penalty for a beacon node whose sync status is unknown.
+pub const UNKNOWN_SYNC_PENALTY: u64 = 200;
+/// The penalty for an optimistically synced beacon node.
+pub const OPTIMISTIC_PENALTY: u64 = 100;
+/// The penalty for a beacon node with an offline execution layer.
+pub const EL_OFFLINE_PENALTY: u64 = 200;
+/// The penalty for a beacon node for which every recent request failed.
+pub const MAX_ERROR_RATE_PENALTY: u64 = 300;
+/// The penalty for each 100ms of latency.
+pub const LATENCY_PENALTY_PER_100MS: u64 = 5;
+/// The score by which a candidate must exceed another in order to be ranked ahead of it.
+pub const HYSTERESIS_MARGIN: u64 = 30;
+/// The weight of each request in the exponential moving average of the error rate.
+pub const ERROR_RATE_WEIGHT: f64 = 0.1;
+
+/// The sync status of a beacon node, as relevant to its health.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyncHealth {
+    /// The number of slots by which the head of the beacon node trails the current slot.
+    pub head_distance: u64,
+    pub is_optimistic: bool,
+    pub el_offline: bool,
+}
+
+impl SyncHealth {
+    /// Determine the sync health from the response of the beacon node's `node/syncing` endpoint.
+    ///
+    /// The head distance is measured against our own clock where possible, since a beacon node
+    /// which is not syncing may still trail the current slot.
+    pub fn from_syncing_data(data: &SyncingData, current_slot: Option<Slot>) -> Self {
+        let head_distance = current_slot
+            .map(|slot| slot.saturating_sub(data.head_slot))
+            .unwrap_or(data.sync_distance)
+            .as_u64();
+        Self {
+            head_distance,
+            is_optimistic: data.is_optimistic,
+            el_offline: data.el_offline,
+        }
+    }
+}
+
+/// The health of a single beacon node.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BeaconNodeHealth {
+    /// The sync status from the latest successful status check.
+    pub sync: Option<SyncHealth>,
+    /// The exponential moving average of the proportion of requests that failed.
+    pub error_rate: f64,
+    /// The latency from the latest latency measurement.
+    pub latency: Option<Duration>,
+}
+
+impl BeaconNodeHealth {
+    /// Record the outcome of a request to the beacon node.
+    pub fn record_request(&mut self, success: bool) {
+        let outcome = if success { 0.0 } else { 1.0 };
+        self.error_rate += ERROR_RATE_WEIGHT * (outcome - self.error_rate);
+    }
+
+    /// Returns the health score, where a higher score is healthier.
+    pub fn score(&self) -> u64 {
+        let sync_penalty = match self.sync {
+            Some(sync) => {
+                let mut penalty = sync.head_distance.saturating_mul(HEAD_DISTANCE_PENALTY);
+                if sync.is_optimistic {
+                    penalty += OPTIMISTIC_PENALTY;
+                }
+                if sync.el_offline {
+                    penalty += EL_OFFLINE_PENALTY;
+                }
+                penalty
+            }
+            None => UNKNOWN_SYNC_PENALTY,
+        };
+        let error_penalty = (self.error_rate * MAX_ERROR_RATE_PENALTY as f64).round() as u64;
+        let latency_penalty = self.latency.map_or(0, |latency| {
+            (latency.as_millis() as u64 / 100).saturating_mul(LATENCY_PENALTY_PER_100MS)
+        });
+
+        MAX_HEALTH_SCORE
+            .saturating_sub(sync_penalty)
+            .saturating_sub(error_penalty)
+            .saturating_sub(latency_penalty)
+    }
+}
+
+/// Re-order `ranking`, a list of candidate indices, by the `scores` of each candidate.
+///
+/// Each position is filled by the highest ranked of the remaining candidates, unless another
+/// remaining candidate has a score higher by more than `HYSTERESIS_MARGIN`, in which case the
+/// candidate with the highest score takes the position instead. Candidates with similar scores
+/// therefore retain their existing order, whilst a much healthier candidate is always promoted
+/// regardless of the candidates ranked between it and the front.
+pub fn rerank(ranking: &mut [usize], scores: &[u64]) {
+    for position in 0..ranking.len() {
+        let incumbent = scores[ranking[position]];
+        // Prefer the earliest of equal scores so that ties retain their existing order.
+        let Some((best_position, best)) = ranking[position..]
+            .iter()
+            .enumerate()
+            .map(|(offset, candidate)| (position + offset, scores[*candidate]))
+            .rev()
+            .max_by_key(|(_, score)| *score)
+        else {
+            return;
+        };
+        if best > incumbent.saturating_add(HYSTERESIS_MARGIN) {
+            // Shift the candidates in between back by one, preserving their relative order.
+            ranking[position..=best_position].rotate_right(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn synced(head_distance: u64) -> BeaconNodeHealth {
+        BeaconNodeHealth {
+            sync: Some(SyncHealth {
+                head_distance,
+                is_optimistic: false,
+                el_offline: false,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn score() {
+        assert_eq!(synced(0).score(), MAX_HEALTH_SCORE);
+        assert!(synced(0).score() > synced(3).score());
+        assert!(BeaconNodeHealth::default().score() < synced(0).score());
+
+        let mut failing = synced(0);
+        for _ in 0..100 {
+            failing.record_request(false);
+        }
+        assert!(failing.score() < synced(3).score());
+
+        let slow = BeaconNodeHealth {
+            latency: Some(Duration::from_millis(450)),
+            ..synced(0)
+        };
+        assert_eq!(
+            slow.score(),
+            MAX_HEALTH_SCORE - 4 * LATENCY_PENALTY_PER_100MS
+        );
+    }
+
+    #[test]
+    fn rerank_with_hysteresis() {
+        let lagging = synced(3).score();
+        let healthy = synced(0).score();
+        let slightly_slower = BeaconNodeHealth {
+            latency: Some(Duration::from_millis(300)),
+            ..synced(0)
+        }
+        .score();
+
+        // A lagging primary is ranked behind a healthy backup.
+        let mut ranking = vec![0, 1];
+        rerank(&mut ranking, &[lagging, healthy]);
+        assert_eq!(ranking, vec![1, 0]);
+
+        // Similar scores do not change the order.
+        let mut ranking = vec![0, 1];
+        rerank(&mut ranking, &[slightly_slower, healthy]);
+        assert_eq!(ranking, vec![0, 1]);
+
+        // Candidates move past multiple others.
+        let mut ranking = vec![0, 1, 2];
+        rerank(&mut ranking, &[lagging, lagging, healthy]);
+        assert_eq!(ranking, vec![2, 0, 1]);
+
+        // The best candidate is promoted even if each adjacent pair is within the margin.
+        let mut ranking = vec![0, 1, 2];
+        rerank(&mut ranking, &[100, 120, 140]);
+        assert_eq!(ranking, vec![2, 0, 1]);
+
+        // Among several candidates exceeding the margin, the highest score is promoted.
+        let mut ranking = vec![0, 1, 2, 3];
+        rerank(&mut ranking, &[100, 140, 160, 150]);
+        assert_eq!(ranking, vec![2, 3, 1, 0]);
+    }
+}
@@ -405,6 +405,17 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         let signing_time_ms =
             Duration::from_secs_f64(signing_timer.map_or(0.0, |t| t.stop_and_record())).as_millis();
 
+        if self.validator_store.shadow_mode() {
+            info!(
+                log,
+                "Not publishing shadow block";
+                "block_type" => ?signed_block.block_type(),
+                "attestations" => signed_block.num_attestations(),
+                "slot" => slot.as_u64(),
+            );
+            return Ok(());
+        }
+
         info!(
             log,
             "Publishing signed block";
@@ -139,6 +139,17 @@ pub fn cli_app() -> Command {
                         delimiter, and must not be part of the certificate path.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("beacon-nodes-api-key-file")
+                .long("beacon-nodes-api-key-file")
+                .value_name("PATH")
+                .action(ArgAction::Set)
+                .help("Path to a file containing an API key to send to the beacon nodes (and \
+                        proposer nodes) in an `Authorization: Bearer` header. Required if the \
+                        beacon nodes are run with --http-api-keys-file and do not allow \
+                        anonymous access to the validator endpoints.")
+                .display_order(0)
+        )
         // This overwrites the graffiti configured in the beacon node.
         .arg(
             Arg::new("graffiti")
@@ -157,6 +168,19 @@ pub fn cli_app() -> Command {
                 .conflicts_with("graffiti")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("proposer-config-file")
+                .long("proposer-config-file")
+                .help("Specify a JSON file of default and per-validator proposer settings \
+                    (fee recipient, gas limit, builder proposals, builder boost factor and \
+                    graffiti). Settings for a specific validator take priority over the \
+                    validator definitions, whilst the defaults apply only where neither \
+                    specifies a value. The file is reloaded whenever it is modified; if it \
+                    becomes invalid an error is logged and the previous settings are retained.")
+                .value_name("PATH")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("suggested-fee-recipient")
                 .long("suggested-fee-recipient")
@@ -364,6 +388,19 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("doppelganger-gossip-monitoring")
+                .long("doppelganger-gossip-monitoring")
+                .help("If this flag is set, doppelganger protection will additionally poll the \
+                    beacon node each slot for the messages of local validators that it has \
+                    observed on gossip, both during detection and continuously afterwards. Any \
+                    such message which was not signed by this validator client will cause it to \
+                    shutdown. Requires Lighthouse beacon nodes.")
+                .action(ArgAction::SetTrue)
+                .requires("enable-doppelganger-protection")
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("builder-proposals")
                 .long("builder-proposals")
@@ -423,6 +460,42 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("shadow")
+                .long("shadow")
+                .help("Run as a shadow validator client. All duties are performed, but instead of \
+                    signing and publishing messages the validator client records what would \
+                    have been signed, checks it against a read-only view of the slashing \
+                    protection database and compares it with the messages published on-chain \
+                    by the live validator client. Useful for validating a new deployment \
+                    prior to migrating keys.")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("enable-doppelganger-protection")
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("enable-validator-performance")
+                .long("enable-validator-performance")
+                .help("Enables a service which records the on-chain outcome of the duties \
+                    performed by each validator, including attestation correctness, \
+                    inclusion distance, proposals and sync committee participation. \
+                    Results are available at /lighthouse/ui/validator_performance.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("validator-performance-retention-epochs")
+                .long("validator-performance-retention-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs of validator performance to retain when \
+                    --enable-validator-performance is set.")
+                .default_value("8192")
+                .requires("enable-validator-performance")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("builder-boost-factor")
                 .long("builder-boost-factor")
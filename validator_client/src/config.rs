@@ -1,5 +1,7 @@
 use crate::beacon_node_fallback::ApiTopic;
 use crate::graffiti_file::GraffitiFile;
+use crate::performance_service::DEFAULT_RETENTION_EPOCHS;
+use crate::proposer_config::ProposerConfig;
 use crate::{http_api, http_metrics};
 use clap::ArgMatches;
 use clap_utils::{flags::DISABLE_MALLOC_TUNING_FLAG, parse_optional, parse_required};
@@ -46,6 +48,8 @@ pub struct Config {
     pub graffiti: Option<Graffiti>,
     /// Graffiti file to load per validator graffitis.
     pub graffiti_file: Option<GraffitiFile>,
+    /// Path to the proposer config file, which is reloaded whenever it is modified.
+    pub proposer_config_file: Option<PathBuf>,
     /// Fallback fallback address.
     pub fee_recipient: Option<Address>,
     /// Configuration for the HTTP REST API.
@@ -57,6 +61,9 @@ pub struct Config {
     /// If true, enable functionality that monitors the network for attestations or proposals from
     /// any of the validators managed by this client before starting up.
     pub enable_doppelganger_protection: bool,
+    /// If true, doppelganger protection also monitors the messages observed on gossip by the
+    /// beacon node, both during and after detection.
+    pub doppelganger_gossip_monitoring: bool,
     /// If true, then we publish validator specific metrics (e.g next attestation duty slot)
     /// for all our managed validators.
     /// Note: We publish validator specific metrics for low validator counts without this flag
@@ -71,12 +78,20 @@ pub struct Config {
     /// A list of custom certificates that the validator client will additionally use when
     /// connecting to a beacon node over SSL/TLS.
     pub beacon_nodes_tls_certs: Option<Vec<PathBuf>>,
+    /// A file containing the API key to send to the beacon nodes (and proposer nodes).
+    pub beacon_nodes_api_key_file: Option<PathBuf>,
     /// Enables broadcasting of various requests (by topic) to all beacon nodes.
     pub broadcast_topics: Vec<ApiTopic>,
     /// Enables a service which attempts to measure latency between the VC and BNs.
     pub enable_latency_measurement_service: bool,
     /// Defines the number of validators per `validator/register_validator` request sent to the BN.
     pub validator_registration_batch_size: usize,
+    /// Perform all duties without signing or publishing any messages.
+    pub shadow: bool,
+    /// Enables a service which records the on-chain outcome of each local validator's duties.
+    pub enable_validator_performance: bool,
+    /// The number of epochs of validator performance to retain.
+    pub validator_performance_retention_epochs: u64,
     /// Enable slashing protection even while using web3signer keys.
     pub enable_web3signer_slashing_protection: bool,
     /// Specifies the boost factor, a percentage multiplier to apply to the builder's payload value.
@@ -114,19 +129,25 @@ impl Default for Config {
             use_long_timeouts: false,
             graffiti: None,
             graffiti_file: None,
+            proposer_config_file: None,
             fee_recipient: None,
             http_api: <_>::default(),
             http_metrics: <_>::default(),
             monitoring_api: None,
             enable_doppelganger_protection: false,
+            doppelganger_gossip_monitoring: false,
             enable_high_validator_count_metrics: false,
             beacon_nodes_tls_certs: None,
+            beacon_nodes_api_key_file: None,
             builder_proposals: false,
             builder_registration_timestamp_override: None,
             gas_limit: None,
             broadcast_topics: vec![ApiTopic::Subscriptions],
             enable_latency_measurement_service: true,
             validator_registration_batch_size: 500,
+            shadow: false,
+            enable_validator_performance: false,
+            validator_performance_retention_epochs: DEFAULT_RETENTION_EPOCHS,
             enable_web3signer_slashing_protection: true,
             builder_boost_factor: None,
             prefer_builder_proposals: false,
@@ -205,6 +226,15 @@ impl Config {
             info!(log, "Successfully loaded graffiti file"; "path" => graffiti_file_path);
         }
 
+        if let Some(proposer_config_path) = cli_args.get_one::<String>("proposer-config-file") {
+            let bytes = fs::read(proposer_config_path)
+                .map_err(|e| format!("Error reading proposer config file: {:?}", e))?;
+            ProposerConfig::from_json_slice(&bytes)
+                .map_err(|e| format!("Invalid proposer config file: {:?}", e))?;
+            config.proposer_config_file = Some(proposer_config_path.into());
+            info!(log, "Successfully loaded proposer config file"; "path" => proposer_config_path);
+        }
+
         if let Some(input_graffiti) = cli_args.get_one::<String>("graffiti") {
             let graffiti_bytes = input_graffiti.as_bytes();
             if graffiti_bytes.len() > GRAFFITI_BYTES_LEN {
@@ -234,6 +264,8 @@ impl Config {
             config.beacon_nodes_tls_certs = Some(tls_certs.split(',').map(PathBuf::from).collect());
         }
 
+        config.beacon_nodes_api_key_file = parse_optional(cli_args, "beacon-nodes-api-key-file")?;
+
         if cli_args.get_flag("distributed") {
             config.distributed = true;
         }
@@ -373,6 +405,10 @@ impl Config {
             config.enable_doppelganger_protection = true;
         }
 
+        if cli_args.get_flag("doppelganger-gossip-monitoring") {
+            config.doppelganger_gossip_monitoring = true;
+        }
+
         if cli_args.get_flag("builder-proposals") {
             config.builder_proposals = true;
         }
@@ -430,6 +466,19 @@ impl Config {
             return Err("validator-registration-batch-size cannot be 0".to_string());
         }
 
+        config.shadow = cli_args.get_flag("shadow");
+        if config.shadow {
+            warn!(
+                log,
+                "Running in shadow mode";
+                "info" => "duties will be performed but no messages will be signed or published"
+            );
+        }
+
+        config.enable_validator_performance = cli_args.get_flag("enable-validator-performance");
+        config.validator_performance_retention_epochs =
+            parse_required(cli_args, "validator-performance-retention-epochs")?;
+
         config.enable_web3signer_slashing_protection =
             if cli_args.get_flag("disable-slashing-protection-web3signer") {
                 warn!(
@@ -11,6 +11,14 @@
 //!
 //! If a doppelganger is detected, the entire validator client will exit.
 //!
+//! ## Gossip monitoring
+//!
+//! Optionally, the service may also poll a Lighthouse beacon node each slot for the gossip messages
+//! it has observed from local validators. This allows a doppelganger to be detected within a slot,
+//! rather than waiting for its messages to be included on-chain. Monitoring continues after
+//! detection has completed, with any message that was not signed by this validator client
+//! considered to be from a doppelganger.
+//!
 //! For validators started during the genesis epoch, there is **no doppelganger protection!**. This
 //! prevents a stale-mate where all validators will cease to function for a few epochs and then all
 //! start at the same time.
@@ -27,12 +35,17 @@
 //! beacon node. Among other loop-holes, two validator clients started at the same time will not
 //! detect each other.
 //!
+//! Gossip monitoring cannot distinguish a doppelganger which signs identical messages to this
+//! validator client, nor observe messages which the beacon node rejects as duplicates before
+//! verifying their signatures.
+//!
 //! Doppelganger protection is a best-effort, last-line-of-defence mitigation. Do not rely upon it.
 
 use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
 use crate::validator_store::ValidatorStore;
 use crate::OfflineOnFailure;
 use environment::RuntimeContext;
+use eth2::lighthouse::{ValidatorSighting, ValidatorSightingsRequest};
 use eth2::types::LivenessResponseData;
 use parking_lot::RwLock;
 use slog::{crit, error, info, Logger};
@@ -42,7 +55,7 @@ use std::future::Future;
 use std::sync::Arc;
 use task_executor::ShutdownReason;
 use tokio::time::sleep;
-use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
+use types::{Epoch, EthSpec, Hash256, PublicKeyBytes, Slot};
 
 /// A wrapper around `PublicKeyBytes` which encodes information about the status of a validator
 /// pubkey with regards to doppelganger protection.
@@ -268,8 +281,52 @@ async fn beacon_node_liveness<'a, T: 'static + SlotClock, E: EthSpec>(
     }
 }
 
+/// Request the gossip sightings of `validator_indices` at or after `from_slot` from the BN.
+///
+/// If the BN fails to respond, simply return an empty response. The next request will cover the
+/// same slots.
+async fn beacon_node_sightings<T: 'static + SlotClock, E: EthSpec>(
+    beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
+    log: Logger,
+    from_slot: Slot,
+    validator_indices: Vec<u64>,
+) -> Vec<ValidatorSighting> {
+    let request = &ValidatorSightingsRequest {
+        from_slot,
+        indices: validator_indices,
+    };
+
+    beacon_nodes
+        .first_success(
+            RequireSynced::Yes,
+            OfflineOnFailure::Yes,
+            |beacon_node| async move {
+                beacon_node
+                    .post_lighthouse_validators_sightings(request)
+                    .await
+                    .map_err(|e| format!("Failed query for validator sightings: {:?}", e))
+                    .map(|result| result.data)
+            },
+        )
+        .await
+        .unwrap_or_else(|e| {
+            error!(
+                log,
+                "Failed gossip sightings query";
+                "error" => %e,
+                "from_slot" => %from_slot,
+            );
+            vec![]
+        })
+}
+
 pub struct DoppelgangerService {
     doppelganger_states: RwLock<HashMap<PublicKeyBytes, DoppelgangerState>>,
+    /// The roots of the messages signed by each local validator at each slot, used to distinguish
+    /// them from the messages of a doppelganger.
+    ///
+    /// This is `None` if gossip monitoring is disabled.
+    signed_roots: Option<RwLock<HashMap<(PublicKeyBytes, Slot), HashSet<Hash256>>>>,
     log: Logger,
 }
 
@@ -277,10 +334,40 @@ impl DoppelgangerService {
     pub fn new(log: Logger) -> Self {
         Self {
             doppelganger_states: <_>::default(),
+            signed_roots: None,
             log,
         }
     }
 
+    /// Additionally monitor the gossip messages observed by the beacon node for local validators.
+    pub fn with_gossip_monitoring(mut self) -> Self {
+        self.signed_roots = Some(<_>::default());
+        self
+    }
+
+    /// Returns `true` if gossip monitoring is enabled.
+    pub fn gossip_monitoring_enabled(&self) -> bool {
+        self.signed_roots.is_some()
+    }
+
+    /// Record that `validator` is signing a message with the given `root` at `slot`.
+    ///
+    /// The `root` is only computed if gossip monitoring is enabled.
+    pub fn record_signed_message(
+        &self,
+        validator: PublicKeyBytes,
+        slot: Slot,
+        root: impl FnOnce() -> Hash256,
+    ) {
+        if let Some(signed_roots) = &self.signed_roots {
+            signed_roots
+                .write()
+                .entry((validator, slot))
+                .or_default()
+                .insert(root());
+        }
+    }
+
     /// Starts a reoccurring future which will try to keep the doppelganger service updated each
     /// slot.
     pub fn start_update_service<E: EthSpec, T: 'static + SlotClock>(
@@ -293,6 +380,18 @@ impl DoppelgangerService {
         // Define the `get_index` function as one that uses the validator store.
         let get_index = move |pubkey| validator_store.validator_index(&pubkey);
 
+        // Define the `get_sightings` function as one that queries the beacon node API.
+        let log = service.log.clone();
+        let sightings_beacon_nodes = beacon_nodes.clone();
+        let get_sightings = move |from_slot, validator_indices| {
+            beacon_node_sightings(
+                sightings_beacon_nodes.clone(),
+                log.clone(),
+                from_slot,
+                validator_indices,
+            )
+        };
+
         // Define the `get_liveness` function as one that queries the beacon node API.
         let log = service.log.clone();
         let get_liveness = move |current_epoch, validator_indices| {
@@ -357,6 +456,22 @@ impl DoppelgangerService {
                                 "error" => ?e
                             );
                         }
+
+                        if let Err(e) = service
+                            .detect_gossip_doppelgangers::<E, _, _, _, _>(
+                                slot,
+                                &get_index,
+                                &get_sightings,
+                                &mut shutdown_func,
+                            )
+                            .await
+                        {
+                            error!(
+                                service.log,
+                                "Error during gossip doppelganger detection";
+                                "error" => ?e
+                            );
+                        }
                     }
                 }
             },
@@ -474,6 +589,121 @@ impl DoppelgangerService {
         )
     }
 
+    /// Contact the beacon node for the gossip sightings of all local validators, shutting down the
+    /// VC if any sighting is not of a message signed by this VC.
+    ///
+    /// Does nothing if gossip monitoring is disabled.
+    async fn detect_gossip_doppelgangers<E, I, G, F, S>(
+        &self,
+        request_slot: Slot,
+        get_index: &I,
+        get_sightings: &G,
+        shutdown_func: &mut S,
+    ) -> Result<(), String>
+    where
+        E: EthSpec,
+        I: Fn(PublicKeyBytes) -> Option<u64>,
+        G: Fn(Slot, Vec<u64>) -> F,
+        F: Future<Output = Vec<ValidatorSighting>>,
+        S: FnMut(),
+    {
+        let Some(signed_roots) = &self.signed_roots else {
+            return Ok(());
+        };
+
+        // Monitor all validators, regardless of whether they have completed detection.
+        let indices_map = self.compute_indices_map(get_index, |_| true);
+
+        if indices_map.is_empty() {
+            // Nothing to do.
+            return Ok(());
+        }
+
+        // Request the sightings of the past epoch, which covers attestations that were received
+        // late.
+        let from_slot = request_slot.saturating_sub(E::slots_per_epoch());
+        let sightings = get_sightings(from_slot, indices_map.keys().copied().collect()).await;
+
+        // Forget the messages which are no longer covered by a request.
+        signed_roots
+            .write()
+            .retain(|(_, slot), _| *slot >= from_slot);
+
+        self.process_sightings::<E, _>(sightings, &indices_map, shutdown_func)
+    }
+
+    /// Process the gossip sightings from the BN, potentially shutting down the VC.
+    fn process_sightings<E: EthSpec, S>(
+        &self,
+        sightings: Vec<ValidatorSighting>,
+        indices_map: &HashMap<u64, PublicKeyBytes>,
+        shutdown_func: &mut S,
+    ) -> Result<(), String>
+    where
+        S: FnMut(),
+    {
+        let Some(signed_roots) = &self.signed_roots else {
+            return Ok(());
+        };
+
+        let mut violators = HashSet::new();
+        for sighting in sightings {
+            // Resolve the index from the server response back to a public key.
+            let pubkey = indices_map.get(&sighting.validator_index).ok_or_else(|| {
+                format!(
+                    "inconsistent indices map for validator index {}",
+                    sighting.validator_index
+                )
+            })?;
+
+            let next_check_epoch = self
+                .doppelganger_states
+                .read()
+                .get(pubkey)
+                .map(|state| state.next_check_epoch)
+                .ok_or_else(|| format!("inconsistent states for validator pubkey {}", pubkey))?;
+
+            // As with liveness, ignore messages from prior to registration, which may have been
+            // produced by a previous instance of this VC.
+            if sighting.slot.epoch(E::slots_per_epoch()) < next_check_epoch {
+                continue;
+            }
+
+            let signed_by_us = signed_roots
+                .read()
+                .get(&(*pubkey, sighting.slot))
+                .map_or(false, |roots| roots.contains(&sighting.root));
+
+            if !signed_by_us {
+                violators.insert(sighting.validator_index);
+            }
+        }
+
+        if violators.is_empty() {
+            return Ok(());
+        }
+
+        crit!(
+            self.log,
+            "Doppelganger(s) detected on gossip";
+            "msg" => "A doppelganger occurs when two different validator clients run the \
+                same public key. This validator client observed a message from a local \
+                validator which it did not sign and is shutting down to prevent potential \
+                slashable offences. Ensure that you are not running a duplicate or overlapping \
+                validator client",
+            "doppelganger_indices" => ?violators
+        );
+
+        // Prevent all validators from signing, even if the validator client fails to shut down.
+        for state in self.doppelganger_states.write().values_mut() {
+            state.remaining_epochs = u64::MAX;
+        }
+
+        shutdown_func();
+
+        Ok(())
+    }
+
     /// Get a map of `validator_index` -> `validator_pubkey` for all validators still requiring
     /// further doppelganger checks.
     ///
@@ -481,18 +711,25 @@ impl DoppelgangerService {
     fn compute_detection_indices_map<F>(&self, get_index: &F) -> HashMap<u64, PublicKeyBytes>
     where
         F: Fn(PublicKeyBytes) -> Option<u64>,
+    {
+        self.compute_indices_map(get_index, DoppelgangerState::requires_further_checks)
+    }
+
+    /// Get a map of `validator_index` -> `validator_pubkey` for all validators with a state
+    /// matching `filter`.
+    ///
+    /// Any validator with an unknown index will be omitted from these results.
+    fn compute_indices_map<F, P>(&self, get_index: &F, filter: P) -> HashMap<u64, PublicKeyBytes>
+    where
+        F: Fn(PublicKeyBytes) -> Option<u64>,
+        P: Fn(&DoppelgangerState) -> bool,
     {
         let detection_pubkeys = self
             .doppelganger_states
             .read()
             .iter()
-            .filter_map(|(pubkey, state)| {
-                if state.requires_further_checks() {
-                    Some(*pubkey)
-                } else {
-                    None
-                }
-            })
+            .filter(|(_, state)| filter(*state))
+            .map(|(pubkey, _)| *pubkey)
             .collect::<Vec<_>>();
 
         // Maps validator indices to pubkeys.
@@ -688,6 +925,7 @@ impl DoppelgangerService {
 mod test {
     use super::*;
     use environment::null_logger;
+    use eth2::lighthouse::ValidatorSightingKind;
     use futures::executor::block_on;
     use slot_clock::TestingSlotClock;
     use std::future;
@@ -1460,4 +1698,65 @@ mod test {
 
         scenario.assert_all_enabled();
     }
+
+    #[test]
+    fn detect_gossip_doppelganger() {
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let slot_clock = TestingSlotClock::new(Slot::new(0), GENESIS_TIME, SLOT_DURATION);
+        let doppelganger =
+            DoppelgangerService::new(null_logger().unwrap()).with_gossip_monitoring();
+
+        // Register during the genesis epoch so that signing is enabled immediately.
+        let pubkey = PublicKeyBytes::random_for_test(&mut rng);
+        doppelganger
+            .register_new_validator::<E, _>(pubkey, &slot_clock)
+            .unwrap();
+        let get_index = |_| Some(0);
+
+        let slot = Slot::new(E::slots_per_epoch());
+        let own_root = Hash256::repeat_byte(1);
+        let other_root = Hash256::repeat_byte(2);
+        doppelganger.record_signed_message(pubkey, slot, || own_root);
+
+        let sighting = |slot, root| ValidatorSighting {
+            validator_index: 0,
+            slot,
+            kind: ValidatorSightingKind::Attestation,
+            root,
+        };
+
+        // Messages signed by this VC, or prior to registration, are ignored.
+        let mut shutdown = false;
+        block_on(doppelganger.detect_gossip_doppelgangers::<E, _, _, _, _>(
+            slot,
+            &get_index,
+            &|_, _| {
+                future::ready(vec![
+                    sighting(Slot::new(0), other_root),
+                    sighting(slot, own_root),
+                ])
+            },
+            &mut || shutdown = true,
+        ))
+        .unwrap();
+        assert!(!shutdown);
+        assert_eq!(
+            doppelganger.validator_status(pubkey),
+            DoppelgangerStatus::SigningEnabled(pubkey)
+        );
+
+        // Any other message is from a doppelganger.
+        block_on(doppelganger.detect_gossip_doppelgangers::<E, _, _, _, _>(
+            slot,
+            &get_index,
+            &|_, _| future::ready(vec![sighting(slot, other_root)]),
+            &mut || shutdown = true,
+        ))
+        .unwrap();
+        assert!(shutdown);
+        assert_eq!(
+            doppelganger.validator_status(pubkey),
+            DoppelgangerStatus::SigningDisabled(pubkey)
+        );
+    }
 }
@@ -8,9 +8,11 @@ mod tests;
 
 pub mod test_utils;
 
+use crate::beacon_node_fallback::BeaconNodeFallback;
 use crate::http_api::graffiti::{delete_graffiti, get_graffiti, set_graffiti};
 
 use crate::http_api::create_signed_voluntary_exit::create_signed_voluntary_exit;
+use crate::performance_service::database::PerformanceDatabase;
 use crate::{determine_graffiti, GraffitiFile, ValidatorStore};
 use account_utils::{
     mnemonic_from_phrase,
@@ -43,7 +45,7 @@ use sysinfo::{System, SystemExt};
 use system_health::observe_system_health_vc;
 use task_executor::TaskExecutor;
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
-use types::{ChainSpec, ConfigAndPreset, EthSpec};
+use types::{ChainSpec, ConfigAndPreset, Epoch, EthSpec};
 use validator_dir::Builder as ValidatorDirBuilder;
 use warp::{sse::Event, Filter};
 use warp_utils::task::blocking_json_task;
@@ -82,6 +84,8 @@ pub struct Context<T: SlotClock, E: EthSpec> {
     pub log: Logger,
     pub sse_logging_components: Option<SSELoggingComponents>,
     pub slot_clock: T,
+    pub beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
+    pub validator_performance: Option<PerformanceDatabase>,
     pub _phantom: PhantomData<E>,
 }
 
@@ -217,6 +221,26 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
     let inner_slot_clock = ctx.slot_clock.clone();
     let slot_clock_filter = warp::any().map(move || inner_slot_clock.clone());
 
+    let inner_beacon_nodes = ctx.beacon_nodes.clone();
+    let beacon_nodes_filter = warp::any()
+        .map(move || inner_beacon_nodes.clone())
+        .and_then(|beacon_nodes: Option<_>| async move {
+            beacon_nodes.ok_or_else(|| {
+                warp_utils::reject::custom_not_found("beacon nodes are not available.".to_string())
+            })
+        });
+
+    let inner_validator_performance = ctx.validator_performance.clone();
+    let validator_performance_filter = warp::any()
+        .map(move || inner_validator_performance.clone())
+        .and_then(|validator_performance: Option<_>| async move {
+            validator_performance.ok_or_else(|| {
+                warp_utils::reject::custom_not_found(
+                    "validator performance is not enabled.".to_string(),
+                )
+            })
+        });
+
     let inner_spec = Arc::new(ctx.spec.clone());
     let spec_filter = warp::any().map(move || inner_spec.clone());
 
@@ -398,6 +422,73 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // GET lighthouse/beacon/health
+    let get_lighthouse_beacon_health = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("health"))
+        .and(warp::path::end())
+        .and(beacon_nodes_filter)
+        .and(task_executor_filter.clone())
+        .then(
+            |beacon_nodes: Arc<BeaconNodeFallback<T, E>>, task_executor: TaskExecutor| {
+                blocking_json_task(move || {
+                    if let Some(handle) = task_executor.handle() {
+                        let health = handle.block_on(beacon_nodes.health());
+                        Ok(api_types::GenericResponse::from(health))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
+    // GET lighthouse/ui/validator_performance
+    let get_lighthouse_ui_validator_performance = warp::path("lighthouse")
+        .and(warp::path("ui"))
+        .and(warp::path("validator_performance"))
+        .and(warp::path::end())
+        .and(warp::query::<api_types::ValidatorPerformanceQuery>())
+        .and(validator_performance_filter)
+        .and(slot_clock_filter.clone())
+        .then(
+            |query: api_types::ValidatorPerformanceQuery,
+             database: PerformanceDatabase,
+             slot_clock: T| {
+                blocking_json_task(move || {
+                    let end_epoch = match query.end_epoch {
+                        Some(epoch) => epoch,
+                        None => slot_clock
+                            .now()
+                            .map(|slot| slot.epoch(E::slots_per_epoch()))
+                            .ok_or_else(|| {
+                                warp_utils::reject::custom_server_error(
+                                    "Unable to read slot clock".to_string(),
+                                )
+                            })?,
+                    };
+                    let start_epoch = query.start_epoch.unwrap_or(Epoch::new(0));
+                    if start_epoch > end_epoch {
+                        return Err(warp_utils::reject::custom_bad_request(format!(
+                            "start_epoch {} is greater than end_epoch {}",
+                            start_epoch, end_epoch
+                        )));
+                    }
+
+                    database
+                        .query(start_epoch, end_epoch)
+                        .map(api_types::GenericResponse::from)
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "unable to read validator performance: {:?}",
+                                e
+                            ))
+                        })
+                })
+            },
+        );
+
     // POST lighthouse/validators/
     let post_validators = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -1253,6 +1344,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(get_lighthouse_validators_pubkey)
                         .or(get_lighthouse_ui_health)
                         .or(get_lighthouse_ui_graffiti)
+                        .or(get_lighthouse_ui_validator_performance)
+                        .or(get_lighthouse_beacon_health)
                         .or(get_fee_recipient)
                         .or(get_gas_limit)
                         .or(get_graffiti)
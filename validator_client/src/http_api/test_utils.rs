@@ -114,6 +114,7 @@ impl ApiTester {
             Some(Arc::new(DoppelgangerService::new(log.clone()))),
             slot_clock.clone(),
             &config,
+            None,
             test_runtime.task_executor.clone(),
             log.clone(),
         ));
@@ -137,6 +138,8 @@ impl ApiTester {
             log,
             sse_logging_components: None,
             slot_clock,
+            beacon_nodes: None,
+            validator_performance: None,
             _phantom: PhantomData,
         });
         let ctx = context;
@@ -102,6 +102,7 @@ impl ApiTester {
             Some(Arc::new(DoppelgangerService::new(log.clone()))),
             slot_clock.clone(),
             &config,
+            None,
             test_runtime.task_executor.clone(),
             log.clone(),
         ));
@@ -132,6 +133,8 @@ impl ApiTester {
             sse_logging_components: None,
             log,
             slot_clock: slot_clock.clone(),
+            beacon_nodes: None,
+            validator_performance: None,
             _phantom: PhantomData,
         });
         let ctx = context.clone();
@@ -852,6 +855,14 @@ async fn routes_with_invalid_auth() {
         .await
         .test_with_invalid_auth(|client| async move { client.get_lighthouse_validators().await })
         .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .get_lighthouse_ui_validator_performance(None, None)
+                .await
+        })
+        .await
+        .test_with_invalid_auth(|client| async move { client.get_lighthouse_beacon_health().await })
+        .await
         .test_with_invalid_auth(|client| async move {
             client
                 .get_lighthouse_validators_pubkey(&PublicKeyBytes::empty())
@@ -8,6 +8,7 @@ pub const SUCCESS: &str = "success";
 pub const SLASHABLE: &str = "slashable";
 pub const SAME_DATA: &str = "same_data";
 pub const UNREGISTERED: &str = "unregistered";
+pub const SHADOW: &str = "shadow";
 pub const FULL_UPDATE: &str = "full_update";
 pub const BEACON_BLOCK: &str = "beacon_block";
 pub const BEACON_BLOCK_HTTP_GET: &str = "beacon_block_http_get";
@@ -83,6 +84,16 @@ lazy_static::lazy_static! {
         "Total count of attempted ContributionAndProof signings",
         &["status"]
     );
+    pub static ref SHADOW_ATTESTATION_COMPARISON_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_shadow_attestation_comparison_total",
+        "Count of shadow attestations by comparison with the attestations included on-chain",
+        &["outcome"]
+    );
+    pub static ref SHADOW_PROPOSAL_COMPARISON_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_shadow_proposal_comparison_total",
+        "Count of shadow block proposals by whether the live validator proposed on-chain",
+        &["outcome"]
+    );
     pub static ref SIGNED_SYNC_SELECTION_PROOFS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
         "vc_signed_sync_selection_proofs_total",
         "Total count of attempted SyncSelectionProof signings",
@@ -200,6 +211,11 @@ lazy_static::lazy_static! {
         "vc_beacon_node_latency_primary_endpoint",
         "Round-trip latency for the primary BN endpoint",
     );
+    pub static ref VC_BEACON_NODE_HEALTH_SCORE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "vc_beacon_node_health_score",
+        "The health score of each BN, where a higher score is healthier",
+        &["endpoint"]
+    );
 }
 
 pub fn gather_prometheus_metrics<E: EthSpec>(
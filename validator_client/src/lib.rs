@@ -1,5 +1,6 @@
 mod attestation_service;
 mod beacon_node_fallback;
+mod beacon_node_health;
 mod block_service;
 mod check_synced;
 mod cli;
@@ -9,7 +10,10 @@ mod http_metrics;
 mod key_cache;
 mod latency;
 mod notifier;
+mod performance_service;
 mod preparation_service;
+mod proposer_config;
+mod shadow_service;
 mod signing_method;
 mod sync_committee_service;
 
@@ -45,8 +49,11 @@ use eth2::{reqwest::ClientBuilder, types::Graffiti, BeaconNodeHttpClient, Status
 use http_api::ApiSecret;
 use notifier::spawn_notifier;
 use parking_lot::RwLock;
+use performance_service::{database::PerformanceDatabase, PerformanceService};
 use preparation_service::{PreparationService, PreparationServiceBuilder};
+use proposer_config::ProposerConfigFile;
 use reqwest::Certificate;
+use shadow_service::ShadowService;
 use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use slot_clock::SystemTimeSlotClock;
@@ -97,6 +104,8 @@ pub struct ProductionValidatorClient<E: EthSpec> {
     sync_committee_service: SyncCommitteeService<SystemTimeSlotClock, E>,
     doppelganger_service: Option<Arc<DoppelgangerService>>,
     preparation_service: PreparationService<SystemTimeSlotClock, E>,
+    performance_service: Option<Arc<PerformanceService<SystemTimeSlotClock, E>>>,
+    proposer_config: Option<Arc<ProposerConfigFile>>,
     validator_store: Arc<ValidatorStore<SystemTimeSlotClock, E>>,
     slot_clock: SystemTimeSlotClock,
     http_api_listen_addr: Option<SocketAddr>,
@@ -294,6 +303,13 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             .checked_sub(1)
             .ok_or_else(|| "No beacon nodes defined.".to_string())?;
 
+        let beacon_nodes_api_key = config
+            .beacon_nodes_api_key_file
+            .as_deref()
+            .map(BeaconNodeHttpClient::load_api_key_from_file)
+            .transpose()
+            .map_err(|e| format!("Unable to read beacon node API key: {:?}", e))?;
+
         let beacon_node_setup = |x: (usize, &SensitiveUrl)| {
             let i = x.0;
             let url = x.1;
@@ -342,11 +358,15 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
                 Timeouts::set_all(slot_duration)
             };
 
-            Ok(BeaconNodeHttpClient::from_components(
+            let beacon_node = BeaconNodeHttpClient::from_components(
                 url.clone(),
                 beacon_node_http_client,
                 timeouts,
-            ))
+            );
+            Ok(match &beacon_nodes_api_key {
+                Some(api_key) => beacon_node.with_api_key(api_key.clone()),
+                None => beacon_node,
+            })
         };
 
         let beacon_nodes: Vec<BeaconNodeHttpClient> = config
@@ -433,16 +453,31 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
         start_fallback_updater_service(context.clone(), proposer_nodes.clone())?;
 
         let doppelganger_service = if config.enable_doppelganger_protection {
-            Some(Arc::new(DoppelgangerService::new(
+            let service = DoppelgangerService::new(
                 context
                     .service_context(DOPPELGANGER_SERVICE_NAME.into())
                     .log()
                     .clone(),
-            )))
+            );
+            if config.doppelganger_gossip_monitoring {
+                Some(Arc::new(service.with_gossip_monitoring()))
+            } else {
+                Some(Arc::new(service))
+            }
         } else {
             None
         };
 
+        let proposer_config = config
+            .proposer_config_file
+            .clone()
+            .map(|path| {
+                ProposerConfigFile::open(path)
+                    .map(Arc::new)
+                    .map_err(|e| format!("Unable to open proposer config file: {:?}", e))
+            })
+            .transpose()?;
+
         let validator_store = Arc::new(ValidatorStore::new(
             validators,
             slashing_protection,
@@ -451,6 +486,7 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             doppelganger_service.clone(),
             slot_clock.clone(),
             &config,
+            proposer_config.clone(),
             context.executor.clone(),
             log.clone(),
         ));
@@ -532,6 +568,28 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             context.service_context("sync_committee".into()),
         );
 
+        let performance_service = if config.enable_validator_performance {
+            let db_path = config
+                .validator_dir
+                .join(performance_service::database::VALIDATOR_PERFORMANCE_FILENAME);
+            let database = PerformanceDatabase::open_or_create(&db_path).map_err(|e| {
+                format!(
+                    "Failed to open validator performance database {:?}: {:?}",
+                    db_path, e
+                )
+            })?;
+            Some(Arc::new(PerformanceService::new(
+                database,
+                validator_store.clone(),
+                slot_clock.clone(),
+                beacon_nodes.clone(),
+                context.service_context("performance".into()),
+                config.validator_performance_retention_epochs,
+            )))
+        } else {
+            None
+        };
+
         Ok(Self {
             context,
             duties_service,
@@ -540,6 +598,8 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             sync_committee_service,
             doppelganger_service,
             preparation_service,
+            performance_service,
+            proposer_config,
             validator_store,
             config,
             slot_clock,
@@ -572,6 +632,11 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
                 config: self.config.http_api.clone(),
                 sse_logging_components: self.context.sse_logging_components.clone(),
                 slot_clock: self.slot_clock.clone(),
+                beacon_nodes: Some(self.duties_service.beacon_nodes.clone()),
+                validator_performance: self
+                    .performance_service
+                    .as_ref()
+                    .map(|service| service.database().clone()),
                 log: log.clone(),
                 _phantom: PhantomData,
             });
@@ -631,6 +696,30 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             info!(log, "Doppelganger protection disabled.")
         }
 
+        if let Some(proposer_config) = self.proposer_config.clone() {
+            proposer_config::start_reload_service(
+                self.context.service_context("proposer_config".into()),
+                proposer_config,
+            );
+        }
+
+        if let Some(shadow_ledger) = self.validator_store.shadow_ledger() {
+            Arc::new(ShadowService::new(
+                shadow_ledger.clone(),
+                self.slot_clock.clone(),
+                self.beacon_nodes.clone(),
+                self.context.service_context("shadow".into()),
+            ))
+            .start_update_service()
+            .map_err(|e| format!("Unable to start shadow service: {}", e))?;
+        }
+
+        if let Some(performance_service) = self.performance_service.clone() {
+            performance_service
+                .start_update_service(&self.context.eth2_config.spec)
+                .map_err(|e| format!("Unable to start performance service: {}", e))?;
+        }
+
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
 
         if self.config.enable_latency_measurement_service {
@@ -0,0 +1,780 @@
+//! The `PerformanceService` maintains a ledger of the on-chain outcome of the duties performed by
+//! local validators.
+//!
+//! Outcomes for epoch `N` are reconciled with the beacon node during epoch
+//! `N + RECONCILIATION_EPOCH_LAG`, once all attestations from epoch `N` have had the opportunity
+//! to be included. Rewards are sourced from the standard `beacon/rewards` endpoints, whilst
+//! attestation votes, inclusion distances and proposal outcomes are determined from the canonical
+//! blocks.
+
+pub mod database;
+
+use crate::beacon_node_fallback::{BeaconNodeFallback, OfflineOnFailure, RequireSynced};
+use crate::validator_store::{DoppelgangerStatus, ValidatorStore};
+use database::PerformanceDatabase;
+use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::{
+    AttestationOutcome, EpochPerformance, ProposalOutcome, SyncCommitteeOutcome,
+    ValidatorPerformanceData,
+};
+use eth2::types::{BlockId, StateId, ValidatorId};
+use slog::{debug, error, info};
+use slot_clock::SlotClock;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Bound;
+use std::sync::Arc;
+use tokio::time::sleep;
+use types::{
+    AttestationRef, ChainSpec, Epoch, EthSpec, Hash256, PublicKeyBytes, SignedBlindedBeaconBlock,
+    Slot,
+};
+
+/// The number of epochs that must elapse before an epoch's outcomes are reconciled.
+///
+/// Attestations from epoch `N` may be included until the end of epoch `N + 1`, and the beacon node
+/// computes attestation rewards for epoch `N` using the state at the end of epoch `N + 1`.
+pub const RECONCILIATION_EPOCH_LAG: u64 = 2;
+
+/// The default number of epochs of outcomes to retain (approximately 36 days on mainnet).
+pub const DEFAULT_RETENTION_EPOCHS: u64 = 8192;
+
+/// Map from `(slot, committee_index)` to the validator indices of that committee.
+pub(crate) type Committees = HashMap<(Slot, u64), Vec<u64>>;
+
+pub struct PerformanceService<T, E: EthSpec> {
+    database: PerformanceDatabase,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    slot_clock: T,
+    beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
+    context: RuntimeContext<E>,
+    retention_epochs: u64,
+}
+
+impl<T: SlotClock + 'static, E: EthSpec> PerformanceService<T, E> {
+    pub fn new(
+        database: PerformanceDatabase,
+        validator_store: Arc<ValidatorStore<T, E>>,
+        slot_clock: T,
+        beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
+        context: RuntimeContext<E>,
+        retention_epochs: u64,
+    ) -> Self {
+        Self {
+            database,
+            validator_store,
+            slot_clock,
+            beacon_nodes,
+            context,
+            retention_epochs,
+        }
+    }
+
+    /// The database in which outcomes are recorded.
+    pub fn database(&self) -> &PerformanceDatabase {
+        &self.database
+    }
+
+    /// Starts the service which reconciles and records duty outcomes once per epoch.
+    ///
+    /// Reconciliation runs one slot into each epoch to avoid competing with the epoch transition
+    /// on the beacon node.
+    pub fn start_update_service(self: Arc<Self>, spec: &ChainSpec) -> Result<(), String> {
+        let log = self.context.log().clone();
+        let spec = spec.clone();
+        let slot_duration = self.slot_clock.slot_duration();
+
+        info!(
+            log,
+            "Validator performance service started";
+            "retention_epochs" => self.retention_epochs,
+        );
+
+        let executor = self.context.executor.clone();
+
+        let interval_fut = async move {
+            loop {
+                let sleep_time = self
+                    .slot_clock
+                    .duration_to_next_epoch(E::slots_per_epoch())
+                    .map_or(slot_duration, |duration| duration + slot_duration);
+                sleep(sleep_time).await;
+
+                let Some(current_epoch) = self
+                    .slot_clock
+                    .now()
+                    .map(|slot| slot.epoch(E::slots_per_epoch()))
+                else {
+                    error!(log, "Failed to read slot clock");
+                    continue;
+                };
+
+                let Some(epoch) = current_epoch
+                    .as_u64()
+                    .checked_sub(RECONCILIATION_EPOCH_LAG)
+                    .map(Epoch::new)
+                else {
+                    continue;
+                };
+
+                match self.reconcile_epoch(epoch, &spec).await {
+                    Ok(records) => {
+                        debug!(
+                            log,
+                            "Reconciled validator performance";
+                            "epoch" => epoch,
+                            "validators" => records.len(),
+                        );
+                        if let Err(e) = self.database.record(&records) {
+                            error!(
+                                log,
+                                "Failed to record validator performance";
+                                "epoch" => epoch,
+                                "error" => ?e,
+                            );
+                        }
+                    }
+                    Err(e) => error!(
+                        log,
+                        "Failed to reconcile validator performance";
+                        "epoch" => epoch,
+                        "error" => e,
+                    ),
+                }
+
+                let min_epoch =
+                    Epoch::new(current_epoch.as_u64().saturating_sub(self.retention_epochs));
+                if let Err(e) = self.database.prune(min_epoch) {
+                    error!(
+                        log,
+                        "Failed to prune validator performance";
+                        "error" => ?e,
+                    );
+                }
+            }
+        };
+
+        executor.spawn(interval_fut, "performance_service");
+        Ok(())
+    }
+
+    /// Determine the outcome of all duties performed by local validators in `epoch`.
+    async fn reconcile_epoch(
+        &self,
+        epoch: Epoch,
+        spec: &ChainSpec,
+    ) -> Result<Vec<ValidatorPerformanceData>, String> {
+        let slots_per_epoch = E::slots_per_epoch();
+
+        // Map from validator index to public key. Validators without an index are inactive.
+        let validators: HashMap<u64, PublicKeyBytes> = self
+            .validator_store
+            .voting_pubkeys::<Vec<_>, _>(DoppelgangerStatus::ignored)
+            .into_iter()
+            .filter_map(|pubkey| Some((self.validator_store.validator_index(&pubkey)?, pubkey)))
+            .collect();
+        if validators.is_empty() {
+            return Ok(vec![]);
+        }
+        let validator_ids = validators
+            .keys()
+            .map(|index| ValidatorId::Index(*index))
+            .collect::<Vec<_>>();
+        let validator_ids = validator_ids.as_slice();
+
+        let mut performance: HashMap<u64, EpochPerformance> = validators
+            .keys()
+            .map(|index| {
+                let epoch_performance = EpochPerformance {
+                    epoch,
+                    attestation: None,
+                    proposals: vec![],
+                    sync_committee: None,
+                };
+                (*index, epoch_performance)
+            })
+            .collect();
+
+        let committees = get_committees(&self.beacon_nodes, epoch).await?;
+        let assigned_validators: HashSet<u64> = committees.values().flatten().copied().collect();
+
+        // Attestations from `epoch` may be included in blocks up until the end of the next epoch.
+        let start_slot = epoch.start_slot(slots_per_epoch);
+        let end_slot = (epoch + 1).end_slot(slots_per_epoch);
+        let blocks = get_blocks(&self.beacon_nodes, start_slot, end_slot).await?;
+
+        /*
+         * Attestations
+         */
+        let votes = attestation_votes(epoch, &blocks, &committees, &validators, spec);
+        let attestation_rewards = self
+            .beacon_nodes
+            .first_success(
+                RequireSynced::Yes,
+                OfflineOnFailure::No,
+                |beacon_node| async move {
+                    beacon_node
+                        .post_beacon_rewards_attestations_for_epoch(epoch, validator_ids)
+                        .await
+                },
+            )
+            .await
+            .map_err(|e| format!("Failed to get attestation rewards: {}", e))?
+            .data;
+
+        for rewards in attestation_rewards.total_rewards {
+            let index = rewards.validator_index;
+            if !assigned_validators.contains(&index) {
+                continue;
+            }
+            if let Some(epoch_performance) = performance.get_mut(&index) {
+                // The correctness of each vote is determined from the on-chain attestations
+                // rather than the sign of its reward, since rewards are zero during an
+                // inactivity leak.
+                let votes = votes.get(&index).copied().unwrap_or_default();
+                let inclusion_delay_reward = rewards
+                    .inclusion_delay
+                    .map_or(0, |reward| reward.value as i64);
+                epoch_performance.attestation = Some(AttestationOutcome {
+                    head: votes.head,
+                    target: votes.target,
+                    source: votes.source,
+                    inclusion_distance: votes.inclusion_distance,
+                    reward: rewards.head
+                        + rewards.target
+                        + rewards.source
+                        + inclusion_delay_reward
+                        + rewards.inactivity,
+                });
+            }
+        }
+
+        /*
+         * Proposals
+         */
+        let proposer_duties = self
+            .beacon_nodes
+            .first_success(
+                RequireSynced::Yes,
+                OfflineOnFailure::No,
+                |beacon_node| async move { beacon_node.get_validator_duties_proposer(epoch).await },
+            )
+            .await
+            .map_err(|e| format!("Failed to get proposer duties: {}", e))?
+            .data;
+
+        for duty in proposer_duties {
+            let Some(epoch_performance) = performance.get_mut(&duty.validator_index) else {
+                continue;
+            };
+            let included = blocks.get(&duty.slot).map_or(false, |block| {
+                block.message().proposer_index() == duty.validator_index
+            });
+            let reward = if included {
+                let block_id = BlockId::Slot(duty.slot);
+                self.beacon_nodes
+                    .first_success(
+                        RequireSynced::Yes,
+                        OfflineOnFailure::No,
+                        |beacon_node| async move {
+                            beacon_node.get_beacon_rewards_block(block_id).await
+                        },
+                    )
+                    .await
+                    .map_err(|e| format!("Failed to get block rewards: {}", e))?
+                    .map_or(0, |rewards| rewards.data.total)
+            } else {
+                0
+            };
+            epoch_performance.proposals.push(ProposalOutcome {
+                slot: duty.slot,
+                included,
+                reward,
+            });
+        }
+
+        /*
+         * Sync committee
+         */
+        let altair_enabled = spec
+            .altair_fork_epoch
+            .map_or(false, |fork_epoch| epoch >= fork_epoch);
+        if altair_enabled {
+            for slot in blocks
+                .keys()
+                .filter(|slot| **slot <= epoch.end_slot(slots_per_epoch))
+            {
+                let block_id = BlockId::Slot(*slot);
+                let sync_rewards = self
+                    .beacon_nodes
+                    .first_success(
+                        RequireSynced::Yes,
+                        OfflineOnFailure::No,
+                        |beacon_node| async move {
+                            beacon_node
+                                .post_beacon_rewards_sync_committee_for_block(
+                                    block_id,
+                                    validator_ids,
+                                )
+                                .await
+                        },
+                    )
+                    .await
+                    .map_err(|e| format!("Failed to get sync committee rewards: {}", e))?
+                    .map(|rewards| rewards.data)
+                    .unwrap_or_default();
+
+                // The sync committee is fixed for the whole epoch, so if none of our validators
+                // are members at this slot there is no need to check the others.
+                if sync_rewards.is_empty() {
+                    break;
+                }
+
+                for reward in sync_rewards {
+                    let Some(epoch_performance) = performance.get_mut(&reward.validator_index)
+                    else {
+                        continue;
+                    };
+                    let outcome =
+                        epoch_performance
+                            .sync_committee
+                            .get_or_insert(SyncCommitteeOutcome {
+                                participated_slots: 0,
+                                missed_slots: 0,
+                                reward: 0,
+                            });
+                    if reward.reward > 0 {
+                        outcome.participated_slots += 1;
+                    } else {
+                        outcome.missed_slots += 1;
+                    }
+                    outcome.reward += reward.reward;
+                }
+            }
+        }
+
+        Ok(performance
+            .into_iter()
+            .filter(|(_, epoch_performance)| {
+                epoch_performance.attestation.is_some()
+                    || !epoch_performance.proposals.is_empty()
+                    || epoch_performance.sync_committee.is_some()
+            })
+            .filter_map(|(index, epoch_performance)| {
+                Some(ValidatorPerformanceData {
+                    voting_pubkey: *validators.get(&index)?,
+                    validator_index: index,
+                    epochs: vec![epoch_performance],
+                })
+            })
+            .collect())
+    }
+}
+
+/// Fetch all beacon committees for `epoch`.
+pub(crate) async fn get_committees<T: SlotClock, E: EthSpec>(
+    beacon_nodes: &BeaconNodeFallback<T, E>,
+    epoch: Epoch,
+) -> Result<Committees, String> {
+    let state_id = StateId::Slot(epoch.start_slot(E::slots_per_epoch()));
+    let committees = beacon_nodes
+        .first_success(
+            RequireSynced::Yes,
+            OfflineOnFailure::No,
+            |beacon_node| async move {
+                beacon_node
+                    .get_beacon_states_committees(state_id, None, None, Some(epoch))
+                    .await
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to get committees: {}", e))?
+        .ok_or_else(|| format!("Beacon node has no state for epoch {}", epoch))?
+        .data;
+
+    Ok(committees
+        .into_iter()
+        .map(|committee| ((committee.slot, committee.index), committee.validators))
+        .collect())
+}
+
+/// Fetch the canonical blocks with `start_slot <= slot <= end_slot`.
+pub(crate) async fn get_blocks<T: SlotClock, E: EthSpec>(
+    beacon_nodes: &BeaconNodeFallback<T, E>,
+    start_slot: Slot,
+    end_slot: Slot,
+) -> Result<BTreeMap<Slot, SignedBlindedBeaconBlock<E>>, String> {
+    let mut blocks = BTreeMap::new();
+    for slot in start_slot.as_u64()..=end_slot.as_u64() {
+        let block_id = BlockId::Slot(Slot::new(slot));
+        let block =
+            beacon_nodes
+                .first_success(
+                    RequireSynced::Yes,
+                    OfflineOnFailure::No,
+                    |beacon_node| async move {
+                        beacon_node.get_beacon_blinded_blocks::<E>(block_id).await
+                    },
+                )
+                .await
+                .map_err(|e| format!("Failed to get block at slot {}: {}", slot, e))?;
+
+        // Skipped slots are absent from the map.
+        if let Some(block) = block {
+            blocks.insert(Slot::new(slot), block.data);
+        }
+    }
+    Ok(blocks)
+}
+
+/// The votes of a validator for an epoch, as determined from the canonical blocks.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct AttestationVotes {
+    /// `true` if an included attestation voted for the canonical head and was timely.
+    head: bool,
+    /// `true` if an included attestation voted for the canonical target and was timely.
+    target: bool,
+    /// `true` if an attestation was included in time for its source vote to be rewarded.
+    source: bool,
+    /// The inclusion distance of the first on-chain attestation.
+    inclusion_distance: Option<u64>,
+}
+
+/// Determine the votes of each of `validators` from the on-chain attestations targeting `epoch`.
+///
+/// Validators may be included in several attestations, in which case each vote is considered
+/// correct if any of the attestations satisfies it. The timeliness rules mirror those of
+/// `get_attestation_participation_flag_indices`.
+fn attestation_votes<E: EthSpec>(
+    epoch: Epoch,
+    blocks: &BTreeMap<Slot, SignedBlindedBeaconBlock<E>>,
+    committees: &Committees,
+    validators: &HashMap<u64, PublicKeyBytes>,
+    spec: &ChainSpec,
+) -> HashMap<u64, AttestationVotes> {
+    let slots_per_epoch = E::slots_per_epoch();
+    let target_root = canonical_block_root(blocks, epoch.start_slot(slots_per_epoch));
+
+    let mut votes: HashMap<u64, AttestationVotes> = HashMap::new();
+    // Blocks are iterated in ascending slot order, so the first inclusion wins.
+    for (slot, block) in blocks {
+        let fork_name = spec.fork_name_at_slot::<E>(*slot);
+        for attestation in block.message().body().attestations() {
+            let data = attestation.data();
+            if data.target.epoch != epoch {
+                continue;
+            }
+            let inclusion_distance = slot.saturating_sub(data.slot).as_u64();
+
+            // Attestations in canonical blocks always have a matching source.
+            let is_matching_target = Some(data.target.root) == target_root;
+            let is_matching_head = is_matching_target
+                && Some(data.beacon_block_root) == canonical_block_root(blocks, data.slot);
+
+            let (source, target, head) = if fork_name.altair_enabled() {
+                (
+                    inclusion_distance <= (slots_per_epoch as f64).sqrt() as u64,
+                    is_matching_target
+                        && (fork_name.deneb_enabled() || inclusion_distance <= slots_per_epoch),
+                    is_matching_head && inclusion_distance == spec.min_attestation_inclusion_delay,
+                )
+            } else {
+                // Prior to Altair the inclusion delay scales a separate reward instead.
+                (true, is_matching_target, is_matching_head)
+            };
+
+            for index in attesting_indices(attestation, committees) {
+                if !validators.contains_key(&index) {
+                    continue;
+                }
+                let validator_votes = votes.entry(index).or_default();
+                validator_votes
+                    .inclusion_distance
+                    .get_or_insert(inclusion_distance);
+                validator_votes.source |= source;
+                validator_votes.target |= target;
+                validator_votes.head |= head;
+            }
+        }
+    }
+    votes
+}
+
+/// Determine the root of the canonical block at `slot` (i.e. the latest block with a slot less
+/// than or equal to `slot`) from the parent of the first block after `slot`.
+///
+/// Returns `None` if `blocks` contains no block after `slot`.
+fn canonical_block_root<E: EthSpec>(
+    blocks: &BTreeMap<Slot, SignedBlindedBeaconBlock<E>>,
+    slot: Slot,
+) -> Option<Hash256> {
+    blocks
+        .range((Bound::Excluded(slot), Bound::Unbounded))
+        .next()
+        .map(|(_, block)| block.message().parent_root())
+}
+
+/// Compute the validator indices that contributed to `attestation`.
+///
+/// Returns an empty list if the committees of the attestation are unknown.
+pub(crate) fn attesting_indices<E: EthSpec>(
+    attestation: AttestationRef<E>,
+    committees: &Committees,
+) -> Vec<u64> {
+    let slot = attestation.data().slot;
+    match attestation {
+        AttestationRef::Base(att) => committees
+            .get(&(slot, att.data.index))
+            .map(|committee| {
+                committee
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| att.aggregation_bits.get(*i).unwrap_or(false))
+                    .map(|(_, validator_index)| *validator_index)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        // Post-Electra the aggregation bits of all committees flagged in `committee_bits` are
+        // concatenated in ascending committee index order.
+        AttestationRef::Electra(att) => {
+            let mut indices = vec![];
+            let mut offset = 0;
+            for committee_index in att.get_committee_indices() {
+                let Some(committee) = committees.get(&(slot, committee_index)) else {
+                    return vec![];
+                };
+                indices.extend(
+                    committee
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| att.aggregation_bits.get(offset + i).unwrap_or(false))
+                        .map(|(_, validator_index)| *validator_index),
+                );
+                offset += committee.len();
+            }
+            indices
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{
+        AggregateSignature, AttestationBase, AttestationData, AttestationElectra, BeaconBlock,
+        BeaconBlockBodyRefMut, BitList, BitVector, BlindedBeaconBlock, Checkpoint, ForkName,
+        MinimalEthSpec, Signature,
+    };
+
+    type E = MinimalEthSpec;
+    type Blocks = BTreeMap<Slot, SignedBlindedBeaconBlock<E>>;
+
+    fn attestation_data(slot: u64, index: u64, head: Hash256, target: Hash256) -> AttestationData {
+        AttestationData {
+            slot: Slot::new(slot),
+            index,
+            beacon_block_root: head,
+            source: Checkpoint::default(),
+            target: Checkpoint {
+                epoch: Slot::new(slot).epoch(E::slots_per_epoch()),
+                root: target,
+            },
+        }
+    }
+
+    /// An attestation from the member of the committee at `position`.
+    fn attestation(
+        data: AttestationData,
+        committee_len: usize,
+        position: usize,
+    ) -> AttestationBase<E> {
+        let mut aggregation_bits = BitList::with_capacity(committee_len).unwrap();
+        aggregation_bits.set(position, true).unwrap();
+        AttestationBase {
+            aggregation_bits,
+            data,
+            signature: AggregateSignature::empty(),
+        }
+    }
+
+    /// Append a block at `slot` to the chain in `blocks`, returning its root.
+    fn add_block(
+        blocks: &mut Blocks,
+        spec: &ChainSpec,
+        slot: u64,
+        attestations: Vec<AttestationBase<E>>,
+    ) -> Hash256 {
+        let mut block = BlindedBeaconBlock::<E>::empty(spec);
+        *block.slot_mut() = Slot::new(slot);
+        *block.parent_root_mut() = blocks
+            .values()
+            .next_back()
+            .map_or(Hash256::zero(), |parent| parent.canonical_root());
+        for attestation in attestations {
+            match block.body_mut() {
+                BeaconBlockBodyRefMut::Base(body) => body.attestations.push(attestation),
+                BeaconBlockBodyRefMut::Deneb(body) => body.attestations.push(attestation),
+                _ => panic!("unsupported fork"),
+            }
+            .unwrap();
+        }
+        let block = SignedBlindedBeaconBlock::from_block(block, Signature::empty());
+        let root = block.canonical_root();
+        blocks.insert(Slot::new(slot), block);
+        root
+    }
+
+    fn validators(indices: &[u64]) -> HashMap<u64, PublicKeyBytes> {
+        indices
+            .iter()
+            .map(|index| (*index, PublicKeyBytes::empty()))
+            .collect()
+    }
+
+    #[test]
+    fn attesting_indices_base() {
+        let committees = Committees::from([((Slot::new(1), 0), vec![10, 11, 12, 13])]);
+        let data = attestation_data(1, 0, Hash256::zero(), Hash256::zero());
+
+        let mut attestation = attestation(data.clone(), 4, 1);
+        attestation.aggregation_bits.set(3, true).unwrap();
+        assert_eq!(
+            attesting_indices(AttestationRef::Base(&attestation), &committees),
+            vec![11, 13]
+        );
+
+        // The committee of an attestation from another slot is unknown.
+        attestation.data.slot = Slot::new(2);
+        assert!(attesting_indices(AttestationRef::Base(&attestation), &committees).is_empty());
+    }
+
+    #[test]
+    fn attesting_indices_electra() {
+        let committees = Committees::from([
+            ((Slot::new(1), 0), vec![10, 11]),
+            ((Slot::new(1), 2), vec![20, 21, 22]),
+        ]);
+        let data = attestation_data(1, 0, Hash256::zero(), Hash256::zero());
+
+        let mut committee_bits = BitVector::new();
+        committee_bits.set(0, true).unwrap();
+        committee_bits.set(2, true).unwrap();
+        let mut aggregation_bits = BitList::with_capacity(5).unwrap();
+        for i in [1, 2, 4] {
+            aggregation_bits.set(i, true).unwrap();
+        }
+        let mut attestation = AttestationElectra::<E> {
+            aggregation_bits,
+            data,
+            committee_bits,
+            signature: AggregateSignature::empty(),
+        };
+        assert_eq!(
+            attesting_indices(AttestationRef::Electra(&attestation), &committees),
+            vec![11, 20, 22]
+        );
+
+        // An attestation is ignored entirely if any of its committees is unknown.
+        attestation.committee_bits.set(1, true).unwrap();
+        assert!(attesting_indices(AttestationRef::Electra(&attestation), &committees).is_empty());
+    }
+
+    #[test]
+    fn attestation_votes_from_blocks() {
+        let spec = ForkName::Deneb.make_genesis_spec(E::default_spec());
+        let epoch = Epoch::new(1);
+        let mut blocks = Blocks::new();
+
+        let target = add_block(&mut blocks, &spec, 8, vec![]);
+        let head = add_block(&mut blocks, &spec, 9, vec![]);
+        // Slot 10 is skipped, so the head at slot 10 is the block at slot 9.
+        let committee_9 = vec![1, 4, 5, 6];
+        let committee_10 = vec![2, 3];
+        let committees = Committees::from([
+            ((Slot::new(9), 0), committee_9.clone()),
+            ((Slot::new(10), 0), committee_10.clone()),
+        ]);
+
+        let correct_9 = attestation_data(9, 0, head, target);
+        let correct_10 = attestation_data(10, 0, head, target);
+        let wrong_head = attestation_data(10, 0, Hash256::repeat_byte(1), target);
+        let wrong_target = attestation_data(9, 0, head, Hash256::repeat_byte(2));
+        add_block(
+            &mut blocks,
+            &spec,
+            11,
+            vec![
+                attestation(correct_9.clone(), committee_9.len(), 0),
+                attestation(correct_10, committee_10.len(), 0),
+                attestation(wrong_head, committee_10.len(), 1),
+                attestation(wrong_target, committee_9.len(), 1),
+                // Not a local validator.
+                attestation(correct_9.clone(), committee_9.len(), 2),
+            ],
+        );
+        add_block(
+            &mut blocks,
+            &spec,
+            13,
+            vec![
+                // A later inclusion does not change the inclusion distance.
+                attestation(correct_9.clone(), committee_9.len(), 0),
+                // Too late to be rewarded for the source vote.
+                attestation(correct_9, committee_9.len(), 3),
+            ],
+        );
+
+        let votes = attestation_votes(
+            epoch,
+            &blocks,
+            &committees,
+            &validators(&[1, 2, 3, 4, 6, 7]),
+            &spec,
+        );
+        let expected = |head, target, source, inclusion_distance| AttestationVotes {
+            head,
+            target,
+            source,
+            inclusion_distance: Some(inclusion_distance),
+        };
+
+        // Correct, but not included in time for the head vote.
+        assert_eq!(votes[&1], expected(false, true, true, 2));
+        // The slot of the attestation was skipped, so its head is the previous block.
+        assert_eq!(votes[&2], expected(true, true, true, 1));
+        assert_eq!(votes[&3], expected(false, true, true, 1));
+        assert_eq!(votes[&4], expected(false, false, true, 2));
+        assert_eq!(votes[&6], expected(false, true, false, 4));
+        // Validators which were not included have no votes.
+        assert!(!votes.contains_key(&5));
+        assert!(!votes.contains_key(&7));
+    }
+
+    #[test]
+    fn attestation_votes_phase0() {
+        let spec = ForkName::Base.make_genesis_spec(E::default_spec());
+        let epoch = Epoch::new(1);
+        let mut blocks = Blocks::new();
+
+        let target = add_block(&mut blocks, &spec, 8, vec![]);
+        let head = add_block(&mut blocks, &spec, 9, vec![]);
+        let committees = Committees::from([((Slot::new(9), 0), vec![1])]);
+        add_block(
+            &mut blocks,
+            &spec,
+            14,
+            vec![attestation(attestation_data(9, 0, head, target), 1, 0)],
+        );
+
+        // Prior to Altair votes are rewarded regardless of the inclusion distance.
+        let votes = attestation_votes(epoch, &blocks, &committees, &validators(&[1]), &spec);
+        assert_eq!(
+            votes[&1],
+            AttestationVotes {
+                head: true,
+                target: true,
+                source: true,
+                inclusion_distance: Some(5),
+            }
+        );
+    }
+}
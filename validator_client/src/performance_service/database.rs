@@ -0,0 +1,434 @@
+//! An SQLite database holding the duty outcomes recorded by the `PerformanceService`.
+//!
+//! The database is a cache of information that can be re-derived from the beacon node, so unlike
+//! the slashing protection database it is safe to delete at any time.
+
+use eth2::lighthouse_vc::types::{
+    AttestationOutcome, EpochPerformance, ProposalOutcome, SyncCommitteeOutcome,
+    ValidatorPerformanceData,
+};
+use filesystem::restrict_file_permissions;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{params, Transaction};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+use types::{Epoch, PublicKeyBytes, Slot};
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+/// The filename of the database within the validator directory.
+pub const VALIDATOR_PERFORMANCE_FILENAME: &str = "validator_performance.sqlite";
+
+/// The performance service is the only writer, and the HTTP API the only reader.
+pub const POOL_SIZE: u32 = 2;
+pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum Error {
+    PermissionsError,
+    IOError(std::io::ErrorKind),
+    SQLError(String),
+    SQLPoolError(String),
+    InvalidPublicKey(String),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IOError(e.kind())
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Self {
+        Error::SQLError(e.to_string())
+    }
+}
+
+impl From<r2d2::Error> for Error {
+    fn from(e: r2d2::Error) -> Self {
+        Error::SQLPoolError(format!("{}", e))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PerformanceDatabase {
+    conn_pool: Pool,
+}
+
+impl PerformanceDatabase {
+    /// Open an existing database at the given `path`, or create one if none exists.
+    pub fn open_or_create(path: &Path) -> Result<Self, Error> {
+        if !path.exists() {
+            let _file = File::options()
+                .write(true)
+                .read(true)
+                .create_new(true)
+                .open(path)?;
+            restrict_file_permissions(path).map_err(|_| Error::PermissionsError)?;
+        }
+
+        let manager = SqliteConnectionManager::file(path)
+            .with_flags(rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE);
+        let conn_pool = Pool::builder()
+            .max_size(POOL_SIZE)
+            .connection_timeout(CONNECTION_TIMEOUT)
+            .build(manager)
+            .map_err(|e| Error::SQLPoolError(format!("Unable to open database: {:?}", e)))?;
+
+        let db = Self { conn_pool };
+        db.with_transaction(Self::create_tables)?;
+        Ok(db)
+    }
+
+    fn create_tables(txn: &Transaction) -> Result<(), Error> {
+        txn.execute(
+            "CREATE TABLE IF NOT EXISTS attestations (
+                public_key TEXT NOT NULL,
+                validator_index INTEGER NOT NULL,
+                epoch INTEGER NOT NULL,
+                head BOOL NOT NULL,
+                target BOOL NOT NULL,
+                source BOOL NOT NULL,
+                inclusion_distance INTEGER,
+                reward INTEGER NOT NULL,
+                UNIQUE (public_key, epoch)
+            )",
+            params![],
+        )?;
+        txn.execute(
+            "CREATE TABLE IF NOT EXISTS proposals (
+                public_key TEXT NOT NULL,
+                validator_index INTEGER NOT NULL,
+                epoch INTEGER NOT NULL,
+                slot INTEGER NOT NULL,
+                included BOOL NOT NULL,
+                reward INTEGER NOT NULL,
+                UNIQUE (public_key, slot)
+            )",
+            params![],
+        )?;
+        txn.execute(
+            "CREATE TABLE IF NOT EXISTS sync_committee (
+                public_key TEXT NOT NULL,
+                validator_index INTEGER NOT NULL,
+                epoch INTEGER NOT NULL,
+                participated_slots INTEGER NOT NULL,
+                missed_slots INTEGER NOT NULL,
+                reward INTEGER NOT NULL,
+                UNIQUE (public_key, epoch)
+            )",
+            params![],
+        )?;
+        Ok(())
+    }
+
+    /// Execute a database transaction as a closure, committing if `f` returns `Ok`.
+    fn with_transaction<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&Transaction) -> Result<T, Error>,
+    {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+        let value = f(&txn)?;
+        txn.commit()?;
+        Ok(value)
+    }
+
+    /// Store the outcomes in `records`, replacing any existing outcomes for the same epochs.
+    pub fn record(&self, records: &[ValidatorPerformanceData]) -> Result<(), Error> {
+        self.with_transaction(|txn| {
+            for record in records {
+                let public_key = record.voting_pubkey.as_hex_string();
+                let validator_index = record.validator_index;
+
+                for epoch_performance in &record.epochs {
+                    let epoch = epoch_performance.epoch;
+
+                    if let Some(attestation) = &epoch_performance.attestation {
+                        txn.execute(
+                            "INSERT OR REPLACE INTO attestations (public_key, validator_index, \
+                             epoch, head, target, source, inclusion_distance, reward)
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                            params![
+                                public_key,
+                                validator_index,
+                                epoch,
+                                attestation.head,
+                                attestation.target,
+                                attestation.source,
+                                attestation.inclusion_distance,
+                                attestation.reward
+                            ],
+                        )?;
+                    }
+
+                    for proposal in &epoch_performance.proposals {
+                        txn.execute(
+                            "INSERT OR REPLACE INTO proposals (public_key, validator_index, \
+                             epoch, slot, included, reward)
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                            params![
+                                public_key,
+                                validator_index,
+                                epoch,
+                                proposal.slot,
+                                proposal.included,
+                                proposal.reward
+                            ],
+                        )?;
+                    }
+
+                    if let Some(sync_committee) = &epoch_performance.sync_committee {
+                        txn.execute(
+                            "INSERT OR REPLACE INTO sync_committee (public_key, \
+                             validator_index, epoch, participated_slots, missed_slots, reward)
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                            params![
+                                public_key,
+                                validator_index,
+                                epoch,
+                                sync_committee.participated_slots,
+                                sync_committee.missed_slots,
+                                sync_committee.reward
+                            ],
+                        )?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Load all outcomes with `start_epoch <= epoch <= end_epoch`, grouped by validator.
+    pub fn query(
+        &self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<ValidatorPerformanceData>, Error> {
+        let mut validators = ValidatorEpochs::new();
+
+        self.with_transaction(|txn| {
+            let mut stmt = txn.prepare(
+                "SELECT public_key, validator_index, epoch, head, target, source,
+                        inclusion_distance, reward
+                 FROM attestations WHERE epoch >= ?1 AND epoch <= ?2",
+            )?;
+            let rows = stmt.query_map(params![start_epoch, end_epoch], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    AttestationOutcome {
+                        head: row.get(3)?,
+                        target: row.get(4)?,
+                        source: row.get(5)?,
+                        inclusion_distance: row.get(6)?,
+                        reward: row.get(7)?,
+                    },
+                ))
+            })?;
+            for row in rows {
+                let (public_key, validator_index, epoch, attestation) = row?;
+                epoch_entry(&mut validators, public_key, validator_index, epoch).attestation =
+                    Some(attestation);
+            }
+
+            let mut stmt = txn.prepare(
+                "SELECT public_key, validator_index, epoch, slot, included, reward
+                 FROM proposals WHERE epoch >= ?1 AND epoch <= ?2
+                 ORDER BY slot ASC",
+            )?;
+            let rows = stmt.query_map(params![start_epoch, end_epoch], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    ProposalOutcome {
+                        slot: row.get::<_, Slot>(3)?,
+                        included: row.get(4)?,
+                        reward: row.get(5)?,
+                    },
+                ))
+            })?;
+            for row in rows {
+                let (public_key, validator_index, epoch, proposal) = row?;
+                epoch_entry(&mut validators, public_key, validator_index, epoch)
+                    .proposals
+                    .push(proposal);
+            }
+
+            let mut stmt = txn.prepare(
+                "SELECT public_key, validator_index, epoch, participated_slots, missed_slots,
+                        reward
+                 FROM sync_committee WHERE epoch >= ?1 AND epoch <= ?2",
+            )?;
+            let rows = stmt.query_map(params![start_epoch, end_epoch], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    SyncCommitteeOutcome {
+                        participated_slots: row.get(3)?,
+                        missed_slots: row.get(4)?,
+                        reward: row.get(5)?,
+                    },
+                ))
+            })?;
+            for row in rows {
+                let (public_key, validator_index, epoch, sync_committee) = row?;
+                epoch_entry(&mut validators, public_key, validator_index, epoch).sync_committee =
+                    Some(sync_committee);
+            }
+
+            Ok(())
+        })?;
+
+        validators
+            .into_iter()
+            .map(|(public_key, (validator_index, epochs))| {
+                Ok(ValidatorPerformanceData {
+                    voting_pubkey: PublicKeyBytes::from_str(&public_key)
+                        .map_err(Error::InvalidPublicKey)?,
+                    validator_index,
+                    epochs: epochs.into_values().collect(),
+                })
+            })
+            .collect()
+    }
+
+    /// Delete all outcomes for epochs prior to `min_epoch`.
+    pub fn prune(&self, min_epoch: Epoch) -> Result<(), Error> {
+        self.with_transaction(|txn| {
+            for table in ["attestations", "proposals", "sync_committee"] {
+                txn.execute(
+                    &format!("DELETE FROM {table} WHERE epoch < ?1"),
+                    params![min_epoch],
+                )?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Map from public key to validator index and per-epoch outcomes.
+type ValidatorEpochs = BTreeMap<String, (u64, BTreeMap<Epoch, EpochPerformance>)>;
+
+fn epoch_entry(
+    validators: &mut ValidatorEpochs,
+    public_key: String,
+    validator_index: u64,
+    epoch: Epoch,
+) -> &mut EpochPerformance {
+    let (_, epochs) = validators
+        .entry(public_key)
+        .or_insert_with(|| (validator_index, BTreeMap::new()));
+    epochs.entry(epoch).or_insert_with(|| EpochPerformance {
+        epoch,
+        attestation: None,
+        proposals: vec![],
+        sync_committee: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn pubkey(i: usize) -> PublicKeyBytes {
+        types::test_utils::generate_deterministic_keypair(i)
+            .pk
+            .compress()
+    }
+
+    fn record(i: usize, epoch: Epoch) -> ValidatorPerformanceData {
+        ValidatorPerformanceData {
+            voting_pubkey: pubkey(i),
+            validator_index: i as u64,
+            epochs: vec![EpochPerformance {
+                epoch,
+                attestation: Some(AttestationOutcome {
+                    head: true,
+                    target: true,
+                    source: true,
+                    inclusion_distance: Some(1),
+                    reward: 14_000,
+                }),
+                proposals: vec![ProposalOutcome {
+                    slot: epoch.start_slot(32),
+                    included: i % 2 == 0,
+                    reward: 30_000_000,
+                }],
+                sync_committee: Some(SyncCommitteeOutcome {
+                    participated_slots: 31,
+                    missed_slots: 1,
+                    reward: -20_000,
+                }),
+            }],
+        }
+    }
+
+    #[test]
+    fn record_and_query() {
+        let dir = tempdir().unwrap();
+        let db =
+            PerformanceDatabase::open_or_create(&dir.path().join(VALIDATOR_PERFORMANCE_FILENAME))
+                .unwrap();
+
+        let records = (0..4)
+            .flat_map(|i| (0..4).map(move |epoch| record(i, Epoch::new(epoch))))
+            .collect::<Vec<_>>();
+        db.record(&records).unwrap();
+
+        let result = db.query(Epoch::new(1), Epoch::new(2)).unwrap();
+        assert_eq!(result.len(), 4);
+        for validator in &result {
+            let i = validator.validator_index as usize;
+            assert_eq!(validator.voting_pubkey, pubkey(i));
+            assert_eq!(
+                validator.epochs,
+                vec![
+                    record(i, Epoch::new(1)).epochs[0].clone(),
+                    record(i, Epoch::new(2)).epochs[0].clone()
+                ]
+            );
+        }
+
+        // Re-recording an epoch replaces the previous outcome.
+        let mut updated = record(0, Epoch::new(1));
+        updated.epochs[0].proposals[0].included = false;
+        db.record(&[updated.clone()]).unwrap();
+        let result = db.query(Epoch::new(1), Epoch::new(1)).unwrap();
+        assert_eq!(result[0].epochs, updated.epochs);
+    }
+
+    #[test]
+    fn prune() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(VALIDATOR_PERFORMANCE_FILENAME);
+        let db = PerformanceDatabase::open_or_create(&path).unwrap();
+
+        let records = (0..8)
+            .map(|epoch| record(0, Epoch::new(epoch)))
+            .collect::<Vec<_>>();
+        db.record(&records).unwrap();
+        db.prune(Epoch::new(5)).unwrap();
+
+        // Re-open the database to check the pruning was persisted.
+        drop(db);
+        let db = PerformanceDatabase::open_or_create(&path).unwrap();
+        let result = db.query(Epoch::new(0), Epoch::new(u64::MAX >> 1)).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0]
+                .epochs
+                .iter()
+                .map(|e| e.epoch.as_u64())
+                .collect::<Vec<_>>(),
+            vec![5, 6, 7]
+        );
+    }
+}
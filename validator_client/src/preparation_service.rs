@@ -370,6 +370,12 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
 
     /// Register validators with builders, used in the blinded block proposal flow.
     async fn register_validators(&self) -> Result<(), String> {
+        // Registrations are signed and forwarded to builders, so they are never sent in shadow
+        // mode.
+        if self.validator_store.shadow_mode() {
+            return Ok(());
+        }
+
         let registration_keys = self.collect_validator_registration_keys();
 
         let mut changed_keys = vec![];
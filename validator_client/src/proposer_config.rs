@@ -0,0 +1,353 @@
+//! Provides a JSON file of proposer settings which is reloaded whenever it changes on disk.
+//!
+//! The file is expected to have the following structure, where every field is optional:
+//!
+//! ```json
+//! {
+//!     "default_config": {
+//!         "fee_recipient": "0x...",
+//!         "gas_limit": 30000000,
+//!         "builder_proposals": true,
+//!         "builder_boost_factor": 100,
+//!         "graffiti": "Lighthouse"
+//!     },
+//!     "proposer_config": {
+//!         "0xa057...": {
+//!             "fee_recipient": "0x..."
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! A setting for a specific validator takes precedence over `validator_definitions.yml`, whilst
+//! the `default_config` applies only where neither specifies a value.
+
+use environment::RuntimeContext;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use slog::{error, info};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::time::sleep;
+use types::{graffiti::GraffitiString, Address, EthSpec, PublicKeyBytes};
+
+/// The interval at which the file is checked for modifications.
+pub const RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum Error {
+    UnableToReadFile(std::io::Error),
+    UnableToParseFile(serde_json::Error),
+    InvalidPublicKey(String),
+    InvalidSettings {
+        validator: Option<PublicKeyBytes>,
+        reason: &'static str,
+    },
+}
+
+/// The proposer settings for a single validator, or the defaults for all validators.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProposerSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_recipient: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub builder_proposals: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub builder_boost_factor: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graffiti: Option<GraffitiString>,
+}
+
+impl ProposerSettings {
+    fn validate(&self, validator: Option<PublicKeyBytes>) -> Result<(), Error> {
+        let invalid = |reason| Error::InvalidSettings { validator, reason };
+        if self.fee_recipient == Some(Address::zero()) {
+            return Err(invalid("fee_recipient cannot be the zero address"));
+        }
+        if self.gas_limit == Some(0) {
+            return Err(invalid("gas_limit cannot be 0"));
+        }
+        Ok(())
+    }
+}
+
+/// The on-disk representation of the proposer config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProposerConfigFileContents {
+    #[serde(default)]
+    pub default_config: ProposerSettings,
+    #[serde(default)]
+    pub proposer_config: BTreeMap<String, ProposerSettings>,
+}
+
+/// A validated proposer config.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProposerConfig {
+    pub default_config: ProposerSettings,
+    pub proposer_config: HashMap<PublicKeyBytes, ProposerSettings>,
+}
+
+impl ProposerConfig {
+    /// Parse and validate the contents of a proposer config file.
+    pub fn from_json_slice(bytes: &[u8]) -> Result<Self, Error> {
+        let contents: ProposerConfigFileContents =
+            serde_json::from_slice(bytes).map_err(Error::UnableToParseFile)?;
+
+        contents.default_config.validate(None)?;
+
+        let proposer_config = contents
+            .proposer_config
+            .into_iter()
+            .map(|(key, settings)| {
+                let pubkey = PublicKeyBytes::from_str(&key).map_err(Error::InvalidPublicKey)?;
+                settings.validate(Some(pubkey))?;
+                Ok((pubkey, settings))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self {
+            default_config: contents.default_config,
+            proposer_config,
+        })
+    }
+}
+
+/// A proposer config file which is reloaded whenever it is modified.
+///
+/// If a modified file fails to load, the previously loaded config remains in use.
+#[derive(Debug)]
+pub struct ProposerConfigFile {
+    path: PathBuf,
+    config: RwLock<ProposerConfig>,
+    /// The modification time of the file when it was last read.
+    last_modified: RwLock<Option<SystemTime>>,
+}
+
+impl ProposerConfigFile {
+    /// Load the file at `path`, failing if it is missing or invalid.
+    pub fn open(path: PathBuf) -> Result<Self, Error> {
+        let last_modified = modified_time(&path);
+        let config = Self::read(&path)?;
+        Ok(Self {
+            path,
+            config: RwLock::new(config),
+            last_modified: RwLock::new(last_modified),
+        })
+    }
+
+    fn read(path: &Path) -> Result<ProposerConfig, Error> {
+        let bytes = fs::read(path).map_err(Error::UnableToReadFile)?;
+        ProposerConfig::from_json_slice(&bytes)
+    }
+
+    /// Reload the file if its modification time has changed since it was last read.
+    ///
+    /// Returns `Ok(true)` if a new config was applied. On error the previous config is retained
+    /// and the file will not be read again until it is next modified.
+    pub fn reload_if_modified(&self) -> Result<bool, Error> {
+        let last_modified = modified_time(&self.path);
+        if last_modified == *self.last_modified.read() {
+            return Ok(false);
+        }
+        *self.last_modified.write() = last_modified;
+
+        let config = Self::read(&self.path)?;
+        let mut current = self.config.write();
+        if *current == config {
+            return Ok(false);
+        }
+        *current = config;
+        Ok(true)
+    }
+
+    /// Resolve a setting for `validator_pubkey`. The priority order is:
+    ///
+    /// 1. The validator's entry in this file.
+    /// 2. `definition`, the value from `validator_definitions.yml`.
+    /// 3. The `default_config` in this file.
+    pub fn resolve<V>(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        definition: Option<V>,
+        setting: impl Fn(&ProposerSettings) -> Option<V>,
+    ) -> Option<V> {
+        let config = self.config.read();
+        config
+            .proposer_config
+            .get(validator_pubkey)
+            .and_then(&setting)
+            .or(definition)
+            .or_else(|| setting(&config.default_config))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// The builder boost factor implied by the resolved `builder_proposals` and
+/// `builder_boost_factor` of a validator, if any.
+///
+/// The two settings must be resolved independently, so that a `builder_proposals` of `true` in
+/// one source overrides a `false` in a source of lower priority. A validator with
+/// `builder_proposals` disabled has a boost factor of 0, indicating a preference for local
+/// payloads.
+pub fn builder_boost_factor(
+    builder_proposals: Option<bool>,
+    builder_boost_factor: Option<u64>,
+) -> Option<u64> {
+    builder_boost_factor.or_else(|| (builder_proposals == Some(false)).then_some(0))
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Starts a service that periodically reloads the proposer config file if it has changed.
+pub fn start_reload_service<E: EthSpec>(
+    context: RuntimeContext<E>,
+    proposer_config: Arc<ProposerConfigFile>,
+) {
+    let log = context.log().clone();
+
+    let future = async move {
+        loop {
+            sleep(RELOAD_INTERVAL).await;
+
+            match proposer_config.reload_if_modified() {
+                Ok(true) => info!(
+                    log,
+                    "Reloaded proposer config file";
+                    "path" => ?proposer_config.path(),
+                ),
+                Ok(false) => (),
+                Err(e) => error!(
+                    log,
+                    "Failed to reload proposer config file";
+                    "error" => ?e,
+                    "path" => ?proposer_config.path(),
+                    "info" => "the previous config remains in use",
+                ),
+            }
+        }
+    };
+
+    context.executor.spawn(future, "proposer_config");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const PK1: &str = "0x800012708dc03f611751aad7a43a082142832b5c1aceed07ff9b543cf836381861352aa923c70eeb02018b638aa306aa";
+    const PK2: &str = "0x80001866ce324de7d80ec73be15e2d064dcf121adf1b34a0d679f2b9ecbab40ce021e03bb877e1a2fe72eaaf475e6e21";
+
+    fn write_config(path: &Path, fee_recipient: &str) {
+        let contents = format!(
+            r#"{{
+                "default_config": {{ "gas_limit": 30000000, "graffiti": "default" }},
+                "proposer_config": {{ "{PK1}": {{ "fee_recipient": "{fee_recipient}" }} }}
+            }}"#
+        );
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn resolve_priority() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("proposer_config.json");
+        write_config(&path, "0x0000000000000000000000000000000000000001");
+        let file = ProposerConfigFile::open(path).unwrap();
+
+        let pk1 = PublicKeyBytes::from_str(PK1).unwrap();
+        let pk2 = PublicKeyBytes::from_str(PK2).unwrap();
+        let definition = Some(Address::from_low_u64_be(2));
+
+        // The validator's entry takes precedence over the definition.
+        assert_eq!(
+            file.resolve(&pk1, definition, |s| s.fee_recipient),
+            Some(Address::from_low_u64_be(1))
+        );
+        // The definition takes precedence over the default.
+        assert_eq!(
+            file.resolve(&pk2, definition, |s| s.fee_recipient),
+            definition
+        );
+        assert_eq!(file.resolve(&pk2, Some(1), |s| s.gas_limit), Some(1));
+        assert_eq!(file.resolve(&pk2, None, |s| s.gas_limit), Some(30000000));
+    }
+
+    #[test]
+    fn resolve_builder_proposals_override() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("proposer_config.json");
+        let contents = format!(
+            r#"{{
+                "default_config": {{ "builder_proposals": true }},
+                "proposer_config": {{ "{PK1}": {{ "builder_proposals": true }} }}
+            }}"#
+        );
+        fs::write(&path, contents).unwrap();
+        let file = ProposerConfigFile::open(path).unwrap();
+
+        let resolve = |pubkey: &str, definition: Option<bool>| {
+            let pubkey = PublicKeyBytes::from_str(pubkey).unwrap();
+            builder_boost_factor(
+                file.resolve(&pubkey, definition, |s| s.builder_proposals),
+                file.resolve(&pubkey, None, |s| s.builder_boost_factor),
+            )
+        };
+
+        // The validator's entry enables builder proposals despite the definition disabling them.
+        assert_eq!(resolve(PK1, Some(false)), None);
+        // The definition takes precedence over the default.
+        assert_eq!(resolve(PK2, Some(false)), Some(0));
+        assert_eq!(resolve(PK2, None), None);
+    }
+
+    #[test]
+    fn invalid_reload_retains_previous_config() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("proposer_config.json");
+        write_config(&path, "0x0000000000000000000000000000000000000001");
+        let file = ProposerConfigFile::open(path.clone()).unwrap();
+        let pk1 = PublicKeyBytes::from_str(PK1).unwrap();
+
+        // Force a reload regardless of the resolution of the file system's timestamps.
+        *file.last_modified.write() = None;
+        write_config(&path, "0x0000000000000000000000000000000000000000");
+        assert!(file.reload_if_modified().is_err());
+        assert_eq!(
+            file.resolve(&pk1, None, |s| s.fee_recipient),
+            Some(Address::from_low_u64_be(1))
+        );
+
+        *file.last_modified.write() = None;
+        write_config(&path, "0x0000000000000000000000000000000000000003");
+        assert!(file.reload_if_modified().unwrap());
+        assert_eq!(
+            file.resolve(&pk1, None, |s| s.fee_recipient),
+            Some(Address::from_low_u64_be(3))
+        );
+    }
+
+    #[test]
+    fn invalid_public_key() {
+        let json = r#"{ "proposer_config": { "0x1234": {} } }"#;
+        assert!(matches!(
+            ProposerConfig::from_json_slice(json.as_bytes()),
+            Err(Error::InvalidPublicKey(_))
+        ));
+    }
+}
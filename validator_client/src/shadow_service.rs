@@ -0,0 +1,373 @@
+//! Support for running the validator client in "shadow" mode.
+//!
+//! A shadow validator client performs every duty (discovery, data fetching, aggregator selection
+//! and timing) but never produces a slashable signature or publishes a message. Instead, the
+//! `ValidatorStore` records what *would* have been signed in the `ShadowLedger`, after checking
+//! it against a read-only view of the slashing protection database.
+//!
+//! The `ShadowService` periodically compares the ledger against the messages published on-chain
+//! by the live validator client, so that a new deployment can be validated prior to cutover.
+
+use crate::beacon_node_fallback::BeaconNodeFallback;
+use crate::http_metrics::metrics;
+use crate::performance_service::{
+    attesting_indices, get_blocks, get_committees, RECONCILIATION_EPOCH_LAG,
+};
+use environment::RuntimeContext;
+use parking_lot::Mutex;
+use slog::{error, info, warn};
+use slot_clock::SlotClock;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::time::sleep;
+use types::{AttestationData, Epoch, EthSpec, Slot};
+
+pub const MATCH: &str = "match";
+pub const HEAD_MISMATCH: &str = "head_mismatch";
+pub const MISMATCH: &str = "mismatch";
+pub const MISSING: &str = "missing";
+pub const PROPOSED: &str = "proposed";
+pub const MISSED: &str = "missed";
+
+/// The messages which a shadow validator client would have signed.
+#[derive(Default)]
+pub struct ShadowLedger {
+    /// Map from target epoch to the attestation data for each validator index.
+    attestations: Mutex<BTreeMap<Epoch, HashMap<u64, AttestationData>>>,
+    /// Map from slot to the index of the local proposer.
+    proposals: Mutex<BTreeMap<Slot, u64>>,
+}
+
+impl ShadowLedger {
+    pub fn record_attestation(&self, validator_index: u64, data: &AttestationData) {
+        self.attestations
+            .lock()
+            .entry(data.target.epoch)
+            .or_default()
+            .entry(validator_index)
+            .or_insert_with(|| data.clone());
+    }
+
+    pub fn record_proposal(&self, slot: Slot, proposer_index: u64) {
+        self.proposals.lock().insert(slot, proposer_index);
+    }
+
+    /// Remove and return all attestations targeting `epoch`.
+    ///
+    /// Attestations for prior epochs are discarded.
+    fn take_attestations(&self, epoch: Epoch) -> HashMap<u64, AttestationData> {
+        let mut attestations = self.attestations.lock();
+        let later = attestations.split_off(&(epoch + 1));
+        let mut current = std::mem::replace(&mut *attestations, later);
+        current.remove(&epoch).unwrap_or_default()
+    }
+
+    /// Remove and return all proposals with `slot <= end_slot`.
+    fn take_proposals(&self, end_slot: Slot) -> BTreeMap<Slot, u64> {
+        let mut proposals = self.proposals.lock();
+        let later = proposals.split_off(&(end_slot + 1));
+        std::mem::replace(&mut *proposals, later)
+    }
+}
+
+/// Compares the `ShadowLedger` with the on-chain messages from the live validator client.
+pub struct ShadowService<T, E: EthSpec> {
+    ledger: Arc<ShadowLedger>,
+    slot_clock: T,
+    beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
+    context: RuntimeContext<E>,
+}
+
+impl<T: SlotClock + 'static, E: EthSpec> ShadowService<T, E> {
+    pub fn new(
+        ledger: Arc<ShadowLedger>,
+        slot_clock: T,
+        beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
+        context: RuntimeContext<E>,
+    ) -> Self {
+        Self {
+            ledger,
+            slot_clock,
+            beacon_nodes,
+            context,
+        }
+    }
+
+    /// Starts the service which compares the ledger with the chain once per epoch.
+    pub fn start_update_service(self: Arc<Self>) -> Result<(), String> {
+        let log = self.context.log().clone();
+        let slot_duration = self.slot_clock.slot_duration();
+
+        warn!(
+            log,
+            "Running in shadow mode";
+            "info" => "no messages will be signed or published by this validator client",
+        );
+
+        let executor = self.context.executor.clone();
+
+        let interval_fut = async move {
+            loop {
+                let sleep_time = self
+                    .slot_clock
+                    .duration_to_next_epoch(E::slots_per_epoch())
+                    .map_or(slot_duration, |duration| duration + slot_duration);
+                sleep(sleep_time).await;
+
+                let Some(epoch) = self
+                    .slot_clock
+                    .now()
+                    .map(|slot| slot.epoch(E::slots_per_epoch()))
+                    .and_then(|epoch| epoch.as_u64().checked_sub(RECONCILIATION_EPOCH_LAG))
+                    .map(Epoch::new)
+                else {
+                    continue;
+                };
+
+                if let Err(e) = self.compare_epoch(epoch).await {
+                    error!(
+                        log,
+                        "Failed to compare shadow duties";
+                        "epoch" => epoch,
+                        "error" => e,
+                    );
+                }
+            }
+        };
+
+        executor.spawn(interval_fut, "shadow_service");
+        Ok(())
+    }
+
+    /// Compare the attestations targeting `epoch` and the proposals up to the end of `epoch` with
+    /// those on-chain.
+    async fn compare_epoch(&self, epoch: Epoch) -> Result<(), String> {
+        let log = self.context.log();
+        let slots_per_epoch = E::slots_per_epoch();
+
+        let attestations = self.ledger.take_attestations(epoch);
+        let proposals = self.ledger.take_proposals(epoch.end_slot(slots_per_epoch));
+        if attestations.is_empty() && proposals.is_empty() {
+            return Ok(());
+        }
+
+        // Attestations from `epoch` may be included in blocks up until the end of the next epoch.
+        let start_slot = epoch.start_slot(slots_per_epoch);
+        let end_slot = (epoch + 1).end_slot(slots_per_epoch);
+        let blocks = get_blocks(&self.beacon_nodes, start_slot, end_slot).await?;
+
+        /*
+         * Proposals
+         */
+        let on_chain_proposers = blocks
+            .iter()
+            .map(|(slot, block)| (*slot, block.message().proposer_index()))
+            .collect();
+        for outcome in compare_proposals(&proposals, &on_chain_proposers).into_values() {
+            metrics::inc_counter_vec(&metrics::SHADOW_PROPOSAL_COMPARISON_TOTAL, &[outcome]);
+        }
+
+        /*
+         * Attestations
+         */
+        if !attestations.is_empty() {
+            let committees = get_committees(&self.beacon_nodes, epoch).await?;
+
+            // Map from validator index to the attestation data included on-chain.
+            let mut on_chain: HashMap<u64, Vec<&AttestationData>> = HashMap::new();
+            for block in blocks.values() {
+                for attestation in block.message().body().attestations() {
+                    if attestation.data().target.epoch != epoch {
+                        continue;
+                    }
+                    for index in attesting_indices(attestation, &committees) {
+                        if attestations.contains_key(&index) {
+                            on_chain.entry(index).or_default().push(attestation.data());
+                        }
+                    }
+                }
+            }
+
+            let mut mismatches = 0;
+            for outcome in compare_attestations(&attestations, &on_chain).into_values() {
+                if outcome == MISMATCH {
+                    mismatches += 1;
+                }
+                metrics::inc_counter_vec(&metrics::SHADOW_ATTESTATION_COMPARISON_TOTAL, &[outcome]);
+            }
+
+            if mismatches > 0 {
+                warn!(
+                    log,
+                    "Shadow attestations differ from live";
+                    "epoch" => epoch,
+                    "mismatches" => mismatches,
+                );
+            }
+        }
+
+        info!(
+            log,
+            "Compared shadow duties with chain";
+            "epoch" => epoch,
+            "attestations" => attestations.len(),
+            "proposals" => proposals.len(),
+        );
+
+        Ok(())
+    }
+}
+
+/// Compare the proposals of the shadow validators with the proposer of each canonical block.
+///
+/// Returns the outcome for each slot in `proposals`.
+fn compare_proposals(
+    proposals: &BTreeMap<Slot, u64>,
+    on_chain_proposers: &BTreeMap<Slot, u64>,
+) -> BTreeMap<Slot, &'static str> {
+    proposals
+        .iter()
+        .map(|(slot, proposer_index)| {
+            let outcome = if on_chain_proposers.get(slot) == Some(proposer_index) {
+                PROPOSED
+            } else {
+                MISSED
+            };
+            (*slot, outcome)
+        })
+        .collect()
+}
+
+/// Compare the attestation data of the shadow validators with that included on-chain.
+///
+/// Returns the outcome for each validator in `attestations`.
+fn compare_attestations(
+    attestations: &HashMap<u64, AttestationData>,
+    on_chain: &HashMap<u64, Vec<&AttestationData>>,
+) -> HashMap<u64, &'static str> {
+    attestations
+        .iter()
+        .map(|(index, shadow_data)| {
+            let outcome = match on_chain.get(index) {
+                None => MISSING,
+                Some(live) if live.iter().any(|data| *data == shadow_data) => MATCH,
+                Some(live)
+                    if live.iter().any(|data| {
+                        data.source == shadow_data.source && data.target == shadow_data.target
+                    }) =>
+                {
+                    HEAD_MISMATCH
+                }
+                Some(_) => MISMATCH,
+            };
+            (*index, outcome)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{Checkpoint, Hash256};
+
+    fn attestation_data(slot: u64, head: u8, target: u8) -> AttestationData {
+        AttestationData {
+            slot: Slot::new(slot),
+            index: 0,
+            beacon_block_root: Hash256::repeat_byte(head),
+            source: Checkpoint::default(),
+            target: Checkpoint {
+                epoch: Epoch::new(slot / 8),
+                root: Hash256::repeat_byte(target),
+            },
+        }
+    }
+
+    #[test]
+    fn ledger_take_attestations() {
+        let ledger = ShadowLedger::default();
+        ledger.record_attestation(1, &attestation_data(0, 1, 1));
+        ledger.record_attestation(1, &attestation_data(8, 1, 1));
+        ledger.record_attestation(2, &attestation_data(9, 1, 1));
+        // Only the first attestation from a validator for each epoch is retained.
+        ledger.record_attestation(2, &attestation_data(10, 2, 2));
+        ledger.record_attestation(3, &attestation_data(16, 1, 1));
+
+        // Attestations from epoch 0 are discarded, and those from epoch 2 are retained.
+        let attestations = ledger.take_attestations(Epoch::new(1));
+        assert_eq!(attestations.len(), 2);
+        assert_eq!(attestations[&1], attestation_data(8, 1, 1));
+        assert_eq!(attestations[&2], attestation_data(9, 1, 1));
+
+        assert!(ledger.take_attestations(Epoch::new(0)).is_empty());
+        assert!(ledger.take_attestations(Epoch::new(1)).is_empty());
+        assert_eq!(ledger.take_attestations(Epoch::new(2)).len(), 1);
+    }
+
+    #[test]
+    fn ledger_take_proposals() {
+        let ledger = ShadowLedger::default();
+        for slot in [1, 7, 8, 12] {
+            ledger.record_proposal(Slot::new(slot), slot);
+        }
+
+        let proposals = ledger.take_proposals(Slot::new(7));
+        assert_eq!(proposals.keys().copied().collect::<Vec<_>>(), [1, 7]);
+        let proposals = ledger.take_proposals(Slot::new(15));
+        assert_eq!(proposals.keys().copied().collect::<Vec<_>>(), [8, 12]);
+    }
+
+    #[test]
+    fn compare_proposals_outcomes() {
+        let proposals =
+            BTreeMap::from([(Slot::new(1), 10), (Slot::new(2), 20), (Slot::new(3), 30)]);
+        // The block at slot 2 is from another proposer and slot 3 was skipped.
+        let on_chain = BTreeMap::from([(Slot::new(1), 10), (Slot::new(2), 21), (Slot::new(4), 40)]);
+
+        let outcomes = compare_proposals(&proposals, &on_chain);
+        assert_eq!(
+            outcomes,
+            BTreeMap::from([
+                (Slot::new(1), PROPOSED),
+                (Slot::new(2), MISSED),
+                (Slot::new(3), MISSED),
+            ])
+        );
+    }
+
+    #[test]
+    fn compare_attestations_outcomes() {
+        let shadow = attestation_data(8, 1, 1);
+        let attestations = HashMap::from([
+            (1, shadow.clone()),
+            (2, shadow.clone()),
+            (3, shadow.clone()),
+            (4, shadow.clone()),
+            (5, shadow.clone()),
+        ]);
+
+        let matching = shadow.clone();
+        let other_head = attestation_data(8, 2, 1);
+        let other_target = attestation_data(8, 2, 2);
+        let on_chain = HashMap::from([
+            (1, vec![&matching]),
+            (2, vec![&other_head]),
+            (3, vec![&other_target]),
+            // Any matching inclusion is sufficient.
+            (4, vec![&other_target, &other_head, &matching]),
+        ]);
+
+        let outcomes = compare_attestations(&attestations, &on_chain);
+        assert_eq!(
+            outcomes,
+            HashMap::from([
+                (1, MATCH),
+                (2, HEAD_MISMATCH),
+                (3, MISMATCH),
+                (4, MATCH),
+                // Missing duties are reported separately from mismatches.
+                (5, MISSING),
+            ])
+        );
+    }
+}
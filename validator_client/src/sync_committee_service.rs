@@ -298,6 +298,17 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .flatten()
             .collect::<Vec<_>>();
 
+        if self.validator_store.shadow_mode() {
+            debug!(
+                log,
+                "Not publishing shadow sync committee messages";
+                "count" => committee_signatures.len(),
+                "head_block" => ?beacon_block_root,
+                "slot" => slot,
+            );
+            return Ok(());
+        }
+
         self.beacon_nodes
             .request(
                 RequireSynced::No,
@@ -451,6 +462,17 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .flatten()
             .collect::<Vec<_>>();
 
+        if self.validator_store.shadow_mode() {
+            debug!(
+                log,
+                "Not publishing shadow sync contributions";
+                "subnet" => %subnet_id,
+                "count" => signed_contributions.len(),
+                "slot" => slot,
+            );
+            return Ok(());
+        }
+
         // Publish to the beacon node.
         self.beacon_nodes
             .first_success(
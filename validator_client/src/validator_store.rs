@@ -2,6 +2,8 @@ use crate::{
     doppelganger_service::DoppelgangerService,
     http_metrics::metrics,
     initialized_validators::InitializedValidators,
+    proposer_config::{self, ProposerConfigFile, ProposerSettings},
+    shadow_service::ShadowLedger,
     signing_method::{Error as SigningError, SignableMessage, SigningContext, SigningMethod},
     Config,
 };
@@ -16,6 +18,7 @@ use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
 use task_executor::TaskExecutor;
+use tree_hash::TreeHash;
 use types::{
     attestation::Error as AttestationError, graffiti::GraffitiString, AbstractExecPayload, Address,
     AggregateAndProof, Attestation, BeaconBlock, BlindedPayload, ChainSpec, ContributionAndProof,
@@ -40,6 +43,7 @@ pub enum Error {
     GreaterThanCurrentEpoch { epoch: Epoch, current_epoch: Epoch },
     UnableToSignAttestation(AttestationError),
     UnableToSign(SigningError),
+    ShadowMode,
 }
 
 impl From<SigningError> for Error {
@@ -73,7 +77,10 @@ pub struct ValidatorStore<T, E: EthSpec> {
     enable_web3signer_slashing_protection: bool,
     prefer_builder_proposals: bool,
     builder_boost_factor: Option<u64>,
+    proposer_config: Option<Arc<ProposerConfigFile>>,
     task_executor: TaskExecutor,
+    /// Present if the validator client is running in shadow mode.
+    shadow_ledger: Option<Arc<ShadowLedger>>,
     _phantom: PhantomData<E>,
 }
 
@@ -89,6 +96,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         doppelganger_service: Option<Arc<DoppelgangerService>>,
         slot_clock: T,
         config: &Config,
+        proposer_config: Option<Arc<ProposerConfigFile>>,
         task_executor: TaskExecutor,
         log: Logger,
     ) -> Self {
@@ -107,11 +115,26 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             enable_web3signer_slashing_protection: config.enable_web3signer_slashing_protection,
             prefer_builder_proposals: config.prefer_builder_proposals,
             builder_boost_factor: config.builder_boost_factor,
+            proposer_config,
             task_executor,
+            shadow_ledger: config.shadow.then(Default::default),
             _phantom: PhantomData,
         }
     }
 
+    /// Returns the `ShadowLedger` if the validator client is running in shadow mode.
+    ///
+    /// In shadow mode slashable and published messages are never signed. Instead, they are
+    /// checked against slashing protection without being recorded, added to the ledger and
+    /// returned with an empty signature.
+    pub fn shadow_ledger(&self) -> Option<&Arc<ShadowLedger>> {
+        self.shadow_ledger.as_ref()
+    }
+
+    pub fn shadow_mode(&self) -> bool {
+        self.shadow_ledger.is_some()
+    }
+
     /// Register all local validators in doppelganger protection to try and prevent instances of
     /// duplicate validators operating on the network at the same time.
     ///
@@ -126,6 +149,19 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(())
     }
 
+    /// Record a message which is about to be signed, so that it is not mistaken for the message of
+    /// a doppelganger when monitoring gossip.
+    fn record_signed_message(
+        &self,
+        validator_pubkey: PublicKeyBytes,
+        slot: Slot,
+        root: impl FnOnce() -> Hash256,
+    ) {
+        if let Some(doppelganger_service) = &self.doppelganger_service {
+            doppelganger_service.record_signed_message(validator_pubkey, slot, root);
+        }
+    }
+
     /// Returns `true` if doppelganger protection is enabled, or else `false`.
     pub fn doppelganger_protection_enabled(&self) -> bool {
         self.doppelganger_service.is_some()
@@ -217,11 +253,23 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             .validator(pubkey)
             .map(|validator| ProposalData {
                 validator_index: validator.get_index(),
-                fee_recipient: self
-                    .get_fee_recipient_defaulting(validator.get_suggested_fee_recipient()),
-                gas_limit: self.get_gas_limit_defaulting(validator.get_gas_limit()),
-                builder_proposals: self
-                    .get_builder_proposals_defaulting(validator.get_builder_proposals()),
+                fee_recipient: self.get_fee_recipient_defaulting(self.resolve_proposer_setting(
+                    pubkey,
+                    validator.get_suggested_fee_recipient(),
+                    |settings| settings.fee_recipient,
+                )),
+                gas_limit: self.get_gas_limit_defaulting(self.resolve_proposer_setting(
+                    pubkey,
+                    validator.get_gas_limit(),
+                    |settings| settings.gas_limit,
+                )),
+                builder_proposals: self.get_builder_proposals_defaulting(
+                    self.resolve_proposer_setting(
+                        pubkey,
+                        validator.get_builder_proposals(),
+                        |settings| settings.builder_proposals,
+                    ),
+                ),
             })
     }
 
@@ -404,18 +452,41 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(signature)
     }
 
+    /// Resolves a proposer setting from the proposer config file, if any, falling back to
+    /// `definition`. See `ProposerConfigFile::resolve` for the priority order.
+    fn resolve_proposer_setting<V>(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        definition: Option<V>,
+        setting: impl Fn(&ProposerSettings) -> Option<V>,
+    ) -> Option<V> {
+        match &self.proposer_config {
+            Some(proposer_config) => proposer_config.resolve(validator_pubkey, definition, setting),
+            None => definition,
+        }
+    }
+
     pub fn graffiti(&self, validator_pubkey: &PublicKeyBytes) -> Option<Graffiti> {
-        self.validators.read().graffiti(validator_pubkey)
+        let definition = self.validators.read().graffiti(validator_pubkey);
+        self.resolve_proposer_setting(validator_pubkey, definition, |settings| {
+            settings.graffiti.clone().map(Into::into)
+        })
     }
 
     /// Returns the fee recipient for the given public key. The priority order for fetching
     /// the fee recipient is:
-    /// 1. validator_definitions.yml
-    /// 2. process level fee recipient
+    /// 1. proposer config file (per validator)
+    /// 2. validator_definitions.yml
+    /// 3. proposer config file (default)
+    /// 4. process level fee recipient
     pub fn get_fee_recipient(&self, validator_pubkey: &PublicKeyBytes) -> Option<Address> {
         // If there is a `suggested_fee_recipient` in the validator definitions yaml
         // file, use that value.
-        self.get_fee_recipient_defaulting(self.suggested_fee_recipient(validator_pubkey))
+        self.get_fee_recipient_defaulting(self.resolve_proposer_setting(
+            validator_pubkey,
+            self.suggested_fee_recipient(validator_pubkey),
+            |settings| settings.fee_recipient,
+        ))
     }
 
     pub fn get_fee_recipient_defaulting(&self, fee_recipient: Option<Address>) -> Option<Address> {
@@ -434,11 +505,18 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     /// Returns the gas limit for the given public key. The priority order for fetching
     /// the gas limit is:
     ///
-    /// 1. validator_definitions.yml
-    /// 2. process level gas limit
-    /// 3. `DEFAULT_GAS_LIMIT`
+    /// 1. proposer config file (per validator)
+    /// 2. validator_definitions.yml
+    /// 3. proposer config file (default)
+    /// 4. process level gas limit
+    /// 5. `DEFAULT_GAS_LIMIT`
     pub fn get_gas_limit(&self, validator_pubkey: &PublicKeyBytes) -> u64 {
-        self.get_gas_limit_defaulting(self.validators.read().gas_limit(validator_pubkey))
+        let definition = self.validators.read().gas_limit(validator_pubkey);
+        self.get_gas_limit_defaulting(self.resolve_proposer_setting(
+            validator_pubkey,
+            definition,
+            |settings| settings.gas_limit,
+        ))
     }
 
     fn get_gas_limit_defaulting(&self, gas_limit: Option<u64>) -> u64 {
@@ -454,25 +532,36 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     /// Returns a `bool` for the given public key that denotes whether this validator should use the
     /// builder API. The priority order for fetching this value is:
     ///
-    /// 1. validator_definitions.yml
-    /// 2. process level flag
+    /// 1. proposer config file (per validator)
+    /// 2. validator_definitions.yml
+    /// 3. proposer config file (default)
+    /// 4. process level flag
     pub fn get_builder_proposals(&self, validator_pubkey: &PublicKeyBytes) -> bool {
         // If there is a `suggested_fee_recipient` in the validator definitions yaml
         // file, use that value.
-        self.get_builder_proposals_defaulting(
-            self.validators.read().builder_proposals(validator_pubkey),
-        )
+        let definition = self.validators.read().builder_proposals(validator_pubkey);
+        self.get_builder_proposals_defaulting(self.resolve_proposer_setting(
+            validator_pubkey,
+            definition,
+            |settings| settings.builder_proposals,
+        ))
     }
 
     /// Returns a `u64` for the given public key that denotes the builder boost factor. The priority order for fetching this value is:
     ///
-    /// 1. validator_definitions.yml
-    /// 2. process level flag
+    /// 1. proposer config file (per validator)
+    /// 2. validator_definitions.yml
+    /// 3. proposer config file (default)
+    /// 4. process level flag
     pub fn get_builder_boost_factor(&self, validator_pubkey: &PublicKeyBytes) -> Option<u64> {
-        self.validators
+        let definition = self
+            .validators
             .read()
-            .builder_boost_factor(validator_pubkey)
-            .or(self.builder_boost_factor)
+            .builder_boost_factor(validator_pubkey);
+        self.resolve_proposer_setting(validator_pubkey, definition, |settings| {
+            settings.builder_boost_factor
+        })
+        .or(self.builder_boost_factor)
     }
 
     /// Returns a `bool` for the given public key that denotes whether this validator should prefer a
@@ -501,6 +590,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     /// - If `builder_proposals` is set to false, set boost factor to 0 to indicate a preference for
     ///   local payloads.
     /// - Else return `None` to indicate no preference between builder and local payloads.
+    ///
+    /// The settings in the proposer config file, if any, are layered around these as described in
+    /// `ProposerConfigFile::resolve`.
     pub fn determine_validator_builder_boost_factor(
         &self,
         validator_pubkey: &PublicKeyBytes,
@@ -514,18 +606,25 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             return Some(u64::MAX);
         }
 
-        self.validators
-            .read()
-            .builder_boost_factor(validator_pubkey)
-            .or_else(|| {
-                if matches!(
-                    self.validators.read().builder_proposals(validator_pubkey),
-                    Some(false)
-                ) {
-                    return Some(0);
-                }
-                None
-            })
+        let (definition_builder_proposals, definition_builder_boost_factor) = {
+            let validators = self.validators.read();
+            (
+                validators.builder_proposals(validator_pubkey),
+                validators.builder_boost_factor(validator_pubkey),
+            )
+        };
+        proposer_config::builder_boost_factor(
+            self.resolve_proposer_setting(
+                validator_pubkey,
+                definition_builder_proposals,
+                |settings| settings.builder_proposals,
+            ),
+            self.resolve_proposer_setting(
+                validator_pubkey,
+                definition_builder_boost_factor,
+                |settings| settings.builder_boost_factor,
+            ),
+        )
     }
 
     /// Translate the process-wide `builder_proposals`, `builder_boost_factor` and
@@ -576,21 +675,37 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         let signing_method = self.doppelganger_checked_signing_method(validator_pubkey)?;
 
         // Check for slashing conditions.
-        let slashing_status = if signing_method
+        let slashing_status = if !signing_method
             .requires_local_slashing_protection(self.enable_web3signer_slashing_protection)
         {
-            self.slashing_protection.check_and_insert_block_proposal(
+            Ok(Safe::Valid)
+        } else if self.shadow_mode() {
+            self.slashing_protection.check_block_proposal_read_only(
                 &validator_pubkey,
                 &block.block_header(),
                 domain_hash,
             )
         } else {
-            Ok(Safe::Valid)
+            self.slashing_protection.check_and_insert_block_proposal(
+                &validator_pubkey,
+                &block.block_header(),
+                domain_hash,
+            )
         };
 
         match slashing_status {
             // We can safely sign this block without slashing.
             Ok(Safe::Valid) => {
+                if let Some(shadow_ledger) = &self.shadow_ledger {
+                    shadow_ledger.record_proposal(block.slot(), block.proposer_index());
+                    metrics::inc_counter_vec(&metrics::SIGNED_BLOCKS_TOTAL, &[metrics::SHADOW]);
+                    return Ok(SignedBeaconBlock::from_block(block, Signature::empty()));
+                }
+
+                self.record_signed_message(validator_pubkey, block.slot(), || {
+                    block.canonical_root()
+                });
+
                 metrics::inc_counter_vec(&metrics::SIGNED_BLOCKS_TOTAL, &[metrics::SUCCESS]);
 
                 let signature = signing_method
@@ -655,21 +770,45 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         let signing_epoch = attestation.data().target.epoch;
         let signing_context = self.signing_context(Domain::BeaconAttester, signing_epoch);
         let domain_hash = signing_context.domain_hash(&self.spec);
-        let slashing_status = if signing_method
+        let slashing_status = if !signing_method
             .requires_local_slashing_protection(self.enable_web3signer_slashing_protection)
         {
-            self.slashing_protection.check_and_insert_attestation(
+            Ok(Safe::Valid)
+        } else if self.shadow_mode() {
+            self.slashing_protection.check_attestation_read_only(
                 &validator_pubkey,
                 attestation.data(),
                 domain_hash,
             )
         } else {
-            Ok(Safe::Valid)
+            self.slashing_protection.check_and_insert_attestation(
+                &validator_pubkey,
+                attestation.data(),
+                domain_hash,
+            )
         };
 
         match slashing_status {
             // We can safely sign this attestation.
             Ok(Safe::Valid) => {
+                if let Some(shadow_ledger) = &self.shadow_ledger {
+                    if let Some(validator_index) = self.validator_index(&validator_pubkey) {
+                        shadow_ledger.record_attestation(validator_index, attestation.data());
+                    }
+                    attestation
+                        .add_signature(&Signature::empty(), validator_committee_position)
+                        .map_err(Error::UnableToSignAttestation)?;
+                    metrics::inc_counter_vec(
+                        &metrics::SIGNED_ATTESTATIONS_TOTAL,
+                        &[metrics::SHADOW],
+                    );
+                    return Ok(());
+                }
+
+                self.record_signed_message(validator_pubkey, attestation.data().slot, || {
+                    attestation.data().tree_hash_root()
+                });
+
                 let signature = signing_method
                     .get_signature::<E, BlindedPayload<E>>(
                         SignableMessage::AttestationData(attestation.data()),
@@ -731,6 +870,10 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         validator_pubkey: PublicKeyBytes,
         voluntary_exit: VoluntaryExit,
     ) -> Result<SignedVoluntaryExit, Error> {
+        if self.shadow_mode() {
+            return Err(Error::ShadowMode);
+        }
+
         let signing_epoch = voluntary_exit.epoch;
         let signing_context = self.signing_context(Domain::VoluntaryExit, signing_epoch);
         let signing_method = self.doppelganger_bypassed_signing_method(validator_pubkey)?;
@@ -756,6 +899,10 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         &self,
         validator_registration_data: ValidatorRegistrationData,
     ) -> Result<SignedValidatorRegistrationData, Error> {
+        if self.shadow_mode() {
+            return Err(Error::ShadowMode);
+        }
+
         let domain_hash = self.spec.get_builder_domain();
         let signing_root = validator_registration_data.signing_root(domain_hash);
 
@@ -799,6 +946,19 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             AggregateAndProof::from_attestation(aggregator_index, aggregate, selection_proof);
 
         let signing_method = self.doppelganger_checked_signing_method(validator_pubkey)?;
+
+        if self.shadow_mode() {
+            metrics::inc_counter_vec(&metrics::SIGNED_AGGREGATES_TOTAL, &[metrics::SHADOW]);
+            return Ok(SignedAggregateAndProof::from_aggregate_and_proof(
+                message,
+                Signature::empty(),
+            ));
+        }
+
+        self.record_signed_message(validator_pubkey, message.aggregate().data().slot, || {
+            message.aggregate().data().tree_hash_root()
+        });
+
         let signature = signing_method
             .get_signature::<E, BlindedPayload<E>>(
                 SignableMessage::SignedAggregateAndProof(message.to_ref()),
@@ -899,6 +1059,19 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         // Bypass `with_validator_signing_method`: sync committee messages are not slashable.
         let signing_method = self.doppelganger_bypassed_signing_method(*validator_pubkey)?;
 
+        if self.shadow_mode() {
+            metrics::inc_counter_vec(
+                &metrics::SIGNED_SYNC_COMMITTEE_MESSAGES_TOTAL,
+                &[metrics::SHADOW],
+            );
+            return Ok(SyncCommitteeMessage {
+                slot,
+                beacon_block_root,
+                validator_index,
+                signature: Signature::empty(),
+            });
+        }
+
         let signature = signing_method
             .get_signature::<E, BlindedPayload<E>>(
                 SignableMessage::SyncCommitteeSignature {
@@ -944,6 +1117,17 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             selection_proof: selection_proof.into(),
         };
 
+        if self.shadow_mode() {
+            metrics::inc_counter_vec(
+                &metrics::SIGNED_SYNC_COMMITTEE_CONTRIBUTIONS_TOTAL,
+                &[metrics::SHADOW],
+            );
+            return Ok(SignedContributionAndProof {
+                message,
+                signature: Signature::empty(),
+            });
+        }
+
         let signature = signing_method
             .get_signature::<E, BlindedPayload<E>>(
                 SignableMessage::SignedContributionAndProof(&message),